@@ -0,0 +1,34 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-check-cfg=cfg(armadillo_portable_simd)");
+
+    // std::simd is nightly-only, so the `portable-simd` feature only takes
+    // effect on a nightly compiler.  On stable it is ignored with a warning,
+    // which keeps `--all-features` builds working.
+    if env::var_os("CARGO_FEATURE_PORTABLE_SIMD").is_none() {
+        return;
+    }
+    if is_nightly() {
+        println!("cargo:rustc-cfg=armadillo_portable_simd");
+    } else {
+        println!(
+            "cargo:warning=the portable-simd feature needs a nightly compiler; \
+             using the portable ChaCha implementation instead"
+        );
+    }
+}
+
+fn is_nightly() -> bool {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| {
+            let version = String::from_utf8_lossy(&output.stdout);
+            version.contains("nightly") || version.contains("-dev")
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,731 @@
+//!
+//! Emits JSON test vectors describing armadillo's exact behaviour, so
+//! implementations of the other side of a protocol (Go, Java, ...) can be
+//! validated against this crate.
+//!
+//! All inputs are derived deterministically from a fixed seed, so running
+//! the generator twice produces identical output.
+//!
+//! ```notrust
+//! cargo run --example test_vectors > vectors.json
+//! ```
+//!
+// The vectors object is one json! invocation, deeper than the default limit
+#![recursion_limit = "256"]
+
+use std::io::Write;
+
+use armadillo::aead::aegis::{Aegis128L, Aegis256};
+use armadillo::aead::{
+    Aes128Ccm, Aes128Gcm, Aes256Gcm, ChaCha20Poly1305, ChaCha20Poly1305Siv, StreamDecryptor,
+    StreamEncryptor, StreamReader, StreamWriter,
+};
+use armadillo::aes::Aes;
+use armadillo::argon2::{argon2, Params as Argon2Params, Variant};
+use armadillo::ascon::{ascon_hash, Ascon128};
+use armadillo::bcrypt::bcrypt;
+use armadillo::blake3::{blake3, blake3_keyed};
+use armadillo::chacha::{ChaCha12, ChaCha20, ChaCha20Legacy, ChaCha8, XChaCha20};
+use armadillo::hash::Digest;
+use armadillo::hkdf;
+use armadillo::mac::{hmac, siphash};
+use armadillo::modes::{Cbc, Cmac, Ctr, Ff1, Xts};
+use armadillo::pbkdf2::pbkdf2;
+use armadillo::poly::{poly1305_aes_mac, poly1305_mac};
+use armadillo::salsa::{Salsa20, XSalsa20};
+use armadillo::scrypt::{scrypt, Params as ScryptParams};
+use armadillo::secretbox;
+use armadillo::sha2::{sha256, sha512, Sha256, Sha512};
+use armadillo::sha3::{sha3_256, sha3_512, Shake, Shake128, Shake256};
+use armadillo::ssh::OpenSshChaCha20Poly1305;
+use serde_json::{json, Value};
+
+///
+/// Message lengths exercised by every algorithm.  Chosen to hit the block
+/// boundaries of both ChaCha20 (64 bytes) and Poly1305 (16 bytes).
+///
+const LENGTHS: [usize; 14] = [0, 1, 15, 16, 17, 63, 64, 65, 127, 128, 129, 255, 256, 1000];
+
+///
+/// Chunk sizes used for the streaming vectors.  Each sequence is fed to a
+/// single cipher instance, one call per chunk.
+///
+const CHUNK_SEQUENCES: [&[usize]; 4] = [&[1, 1, 1, 1], &[2, 2], &[63, 1, 64], &[100, 28, 200]];
+
+///
+/// Plaintext bytes per chunk of the STREAM files, kept small so the files
+/// span several chunks.
+///
+const FILE_CHUNK_LENGTH: usize = 100;
+
+///
+/// Deterministic source of test material, built on the crate's own
+/// ChaCha20 keystream so the vectors are reproducible.
+///
+struct Material {
+    cipher: ChaCha20,
+}
+
+impl Material {
+    fn new(label: &str) -> Self {
+        let mut key = [0u8; 32];
+        key.iter_mut()
+            .zip(label.as_bytes().iter().cycle())
+            .for_each(|(k, l)| *k = *l);
+        Material {
+            cipher: ChaCha20::new(key, [0u8; 12]),
+        }
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        self.cipher.encrypt(&vec![0u8; len])
+    }
+
+    fn array<const N: usize>(&mut self) -> [u8; N] {
+        self.bytes(N).try_into().unwrap()
+    }
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+///
+/// Vectors for a stream cipher whose keystream starts at block `counter`,
+/// one message of each length under a fresh key and nonce.
+///
+fn stream_cipher_vectors<const KEY: usize, const NONCE: usize>(
+    label: &str,
+    counter: u64,
+    encrypt: impl Fn([u8; KEY], [u8; NONCE], &[u8]) -> Vec<u8>,
+) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<KEY>();
+            let nonce = material.array::<NONCE>();
+            let plaintext = material.bytes(len);
+            let ciphertext = encrypt(key, nonce, &plaintext);
+            json!({
+                "key": hex(&key),
+                "nonce": hex(&nonce),
+                "counter": counter,
+                "plaintext": hex(&plaintext),
+                "ciphertext": hex(&ciphertext),
+            })
+        })
+        .collect()
+}
+
+fn chacha20_stream_vectors() -> Vec<Value> {
+    let mut material = Material::new("chacha20-stream");
+    CHUNK_SEQUENCES
+        .iter()
+        .map(|chunks| {
+            let key = material.array::<32>();
+            let nonce = material.array::<12>();
+            let mut cipher = ChaCha20::new(key, nonce);
+            let chunks = chunks
+                .iter()
+                .map(|&len| {
+                    let plaintext = material.bytes(len);
+                    let ciphertext = cipher.encrypt(&plaintext);
+                    json!({
+                        "plaintext": hex(&plaintext),
+                        "ciphertext": hex(&ciphertext),
+                    })
+                })
+                .collect::<Vec<Value>>();
+            json!({
+                "key": hex(&key),
+                "nonce": hex(&nonce),
+                "counter": 1,
+                "chunks": chunks,
+            })
+        })
+        .collect()
+}
+
+///
+/// Vectors for an AEAD whose `seal` appends a `tag_length`-byte tag, with
+/// the plaintext and associated data lengths running in opposite
+/// directions.
+///
+fn aead_vectors<const KEY: usize, const NONCE: usize>(
+    label: &str,
+    tag_length: usize,
+    seal: impl Fn([u8; KEY], [u8; NONCE], &[u8], &[u8]) -> Vec<u8>,
+) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .zip(LENGTHS.iter().rev())
+        .map(|(&len, &aad_len)| {
+            let key = material.array::<KEY>();
+            let nonce = material.array::<NONCE>();
+            let aad = material.bytes(aad_len);
+            let plaintext = material.bytes(len);
+            let sealed = seal(key, nonce, &aad, &plaintext);
+            assert_eq!(sealed.len(), len + tag_length);
+            let (ciphertext, tag) = sealed.split_at(len);
+            json!({
+                "key": hex(&key),
+                "nonce": hex(&nonce),
+                "aad": hex(&aad),
+                "plaintext": hex(&plaintext),
+                "ciphertext": hex(ciphertext),
+                "tag": hex(tag),
+            })
+        })
+        .collect()
+}
+
+///
+/// STREAM chunk sequences: every chunk but the last is sealed with
+/// `next_chunk`, all under the same associated data.
+///
+fn stream_vectors() -> Vec<Value> {
+    let mut material = Material::new("stream");
+    CHUNK_SEQUENCES
+        .iter()
+        .map(|chunks| {
+            let key = material.array::<32>();
+            let nonce_prefix = material.array::<7>();
+            let aad = material.bytes(16);
+            let mut encryptor = Some(StreamEncryptor::new(key, nonce_prefix));
+            let chunks = chunks
+                .iter()
+                .enumerate()
+                .map(|(i, &len)| {
+                    let plaintext = material.bytes(len);
+                    let sealed = if i + 1 == chunks.len() {
+                        encryptor.take().unwrap().last_chunk(&aad, &plaintext)
+                    } else {
+                        let encryptor = encryptor.as_mut().unwrap();
+                        encryptor.next_chunk(&aad, &plaintext).unwrap()
+                    };
+                    json!({
+                        "plaintext": hex(&plaintext),
+                        "sealed": hex(&sealed),
+                    })
+                })
+                .collect::<Vec<Value>>();
+            json!({
+                "key": hex(&key),
+                "nonce_prefix": hex(&nonce_prefix),
+                "aad": hex(&aad),
+                "chunks": chunks,
+            })
+        })
+        .collect()
+}
+
+///
+/// Whole files written by `StreamWriter`: the sealed chunks back to back,
+/// with no header, and empty associated data.  Each file is read back to
+/// check it.
+///
+fn stream_file_vectors() -> Vec<Value> {
+    let mut material = Material::new("stream-file");
+    [0, 1, 99, 100, 101, 250]
+        .iter()
+        .map(|&len| {
+            let key = material.array::<32>();
+            let nonce_prefix = material.array::<7>();
+            let plaintext = material.bytes(len);
+
+            let encryptor = StreamEncryptor::new(key, nonce_prefix);
+            let mut writer =
+                StreamWriter::with_chunk_length(Vec::new(), encryptor, FILE_CHUNK_LENGTH);
+            writer.write_all(&plaintext).unwrap();
+            let file = writer.finish().unwrap();
+
+            let decryptor = StreamDecryptor::new(key, nonce_prefix);
+            let mut reader =
+                StreamReader::with_chunk_length(&file[..], decryptor, FILE_CHUNK_LENGTH);
+            let mut opened = Vec::new();
+            std::io::Read::read_to_end(&mut reader, &mut opened).unwrap();
+            assert_eq!(opened, plaintext);
+
+            json!({
+                "key": hex(&key),
+                "nonce_prefix": hex(&nonce_prefix),
+                "chunk_length": FILE_CHUNK_LENGTH,
+                "plaintext": hex(&plaintext),
+                "file": hex(&file),
+            })
+        })
+        .collect()
+}
+
+///
+/// Vectors for a MAC under a `KEY`-byte key, one message of each length.
+///
+fn mac_vectors<const KEY: usize>(
+    label: &str,
+    mac: impl Fn([u8; KEY], &[u8]) -> Vec<u8>,
+) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<KEY>();
+            let message = material.bytes(len);
+            let tag = mac(key, &message);
+            json!({
+                "key": hex(&key),
+                "message": hex(&message),
+                "tag": hex(&tag),
+            })
+        })
+        .collect()
+}
+
+///
+/// Vectors for a hash, or an XOF read to a fixed length, one message of
+/// each length.
+///
+fn hash_vectors(label: &str, hash: impl Fn(&[u8]) -> Vec<u8>) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let message = material.bytes(len);
+            json!({
+                "message": hex(&message),
+                "hash": hex(&hash(&message)),
+            })
+        })
+        .collect()
+}
+
+///
+/// The first 64 bytes of SHAKE output.
+///
+fn shake<const RATE: usize>(message: &[u8], shake: impl FnOnce() -> Shake<RATE>) -> Vec<u8> {
+    let mut hasher = shake();
+    hasher.update(message);
+    let mut output = vec![0u8; 64];
+    hasher.finalize_xof().read(&mut output);
+    output
+}
+
+fn poly1305_aes_vectors() -> Vec<Value> {
+    let mut material = Material::new("poly1305-aes");
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<32>();
+            let nonce = material.array::<16>();
+            let message = material.bytes(len);
+            let tag = poly1305_aes_mac(key, nonce, &message);
+            json!({
+                "key": hex(&key),
+                "nonce": hex(&nonce),
+                "message": hex(&message),
+                "tag": hex(tag.as_ref()),
+            })
+        })
+        .collect()
+}
+
+///
+/// secretbox vectors.  `secretbox::seal` puts the tag before the
+/// ciphertext, as libsodium's `crypto_secretbox_easy` does.
+///
+fn secretbox_vectors() -> Vec<Value> {
+    let mut material = Material::new("secretbox");
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<32>();
+            let nonce = material.array::<24>();
+            let plaintext = material.bytes(len);
+            let (ciphertext, tag) = secretbox::seal_detached(key, nonce, &plaintext);
+            assert_eq!(
+                secretbox::seal(key, nonce, &plaintext),
+                [tag.as_ref(), &ciphertext].concat()
+            );
+            json!({
+                "key": hex(&key),
+                "nonce": hex(&nonce),
+                "plaintext": hex(&plaintext),
+                "ciphertext": hex(&ciphertext),
+                "tag": hex(tag.as_ref()),
+            })
+        })
+        .collect()
+}
+
+///
+/// chacha20-poly1305@openssh.com packets: the 4-byte big-endian length
+/// followed by a payload of each length, encrypted under a 64-byte key at
+/// some sequence number.
+///
+fn openssh_chacha20_poly1305_vectors() -> Vec<Value> {
+    let mut material = Material::new("openssh-chacha20-poly1305");
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<64>();
+            let sequence_number = u32::from_le_bytes(material.array::<4>());
+            let mut packet = (len as u32).to_be_bytes().to_vec();
+            packet.extend(material.bytes(len));
+            let sealed = OpenSshChaCha20Poly1305::new(key)
+                .encrypt(sequence_number as u64, &packet)
+                .unwrap();
+            let (ciphertext, tag) = sealed.split_at(packet.len());
+            json!({
+                "key": hex(&key),
+                "sequence_number": sequence_number,
+                "packet": hex(&packet),
+                "ciphertext": hex(ciphertext),
+                "tag": hex(tag),
+            })
+        })
+        .collect()
+}
+
+fn aes_ctr_vectors<const KEY: usize>(label: &str) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<KEY>();
+            let initial_counter = material.array::<16>();
+            let plaintext = material.bytes(len);
+            let ciphertext = Ctr::new(Aes::new(key), initial_counter).encrypt(&plaintext);
+            json!({
+                "key": hex(&key),
+                "initial_counter": hex(&initial_counter),
+                "plaintext": hex(&plaintext),
+                "ciphertext": hex(&ciphertext),
+            })
+        })
+        .collect()
+}
+
+///
+/// CBC vectors, with the plaintext padded with PKCS#7 before encryption.
+///
+fn aes_cbc_vectors<const KEY: usize>(label: &str) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .map(|&len| {
+            let key = material.array::<KEY>();
+            let iv = material.array::<16>();
+            let plaintext = material.bytes(len);
+            let ciphertext = Cbc::new(Aes::new(key)).encrypt_padded(iv, &plaintext);
+            json!({
+                "key": hex(&key),
+                "iv": hex(&iv),
+                "plaintext": hex(&plaintext),
+                "ciphertext": hex(&ciphertext),
+            })
+        })
+        .collect()
+}
+
+///
+/// XTS vectors for the lengths of at least one block.  The key is the data
+/// key followed by the tweak key, and the sector number is the tweak as a
+/// 128-bit little-endian integer.
+///
+fn aes_xts_vectors<const KEY: usize>(label: &str) -> Vec<Value> {
+    let mut material = Material::new(label);
+    LENGTHS
+        .iter()
+        .filter(|&&len| len >= 16)
+        .map(|&len| {
+            let data_key = material.array::<KEY>();
+            let tweak_key = material.array::<KEY>();
+            let sector = u32::from_le_bytes(material.array::<4>());
+            let plaintext = material.bytes(len);
+            let mut ciphertext = plaintext.clone();
+            Xts::new(Aes::new(data_key), Aes::new(tweak_key))
+                .encrypt_sector(sector as u128, &mut ciphertext)
+                .unwrap();
+            json!({
+                "key": hex(&[data_key, tweak_key].concat()),
+                "sector": sector,
+                "plaintext": hex(&plaintext),
+                "ciphertext": hex(&ciphertext),
+            })
+        })
+        .collect()
+}
+
+///
+/// FF1 vectors for decimal and base-36 strings, with tweaks of varying
+/// length.
+///
+fn aes_ff1_vectors<const KEY: usize>(label: &str) -> Vec<Value> {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut material = Material::new(label);
+    [
+        (10, 6),
+        (10, 10),
+        (10, 16),
+        (10, 19),
+        (10, 32),
+        (36, 4),
+        (36, 8),
+        (36, 16),
+        (36, 32),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, &(radix, len))| {
+        let key = material.array::<KEY>();
+        let tweak = material.bytes(i * 3);
+        let plaintext = material
+            .bytes(len)
+            .iter()
+            .map(|&byte| DIGITS[byte as usize % radix as usize] as char)
+            .collect::<String>();
+        let ciphertext = Ff1::new(Aes::new(key), radix)
+            .unwrap()
+            .encrypt_str(&tweak, &plaintext)
+            .unwrap();
+        json!({
+            "key": hex(&key),
+            "radix": radix,
+            "tweak": hex(&tweak),
+            "plaintext": plaintext,
+            "ciphertext": ciphertext,
+        })
+    })
+    .collect()
+}
+
+fn cmac<const KEY: usize>(key: [u8; KEY], message: &[u8]) -> Vec<u8> {
+    let mut mac = Cmac::new(Aes::new(key));
+    mac.update(message);
+    mac.finalize().to_vec()
+}
+
+fn hkdf_vectors<D: Digest>(label: &str) -> Vec<Value> {
+    let mut material = Material::new(label);
+    [
+        (22, 13, 10, 42),
+        (80, 80, 80, 82),
+        (22, 0, 0, 42),
+        (32, 32, 16, D::OUTPUT_LENGTH * 255),
+    ]
+    .iter()
+    .map(|&(ikm_len, salt_len, info_len, okm_len)| {
+        let ikm = material.bytes(ikm_len);
+        let salt = material.bytes(salt_len);
+        let info = material.bytes(info_len);
+        let mut okm = vec![0u8; okm_len];
+        hkdf::derive::<D>(&salt, &ikm, &info, &mut okm).unwrap();
+        json!({
+            "ikm": hex(&ikm),
+            "salt": hex(&salt),
+            "info": hex(&info),
+            "okm": hex(&okm),
+        })
+    })
+    .collect()
+}
+
+fn pbkdf2_vectors<D: Digest>(label: &str) -> Vec<Value> {
+    let mut material = Material::new(label);
+    [
+        (8, 8, 1, 32),
+        (8, 8, 2, 32),
+        (24, 36, 4096, 40),
+        (0, 16, 10, 64),
+        (100, 16, 1000, 100),
+    ]
+    .iter()
+    .map(|&(password_len, salt_len, iterations, output_len)| {
+        let password = material.bytes(password_len);
+        let salt = material.bytes(salt_len);
+        let mut output = vec![0u8; output_len];
+        pbkdf2::<D>(&password, &salt, iterations, &mut output).unwrap();
+        json!({
+            "password": hex(&password),
+            "salt": hex(&salt),
+            "iterations": iterations,
+            "output": hex(&output),
+        })
+    })
+    .collect()
+}
+
+fn scrypt_vectors() -> Vec<Value> {
+    let mut material = Material::new("scrypt");
+    [(16, 1, 1, 64), (1024, 8, 1, 64), (64, 2, 3, 32)]
+        .iter()
+        .map(|&(n, r, p, output_len)| {
+            let password = material.bytes(16);
+            let salt = material.bytes(16);
+            let params = ScryptParams::new(n, r, p).unwrap();
+            let mut output = vec![0u8; output_len];
+            scrypt(&password, &salt, &params, &mut output).unwrap();
+            json!({
+                "password": hex(&password),
+                "salt": hex(&salt),
+                "n": n,
+                "r": r,
+                "p": p,
+                "output": hex(&output),
+            })
+        })
+        .collect()
+}
+
+fn argon2_vectors() -> Vec<Value> {
+    let mut material = Material::new("argon2");
+    let variants = [
+        (Variant::Argon2d, "argon2d"),
+        (Variant::Argon2i, "argon2i"),
+        (Variant::Argon2id, "argon2id"),
+    ];
+    variants
+        .iter()
+        .flat_map(|&variant| [(variant, 32, 3, 4), (variant, 64, 1, 1)])
+        .map(|((variant, name), memory, iterations, lanes)| {
+            let password = material.bytes(32);
+            let salt = material.bytes(16);
+            let params = Argon2Params::new(memory, iterations, lanes).unwrap();
+            let mut output = vec![0u8; 32];
+            argon2(variant, &params, &password, &salt, &mut output).unwrap();
+            json!({
+                "variant": name,
+                "memory": memory,
+                "iterations": iterations,
+                "lanes": lanes,
+                "password": hex(&password),
+                "salt": hex(&salt),
+                "output": hex(&output),
+            })
+        })
+        .collect()
+}
+
+///
+/// bcrypt vectors: the raw 23-byte hash and the `$2b$` string.  Passwords
+/// past 72 bytes show that the rest is ignored.
+///
+fn bcrypt_vectors() -> Vec<Value> {
+    let mut material = Material::new("bcrypt");
+    [(0, 4), (1, 4), (16, 5), (72, 4), (80, 4)]
+        .iter()
+        .map(|&(password_len, cost)| {
+            let password = material.bytes(password_len);
+            let salt = material.array::<16>();
+            let hash = bcrypt(&password, &salt, cost).unwrap();
+            let encoded = armadillo::bcrypt::hash_password(&password, &salt, cost).unwrap();
+            json!({
+                "password": hex(&password),
+                "salt": hex(&salt),
+                "cost": cost,
+                "hash": hex(&hash),
+                "encoded": encoded,
+            })
+        })
+        .collect()
+}
+
+fn main() {
+    let vectors = json!({
+        "generator": "armadillo",
+        "version": env!("CARGO_PKG_VERSION"),
+        "chacha20": stream_cipher_vectors("chacha20", 1, |key, nonce: [u8; 12], plaintext| {
+            ChaCha20::new(key, nonce).encrypt(plaintext)
+        }),
+        "chacha20_stream": chacha20_stream_vectors(),
+        "poly1305": mac_vectors("poly1305", |key, message| {
+            poly1305_mac(key, message).as_ref().to_vec()
+        }),
+        "xchacha20": stream_cipher_vectors("xchacha20", 1, |key, nonce, plaintext| {
+            XChaCha20::new(key, nonce).encrypt(plaintext)
+        }),
+        "chacha12": stream_cipher_vectors("chacha12", 1, |key, nonce: [u8; 12], plaintext| {
+            ChaCha12::new(key, nonce).encrypt(plaintext)
+        }),
+        "chacha8": stream_cipher_vectors("chacha8", 1, |key, nonce: [u8; 12], plaintext| {
+            ChaCha8::new(key, nonce).encrypt(plaintext)
+        }),
+        "chacha20_legacy": stream_cipher_vectors("chacha20-legacy", 0, |key, nonce, plaintext| {
+            ChaCha20Legacy::new(key, nonce).encrypt(plaintext)
+        }),
+        "salsa20": stream_cipher_vectors("salsa20", 0, |key, nonce, plaintext| {
+            Salsa20::new(key, nonce).encrypt(plaintext)
+        }),
+        "xsalsa20": stream_cipher_vectors("xsalsa20", 0, |key, nonce, plaintext| {
+            XSalsa20::new(key, nonce).encrypt(plaintext)
+        }),
+        "aes128_ctr": aes_ctr_vectors::<16>("aes128-ctr"),
+        "aes256_ctr": aes_ctr_vectors::<32>("aes256-ctr"),
+        "aes128_cbc": aes_cbc_vectors::<16>("aes128-cbc"),
+        "aes256_cbc": aes_cbc_vectors::<32>("aes256-cbc"),
+        "aes128_xts": aes_xts_vectors::<16>("aes128-xts"),
+        "aes256_xts": aes_xts_vectors::<32>("aes256-xts"),
+        "aes128_ff1": aes_ff1_vectors::<16>("aes128-ff1"),
+        "aes256_ff1": aes_ff1_vectors::<32>("aes256-ff1"),
+        "chacha20_poly1305": aead_vectors("chacha20-poly1305", 16, |key, nonce: [u8; 12], aad, plaintext| {
+            ChaCha20Poly1305::new(key).seal(nonce, aad, plaintext)
+        }),
+        "aes128_gcm": aead_vectors("aes128-gcm", 16, |key, nonce, aad, plaintext| {
+            Aes128Gcm::new(key).seal(nonce, aad, plaintext)
+        }),
+        "aes256_gcm": aead_vectors("aes256-gcm", 16, |key, nonce, aad, plaintext| {
+            Aes256Gcm::new(key).seal(nonce, aad, plaintext)
+        }),
+        "aes128_ccm_16_12": aead_vectors("aes128-ccm", 16, |key, nonce, aad, plaintext| {
+            Aes128Ccm::<16, 12>::new(key).seal(nonce, aad, plaintext)
+        }),
+        "aegis128l": aead_vectors("aegis128l", 16, |key, nonce, aad, plaintext| {
+            Aegis128L::new(key).seal(nonce, aad, plaintext)
+        }),
+        "aegis256": aead_vectors("aegis256", 16, |key, nonce, aad, plaintext| {
+            Aegis256::new(key).seal(nonce, aad, plaintext)
+        }),
+        "ascon128": aead_vectors("ascon128", 16, |key, nonce, aad, plaintext| {
+            Ascon128::new(key).seal(nonce, aad, plaintext)
+        }),
+        "chacha20_poly1305_siv": aead_vectors("chacha20-poly1305-siv", 16, |key, nonce: [u8; 12], aad, plaintext| {
+            ChaCha20Poly1305Siv::new(key).seal(nonce, aad, plaintext)
+        }),
+        "secretbox": secretbox_vectors(),
+        "openssh_chacha20_poly1305": openssh_chacha20_poly1305_vectors(),
+        "stream": stream_vectors(),
+        "stream_file": stream_file_vectors(),
+        "sha256": hash_vectors("sha256", |message| sha256(message).to_vec()),
+        "sha512": hash_vectors("sha512", |message| sha512(message).to_vec()),
+        "sha3_256": hash_vectors("sha3-256", |message| sha3_256(message).to_vec()),
+        "sha3_512": hash_vectors("sha3-512", |message| sha3_512(message).to_vec()),
+        "shake128_64": hash_vectors("shake128", |message| shake(message, Shake128::new)),
+        "shake256_64": hash_vectors("shake256", |message| shake(message, Shake256::new)),
+        "blake3": hash_vectors("blake3", |message| blake3(message).to_vec()),
+        "ascon_hash": hash_vectors("ascon-hash", |message| ascon_hash(message).to_vec()),
+        "hmac_sha256": mac_vectors("hmac-sha256", |key: [u8; 32], message| {
+            hmac::<Sha256>(&key, message).to_vec()
+        }),
+        "hmac_sha512": mac_vectors("hmac-sha512", |key: [u8; 64], message| {
+            hmac::<Sha512>(&key, message).to_vec()
+        }),
+        "blake3_keyed": mac_vectors("blake3-keyed", |key, message| {
+            blake3_keyed(key, message).to_vec()
+        }),
+        "aes128_cmac": mac_vectors("aes128-cmac", cmac::<16>),
+        "aes256_cmac": mac_vectors("aes256-cmac", cmac::<32>),
+        "siphash24": mac_vectors("siphash24", |key, message| {
+            siphash(&key, message).to_le_bytes().to_vec()
+        }),
+        "poly1305_aes": poly1305_aes_vectors(),
+        "hkdf_sha256": hkdf_vectors::<Sha256>("hkdf-sha256"),
+        "hkdf_sha512": hkdf_vectors::<Sha512>("hkdf-sha512"),
+        "pbkdf2_hmac_sha256": pbkdf2_vectors::<Sha256>("pbkdf2-hmac-sha256"),
+        "pbkdf2_hmac_sha512": pbkdf2_vectors::<Sha512>("pbkdf2-hmac-sha512"),
+        "scrypt": scrypt_vectors(),
+        "argon2": argon2_vectors(),
+        "bcrypt": bcrypt_vectors(),
+    });
+    println!("{}", serde_json::to_string_pretty(&vectors).unwrap());
+}
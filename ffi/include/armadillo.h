@@ -0,0 +1,104 @@
+#ifndef ARMADILLO_H
+#define ARMADILLO_H
+
+/* Generated by cbindgen from ffi/src/lib.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum ArmadilloStatus {
+  ARMADILLO_STATUS_OK = 0,
+  // A required pointer was null.
+  ARMADILLO_STATUS_NULL_POINTER = 1,
+  // The data would run past the last block of the 32-bit counter.
+  ARMADILLO_STATUS_COUNTER_OVERFLOW = 2,
+  // The ciphertext is shorter than its tag.
+  ARMADILLO_STATUS_TOO_SHORT = 3,
+  // The tag did not match the ciphertext and associated data.
+  ARMADILLO_STATUS_INVALID_TAG = 4,
+} ArmadilloStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+//
+// Encrypts (or decrypts) `length` bytes from `input` into `output` with
+// ChaCha20, starting at block `counter`.  `input` and `output` may be the
+// same buffer.
+//
+// # Safety
+//
+// `key` must point to 32 readable bytes and `nonce` to 12.  `input` and
+// `output` must each point to `length` bytes, readable and writable
+// respectively, and must either be the same pointer or not overlap.
+//
+enum ArmadilloStatus armadillo_chacha20_encrypt(const uint8_t *key,
+                                                const uint8_t *nonce,
+                                                uint32_t counter,
+                                                const uint8_t *input,
+                                                uint8_t *output,
+                                                size_t length);
+
+//
+// Computes the Poly1305 tag of `message` under the one-time `key`.
+//
+// # Safety
+//
+// `key` must point to 32 readable bytes, `message` to `length` readable
+// bytes, and `tag` to 16 writable bytes.
+//
+enum ArmadilloStatus armadillo_poly1305_mac(const uint8_t *key,
+                                            const uint8_t *message,
+                                            size_t length,
+                                            uint8_t *tag);
+
+//
+// Encrypts `plaintext` and authenticates it together with `aad` using
+// ChaCha20-Poly1305.  `output` receives the ciphertext followed by the
+// 16-byte tag, `plaintext_length + 16` bytes in total.
+//
+// # Safety
+//
+// `key` must point to 32 readable bytes and `nonce` to 12.  `aad` and
+// `plaintext` must point to as many readable bytes as their lengths say,
+// and `output` to `plaintext_length + 16` writable bytes.
+//
+// Returns `CounterOverflow` without reading or writing anything if the
+// plaintext would run past the last block of the 32-bit counter.
+//
+enum ArmadilloStatus armadillo_aead_seal(const uint8_t *key,
+                                         const uint8_t *nonce,
+                                         const uint8_t *aad,
+                                         size_t aad_length,
+                                         const uint8_t *plaintext,
+                                         size_t plaintext_length,
+                                         uint8_t *output);
+
+//
+// Verifies and decrypts a ciphertext produced by `armadillo_aead_seal`.
+// `output` receives the `ciphertext_length - 16` bytes of plaintext, and
+// is left untouched unless the tag is valid.
+//
+// # Safety
+//
+// `key` must point to 32 readable bytes and `nonce` to 12.  `aad` and
+// `ciphertext` must point to as many readable bytes as their lengths say,
+// and `output` to `ciphertext_length - 16` writable bytes.
+//
+enum ArmadilloStatus armadillo_aead_open(const uint8_t *key,
+                                         const uint8_t *nonce,
+                                         const uint8_t *aad,
+                                         size_t aad_length,
+                                         const uint8_t *ciphertext,
+                                         size_t ciphertext_length,
+                                         uint8_t *output);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* ARMADILLO_H */
@@ -0,0 +1,218 @@
+//!
+//! A C ABI over armadillo, so C and C++ projects can link against it as
+//! `libarmadillo_ffi`.  The matching header is `include/armadillo.h`.
+//!
+//! Every function returns an `ArmadilloStatus`.  Outputs are only written
+//! on success, and pointers may be null wherever the matching length is 0.
+//!
+use std::slice;
+
+use armadillo::aead::{AeadError, ChaCha20Poly1305, TAG_LENGTH};
+use armadillo::chacha::ChaCha20;
+use armadillo::poly::poly1305_mac;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmadilloStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// The data would run past the last block of the 32-bit counter.
+    CounterOverflow = 2,
+    /// The ciphertext is shorter than its tag.
+    TooShort = 3,
+    /// The tag did not match the ciphertext and associated data.
+    InvalidTag = 4,
+}
+
+impl From<AeadError> for ArmadilloStatus {
+    fn from(error: AeadError) -> Self {
+        match error {
+            AeadError::TooShort => ArmadilloStatus::TooShort,
+            AeadError::InvalidTag => ArmadilloStatus::InvalidTag,
+            AeadError::CounterOverflow => ArmadilloStatus::CounterOverflow,
+            // Only the padded API, which is not exported, reports this
+            AeadError::InvalidPadding => ArmadilloStatus::InvalidTag,
+        }
+    }
+}
+
+///
+/// Borrows `length` bytes from C, allowing a null pointer for no bytes.
+///
+unsafe fn input<'a>(pointer: *const u8, length: usize) -> Option<&'a [u8]> {
+    match (pointer.is_null(), length) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(pointer, length)),
+    }
+}
+
+unsafe fn output<'a>(pointer: *mut u8, length: usize) -> Option<&'a mut [u8]> {
+    match (pointer.is_null(), length) {
+        (_, 0) => Some(&mut []),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts_mut(pointer, length)),
+    }
+}
+
+unsafe fn array<'a, const N: usize>(pointer: *const u8) -> Option<&'a [u8; N]> {
+    pointer.cast::<[u8; N]>().as_ref()
+}
+
+///
+/// Encrypts (or decrypts) `length` bytes from `input` into `output` with
+/// ChaCha20, starting at block `counter`.  `input` and `output` may be the
+/// same buffer.
+///
+/// # Safety
+///
+/// `key` must point to 32 readable bytes and `nonce` to 12.  `input` and
+/// `output` must each point to `length` bytes, readable and writable
+/// respectively, and must either be the same pointer or not overlap.
+///
+#[no_mangle]
+pub unsafe extern "C" fn armadillo_chacha20_encrypt(
+    key: *const u8,
+    nonce: *const u8,
+    counter: u32,
+    input: *const u8,
+    output: *mut u8,
+    length: usize,
+) -> ArmadilloStatus {
+    let (Some(key), Some(nonce)) = (array::<32>(key), array::<12>(nonce)) else {
+        return ArmadilloStatus::NullPointer;
+    };
+    if length > 0 && (input.is_null() || output.is_null()) {
+        return ArmadilloStatus::NullPointer;
+    }
+
+    // Check for overflow before anything is written
+    let end = counter as u64 * 64 + length as u64;
+    if end > (1 << 32) * 64 {
+        return ArmadilloStatus::CounterOverflow;
+    }
+
+    if length > 0 && !std::ptr::eq(input, output) {
+        std::ptr::copy_nonoverlapping(input, output, length);
+    }
+    let data = self::output(output, length).unwrap();
+    match ChaCha20::new_with_counter(*key, *nonce, counter).try_apply_keystream(data) {
+        Ok(()) => ArmadilloStatus::Ok,
+        Err(_) => ArmadilloStatus::CounterOverflow,
+    }
+}
+
+///
+/// Computes the Poly1305 tag of `message` under the one-time `key`.
+///
+/// # Safety
+///
+/// `key` must point to 32 readable bytes, `message` to `length` readable
+/// bytes, and `tag` to 16 writable bytes.
+///
+#[no_mangle]
+pub unsafe extern "C" fn armadillo_poly1305_mac(
+    key: *const u8,
+    message: *const u8,
+    length: usize,
+    tag: *mut u8,
+) -> ArmadilloStatus {
+    let (Some(key), Some(message), Some(tag)) =
+        (array::<32>(key), input(message, length), output(tag, 16))
+    else {
+        return ArmadilloStatus::NullPointer;
+    };
+    tag.copy_from_slice(poly1305_mac(*key, message).as_ref());
+    ArmadilloStatus::Ok
+}
+
+///
+/// Encrypts `plaintext` and authenticates it together with `aad` using
+/// ChaCha20-Poly1305.  `output` receives the ciphertext followed by the
+/// 16-byte tag, `plaintext_length + 16` bytes in total.
+///
+/// # Safety
+///
+/// `key` must point to 32 readable bytes and `nonce` to 12.  `aad` and
+/// `plaintext` must point to as many readable bytes as their lengths say,
+/// and `output` to `plaintext_length + 16` writable bytes.
+///
+/// Returns `CounterOverflow` without reading or writing anything if the
+/// plaintext would run past the last block of the 32-bit counter.
+///
+#[no_mangle]
+pub unsafe extern "C" fn armadillo_aead_seal(
+    key: *const u8,
+    nonce: *const u8,
+    aad: *const u8,
+    aad_length: usize,
+    plaintext: *const u8,
+    plaintext_length: usize,
+    output: *mut u8,
+) -> ArmadilloStatus {
+    // Block 0 keys Poly1305, so the plaintext starts at block 1.  Check
+    // before any slices are made from the lengths
+    let Some(output_length) = plaintext_length.checked_add(TAG_LENGTH) else {
+        return ArmadilloStatus::CounterOverflow;
+    };
+    if plaintext_length as u64 > ((1 << 32) - 1) * 64 {
+        return ArmadilloStatus::CounterOverflow;
+    }
+
+    let (Some(key), Some(nonce), Some(aad), Some(plaintext)) = (
+        array::<32>(key),
+        array::<12>(nonce),
+        input(aad, aad_length),
+        input(plaintext, plaintext_length),
+    ) else {
+        return ArmadilloStatus::NullPointer;
+    };
+    let Some(output) = self::output(output, output_length) else {
+        return ArmadilloStatus::NullPointer;
+    };
+
+    output.copy_from_slice(&ChaCha20Poly1305::new(*key).seal(*nonce, aad, plaintext));
+    ArmadilloStatus::Ok
+}
+
+///
+/// Verifies and decrypts a ciphertext produced by `armadillo_aead_seal`.
+/// `output` receives the `ciphertext_length - 16` bytes of plaintext, and
+/// is left untouched unless the tag is valid.
+///
+/// # Safety
+///
+/// `key` must point to 32 readable bytes and `nonce` to 12.  `aad` and
+/// `ciphertext` must point to as many readable bytes as their lengths say,
+/// and `output` to `ciphertext_length - 16` writable bytes.
+///
+#[no_mangle]
+pub unsafe extern "C" fn armadillo_aead_open(
+    key: *const u8,
+    nonce: *const u8,
+    aad: *const u8,
+    aad_length: usize,
+    ciphertext: *const u8,
+    ciphertext_length: usize,
+    output: *mut u8,
+) -> ArmadilloStatus {
+    let (Some(key), Some(nonce), Some(aad), Some(ciphertext)) = (
+        array::<32>(key),
+        array::<12>(nonce),
+        input(aad, aad_length),
+        input(ciphertext, ciphertext_length),
+    ) else {
+        return ArmadilloStatus::NullPointer;
+    };
+    let plaintext = match ChaCha20Poly1305::new(*key).open(*nonce, aad, ciphertext) {
+        Ok(plaintext) => plaintext,
+        Err(error) => return error.into(),
+    };
+    let Some(output) = self::output(output, plaintext.len()) else {
+        return ArmadilloStatus::NullPointer;
+    };
+
+    output.copy_from_slice(&plaintext);
+    ArmadilloStatus::Ok
+}
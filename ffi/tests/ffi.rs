@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod ffi_tests {
+    extern crate armadillo_ffi;
+
+    use std::ptr;
+
+    use hex_literal::hex;
+
+    use armadillo_ffi::{
+        armadillo_aead_open, armadillo_aead_seal, armadillo_chacha20_encrypt,
+        armadillo_poly1305_mac, ArmadilloStatus,
+    };
+
+    const TEST_KEY: [u8; 32] =
+        hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+    const TEST_NONCE: [u8; 12] = hex!("070000004041424344454647");
+    const TEST_AAD: [u8; 12] = hex!("50515253c0c1c2c3c4c5c6c7");
+    const TEST_PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    ///
+    /// Sealing and opening through the C ABI must match the RFC8439 example.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.8.2)
+    ///
+    #[test]
+    fn aead_seal_open_test() {
+        let expected = hex!("d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b61161ae10b594f09e26a7e902ecbd0600691");
+
+        let mut sealed = vec![0u8; TEST_PLAINTEXT.len() + 16];
+        let status = unsafe {
+            armadillo_aead_seal(
+                TEST_KEY.as_ptr(),
+                TEST_NONCE.as_ptr(),
+                TEST_AAD.as_ptr(),
+                TEST_AAD.len(),
+                TEST_PLAINTEXT.as_ptr(),
+                TEST_PLAINTEXT.len(),
+                sealed.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::Ok);
+        assert_eq!(sealed, expected);
+
+        let mut opened = vec![0u8; TEST_PLAINTEXT.len()];
+        let status = unsafe {
+            armadillo_aead_open(
+                TEST_KEY.as_ptr(),
+                TEST_NONCE.as_ptr(),
+                TEST_AAD.as_ptr(),
+                TEST_AAD.len(),
+                sealed.as_ptr(),
+                sealed.len(),
+                opened.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::Ok);
+        assert_eq!(opened, TEST_PLAINTEXT);
+
+        // A forged tag must be rejected without touching the output
+        sealed[0] ^= 1;
+        let mut untouched = vec![0u8; TEST_PLAINTEXT.len()];
+        let status = unsafe {
+            armadillo_aead_open(
+                TEST_KEY.as_ptr(),
+                TEST_NONCE.as_ptr(),
+                ptr::null(),
+                0,
+                sealed.as_ptr(),
+                sealed.len(),
+                untouched.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::InvalidTag);
+        assert!(untouched.iter().all(|&b| b == 0));
+    }
+
+    ///
+    /// Lengths that would overflow the output size or run past the 32-bit
+    /// counter must fail before any slice is made from them, so the
+    /// pointers here are never read through.
+    ///
+    #[test]
+    fn aead_seal_oversized_test() {
+        let mut output = [0u8; 16];
+        for length in [usize::MAX, usize::MAX - 15] {
+            let status = unsafe {
+                armadillo_aead_seal(
+                    TEST_KEY.as_ptr(),
+                    TEST_NONCE.as_ptr(),
+                    ptr::null(),
+                    0,
+                    TEST_PLAINTEXT.as_ptr(),
+                    length,
+                    output.as_mut_ptr(),
+                )
+            };
+            assert_eq!(status, ArmadilloStatus::CounterOverflow);
+        }
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let status = unsafe {
+                armadillo_aead_seal(
+                    TEST_KEY.as_ptr(),
+                    TEST_NONCE.as_ptr(),
+                    ptr::null(),
+                    0,
+                    TEST_PLAINTEXT.as_ptr(),
+                    ((1 << 32) - 1) * 64 + 1,
+                    output.as_mut_ptr(),
+                )
+            };
+            assert_eq!(status, ArmadilloStatus::CounterOverflow);
+        }
+        assert_eq!(output, [0u8; 16]);
+    }
+
+    ///
+    /// Encrypting in place and into a separate buffer must give the same
+    /// ciphertext, and running past the counter must fail before writing.
+    ///
+    #[test]
+    fn chacha20_encrypt_test() {
+        let mut separate = vec![0u8; TEST_PLAINTEXT.len()];
+        let status = unsafe {
+            armadillo_chacha20_encrypt(
+                TEST_KEY.as_ptr(),
+                TEST_NONCE.as_ptr(),
+                1,
+                TEST_PLAINTEXT.as_ptr(),
+                separate.as_mut_ptr(),
+                TEST_PLAINTEXT.len(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::Ok);
+
+        let mut in_place = TEST_PLAINTEXT.to_vec();
+        let status = unsafe {
+            armadillo_chacha20_encrypt(
+                TEST_KEY.as_ptr(),
+                TEST_NONCE.as_ptr(),
+                1,
+                in_place.as_ptr(),
+                in_place.as_mut_ptr(),
+                in_place.len(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::Ok);
+        assert_eq!(in_place, separate);
+        assert_eq!(
+            in_place,
+            &armadillo::chacha::ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(TEST_PLAINTEXT)[..]
+        );
+
+        let mut data = [0u8; 65];
+        let status = unsafe {
+            armadillo_chacha20_encrypt(
+                TEST_KEY.as_ptr(),
+                TEST_NONCE.as_ptr(),
+                u32::MAX,
+                data.as_ptr(),
+                data.as_mut_ptr(),
+                data.len(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::CounterOverflow);
+        assert_eq!(data, [0u8; 65]);
+    }
+
+    ///
+    /// The MAC must match the RFC8439 example, and null pointers must be
+    /// reported instead of dereferenced.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.5.2)
+    ///
+    #[test]
+    fn poly1305_mac_test() {
+        let key = hex!("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
+        let message = b"Cryptographic Forum Research Group";
+
+        let mut tag = [0u8; 16];
+        let status = unsafe {
+            armadillo_poly1305_mac(
+                key.as_ptr(),
+                message.as_ptr(),
+                message.len(),
+                tag.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, ArmadilloStatus::Ok);
+        assert_eq!(tag, hex!("a8061dc1305136c6c22b8baf0c0127a9"));
+
+        let status =
+            unsafe { armadillo_poly1305_mac(key.as_ptr(), ptr::null(), 1, tag.as_mut_ptr()) };
+        assert_eq!(status, ArmadilloStatus::NullPointer);
+    }
+}
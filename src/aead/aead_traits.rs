@@ -0,0 +1,208 @@
+//!
+//! RustCrypto `aead` trait implementations, so `ChaCha20Poly1305`,
+//! `AesGcm` and `AesCcm` can be used wherever a `KeyInit + AeadInPlace`
+//! (or, with `alloc`, `Aead`) type is expected.
+//!
+use ::aead::consts::{U0, U10, U11, U12, U13, U14, U16, U32, U4, U6, U7, U8, U9};
+use ::aead::{AeadCore, AeadInPlace, Error, KeyInit, KeySizeUser};
+
+use super::{AesCcm, AesGcm, ChaCha20Poly1305, Tag, GCM_MAX_PLAINTEXT_LENGTH};
+
+/// Longest plaintext before the block counter, which starts at 1, runs out.
+const MAX_PLAINTEXT_LENGTH: u64 = (u32::MAX as u64) * 64;
+
+impl KeySizeUser for ChaCha20Poly1305 {
+    type KeySize = U32;
+}
+
+impl KeyInit for ChaCha20Poly1305 {
+    fn new(key: &::aead::Key<Self>) -> Self {
+        ChaCha20Poly1305::new(<[u8; 32]>::from(*key))
+    }
+}
+
+impl AeadCore for ChaCha20Poly1305 {
+    type NonceSize = U12;
+    type TagSize = U16;
+    type CiphertextOverhead = U0;
+}
+
+impl AeadInPlace for ChaCha20Poly1305 {
+    ///
+    /// Fails instead of panicking if the plaintext is too long for the
+    /// block counter.
+    ///
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<::aead::Tag<Self>, Error> {
+        if buffer.len() as u64 > MAX_PLAINTEXT_LENGTH {
+            return Err(Error);
+        }
+        let tag = self.seal_in_place_detached(<[u8; 12]>::from(*nonce), associated_data, buffer);
+        Ok(tag.into_bytes().into())
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &::aead::Tag<Self>,
+    ) -> Result<(), Error> {
+        let tag = Tag::from(<[u8; 16]>::from(*tag));
+        self.open_in_place_detached(<[u8; 12]>::from(*nonce), associated_data, buffer, &tag)
+            .map_err(|_| Error)
+    }
+}
+
+impl KeySizeUser for AesGcm<16> {
+    type KeySize = U16;
+}
+
+impl KeyInit for AesGcm<16> {
+    fn new(key: &::aead::Key<Self>) -> Self {
+        AesGcm::new(<[u8; 16]>::from(*key))
+    }
+}
+
+impl KeySizeUser for AesGcm<32> {
+    type KeySize = U32;
+}
+
+impl KeyInit for AesGcm<32> {
+    fn new(key: &::aead::Key<Self>) -> Self {
+        AesGcm::new(<[u8; 32]>::from(*key))
+    }
+}
+
+impl<const KEY_LENGTH: usize> AeadCore for AesGcm<KEY_LENGTH> {
+    type NonceSize = U12;
+    type TagSize = U16;
+    type CiphertextOverhead = U0;
+}
+
+impl<const KEY_LENGTH: usize> AeadInPlace for AesGcm<KEY_LENGTH> {
+    ///
+    /// Fails instead of panicking if the plaintext is too long for the
+    /// block counter.
+    ///
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<::aead::Tag<Self>, Error> {
+        if buffer.len() as u64 > GCM_MAX_PLAINTEXT_LENGTH {
+            return Err(Error);
+        }
+        let tag = self.seal_in_place_detached(<[u8; 12]>::from(*nonce), associated_data, buffer);
+        Ok(tag.into_bytes().into())
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &::aead::Tag<Self>,
+    ) -> Result<(), Error> {
+        let tag = Tag::from(<[u8; 16]>::from(*tag));
+        self.open_in_place_detached(<[u8; 12]>::from(*nonce), associated_data, buffer, &tag)
+            .map_err(|_| Error)
+    }
+}
+
+impl<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> KeySizeUser
+    for AesCcm<16, TAG_LENGTH, NONCE_LENGTH>
+{
+    type KeySize = U16;
+}
+
+impl<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> KeyInit
+    for AesCcm<16, TAG_LENGTH, NONCE_LENGTH>
+{
+    fn new(key: &::aead::Key<Self>) -> Self {
+        AesCcm::new(<[u8; 16]>::from(*key))
+    }
+}
+
+impl<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> KeySizeUser
+    for AesCcm<32, TAG_LENGTH, NONCE_LENGTH>
+{
+    type KeySize = U32;
+}
+
+impl<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> KeyInit
+    for AesCcm<32, TAG_LENGTH, NONCE_LENGTH>
+{
+    fn new(key: &::aead::Key<Self>) -> Self {
+        AesCcm::new(<[u8; 32]>::from(*key))
+    }
+}
+
+///
+/// `AeadCore` for every valid tag length with one nonce length, as the
+/// trait needs the lengths as types.
+///
+macro_rules! impl_ccm_aead_core {
+    ($nonce_size:ty, $nonce_length:literal) => {
+        impl_ccm_aead_core!($nonce_size, $nonce_length, U4 4, U6 6, U8 8, U10 10, U12 12, U14 14, U16 16);
+    };
+    ($nonce_size:ty, $nonce_length:literal, $($tag_size:ident $tag_length:literal),*) => {
+        $(
+            impl<const KEY_LENGTH: usize> AeadCore for AesCcm<KEY_LENGTH, $tag_length, $nonce_length> {
+                type NonceSize = $nonce_size;
+                type TagSize = $tag_size;
+                type CiphertextOverhead = U0;
+            }
+        )*
+    };
+}
+
+impl_ccm_aead_core!(U7, 7);
+impl_ccm_aead_core!(U8, 8);
+impl_ccm_aead_core!(U9, 9);
+impl_ccm_aead_core!(U10, 10);
+impl_ccm_aead_core!(U11, 11);
+impl_ccm_aead_core!(U12, 12);
+impl_ccm_aead_core!(U13, 13);
+
+impl<const KEY_LENGTH: usize, const TAG_LENGTH: usize, const NONCE_LENGTH: usize> AeadInPlace
+    for AesCcm<KEY_LENGTH, TAG_LENGTH, NONCE_LENGTH>
+where
+    Self: AeadCore,
+{
+    ///
+    /// Fails instead of panicking if the plaintext is too long for the
+    /// length field.
+    ///
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<::aead::Tag<Self>, Error> {
+        if buffer.len() as u64 > Self::MAX_PLAINTEXT_LENGTH {
+            return Err(Error);
+        }
+        let nonce = nonce.as_slice().try_into().map_err(|_| Error)?;
+        let tag = self.seal_in_place_detached(nonce, associated_data, buffer);
+        Ok(::aead::Tag::<Self>::clone_from_slice(&tag))
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &::aead::Tag<Self>,
+    ) -> Result<(), Error> {
+        let nonce = nonce.as_slice().try_into().map_err(|_| Error)?;
+        let tag = tag.as_slice().try_into().map_err(|_| Error)?;
+        self.open_in_place_detached(nonce, associated_data, buffer, &tag)
+            .map_err(|_| Error)
+    }
+}
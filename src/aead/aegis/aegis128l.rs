@@ -0,0 +1,290 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+#[cfg(feature = "alloc")]
+use crate::aead::TAG_LENGTH;
+use crate::aead::{AeadError, Tag};
+use crate::aes::aes_rounds;
+
+/// Length of the longer tag AEGIS offers, which the draft recommends.
+pub const TAG_256_LENGTH: usize = 32;
+
+/// The first 16 bytes of the Fibonacci sequence modulo 256.
+pub(super) const C0: u128 = u128::from_le_bytes([
+    0x00, 0x01, 0x01, 0x02, 0x03, 0x05, 0x08, 0x0d, 0x15, 0x22, 0x37, 0x59, 0x90, 0xe9, 0x79, 0x62,
+]);
+
+/// The next 16 bytes of the Fibonacci sequence modulo 256.
+pub(super) const C1: u128 = u128::from_le_bytes([
+    0xdb, 0x3d, 0x18, 0x55, 0x6d, 0xc2, 0x2f, 0xf1, 0x20, 0x11, 0x31, 0x42, 0x73, 0xb5, 0x28, 0xdd,
+]);
+
+/// Bytes absorbed or encrypted per update.
+const RATE: usize = 32;
+
+///
+/// The AEGIS-128L AEAD, with a 16-byte key and a 16-byte nonce.
+///
+/// The state is eight AES blocks, each updated with one AES round of its
+/// neighbour per 32 bytes of input, which is why AEGIS is so fast on CPUs
+/// with AES instructions.  The keystream is taken from the state, and the
+/// plaintext is then absorbed into it, so the tag covers the plaintext
+/// without a separate MAC.
+///
+/// A nonce must never be used twice with the same key.  A failed `open`
+/// does not reveal the plaintext.
+///
+/// [Source](https://datatracker.ietf.org/doc/draft-irtf-cfrg-aegis-aead/)
+///
+pub struct Aegis128L {
+    key: [u8; 16],
+}
+
+struct State([u128; 8]);
+
+impl State {
+    fn new(key: &[u8; 16], nonce: &[u8; 16]) -> Self {
+        let key = u128::from_le_bytes(*key);
+        let nonce = u128::from_le_bytes(*nonce);
+        let mut state = State([
+            key ^ nonce,
+            C1,
+            C0,
+            C1,
+            key ^ nonce,
+            key ^ C0,
+            key ^ C1,
+            key ^ C0,
+        ]);
+        for _ in 0..10 {
+            state.update(nonce, key);
+        }
+        state
+    }
+
+    fn update(&mut self, m0: u128, m1: u128) {
+        let s = &self.0;
+        self.0 = aes_rounds(
+            [s[7], s[0], s[1], s[2], s[3], s[4], s[5], s[6]],
+            [s[0] ^ m0, s[1], s[2], s[3], s[4] ^ m1, s[5], s[6], s[7]],
+        );
+    }
+
+    fn keystream(&self) -> [u8; RATE] {
+        let s = &self.0;
+        let z0 = s[6] ^ s[1] ^ (s[2] & s[3]);
+        let z1 = s[2] ^ s[5] ^ (s[6] & s[7]);
+        let mut keystream = [0u8; RATE];
+        keystream[..16].copy_from_slice(&z0.to_le_bytes());
+        keystream[16..].copy_from_slice(&z1.to_le_bytes());
+        keystream
+    }
+
+    fn absorb_block(&mut self, block: &[u8; RATE]) {
+        let (m0, m1) = split(block);
+        self.update(m0, m1);
+    }
+
+    ///
+    /// Absorbs `data`, the last block zero-padded.
+    ///
+    fn absorb(&mut self, data: &[u8]) {
+        let mut blocks = data.chunks_exact(RATE);
+        for block in &mut blocks {
+            self.absorb_block(block.try_into().unwrap());
+        }
+        let remainder = blocks.remainder();
+        if !remainder.is_empty() {
+            let mut block = [0u8; RATE];
+            block[..remainder.len()].copy_from_slice(remainder);
+            self.absorb_block(&block);
+        }
+    }
+
+    fn encrypt(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(RATE) {
+            // The last block is encrypted zero-padded, and truncated
+            let mut block = [0u8; RATE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            let keystream = self.keystream();
+            self.absorb_block(&block);
+            for (byte, key) in chunk.iter_mut().zip(keystream) {
+                *byte ^= key;
+            }
+            block.zeroize();
+        }
+    }
+
+    fn decrypt(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(RATE) {
+            // The plaintext of the last block is absorbed zero-padded
+            let mut block = [0u8; RATE];
+            for ((byte, key), plain) in chunk.iter_mut().zip(self.keystream()).zip(&mut block) {
+                *byte ^= key;
+                *plain = *byte;
+            }
+            self.absorb_block(&block);
+            block.zeroize();
+        }
+    }
+
+    fn finalize(&mut self, aad_length: usize, message_length: usize) {
+        let lengths = ((aad_length as u128) << 3) | ((message_length as u128) << 67);
+        let t = self.0[2] ^ lengths;
+        for _ in 0..7 {
+            self.update(t, t);
+        }
+    }
+
+    fn tag(&self) -> Tag {
+        let tag = self.0[..7].iter().fold(0, |acc, s| acc ^ s);
+        Tag::from(tag.to_le_bytes())
+    }
+
+    fn tag_256(&self) -> [u8; TAG_256_LENGTH] {
+        let s = &self.0;
+        let mut tag = [0u8; TAG_256_LENGTH];
+        tag[..16].copy_from_slice(&(s[0] ^ s[1] ^ s[2] ^ s[3]).to_le_bytes());
+        tag[16..].copy_from_slice(&(s[4] ^ s[5] ^ s[6] ^ s[7]).to_le_bytes());
+        tag
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+fn split(block: &[u8; RATE]) -> (u128, u128) {
+    (
+        u128::from_le_bytes(block[..16].try_into().unwrap()),
+        u128::from_le_bytes(block[16..].try_into().unwrap()),
+    )
+}
+
+impl Aegis128L {
+    pub fn new(key: [u8; 16]) -> Self {
+        Aegis128L { key }
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the 16-byte tag appended.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: [u8; 16], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        let tag = self.seal_in_place_detached(nonce, aad, &mut output);
+        output.extend_from_slice(tag.as_ref());
+        output
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: [u8; 16],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let tag = Tag::try_from(tag).map_err(|_| AeadError::TooShort)?;
+
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, &tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the 16-byte tag, without
+    /// allocating.
+    ///
+    pub fn seal_in_place_detached(&self, nonce: [u8; 16], aad: &[u8], buffer: &mut [u8]) -> Tag {
+        self.seal_state(&nonce, aad, buffer).tag()
+    }
+
+    ///
+    /// Decrypts `buffer` in place and verifies the 16-byte `tag`, without
+    /// allocating.  The tag covers the plaintext, so it can only be checked
+    /// after decryption; if it is invalid, `buffer` is zeroed.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: [u8; 16],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), AeadError> {
+        // Constant-time tag comparison
+        let expected = self.open_state(&nonce, aad, buffer).tag();
+        if !expected.verify(tag) {
+            buffer.zeroize();
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Like `seal_in_place_detached`, but with a 32-byte tag.
+    ///
+    pub fn seal_in_place_detached_256(
+        &self,
+        nonce: [u8; 16],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> [u8; TAG_256_LENGTH] {
+        self.seal_state(&nonce, aad, buffer).tag_256()
+    }
+
+    ///
+    /// Like `open_in_place_detached`, but with a 32-byte tag.
+    ///
+    pub fn open_in_place_detached_256(
+        &self,
+        nonce: [u8; 16],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_256_LENGTH],
+    ) -> Result<(), AeadError> {
+        // Constant-time tag comparison
+        let expected = self.open_state(&nonce, aad, buffer).tag_256();
+        if !bool::from(expected.ct_eq(tag)) {
+            buffer.zeroize();
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    fn seal_state(&self, nonce: &[u8; 16], aad: &[u8], buffer: &mut [u8]) -> State {
+        let mut state = State::new(&self.key, nonce);
+        state.absorb(aad);
+        state.encrypt(buffer);
+        state.finalize(aad.len(), buffer.len());
+        state
+    }
+
+    fn open_state(&self, nonce: &[u8; 16], aad: &[u8], buffer: &mut [u8]) -> State {
+        let mut state = State::new(&self.key, nonce);
+        state.absorb(aad);
+        state.decrypt(buffer);
+        state.finalize(aad.len(), buffer.len());
+        state
+    }
+}
+
+impl Drop for Aegis128L {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
@@ -0,0 +1,265 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+#[cfg(feature = "alloc")]
+use crate::aead::TAG_LENGTH;
+use crate::aead::{AeadError, Tag};
+use crate::aes::aes_rounds;
+
+use super::aegis128l::{C0, C1, TAG_256_LENGTH};
+
+/// Bytes absorbed or encrypted per update.
+const RATE: usize = 16;
+
+///
+/// The AEGIS-256 AEAD, with a 32-byte key and a 32-byte nonce.
+///
+/// The state is six AES blocks, updated per 16 bytes of input.  It is
+/// slower than AEGIS-128L, but its nonces are long enough to be picked at
+/// random.
+///
+/// A nonce must never be used twice with the same key.  A failed `open`
+/// does not reveal the plaintext.
+///
+/// [Source](https://datatracker.ietf.org/doc/draft-irtf-cfrg-aegis-aead/)
+///
+pub struct Aegis256 {
+    key: [u8; 32],
+}
+
+struct State([u128; 6]);
+
+impl State {
+    fn new(key: &[u8; 32], nonce: &[u8; 32]) -> Self {
+        let (k0, k1) = split(key);
+        let (n0, n1) = split(nonce);
+        let mut state = State([k0 ^ n0, k1 ^ n1, C1, C0, k0 ^ C0, k1 ^ C1]);
+        for _ in 0..4 {
+            state.update(k0);
+            state.update(k1);
+            state.update(k0 ^ n0);
+            state.update(k1 ^ n1);
+        }
+        state
+    }
+
+    fn update(&mut self, m: u128) {
+        let s = &self.0;
+        self.0 = aes_rounds(
+            [s[5], s[0], s[1], s[2], s[3], s[4]],
+            [s[0] ^ m, s[1], s[2], s[3], s[4], s[5]],
+        );
+    }
+
+    fn keystream(&self) -> [u8; RATE] {
+        let s = &self.0;
+        (s[1] ^ s[4] ^ s[5] ^ (s[2] & s[3])).to_le_bytes()
+    }
+
+    fn absorb_block(&mut self, block: &[u8; RATE]) {
+        self.update(u128::from_le_bytes(*block));
+    }
+
+    ///
+    /// Absorbs `data`, the last block zero-padded.
+    ///
+    fn absorb(&mut self, data: &[u8]) {
+        let mut blocks = data.chunks_exact(RATE);
+        for block in &mut blocks {
+            self.absorb_block(block.try_into().unwrap());
+        }
+        let remainder = blocks.remainder();
+        if !remainder.is_empty() {
+            let mut block = [0u8; RATE];
+            block[..remainder.len()].copy_from_slice(remainder);
+            self.absorb_block(&block);
+        }
+    }
+
+    fn encrypt(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(RATE) {
+            // The last block is encrypted zero-padded, and truncated
+            let mut block = [0u8; RATE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            let keystream = self.keystream();
+            self.absorb_block(&block);
+            for (byte, key) in chunk.iter_mut().zip(keystream) {
+                *byte ^= key;
+            }
+            block.zeroize();
+        }
+    }
+
+    fn decrypt(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(RATE) {
+            // The plaintext of the last block is absorbed zero-padded
+            let mut block = [0u8; RATE];
+            for ((byte, key), plain) in chunk.iter_mut().zip(self.keystream()).zip(&mut block) {
+                *byte ^= key;
+                *plain = *byte;
+            }
+            self.absorb_block(&block);
+            block.zeroize();
+        }
+    }
+
+    fn finalize(&mut self, aad_length: usize, message_length: usize) {
+        let lengths = ((aad_length as u128) << 3) | ((message_length as u128) << 67);
+        let t = self.0[3] ^ lengths;
+        for _ in 0..7 {
+            self.update(t);
+        }
+    }
+
+    fn tag(&self) -> Tag {
+        let tag = self.0.iter().fold(0, |acc, s| acc ^ s);
+        Tag::from(tag.to_le_bytes())
+    }
+
+    fn tag_256(&self) -> [u8; TAG_256_LENGTH] {
+        let s = &self.0;
+        let mut tag = [0u8; TAG_256_LENGTH];
+        tag[..16].copy_from_slice(&(s[0] ^ s[1] ^ s[2]).to_le_bytes());
+        tag[16..].copy_from_slice(&(s[3] ^ s[4] ^ s[5]).to_le_bytes());
+        tag
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+fn split(bytes: &[u8; 32]) -> (u128, u128) {
+    (
+        u128::from_le_bytes(bytes[..16].try_into().unwrap()),
+        u128::from_le_bytes(bytes[16..].try_into().unwrap()),
+    )
+}
+
+impl Aegis256 {
+    pub fn new(key: [u8; 32]) -> Self {
+        Aegis256 { key }
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the 16-byte tag appended.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: [u8; 32], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        let tag = self.seal_in_place_detached(nonce, aad, &mut output);
+        output.extend_from_slice(tag.as_ref());
+        output
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: [u8; 32],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let tag = Tag::try_from(tag).map_err(|_| AeadError::TooShort)?;
+
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, &tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the 16-byte tag, without
+    /// allocating.
+    ///
+    pub fn seal_in_place_detached(&self, nonce: [u8; 32], aad: &[u8], buffer: &mut [u8]) -> Tag {
+        self.seal_state(&nonce, aad, buffer).tag()
+    }
+
+    ///
+    /// Decrypts `buffer` in place and verifies the 16-byte `tag`, without
+    /// allocating.  The tag covers the plaintext, so it can only be checked
+    /// after decryption; if it is invalid, `buffer` is zeroed.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: [u8; 32],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), AeadError> {
+        // Constant-time tag comparison
+        let expected = self.open_state(&nonce, aad, buffer).tag();
+        if !expected.verify(tag) {
+            buffer.zeroize();
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Like `seal_in_place_detached`, but with a 32-byte tag.
+    ///
+    pub fn seal_in_place_detached_256(
+        &self,
+        nonce: [u8; 32],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> [u8; TAG_256_LENGTH] {
+        self.seal_state(&nonce, aad, buffer).tag_256()
+    }
+
+    ///
+    /// Like `open_in_place_detached`, but with a 32-byte tag.
+    ///
+    pub fn open_in_place_detached_256(
+        &self,
+        nonce: [u8; 32],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_256_LENGTH],
+    ) -> Result<(), AeadError> {
+        // Constant-time tag comparison
+        let expected = self.open_state(&nonce, aad, buffer).tag_256();
+        if !bool::from(expected.ct_eq(tag)) {
+            buffer.zeroize();
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    fn seal_state(&self, nonce: &[u8; 32], aad: &[u8], buffer: &mut [u8]) -> State {
+        let mut state = State::new(&self.key, nonce);
+        state.absorb(aad);
+        state.encrypt(buffer);
+        state.finalize(aad.len(), buffer.len());
+        state
+    }
+
+    fn open_state(&self, nonce: &[u8; 32], aad: &[u8], buffer: &mut [u8]) -> State {
+        let mut state = State::new(&self.key, nonce);
+        state.absorb(aad);
+        state.decrypt(buffer);
+        state.finalize(aad.len(), buffer.len());
+        state
+    }
+}
+
+impl Drop for Aegis256 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
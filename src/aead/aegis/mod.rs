@@ -0,0 +1,5 @@
+mod aegis128l;
+mod aegis256;
+
+pub use aegis128l::*;
+pub use aegis256::*;
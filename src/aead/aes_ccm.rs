@@ -0,0 +1,262 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use crate::aes::{Aes, Block, BLOCK_LENGTH};
+use crate::modes::Ctr;
+
+use super::AeadError;
+
+///
+/// The AES-CCM AEAD construction, with a key of `KEY_LENGTH` bytes, tags of
+/// `TAG_LENGTH` bytes and nonces of `NONCE_LENGTH` bytes.  Tags are 4 to 16
+/// bytes long and even, nonces 7 to 13 bytes long; other lengths fail to
+/// compile.  Bluetooth LE and Zigbee use 13-byte nonces with 4-byte and
+/// 4-, 8- or 16-byte tags respectively.
+///
+/// The tag is a CBC-MAC over a block encoding the lengths and nonce, the
+/// length-prefixed associated data and the plaintext, each zero-padded to
+/// a multiple of 16 bytes.  The plaintext is encrypted with AES in counter
+/// mode from counter 1, and the tag with counter 0.  The counter takes the
+/// `15 - NONCE_LENGTH` bytes the nonce leaves free, which also bound the
+/// message length: 64 KiB with 13-byte nonces.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc3610)
+///
+pub struct AesCcm<const KEY_LENGTH: usize, const TAG_LENGTH: usize, const NONCE_LENGTH: usize> {
+    aes: Aes<KEY_LENGTH>,
+}
+
+pub type Aes128Ccm<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> =
+    AesCcm<16, TAG_LENGTH, NONCE_LENGTH>;
+pub type Aes256Ccm<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> =
+    AesCcm<32, TAG_LENGTH, NONCE_LENGTH>;
+
+impl<const KEY_LENGTH: usize, const TAG_LENGTH: usize, const NONCE_LENGTH: usize>
+    AesCcm<KEY_LENGTH, TAG_LENGTH, NONCE_LENGTH>
+{
+    /// Bytes of the length field and the counter.
+    const COUNTER_LENGTH: usize = 15 - NONCE_LENGTH;
+
+    /// Longest plaintext the length field can encode.
+    pub(super) const MAX_PLAINTEXT_LENGTH: u64 = if Self::COUNTER_LENGTH >= 8 {
+        u64::MAX
+    } else {
+        (1 << (8 * Self::COUNTER_LENGTH)) - 1
+    };
+
+    const VALID_PARAMETERS: () = {
+        assert!(
+            TAG_LENGTH >= 4 && TAG_LENGTH <= 16 && TAG_LENGTH % 2 == 0,
+            "CCM tags are 4, 6, 8, 10, 12, 14 or 16 bytes long"
+        );
+        assert!(
+            NONCE_LENGTH >= 7 && NONCE_LENGTH <= 13,
+            "CCM nonces are 7 to 13 bytes long"
+        );
+    };
+
+    pub fn new(key: [u8; KEY_LENGTH]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_PARAMETERS;
+        AesCcm { aes: Aes::new(key) }
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the tag appended.
+    ///
+    /// A nonce must never be used twice with the same key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plaintext is too long for the length field.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: [u8; NONCE_LENGTH], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        let tag = self.seal_in_place_detached(nonce, aad, &mut output);
+        output.extend_from_slice(&tag);
+        output
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: [u8; NONCE_LENGTH],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let tag = tag.try_into().map_err(|_| AeadError::TooShort)?;
+
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, &tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the tag, without allocating.
+    ///
+    pub fn seal_in_place_detached(
+        &self,
+        nonce: [u8; NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> [u8; TAG_LENGTH] {
+        assert!(
+            buffer.len() as u64 <= Self::MAX_PLAINTEXT_LENGTH,
+            "plaintext too long for the CCM nonce length"
+        );
+        let tag = self.tag(&nonce, aad, buffer);
+        self.apply_keystream(&nonce, buffer);
+        tag
+    }
+
+    ///
+    /// Decrypts `buffer` in place and verifies `tag`, without allocating.
+    /// The tag covers the plaintext, so it can only be checked after
+    /// decryption; if it is invalid, `buffer` is zeroed.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: [u8; NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_LENGTH],
+    ) -> Result<(), AeadError> {
+        // Longer ciphertexts cannot have been sealed with this nonce length
+        if buffer.len() as u64 > Self::MAX_PLAINTEXT_LENGTH {
+            return Err(AeadError::InvalidTag);
+        }
+
+        self.apply_keystream(&nonce, buffer);
+        let expected = self.tag(&nonce, aad, buffer);
+        if !bool::from(expected.ct_eq(tag)) {
+            buffer.zeroize();
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    ///
+    /// XORs `buffer` with the encrypted counter blocks from counter 1.
+    ///
+    fn apply_keystream(&self, nonce: &[u8; NONCE_LENGTH], buffer: &mut [u8]) {
+        // The counter cannot carry into the nonce below the length limit
+        Ctr::new(&self.aes, Self::counter_block(nonce, 1)).apply_keystream(buffer);
+    }
+
+    ///
+    /// Computes the CBC-MAC of the first block, the encoded associated data
+    /// and the plaintext, truncated and encrypted with counter 0.
+    ///
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc3610#section-2.2)
+    ///
+    fn tag(&self, nonce: &[u8; NONCE_LENGTH], aad: &[u8], plaintext: &[u8]) -> [u8; TAG_LENGTH] {
+        // Flags: whether there is associated data, the tag length and the
+        // length field size, then the nonce and the plaintext length
+        let mut first = [0u8; BLOCK_LENGTH];
+        first[0] = (u8::from(!aad.is_empty()) << 6)
+            | (((TAG_LENGTH - 2) / 2) as u8) << 3
+            | (Self::COUNTER_LENGTH - 1) as u8;
+        first[1..=NONCE_LENGTH].copy_from_slice(nonce);
+        let length = (plaintext.len() as u64).to_be_bytes();
+        first[NONCE_LENGTH + 1..].copy_from_slice(&length[8 - Self::COUNTER_LENGTH.min(8)..]);
+
+        let mut mac = CbcMac::new(&self.aes);
+        mac.update(&first);
+        if !aad.is_empty() {
+            let length = aad.len() as u64;
+            if length < 0xff00 {
+                mac.update(&(length as u16).to_be_bytes());
+            } else if length <= u32::MAX as u64 {
+                mac.update(&[0xff, 0xfe]);
+                mac.update(&(length as u32).to_be_bytes());
+            } else {
+                mac.update(&[0xff, 0xff]);
+                mac.update(&length.to_be_bytes());
+            }
+            mac.update(aad);
+            mac.pad();
+        }
+        mac.update(plaintext);
+        mac.pad();
+
+        let mut mask = Self::counter_block(nonce, 0);
+        self.aes.encrypt_block(&mut mask);
+        let mut tag = [0u8; TAG_LENGTH];
+        for ((byte, mac), mask) in tag.iter_mut().zip(mac.state).zip(mask) {
+            *byte = mac ^ mask;
+        }
+        tag
+    }
+
+    fn counter_block(nonce: &[u8; NONCE_LENGTH], counter: u64) -> Block {
+        let mut block = [0u8; BLOCK_LENGTH];
+        block[0] = (Self::COUNTER_LENGTH - 1) as u8;
+        block[1..=NONCE_LENGTH].copy_from_slice(nonce);
+        let counter = counter.to_be_bytes();
+        block[NONCE_LENGTH + 1..].copy_from_slice(&counter[8 - Self::COUNTER_LENGTH.min(8)..]);
+        block
+    }
+}
+
+///
+/// CBC-MAC with a zero IV, absorbing the message as it arrives by XORing
+/// it straight into the chaining value.
+///
+struct CbcMac<'a, const KEY_LENGTH: usize> {
+    aes: &'a Aes<KEY_LENGTH>,
+    state: Block,
+    // Bytes XORed into the state since its last encryption
+    position: usize,
+}
+
+impl<'a, const KEY_LENGTH: usize> CbcMac<'a, KEY_LENGTH> {
+    fn new(aes: &'a Aes<KEY_LENGTH>) -> Self {
+        CbcMac {
+            aes,
+            state: [0; BLOCK_LENGTH],
+            position: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state[self.position] ^= byte;
+            self.position += 1;
+            if self.position == BLOCK_LENGTH {
+                self.aes.encrypt_block(&mut self.state);
+                self.position = 0;
+            }
+        }
+    }
+
+    ///
+    /// Ends a zero-padded field, as if zeros were added up to the block
+    /// boundary.
+    ///
+    fn pad(&mut self) {
+        if self.position > 0 {
+            self.aes.encrypt_block(&mut self.state);
+            self.position = 0;
+        }
+    }
+}
+
+impl<const KEY_LENGTH: usize> Drop for CbcMac<'_, KEY_LENGTH> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
@@ -0,0 +1,238 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::aes::{Aes, Block, BLOCK_LENGTH};
+use crate::ghash::GHash;
+use crate::modes::Ctr;
+
+use super::{AeadError, Tag, TAG_LENGTH};
+
+pub const GCM_NONCE_LENGTH: usize = 12;
+
+/// Longest plaintext before the 32-bit block counter, which starts at 2,
+/// wraps around into the block that encrypts the tag.
+pub(super) const GCM_MAX_PLAINTEXT_LENGTH: u64 = ((1 << 32) - 2) * BLOCK_LENGTH as u64;
+
+///
+/// The AES-GCM AEAD construction, with a key of `KEY_LENGTH` bytes and
+/// 12-byte nonces.
+///
+/// The plaintext is encrypted with AES in counter mode, from counter block
+/// `nonce || 2`.  The tag is the GHASH, keyed with the encryption of the
+/// zero block, of the associated data and the ciphertext, each zero-padded
+/// to a multiple of 16 bytes, followed by both of their lengths in bits as
+/// 64-bit big-endian integers; it is then encrypted with counter block
+/// `nonce || 1`.
+///
+/// Repeating a nonce under the same key reveals the GHASH key and with it
+/// the ability to forge messages, so random nonces should only be used for
+/// well below 2^32 messages per key.
+///
+/// [Source](https://csrc.nist.gov/pubs/sp/800/38/d/final)
+///
+pub struct AesGcm<const KEY_LENGTH: usize> {
+    aes: Aes<KEY_LENGTH>,
+    h: Block,
+}
+
+pub type Aes128Gcm = AesGcm<16>;
+pub type Aes256Gcm = AesGcm<32>;
+
+impl<const KEY_LENGTH: usize> AesGcm<KEY_LENGTH> {
+    pub fn new(key: [u8; KEY_LENGTH]) -> Self {
+        let aes = Aes::new(key);
+        let mut h = [0u8; BLOCK_LENGTH];
+        aes.encrypt_block(&mut h);
+        AesGcm { aes, h }
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the 16-byte tag appended.
+    ///
+    /// A nonce must never be used twice with the same key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plaintext is longer than about 64 GiB, where the block
+    /// counter runs out.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: [u8; GCM_NONCE_LENGTH], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        self.seal_in_place_append_tag(nonce, aad, &mut output);
+        output
+    }
+
+    ///
+    /// Like `seal`, but returns the tag separately from the ciphertext.  The
+    /// ciphertext is as long as the plaintext.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal_detached(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> (Vec<u8>, Tag) {
+        let mut ciphertext = plaintext.to_vec();
+        let tag = self.seal_in_place_detached(nonce, aad, &mut ciphertext);
+        (ciphertext, tag)
+    }
+
+    ///
+    /// Like `seal`, but encrypts `buffer` in place and appends the tag to
+    /// it, so no second buffer is allocated.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal_in_place_append_tag(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &mut Vec<u8>,
+    ) {
+        let tag = self.seal_in_place_detached(nonce, aad, buffer);
+        buffer.extend_from_slice(tag.as_ref());
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the tag, without allocating.
+    ///
+    pub fn seal_in_place_detached(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Tag {
+        self.apply_keystream(&nonce, buffer);
+        self.tag(&nonce, aad, buffer)
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        let mut buffer = ciphertext.to_vec();
+        let length = self.open_in_place(nonce, aad, &mut buffer)?.len();
+        buffer.truncate(length);
+        Ok(buffer)
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext and tag produced by
+    /// `seal_detached`.  The plaintext is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open_detached(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &Tag,
+    ) -> Result<Vec<u8>, AeadError> {
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Verifies and decrypts, in place, a ciphertext with its tag appended
+    /// as `seal` produces it.  Returns the part of `buffer` holding the
+    /// plaintext.  If the tag is invalid, `buffer` is left untouched.
+    ///
+    pub fn open_in_place<'a>(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &'a mut [u8],
+    ) -> Result<&'a mut [u8], AeadError> {
+        if buffer.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = buffer.split_at_mut(buffer.len() - TAG_LENGTH);
+        let tag = Tag::try_from(&tag[..]).map_err(|_| AeadError::TooShort)?;
+        self.open_in_place_detached(nonce, aad, ciphertext, &tag)?;
+        Ok(ciphertext)
+    }
+
+    ///
+    /// Verifies `tag` and decrypts `buffer` in place, without allocating.
+    /// If the tag is invalid, `buffer` is left untouched.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), AeadError> {
+        // Constant-time tag comparison
+        let expected = self.tag(&nonce, aad, buffer);
+        if !expected.verify(tag) {
+            return Err(AeadError::InvalidTag);
+        }
+
+        self.apply_keystream(&nonce, buffer);
+        Ok(())
+    }
+
+    ///
+    /// XORs `buffer` with the encrypted counter blocks from `nonce || 2`.
+    ///
+    fn apply_keystream(&self, nonce: &[u8; GCM_NONCE_LENGTH], buffer: &mut [u8]) {
+        assert!(
+            buffer.len() as u64 <= GCM_MAX_PLAINTEXT_LENGTH,
+            "plaintext too long for AES-GCM"
+        );
+
+        // GCM only increments the last 32 bits, but they cannot wrap below
+        // the length limit, so a full 128-bit counter gives the same blocks
+        Ctr::new(&self.aes, counter_block(nonce, 2)).apply_keystream(buffer);
+    }
+
+    ///
+    /// Computes the GHASH of the padded associated data, the padded
+    /// ciphertext and their lengths in bits, encrypted with counter block
+    /// `nonce || 1`.
+    ///
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/38/d/final) (Section 7.1)
+    ///
+    fn tag(&self, nonce: &[u8; GCM_NONCE_LENGTH], aad: &[u8], ciphertext: &[u8]) -> Tag {
+        let mut hash = GHash::new(self.h);
+        hash.update_padded(aad);
+        hash.update_padded(ciphertext);
+        hash.update(&(aad.len() as u64 * 8).to_be_bytes());
+        hash.update(&(ciphertext.len() as u64 * 8).to_be_bytes());
+        let mut tag = hash.finalize();
+
+        let mut mask = counter_block(nonce, 1);
+        self.aes.encrypt_block(&mut mask);
+        for (byte, mask) in tag.iter_mut().zip(mask) {
+            *byte ^= mask;
+        }
+        Tag::from(tag)
+    }
+}
+
+impl<const KEY_LENGTH: usize> Drop for AesGcm<KEY_LENGTH> {
+    fn drop(&mut self) {
+        self.h.zeroize();
+    }
+}
+
+fn counter_block(nonce: &[u8; GCM_NONCE_LENGTH], counter: u32) -> Block {
+    let mut block = [0u8; BLOCK_LENGTH];
+    block[..GCM_NONCE_LENGTH].copy_from_slice(nonce);
+    block[GCM_NONCE_LENGTH..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
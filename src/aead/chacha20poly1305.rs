@@ -0,0 +1,243 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::chacha::{ChaCha20, Key, Nonce};
+#[cfg(feature = "alloc")]
+use crate::padding::{padme_pad, padme_unpad};
+use crate::poly::{poly1305_key_gen, Poly1305};
+
+pub use crate::poly::Tag;
+
+pub const TAG_LENGTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AeadError {
+    /// The ciphertext is shorter than its tag.
+    TooShort,
+    /// The Poly1305 tag did not match the ciphertext and associated data.
+    InvalidTag,
+    /// A STREAM ran out of chunk numbers.
+    CounterOverflow,
+    /// An authentic plaintext opened with `open_padded` was not padded.
+    InvalidPadding,
+}
+
+impl fmt::Display for AeadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AeadError::TooShort => write!(f, "ciphertext is too short"),
+            AeadError::InvalidTag => write!(f, "ciphertext authentication failed"),
+            AeadError::CounterOverflow => write!(f, "stream chunk counter overflow"),
+            AeadError::InvalidPadding => write!(f, "plaintext padding is invalid"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AeadError {}
+
+///
+/// The ChaCha20-Poly1305 AEAD construction.
+///
+/// The plaintext is encrypted with ChaCha20 starting at block counter 1,
+/// while block 0 provides the one-time Poly1305 key.  The tag covers the
+/// associated data and the ciphertext, each zero-padded to a multiple of 16
+/// bytes, followed by both of their lengths as 64-bit little-endian
+/// integers.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.8)
+///
+pub struct ChaCha20Poly1305 {
+    key: Key,
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: impl Into<Key>) -> Self {
+        ChaCha20Poly1305 { key: key.into() }
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the 16-byte tag appended.
+    ///
+    /// A nonce must never be used twice with the same key.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: impl Into<Nonce>, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        self.seal_in_place_append_tag(nonce, aad, &mut output);
+        output
+    }
+
+    ///
+    /// Like `seal`, but returns the tag separately from the ciphertext, as
+    /// libsodium's detached mode does.  The ciphertext is as long as the
+    /// plaintext.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal_detached(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> (Vec<u8>, Tag) {
+        let mut ciphertext = plaintext.to_vec();
+        let tag = self.seal_in_place_detached(nonce, aad, &mut ciphertext);
+        (ciphertext, tag)
+    }
+
+    ///
+    /// Like `seal`, but encrypts `buffer` in place and appends the tag to
+    /// it, so no second buffer is allocated.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal_in_place_append_tag(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        buffer: &mut Vec<u8>,
+    ) {
+        let tag = self.seal_in_place_detached(nonce, aad, buffer);
+        buffer.extend_from_slice(tag.as_ref());
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the tag, without allocating.
+    ///
+    pub fn seal_in_place_detached(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Tag {
+        let nonce = nonce.into();
+        ChaCha20::new(self.key.clone(), nonce.clone()).apply_keystream(buffer);
+        self.tag(&nonce, aad, buffer)
+    }
+
+    ///
+    /// Like `seal`, but first pads the plaintext to a PADMÉ length, so the
+    /// ciphertext leaks at most O(log log L) bits of the plaintext's length
+    /// for at most 12% overhead.  Open it with `open_padded`.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal_padded(&self, nonce: impl Into<Nonce>, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut buffer = padme_pad(plaintext);
+        self.seal_in_place_append_tag(nonce, aad, &mut buffer);
+        buffer
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        let mut buffer = ciphertext.to_vec();
+        let length = self.open_in_place(nonce, aad, &mut buffer)?.len();
+        buffer.truncate(length);
+        Ok(buffer)
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal_padded`, and
+    /// removes the padding.  Fails with `AeadError::InvalidPadding` if the
+    /// tag is valid but the plaintext was not padded.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open_padded(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        let mut plaintext = self.open(nonce, aad, ciphertext)?;
+        let length = padme_unpad(&plaintext)
+            .map_err(|_| AeadError::InvalidPadding)?
+            .len();
+        plaintext.truncate(length);
+        Ok(plaintext)
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext and tag produced by
+    /// `seal_detached`.  The plaintext is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open_detached(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &Tag,
+    ) -> Result<Vec<u8>, AeadError> {
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Verifies and decrypts, in place, a ciphertext with its tag appended
+    /// as `seal` produces it.  Returns the part of `buffer` holding the
+    /// plaintext.  If the tag is invalid, `buffer` is left untouched.
+    ///
+    pub fn open_in_place<'a>(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        buffer: &'a mut [u8],
+    ) -> Result<&'a mut [u8], AeadError> {
+        if buffer.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = buffer.split_at_mut(buffer.len() - TAG_LENGTH);
+        let tag = Tag::try_from(&tag[..]).map_err(|_| AeadError::TooShort)?;
+        self.open_in_place_detached(nonce, aad, ciphertext, &tag)?;
+        Ok(ciphertext)
+    }
+
+    ///
+    /// Verifies `tag` and decrypts `buffer` in place, without allocating.
+    /// If the tag is invalid, `buffer` is left untouched.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), AeadError> {
+        let nonce = nonce.into();
+
+        // Constant-time tag comparison
+        let expected = self.tag(&nonce, aad, buffer);
+        if !expected.verify(tag) {
+            return Err(AeadError::InvalidTag);
+        }
+
+        ChaCha20::new(self.key.clone(), nonce).apply_keystream(buffer);
+        Ok(())
+    }
+
+    ///
+    /// Computes the Poly1305 tag over the padded associated data and
+    /// ciphertext, keyed with the first 32 bytes of keystream block 0.
+    ///
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.6)
+    ///
+    fn tag(&self, nonce: &Nonce, aad: &[u8], ciphertext: &[u8]) -> Tag {
+        let mut mac = Poly1305::new(poly1305_key_gen(self.key.clone(), nonce.clone()));
+        mac.update_padded(aad);
+        mac.update_padded(ciphertext);
+        mac.update(&(aad.len() as u64).to_le_bytes());
+        mac.update(&(ciphertext.len() as u64).to_le_bytes());
+        mac.finalize()
+    }
+}
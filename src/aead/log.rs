@@ -0,0 +1,179 @@
+use alloc::vec::Vec;
+
+use subtle::ConstantTimeEq;
+
+use crate::chacha::Key;
+
+use super::{AeadError, ChaCha20Poly1305, TAG_LENGTH};
+
+pub const LOG_ID_LENGTH: usize = 4;
+
+///
+/// Where a log stands after some records: how many there are, and the tag
+/// of the last one, which the next record is chained to.  Kept apart from
+/// the log, for example in a database row or a signed statement, it lets
+/// a replay notice records cut off the end.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogCheckpoint {
+    pub records: u64,
+    pub tag: [u8; TAG_LENGTH],
+}
+
+///
+/// Builds the nonce of record `index`: the log id followed by the index as
+/// a 64-bit big-endian integer.
+///
+fn record_nonce(log_id: &[u8; LOG_ID_LENGTH], index: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..LOG_ID_LENGTH].copy_from_slice(log_id);
+    nonce[LOG_ID_LENGTH..].copy_from_slice(&index.to_be_bytes());
+    nonce
+}
+
+///
+/// An append-only log of records sealed with ChaCha20-Poly1305, for audit
+/// trails and similar logs whose integrity matters as a whole.
+///
+/// Each record's nonce is derived from the log id and the record's index,
+/// and its associated data is the tag of the record before it (zeros for
+/// the first).  A `LogReader` replaying the log therefore rejects records
+/// that were changed, swapped, dropped from the middle or copied from
+/// another log.  Records cut off the end leave a shorter log that is still
+/// valid, so compare the replay against a `LogCheckpoint` stored elsewhere
+/// to notice those.
+///
+/// Every log needs a key and log id pair that is never used for another
+/// log.
+///
+pub struct EncryptedLog {
+    aead: ChaCha20Poly1305,
+    log_id: [u8; LOG_ID_LENGTH],
+    records: u64,
+    tag: [u8; TAG_LENGTH],
+}
+
+///
+/// Replays an `EncryptedLog`, opening its records in the order they were
+/// appended.
+///
+pub struct LogReader {
+    log: EncryptedLog,
+}
+
+impl EncryptedLog {
+    ///
+    /// Starts a new, empty log.
+    ///
+    pub fn new(key: impl Into<Key>, log_id: [u8; LOG_ID_LENGTH]) -> Self {
+        EncryptedLog {
+            aead: ChaCha20Poly1305::new(key),
+            log_id,
+            records: 0,
+            tag: [0u8; TAG_LENGTH],
+        }
+    }
+
+    ///
+    /// Continues appending to a log that ends at `checkpoint`, without
+    /// replaying it.  Use `LogReader::into_log` to check the log first.
+    ///
+    pub fn resume(
+        key: impl Into<Key>,
+        log_id: [u8; LOG_ID_LENGTH],
+        checkpoint: &LogCheckpoint,
+    ) -> Self {
+        EncryptedLog {
+            records: checkpoint.records,
+            tag: checkpoint.tag,
+            ..Self::new(key, log_id)
+        }
+    }
+
+    ///
+    /// Seals `record` as the next record of the log, and returns the
+    /// ciphertext followed by the tag.  Fails with
+    /// `AeadError::CounterOverflow` once the log holds 2^64 - 1 records.
+    ///
+    pub fn append(&mut self, record: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if self.records == u64::MAX {
+            return Err(AeadError::CounterOverflow);
+        }
+        let nonce = record_nonce(&self.log_id, self.records);
+        let sealed = self.aead.seal(nonce, &self.tag, record);
+
+        self.tag
+            .copy_from_slice(&sealed[sealed.len() - TAG_LENGTH..]);
+        self.records += 1;
+        Ok(sealed)
+    }
+
+    ///
+    /// Returns where the log stands after the records appended so far.
+    ///
+    pub fn checkpoint(&self) -> LogCheckpoint {
+        LogCheckpoint {
+            records: self.records,
+            tag: self.tag,
+        }
+    }
+}
+
+impl LogReader {
+    pub fn new(key: impl Into<Key>, log_id: [u8; LOG_ID_LENGTH]) -> Self {
+        LogReader {
+            log: EncryptedLog::new(key, log_id),
+        }
+    }
+
+    ///
+    /// Opens the next record of the log.  Fails with `AeadError::InvalidTag`
+    /// if it is not the record appended at this position of this log, with
+    /// `AeadError::TooShort` if it is shorter than a tag, and with
+    /// `AeadError::CounterOverflow` past 2^64 - 1 records.  A failed record
+    /// does not move the reader on.
+    ///
+    pub fn next_record(&mut self, sealed: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if self.log.records == u64::MAX {
+            return Err(AeadError::CounterOverflow);
+        }
+        let nonce = record_nonce(&self.log.log_id, self.log.records);
+        let record = self.log.aead.open(nonce, &self.log.tag, sealed)?;
+
+        self.log
+            .tag
+            .copy_from_slice(&sealed[sealed.len() - TAG_LENGTH..]);
+        self.log.records += 1;
+        Ok(record)
+    }
+
+    ///
+    /// Returns where the log stands after the records opened so far.
+    ///
+    pub fn checkpoint(&self) -> LogCheckpoint {
+        self.log.checkpoint()
+    }
+
+    ///
+    /// Checks that the records opened so far are the whole log up to
+    /// `checkpoint`, comparing the tags in constant time.  Fails with
+    /// `AeadError::InvalidTag` if records are missing from the end, or the
+    /// log went on past the checkpoint.
+    ///
+    pub fn verify_checkpoint(&self, checkpoint: &LogCheckpoint) -> Result<(), AeadError> {
+        let tags_match: bool = self.log.tag.ct_eq(&checkpoint.tag).into();
+        if tags_match && self.log.records == checkpoint.records {
+            Ok(())
+        } else {
+            Err(AeadError::InvalidTag)
+        }
+    }
+
+    ///
+    /// Ends the replay, returning a log that appends after the last record
+    /// opened.
+    ///
+    pub fn into_log(self) -> EncryptedLog {
+        self.log
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(feature = "rustcrypto-compat")]
+mod aead_traits;
+pub mod aegis;
+mod aes_ccm;
+mod aes_gcm;
+mod chacha20poly1305;
+#[cfg(feature = "alloc")]
+mod log;
+mod siv;
+#[cfg(feature = "alloc")]
+mod stream;
+#[cfg(feature = "std")]
+mod stream_io;
+
+pub use aes_ccm::*;
+pub use aes_gcm::*;
+pub use chacha20poly1305::*;
+#[cfg(feature = "alloc")]
+pub use log::*;
+pub use siv::*;
+#[cfg(feature = "alloc")]
+pub use stream::*;
+#[cfg(feature = "std")]
+pub use stream_io::*;
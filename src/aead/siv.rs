@@ -0,0 +1,167 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::chacha::{hchacha20, ChaCha20, Key, Nonce, XChaCha20};
+use crate::poly::Poly1305;
+
+use super::{AeadError, Tag, TAG_LENGTH};
+
+///
+/// A nonce-misuse-resistant ChaCha20-Poly1305 (a deterministic AEAD in the
+/// SIV style), for when nonces cannot be trusted to be unique, such as
+/// after a virtual machine snapshot is restored.
+///
+/// The tag is a synthetic IV computed from the nonce, the associated data
+/// and the plaintext, and the plaintext is then encrypted with XChaCha20
+/// under that tag.  Repeating a nonce only reveals whether the same
+/// message was sent twice with the same associated data; nothing else
+/// leaks.  The price is a second pass over the data, as the whole message
+/// has to be hashed before encryption can start.
+///
+/// Three subkeys are taken from the ChaCha20 keystream of the key, under
+/// the all-zero nonce:
+///
+/// * the Poly1305 key hashing the padded nonce, associated data and
+///   plaintext, followed by the lengths of the last two, like the
+///   ChaCha20-Poly1305 tag does
+/// * the HChaCha20 key turning that hash into the tag, its first 16 bytes
+/// * the XChaCha20 key, used with the tag followed by eight zero bytes as
+///   its nonce
+///
+/// The construction is not interoperable with other libraries, and the
+/// key must not be used with any other algorithm.
+///
+/// [Source](https://web.cs.ucdavis.edu/~rogaway/papers/keywrap.pdf)
+///
+pub struct ChaCha20Poly1305Siv {
+    mac_key: Key,
+    prf_key: Key,
+    encryption_key: Key,
+}
+
+impl ChaCha20Poly1305Siv {
+    pub fn new(key: impl Into<Key>) -> Self {
+        let mut subkeys = [0u8; 96];
+        ChaCha20::new_with_counter(key, [0u8; 12], 0).fill(&mut subkeys);
+
+        let siv = ChaCha20Poly1305Siv {
+            mac_key: Key::try_from(&subkeys[..32]).unwrap(),
+            prf_key: Key::try_from(&subkeys[32..64]).unwrap(),
+            encryption_key: Key::try_from(&subkeys[64..]).unwrap(),
+        };
+        subkeys.zeroize();
+        siv
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the 16-byte tag appended.  The output
+    /// only depends on the key, nonce, associated data and plaintext.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plaintext is too long for the XChaCha20 block counter.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: impl Into<Nonce>, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        let tag = self.seal_in_place_detached(nonce, aad, &mut output);
+        output.extend_from_slice(tag.as_ref());
+        output
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let tag = Tag::try_from(tag).map_err(|_| AeadError::TooShort)?;
+
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, &tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the tag, without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plaintext is too long for the XChaCha20 block counter.
+    ///
+    pub fn seal_in_place_detached(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Tag {
+        let tag = self.synthetic_iv(&nonce.into(), aad, buffer);
+        self.cipher(&tag).apply_keystream(buffer);
+        tag
+    }
+
+    ///
+    /// Decrypts `buffer` in place and verifies `tag`, without allocating.
+    /// The plaintext is needed to check the tag, so it is decrypted first;
+    /// if the tag is invalid, `buffer` is encrypted again and left as it was.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: impl Into<Nonce>,
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), AeadError> {
+        let mut cipher = self.cipher(tag);
+        cipher
+            .try_apply_keystream(buffer)
+            .map_err(|_| AeadError::InvalidTag)?;
+
+        // Constant-time tag comparison
+        let expected = self.synthetic_iv(&nonce.into(), aad, buffer);
+        if !expected.verify(tag) {
+            self.cipher(tag).apply_keystream(buffer);
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Hashes the padded nonce, associated data and plaintext and their
+    /// lengths with Poly1305, and runs the hash through HChaCha20, which
+    /// turns the universal hash into a pseudorandom function.
+    ///
+    fn synthetic_iv(&self, nonce: &Nonce, aad: &[u8], plaintext: &[u8]) -> Tag {
+        let mut mac = Poly1305::new(*self.mac_key.as_bytes());
+        mac.update_padded(nonce.as_bytes());
+        mac.update_padded(aad);
+        mac.update_padded(plaintext);
+        mac.update(&(aad.len() as u64).to_le_bytes());
+        mac.update(&(plaintext.len() as u64).to_le_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        let mut output = hchacha20(self.prf_key.clone(), hash);
+        let tag = Tag::try_from(&output[..TAG_LENGTH]).unwrap();
+        output.zeroize();
+        tag
+    }
+
+    fn cipher(&self, tag: &Tag) -> XChaCha20 {
+        let mut nonce = [0u8; 24];
+        nonce[..TAG_LENGTH].copy_from_slice(tag.as_ref());
+        XChaCha20::new(self.encryption_key.clone(), nonce)
+    }
+}
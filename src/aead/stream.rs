@@ -0,0 +1,164 @@
+use alloc::vec::Vec;
+
+use crate::chacha::Key;
+
+use super::{AeadError, ChaCha20Poly1305};
+
+pub const NONCE_PREFIX_LENGTH: usize = 7;
+
+///
+/// Builds the nonce of one chunk: the prefix, the chunk's position as a
+/// 32-bit big-endian counter, and a byte that is 1 on the last chunk only.
+///
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LENGTH], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LENGTH].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LENGTH..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+///
+/// The encrypting half of the STREAM construction, for messages too large
+/// to seal at once.  The message is split into chunks, each sealed with
+/// ChaCha20-Poly1305 under a nonce made of a fixed prefix, the chunk's
+/// position and a last-chunk flag.  Chunks therefore cannot be reordered,
+/// dropped or duplicated, and a stream cut short after any chunk fails to
+/// decrypt because its last chunk is missing.
+///
+/// Every stream needs a key and nonce prefix pair that is never used for
+/// another stream.
+///
+/// A stream made with `new_padded` pads every chunk to a PADMÉ length
+/// before sealing it, so the ciphertext no longer gives away the exact
+/// length of each record.  It must be decrypted by a `StreamDecryptor`
+/// that is padded as well.
+///
+/// [Source](https://eprint.iacr.org/2015/189)
+///
+pub struct StreamEncryptor {
+    aead: ChaCha20Poly1305,
+    nonce_prefix: [u8; NONCE_PREFIX_LENGTH],
+    counter: u32,
+    padded: bool,
+}
+
+///
+/// The decrypting half of the STREAM construction.  Chunks must be passed
+/// in the order they were encrypted, and the stream is only complete once
+/// `last_chunk` succeeds.  It must be padded if and only if the encryptor
+/// was.
+///
+pub struct StreamDecryptor {
+    aead: ChaCha20Poly1305,
+    nonce_prefix: [u8; NONCE_PREFIX_LENGTH],
+    counter: u32,
+    padded: bool,
+}
+
+impl StreamEncryptor {
+    pub fn new(key: impl Into<Key>, nonce_prefix: [u8; NONCE_PREFIX_LENGTH]) -> Self {
+        StreamEncryptor {
+            aead: ChaCha20Poly1305::new(key),
+            nonce_prefix,
+            counter: 0,
+            padded: false,
+        }
+    }
+
+    ///
+    /// Like `new`, but pads every chunk to a PADMÉ length before sealing it.
+    ///
+    pub fn new_padded(key: impl Into<Key>, nonce_prefix: [u8; NONCE_PREFIX_LENGTH]) -> Self {
+        StreamEncryptor {
+            padded: true,
+            ..Self::new(key, nonce_prefix)
+        }
+    }
+
+    ///
+    /// Seals the next chunk, which is not the last one.  Fails with
+    /// `AeadError::CounterOverflow` once 2^32 - 1 chunks were sealed, as only
+    /// the last chunk is left then.
+    ///
+    pub fn next_chunk(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if self.counter == u32::MAX {
+            return Err(AeadError::CounterOverflow);
+        }
+        let nonce = chunk_nonce(&self.nonce_prefix, self.counter, false);
+        self.counter += 1;
+        Ok(self.seal(nonce, aad, plaintext))
+    }
+
+    ///
+    /// Seals the last chunk and ends the stream.
+    ///
+    pub fn last_chunk(self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let nonce = chunk_nonce(&self.nonce_prefix, self.counter, true);
+        self.seal(nonce, aad, plaintext)
+    }
+
+    fn seal(&self, nonce: [u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        if self.padded {
+            self.aead.seal_padded(nonce, aad, plaintext)
+        } else {
+            self.aead.seal(nonce, aad, plaintext)
+        }
+    }
+}
+
+impl StreamDecryptor {
+    pub fn new(key: impl Into<Key>, nonce_prefix: [u8; NONCE_PREFIX_LENGTH]) -> Self {
+        StreamDecryptor {
+            aead: ChaCha20Poly1305::new(key),
+            nonce_prefix,
+            counter: 0,
+            padded: false,
+        }
+    }
+
+    ///
+    /// Like `new`, but removes the padding a padded `StreamEncryptor` added to every chunk.
+    ///
+    pub fn new_padded(key: impl Into<Key>, nonce_prefix: [u8; NONCE_PREFIX_LENGTH]) -> Self {
+        StreamDecryptor {
+            padded: true,
+            ..Self::new(key, nonce_prefix)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn is_padded(&self) -> bool {
+        self.padded
+    }
+
+    ///
+    /// Opens the next chunk, which must not be the last one.  A chunk that
+    /// fails to open does not advance the stream.
+    ///
+    pub fn next_chunk(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if self.counter == u32::MAX {
+            return Err(AeadError::CounterOverflow);
+        }
+        let nonce = chunk_nonce(&self.nonce_prefix, self.counter, false);
+        let plaintext = self.open(nonce, aad, ciphertext)?;
+        self.counter += 1;
+        Ok(plaintext)
+    }
+
+    ///
+    /// Opens the last chunk and ends the stream.
+    ///
+    pub fn last_chunk(self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        let nonce = chunk_nonce(&self.nonce_prefix, self.counter, true);
+        self.open(nonce, aad, ciphertext)
+    }
+
+    fn open(&self, nonce: [u8; 12], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if self.padded {
+            self.aead.open_padded(nonce, aad, ciphertext)
+        } else {
+            self.aead.open(nonce, aad, ciphertext)
+        }
+    }
+}
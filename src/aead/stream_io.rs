@@ -0,0 +1,235 @@
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use super::{AeadError, StreamDecryptor, StreamEncryptor, TAG_LENGTH};
+use crate::padding::padme_length;
+
+/// Plaintext bytes per chunk unless another length is given.
+pub const DEFAULT_CHUNK_LENGTH: usize = 64 * 1024;
+
+///
+/// Wraps a writer and encrypts everything written through it as a STREAM
+/// of fixed-length chunks, the last of which may be shorter.
+///
+/// A chunk is only sealed once more data arrives, since until then it may
+/// turn out to be the last one.  Call `finish` to seal the last chunk; a
+/// writer that is dropped instead leaves a stream that fails to decrypt.
+///
+pub struct StreamWriter<W: Write> {
+    inner: W,
+    encryptor: StreamEncryptor,
+    buffer: Vec<u8>,
+    chunk_length: usize,
+}
+
+///
+/// Wraps a reader of a STREAM written with the same chunk length, and
+/// decrypts everything read through it.  Padded streams are read with a
+/// padded `StreamDecryptor`.
+///
+/// Plaintext is only returned once its chunk is authenticated.  A
+/// tampered, reordered or truncated stream fails with
+/// `ErrorKind::InvalidData`, and every read after that fails as well.
+///
+pub struct StreamReader<R: Read> {
+    inner: R,
+    // None once the last chunk has been opened
+    decryptor: Option<StreamDecryptor>,
+    chunk_length: usize,
+    ciphertext: Vec<u8>,
+    plaintext: Vec<u8>,
+    position: usize,
+    failed: bool,
+}
+
+fn stream_error(error: AeadError) -> io::Error {
+    match error {
+        AeadError::CounterOverflow => io::Error::new(io::ErrorKind::Other, error),
+        _ => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
+
+impl<W: Write> StreamWriter<W> {
+    pub fn new(inner: W, encryptor: StreamEncryptor) -> Self {
+        Self::with_chunk_length(inner, encryptor, DEFAULT_CHUNK_LENGTH)
+    }
+
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_length` is 0.
+    ///
+    pub fn with_chunk_length(inner: W, encryptor: StreamEncryptor, chunk_length: usize) -> Self {
+        assert!(chunk_length > 0, "STREAM chunk length must not be 0");
+        StreamWriter {
+            inner,
+            encryptor,
+            buffer: Vec::with_capacity(chunk_length),
+            chunk_length,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    ///
+    /// Seals whatever is buffered as the last chunk, writes it out, and
+    /// returns the inner writer.
+    ///
+    pub fn finish(self) -> io::Result<W> {
+        let StreamWriter {
+            mut inner,
+            encryptor,
+            buffer,
+            ..
+        } = self;
+        inner.write_all(&encryptor.last_chunk(&[], &buffer))?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for StreamWriter<W> {
+    ///
+    /// Fails with `ErrorKind::Other` if the stream runs out of chunk
+    /// numbers.  The stream is unusable after any error.
+    ///
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // More data is coming, so a full buffer is not the last chunk
+        if self.buffer.len() == self.chunk_length {
+            let chunk = self
+                .encryptor
+                .next_chunk(&[], &self.buffer)
+                .map_err(stream_error)?;
+            self.inner.write_all(&chunk)?;
+            self.buffer.clear();
+        }
+
+        let length = buf.len().min(self.chunk_length - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..length]);
+        Ok(length)
+    }
+
+    ///
+    /// Flushes the inner writer.  Buffered plaintext stays buffered, as it
+    /// cannot be sealed before it is known whether more follows.
+    ///
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> StreamReader<R> {
+    pub fn new(inner: R, decryptor: StreamDecryptor) -> Self {
+        Self::with_chunk_length(inner, decryptor, DEFAULT_CHUNK_LENGTH)
+    }
+
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_length` is 0.
+    ///
+    pub fn with_chunk_length(inner: R, decryptor: StreamDecryptor, chunk_length: usize) -> Self {
+        assert!(chunk_length > 0, "STREAM chunk length must not be 0");
+        StreamReader {
+            inner,
+            decryptor: Some(decryptor),
+            chunk_length,
+            ciphertext: Vec::new(),
+            plaintext: Vec::new(),
+            position: 0,
+            failed: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    ///
+    /// Reads and opens the next chunk.  One byte past a full chunk is read
+    /// ahead, as only the end of the data tells whether a chunk is the last.
+    /// An I/O error leaves the stream as it was, so the read can be retried.
+    ///
+    fn read_chunk(&mut self) -> io::Result<()> {
+        // Full chunks are all padded to the same length
+        let sealed_length = match &self.decryptor {
+            Some(decryptor) if decryptor.is_padded() => padme_length(self.chunk_length + 1),
+            _ => self.chunk_length,
+        } + TAG_LENGTH;
+        while self.ciphertext.len() <= sealed_length {
+            let start = self.ciphertext.len();
+            self.ciphertext.resize(sealed_length + 1, 0);
+            let result = self.inner.read(&mut self.ciphertext[start..]);
+            let length = *result.as_ref().unwrap_or(&0);
+            self.ciphertext.truncate(start + length);
+            match result {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        let opened = match self.decryptor.take() {
+            Some(mut decryptor) if self.ciphertext.len() > sealed_length => {
+                let opened = decryptor.next_chunk(&[], &self.ciphertext[..sealed_length]);
+                self.ciphertext.drain(..sealed_length);
+                self.decryptor = Some(decryptor);
+                opened
+            }
+            Some(decryptor) => {
+                let opened = decryptor.last_chunk(&[], &self.ciphertext);
+                self.ciphertext.clear();
+                opened
+            }
+            None => return Ok(()),
+        };
+
+        match opened {
+            Ok(plaintext) => {
+                self.plaintext = plaintext;
+                self.position = 0;
+                Ok(())
+            }
+            Err(error) => {
+                self.failed = true;
+                Err(stream_error(error))
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for StreamReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.failed {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "STREAM failed to authenticate earlier",
+            ));
+        }
+
+        // Chunks may be empty, so keep going until there is plaintext or
+        // the stream has ended
+        while self.position == self.plaintext.len() {
+            if self.decryptor.is_none() {
+                return Ok(0);
+            }
+            self.read_chunk()?;
+        }
+
+        let length = buf.len().min(self.plaintext.len() - self.position);
+        buf[..length].copy_from_slice(&self.plaintext[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
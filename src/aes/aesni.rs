@@ -0,0 +1,147 @@
+//!
+//! AES with the x86 AES-NI instructions, which run a whole round in one
+//! instruction, in constant time.  Eight blocks are kept in flight, as
+//! each round instruction has a latency of several cycles but a throughput
+//! of one or two per cycle.
+//!
+//! The round keys come from the software key expansion; decryption turns
+//! them into the ones of the equivalent inverse cipher on the fly.
+//!
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::Block;
+
+/// Blocks encrypted or decrypted side by side.
+const PARALLEL_BLOCKS: usize = 8;
+
+///
+/// Encrypts every block with the `round_keys`, one more than the rounds.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AES-NI and SSE2.
+///
+#[target_feature(enable = "aes,sse2")]
+pub(super) unsafe fn encrypt_blocks(round_keys: &[Block], blocks: &mut [Block]) {
+    let keys = load_keys(round_keys);
+    let (first, middle, last) = split_keys(&keys[..round_keys.len()]);
+
+    for chunk in blocks.chunks_mut(PARALLEL_BLOCKS) {
+        let mut state = load(chunk);
+        let state = &mut state[..chunk.len()];
+        for block in state.iter_mut() {
+            *block = _mm_xor_si128(*block, first);
+        }
+        for &key in middle {
+            for block in state.iter_mut() {
+                *block = _mm_aesenc_si128(*block, key);
+            }
+        }
+        for block in state.iter_mut() {
+            *block = _mm_aesenclast_si128(*block, last);
+        }
+        store(state, chunk);
+    }
+}
+
+///
+/// Decrypts every block with the encryption `round_keys`, one more than
+/// the rounds.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AES-NI and SSE2.
+///
+#[target_feature(enable = "aes,sse2")]
+pub(super) unsafe fn decrypt_blocks(round_keys: &[Block], blocks: &mut [Block]) {
+    // The equivalent inverse cipher takes the keys in reverse, with
+    // InvMixColumns applied to all but the outer two
+    let mut keys = load_keys(round_keys);
+    let keys = &mut keys[..round_keys.len()];
+    keys.reverse();
+    let inner = keys.len() - 1;
+    for key in &mut keys[1..inner] {
+        *key = _mm_aesimc_si128(*key);
+    }
+    let (first, middle, last) = split_keys(keys);
+
+    for chunk in blocks.chunks_mut(PARALLEL_BLOCKS) {
+        let mut state = load(chunk);
+        let state = &mut state[..chunk.len()];
+        for block in state.iter_mut() {
+            *block = _mm_xor_si128(*block, first);
+        }
+        for &key in middle {
+            for block in state.iter_mut() {
+                *block = _mm_aesdec_si128(*block, key);
+            }
+        }
+        for block in state.iter_mut() {
+            *block = _mm_aesdeclast_si128(*block, last);
+        }
+        store(state, chunk);
+    }
+}
+
+///
+/// Runs `N` AES rounds at once, `inputs[i]` keyed with `round_keys[i]`,
+/// which is exactly what one `aesenc` does.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AES-NI and SSE2.
+///
+#[target_feature(enable = "aes,sse2")]
+pub(super) unsafe fn aes_rounds<const N: usize>(
+    inputs: [u128; N],
+    round_keys: [u128; N],
+) -> [u128; N] {
+    let mut outputs = [0u128; N];
+    for ((output, input), round_key) in outputs.iter_mut().zip(inputs).zip(round_keys) {
+        let input = input.to_le_bytes();
+        let round_key = round_key.to_le_bytes();
+        let result = _mm_aesenc_si128(
+            _mm_loadu_si128(input.as_ptr() as *const __m128i),
+            _mm_loadu_si128(round_key.as_ptr() as *const __m128i),
+        );
+        let mut bytes = [0u8; 16];
+        _mm_storeu_si128(bytes.as_mut_ptr() as *mut __m128i, result);
+        *output = u128::from_le_bytes(bytes);
+    }
+    outputs
+}
+
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn load_keys(round_keys: &[Block]) -> [__m128i; 15] {
+    let mut keys = [_mm_setzero_si128(); 15];
+    for (key, round_key) in keys.iter_mut().zip(round_keys) {
+        *key = _mm_loadu_si128(round_key.as_ptr() as *const __m128i);
+    }
+    keys
+}
+
+fn split_keys(keys: &[__m128i]) -> (__m128i, &[__m128i], __m128i) {
+    (keys[0], &keys[1..keys.len() - 1], keys[keys.len() - 1])
+}
+
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn load(blocks: &[Block]) -> [__m128i; PARALLEL_BLOCKS] {
+    let mut state = [_mm_setzero_si128(); PARALLEL_BLOCKS];
+    for (state, block) in state.iter_mut().zip(blocks) {
+        *state = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+    }
+    state
+}
+
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn store(state: &[__m128i], blocks: &mut [Block]) {
+    for (state, block) in state.iter().zip(blocks) {
+        _mm_storeu_si128(block.as_mut_ptr() as *mut __m128i, *state);
+    }
+}
@@ -0,0 +1,223 @@
+use zeroize::Zeroize;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::backend::has_aes_ni;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::aesni;
+use super::soft::{self, PARALLEL_BLOCKS};
+
+pub const BLOCK_LENGTH: usize = 16;
+
+pub type Block = [u8; BLOCK_LENGTH];
+
+/// Round keys of AES-256, the most any key length needs.
+const MAX_ROUND_KEYS: usize = 15;
+
+/// The powers of x that key expansion adds to the first byte of a word.
+const ROUND_CONSTANTS: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+///
+/// The AES block cipher, with a key of `KEY_LENGTH` bytes: 16, 24 or 32,
+/// for 10, 12 or 14 rounds.  Any other length fails to compile.
+///
+/// This is the bare block cipher, the building block of modes like CTR
+/// and GCM; encrypting data with it directly, block by block, is ECB and
+/// leaks which blocks are equal.
+///
+/// With AES-NI, detected at runtime, every round is one instruction.  The
+/// software fallback is constant time as well: the S-box is computed on bit
+/// planes instead of looked up in a table, eight blocks at a time, so
+/// `encrypt_blocks` is much faster per block than `encrypt_block`.
+///
+/// [Source](https://csrc.nist.gov/pubs/fips/197/final)
+///
+#[derive(Clone)]
+pub struct Aes<const KEY_LENGTH: usize> {
+    round_keys: [Block; MAX_ROUND_KEYS],
+    // Skips AES-NI even where it is detected
+    #[cfg_attr(
+        not(any(target_arch = "x86", target_arch = "x86_64")),
+        allow(dead_code)
+    )]
+    soft: bool,
+}
+
+pub type Aes128 = Aes<16>;
+pub type Aes192 = Aes<24>;
+pub type Aes256 = Aes<32>;
+
+impl<const KEY_LENGTH: usize> Aes<KEY_LENGTH> {
+    const ROUNDS: usize = KEY_LENGTH / 4 + 6;
+
+    const VALID_KEY_LENGTH: () = assert!(
+        KEY_LENGTH == 16 || KEY_LENGTH == 24 || KEY_LENGTH == 32,
+        "AES keys are 16, 24 or 32 bytes long"
+    );
+
+    pub fn new(key: [u8; KEY_LENGTH]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_KEY_LENGTH;
+
+        // Key expansion works on 4-byte words, of which the key is the first
+        // KEY_LENGTH / 4
+        let key_words = KEY_LENGTH / 4;
+        let mut words = [[0u8; 4]; 4 * MAX_ROUND_KEYS];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(chunk);
+        }
+        for i in key_words..4 * (Self::ROUNDS + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word.rotate_left(1);
+                word = sub_word(word);
+                word[0] ^= ROUND_CONSTANTS[i / key_words - 1];
+            } else if key_words > 6 && i % key_words == 4 {
+                word = sub_word(word);
+            }
+            for (byte, previous) in word.iter_mut().zip(words[i - key_words]) {
+                *byte ^= previous;
+            }
+            words[i] = word;
+        }
+
+        let mut round_keys = [[0u8; BLOCK_LENGTH]; MAX_ROUND_KEYS];
+        for (round_key, words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (bytes, word) in round_key.chunks_exact_mut(4).zip(words) {
+                bytes.copy_from_slice(word);
+            }
+        }
+        words.zeroize();
+        Aes {
+            round_keys,
+            soft: false,
+        }
+    }
+
+    ///
+    /// Like `new`, but always runs the constant-time software code, even
+    /// where AES-NI is detected.  Meant for testing and benchmarking the
+    /// fallback; `new` is as safe and much faster.
+    ///
+    pub fn new_soft(key: [u8; KEY_LENGTH]) -> Self {
+        let mut aes = Self::new(key);
+        aes.soft = true;
+        aes
+    }
+
+    pub fn encrypt_block(&self, block: &mut Block) {
+        self.encrypt_blocks(core::slice::from_mut(block));
+    }
+
+    pub fn decrypt_block(&self, block: &mut Block) {
+        self.decrypt_blocks(core::slice::from_mut(block));
+    }
+
+    ///
+    /// Encrypts every block independently, eight at a time.
+    ///
+    pub fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if !self.soft && has_aes_ni() {
+            // SAFETY: AES-NI was detected above
+            unsafe { aesni::encrypt_blocks(&self.round_keys[..=Self::ROUNDS], blocks) };
+            return;
+        }
+        for chunk in blocks.chunks_mut(PARALLEL_BLOCKS) {
+            self.encrypt_parallel(chunk);
+        }
+    }
+
+    ///
+    /// Decrypts every block independently, eight at a time.
+    ///
+    pub fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if !self.soft && has_aes_ni() {
+            // SAFETY: AES-NI was detected above
+            unsafe { aesni::decrypt_blocks(&self.round_keys[..=Self::ROUNDS], blocks) };
+            return;
+        }
+        for chunk in blocks.chunks_mut(PARALLEL_BLOCKS) {
+            self.decrypt_parallel(chunk);
+        }
+    }
+
+    fn encrypt_parallel(&self, blocks: &mut [Block]) {
+        add_round_key(blocks, &self.round_keys[0]);
+        for round_key in &self.round_keys[1..Self::ROUNDS] {
+            soft::sub_bytes(blocks);
+            for block in blocks.iter_mut() {
+                *block = soft::mix_columns(&soft::shift_rows(block));
+            }
+            add_round_key(blocks, round_key);
+        }
+
+        // The last round skips MixColumns
+        soft::sub_bytes(blocks);
+        for block in blocks.iter_mut() {
+            *block = soft::shift_rows(block);
+        }
+        add_round_key(blocks, &self.round_keys[Self::ROUNDS]);
+    }
+
+    fn decrypt_parallel(&self, blocks: &mut [Block]) {
+        add_round_key(blocks, &self.round_keys[Self::ROUNDS]);
+        for round_key in self.round_keys[1..Self::ROUNDS].iter().rev() {
+            for block in blocks.iter_mut() {
+                *block = soft::inv_shift_rows(block);
+            }
+            soft::inv_sub_bytes(blocks);
+            add_round_key(blocks, round_key);
+            for block in blocks.iter_mut() {
+                *block = soft::inv_mix_columns(block);
+            }
+        }
+
+        for block in blocks.iter_mut() {
+            *block = soft::inv_shift_rows(block);
+        }
+        soft::inv_sub_bytes(blocks);
+        add_round_key(blocks, &self.round_keys[0]);
+    }
+}
+
+impl<const KEY_LENGTH: usize> Drop for Aes<KEY_LENGTH> {
+    fn drop(&mut self) {
+        self.round_keys.zeroize();
+    }
+}
+
+///
+/// Runs `N` AES rounds at once, `inputs[i]` keyed with `round_keys[i]`, as
+/// AEGIS uses them.  Blocks are 16 bytes in little-endian order.
+///
+pub(crate) fn aes_rounds<const N: usize>(inputs: [u128; N], round_keys: [u128; N]) -> [u128; N] {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if has_aes_ni() {
+        // SAFETY: AES-NI was detected above
+        return unsafe { aesni::aes_rounds(inputs, round_keys) };
+    }
+    soft::aes_rounds(inputs, round_keys)
+}
+
+fn add_round_key(blocks: &mut [Block], round_key: &Block) {
+    for block in blocks {
+        for (byte, key) in block.iter_mut().zip(round_key) {
+            *byte ^= key;
+        }
+    }
+}
+
+///
+/// The S-box on each byte of a key expansion word.
+///
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let mut block = [[0u8; BLOCK_LENGTH]];
+    block[0][..4].copy_from_slice(&word);
+    soft::sub_bytes(&mut block);
+    let mut word = [0u8; 4];
+    word.copy_from_slice(&block[0][..4]);
+    block.zeroize();
+    word
+}
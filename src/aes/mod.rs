@@ -0,0 +1,7 @@
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod aesni;
+mod block_cipher;
+mod soft;
+
+pub(crate) use block_cipher::aes_rounds;
+pub use block_cipher::*;
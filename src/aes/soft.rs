@@ -0,0 +1,199 @@
+//!
+//! Constant-time software AES on bit planes.
+//!
+//! The S-box is not a table, whose lookups would leak the state through
+//! the cache, but the inversion in GF(2^8) computed on bit planes, one bit
+//! of every byte per plane.  A 128-bit plane holds a bit of up to eight
+//! blocks, so eight blocks go through SubBytes for the price of one.  The
+//! other steps work on bytes without data-dependent branches or lookups.
+//!
+//! [Source](https://csrc.nist.gov/pubs/fips/197/final)
+//!
+use super::Block;
+
+/// Blocks that fit the bit planes at once.
+pub(crate) const PARALLEL_BLOCKS: usize = 8;
+
+/// The AES polynomial x^8 + x^4 + x^3 + x + 1 without its top bit.
+const REDUCTION: u8 = 0x1b;
+
+/// The constant added by the affine transformation of the S-box.
+const AFFINE_CONSTANT: u8 = 0x63;
+
+///
+/// Runs `N` AES rounds at once, `inputs[i]` keyed with `round_keys[i]`.
+/// At most eight blocks fit the bit planes.
+///
+pub(crate) fn aes_rounds<const N: usize>(inputs: [u128; N], round_keys: [u128; N]) -> [u128; N] {
+    let mut blocks = [[0u8; 16]; N];
+    for (block, input) in blocks.iter_mut().zip(inputs) {
+        *block = input.to_le_bytes();
+    }
+    sub_bytes(&mut blocks);
+
+    let mut outputs = [0u128; N];
+    for ((output, block), round_key) in outputs.iter_mut().zip(&blocks).zip(round_keys) {
+        *output = u128::from_le_bytes(mix_columns(&shift_rows(block))) ^ round_key;
+    }
+    outputs
+}
+
+///
+/// SubBytes on up to eight blocks.
+///
+pub(crate) fn sub_bytes(blocks: &mut [Block]) {
+    let inverse = invert(&to_planes(blocks));
+
+    // Bit i of the output is the XOR of input bits i, i+4, i+5, i+6 and i+7
+    let mut output = [0u128; 8];
+    for (i, plane) in output.iter_mut().enumerate() {
+        *plane = inverse[i]
+            ^ inverse[(i + 4) % 8]
+            ^ inverse[(i + 5) % 8]
+            ^ inverse[(i + 6) % 8]
+            ^ inverse[(i + 7) % 8];
+        if (AFFINE_CONSTANT >> i) & 1 == 1 {
+            *plane = !*plane;
+        }
+    }
+    from_planes(&output, blocks);
+}
+
+///
+/// InvSubBytes on up to eight blocks: the inverse affine transformation,
+/// then the inversion, which is its own inverse.
+///
+pub(crate) fn inv_sub_bytes(blocks: &mut [Block]) {
+    let mut planes = to_planes(blocks);
+    for (i, plane) in planes.iter_mut().enumerate() {
+        if (AFFINE_CONSTANT >> i) & 1 == 1 {
+            *plane = !*plane;
+        }
+    }
+
+    // Bit i of the input is the XOR of output bits i-1, i-3 and i-6
+    let mut input = [0u128; 8];
+    for (i, plane) in input.iter_mut().enumerate() {
+        *plane = planes[(i + 7) % 8] ^ planes[(i + 5) % 8] ^ planes[(i + 2) % 8];
+    }
+    from_planes(&invert(&input), blocks);
+}
+
+///
+/// Rotates row r of the column-major state left by r bytes.
+///
+pub(crate) fn shift_rows(state: &Block) -> Block {
+    let mut output = [0u8; 16];
+    for (i, byte) in output.iter_mut().enumerate() {
+        *byte = state[(i + 4 * (i % 4)) % 16];
+    }
+    output
+}
+
+///
+/// Rotates row r of the column-major state right by r bytes.
+///
+pub(crate) fn inv_shift_rows(state: &Block) -> Block {
+    let mut output = [0u8; 16];
+    for (i, byte) in output.iter_mut().enumerate() {
+        *byte = state[(i + 16 - 4 * (i % 4)) % 16];
+    }
+    output
+}
+
+pub(crate) fn mix_columns(state: &Block) -> Block {
+    let mut output = [0u8; 16];
+    for (column, output) in state.chunks_exact(4).zip(output.chunks_exact_mut(4)) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        output[0] = a0 ^ all ^ xtime(a0 ^ a1);
+        output[1] = a1 ^ all ^ xtime(a1 ^ a2);
+        output[2] = a2 ^ all ^ xtime(a2 ^ a3);
+        output[3] = a3 ^ all ^ xtime(a3 ^ a0);
+    }
+    output
+}
+
+///
+/// InvMixColumns, as a multiplication by 4x^2 + 5 followed by MixColumns.
+///
+pub(crate) fn inv_mix_columns(state: &Block) -> Block {
+    let mut premixed = *state;
+    for column in premixed.chunks_exact_mut(4) {
+        let even = xtime(xtime(column[0] ^ column[2]));
+        let odd = xtime(xtime(column[1] ^ column[3]));
+        column[0] ^= even;
+        column[1] ^= odd;
+        column[2] ^= even;
+        column[3] ^= odd;
+    }
+    mix_columns(&premixed)
+}
+
+///
+/// Multiplication by x in GF(2^8), without branching on the top bit.
+///
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ (REDUCTION & 0u8.wrapping_sub(x >> 7))
+}
+
+fn to_planes(blocks: &[Block]) -> [u128; 8] {
+    debug_assert!(blocks.len() <= PARALLEL_BLOCKS);
+    let mut planes = [0u128; 8];
+    for (i, byte) in blocks.iter().flatten().enumerate() {
+        for (bit, plane) in planes.iter_mut().enumerate() {
+            *plane |= (((byte >> bit) & 1) as u128) << i;
+        }
+    }
+    planes
+}
+
+fn from_planes(planes: &[u128; 8], blocks: &mut [Block]) {
+    for (i, byte) in blocks.iter_mut().flatten().enumerate() {
+        *byte = (0..8).fold(0, |acc, bit| {
+            acc | ((((planes[bit] >> i) & 1) as u8) << bit)
+        });
+    }
+}
+
+///
+/// The inverse in GF(2^8) on bit planes, as x^254, which maps 0 to 0.
+///
+fn invert(x: &[u128; 8]) -> [u128; 8] {
+    let x2 = multiply(x, x);
+    let x3 = multiply(&x2, x);
+    let x6 = multiply(&x3, &x3);
+    let x12 = multiply(&x6, &x6);
+    let x15 = multiply(&x12, &x3);
+    let x30 = multiply(&x15, &x15);
+    let x60 = multiply(&x30, &x30);
+    let x120 = multiply(&x60, &x60);
+    let x240 = multiply(&x120, &x120);
+    let x252 = multiply(&x240, &x12);
+    multiply(&x252, &x2)
+}
+
+///
+/// Multiplication in GF(2^8) on bit planes.
+///
+fn multiply(a: &[u128; 8], b: &[u128; 8]) -> [u128; 8] {
+    let mut product = [0u128; 15];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] ^= a & b;
+        }
+    }
+
+    // x^8 = x^4 + x^3 + x + 1, from the top down so that the bits folded
+    // into 8 and above are folded again
+    for k in (8..15).rev() {
+        let high = product[k];
+        product[k - 4] ^= high;
+        product[k - 5] ^= high;
+        product[k - 7] ^= high;
+        product[k - 8] ^= high;
+    }
+    let mut output = [0u128; 8];
+    output.copy_from_slice(&product[..8]);
+    output
+}
@@ -0,0 +1,157 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub(super) const BLAKE2B_MAX_LENGTH: usize = 64;
+
+const BLOCK_LENGTH: usize = 128;
+
+/// The SHA-512 initial state.
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// The order message words are taken in by each round.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+///
+/// Unkeyed BLAKE2b with 1 to 64 bytes of output, the hash Argon2 is built
+/// on.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7693)
+///
+pub(super) struct Blake2b {
+    state: [u64; 8],
+    buffer: [u8; BLOCK_LENGTH],
+    buffer_length: usize,
+    // Bytes compressed so far
+    length: u128,
+    output_length: usize,
+}
+
+impl Blake2b {
+    pub(super) fn new(output_length: usize) -> Self {
+        debug_assert!((1..=BLAKE2B_MAX_LENGTH).contains(&output_length));
+        let mut state = IV;
+        state[0] ^= 0x01010000 ^ output_length as u64;
+        Blake2b {
+            state,
+            buffer: [0; BLOCK_LENGTH],
+            buffer_length: 0,
+            length: 0,
+            output_length,
+        }
+    }
+
+    pub(super) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // A full buffer is only compressed once more data shows it is not
+            // the last block
+            if self.buffer_length == BLOCK_LENGTH {
+                self.length += BLOCK_LENGTH as u128;
+                let block = self.buffer;
+                compress(&mut self.state, &block, self.length, false);
+                self.buffer_length = 0;
+            }
+
+            let length = (BLOCK_LENGTH - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+        }
+    }
+
+    ///
+    /// Writes the hash to `output`, which must be as long as the output
+    /// length the hash was created with.
+    ///
+    pub(super) fn finalize(mut self, output: &mut [u8]) {
+        self.length += self.buffer_length as u128;
+        self.buffer[self.buffer_length..].fill(0);
+        let block = self.buffer;
+        compress(&mut self.state, &block, self.length, true);
+
+        let mut bytes = [0u8; BLAKE2B_MAX_LENGTH];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(self.state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output.copy_from_slice(&bytes[..self.output_length]);
+        bytes.zeroize();
+    }
+}
+
+impl Drop for Blake2b {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Blake2b {}
+
+#[inline(always)]
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+///
+/// The compression function, over one block with `length` bytes hashed
+/// up to its end.
+///
+fn compress(state: &mut [u64; 8], block: &[u8; BLOCK_LENGTH], length: u128, last: bool) {
+    let mut m = [0u64; 16];
+    for (word, chunk) in m.iter_mut().zip(block.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(state);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= length as u64;
+    v[13] ^= (length >> 64) as u64;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for round in 0..12 {
+        let s = &SIGMA[round % 10];
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for (i, word) in state.iter_mut().enumerate() {
+        *word ^= v[i] ^ v[i + 8];
+    }
+    m.zeroize();
+    v.zeroize();
+}
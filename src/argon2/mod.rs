@@ -0,0 +1,45 @@
+mod blake2b;
+mod phc;
+mod rfc9106;
+
+pub use phc::*;
+pub use rfc9106::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Argon2Error {
+    /// The memory is less than 8 KiB per lane, or more than the caller
+    /// allows.
+    InvalidMemory,
+    /// The iteration count is zero, or more than the caller allows.
+    InvalidIterations,
+    /// The lane count is zero or at least 2^24.
+    InvalidLanes,
+    /// The salt is shorter than 8 bytes or longer than 2^32 - 1.
+    InvalidSaltLength,
+    /// The output is shorter than 4 bytes or longer than 2^32 - 1.
+    InvalidOutputLength,
+    /// The password, secret or associated data is longer than 2^32 - 1
+    /// bytes.
+    InvalidInputLength,
+    /// The PHC string is malformed or for an unsupported version.
+    InvalidEncoding,
+}
+
+impl fmt::Display for Argon2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Argon2Error::InvalidMemory => write!(f, "invalid memory size"),
+            Argon2Error::InvalidIterations => write!(f, "invalid iteration count"),
+            Argon2Error::InvalidLanes => write!(f, "invalid lane count"),
+            Argon2Error::InvalidSaltLength => write!(f, "invalid salt length"),
+            Argon2Error::InvalidOutputLength => write!(f, "invalid output length"),
+            Argon2Error::InvalidInputLength => write!(f, "invalid input length"),
+            Argon2Error::InvalidEncoding => write!(f, "invalid PHC string"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Argon2Error {}
@@ -0,0 +1,206 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use super::{argon2, Argon2Error, Params, Variant, ARGON2_MIN_OUTPUT_LENGTH, ARGON2_VERSION};
+
+/// Length of the hashes `hash_password` produces.
+pub const ARGON2_HASH_LENGTH: usize = 32;
+/// Length of the salts `generate_salt` produces.
+pub const ARGON2_SALT_LENGTH: usize = 16;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+///
+/// Hashes `password` for storage, returning a PHC string such as
+/// `$argon2id$v=19$m=65536,t=3,p=4$<salt>$<hash>` that holds everything
+/// `verify_password` needs.
+///
+/// [Source](https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md)
+///
+pub fn hash_password(
+    variant: Variant,
+    params: &Params,
+    password: &[u8],
+    salt: &[u8],
+) -> Result<String, Argon2Error> {
+    let mut hash = [0u8; ARGON2_HASH_LENGTH];
+    argon2(variant, params, password, salt, &mut hash)?;
+    let encoded = format!(
+        "${}$v={}$m={},t={},p={}${}${}",
+        variant.name(),
+        ARGON2_VERSION,
+        params.memory(),
+        params.iterations(),
+        params.lanes(),
+        base64_encode(salt),
+        base64_encode(&hash)
+    );
+    hash.zeroize();
+    Ok(encoded)
+}
+
+///
+/// Generates a random salt for `hash_password` with the operating system's
+/// secure random number generator.
+///
+/// # Panics
+///
+/// Panics if the operating system cannot provide random bytes.
+///
+#[cfg(feature = "os_rng")]
+pub fn generate_salt() -> [u8; ARGON2_SALT_LENGTH] {
+    let mut salt = [0u8; ARGON2_SALT_LENGTH];
+    getrandom::getrandom(&mut salt).expect("operating system random number generator failed");
+    salt
+}
+
+///
+/// Checks `password` against a PHC string from `hash_password` or another
+/// Argon2 implementation, comparing the hashes in constant time.
+///
+/// Fails with `Argon2Error::InvalidEncoding` if the string is malformed or
+/// not version 1.3, and with the parameter errors if it holds parameters
+/// Argon2 does not allow.
+///
+/// The memory and iteration counts are taken from the string as they are,
+/// so the string must be trusted: `m=4294967295` asks for 4 TiB, and the
+/// allocation aborts the process.  Use `verify_password_bounded` for
+/// strings an attacker may have written.
+///
+pub fn verify_password(encoded: &str, password: &[u8]) -> Result<bool, Argon2Error> {
+    verify_password_bounded(encoded, password, u32::MAX, u32::MAX)
+}
+
+///
+/// Like `verify_password`, but fails with `Argon2Error::InvalidMemory` if
+/// the string asks for more than `max_memory` KiB, and with
+/// `Argon2Error::InvalidIterations` if it asks for more than
+/// `max_iterations` passes, before any memory is allocated.
+///
+pub fn verify_password_bounded(
+    encoded: &str,
+    password: &[u8],
+    max_memory: u32,
+    max_iterations: u32,
+) -> Result<bool, Argon2Error> {
+    let mut fields = encoded.split('$');
+    let (Some(""), Some(name), Some(version), Some(parameters), Some(salt), Some(hash), None) = (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) else {
+        return Err(Argon2Error::InvalidEncoding);
+    };
+
+    let variant = [Variant::Argon2d, Variant::Argon2i, Variant::Argon2id]
+        .into_iter()
+        .find(|variant| variant.name() == name)
+        .ok_or(Argon2Error::InvalidEncoding)?;
+    if parse_field(version, "v")? != ARGON2_VERSION {
+        return Err(Argon2Error::InvalidEncoding);
+    }
+
+    let mut parameters = parameters.split(',');
+    let (Some(memory), Some(iterations), Some(lanes), None) = (
+        parameters.next(),
+        parameters.next(),
+        parameters.next(),
+        parameters.next(),
+    ) else {
+        return Err(Argon2Error::InvalidEncoding);
+    };
+    let params = Params::new(
+        parse_field(memory, "m")?,
+        parse_field(iterations, "t")?,
+        parse_field(lanes, "p")?,
+    )?;
+    if params.memory() > max_memory {
+        return Err(Argon2Error::InvalidMemory);
+    }
+    if params.iterations() > max_iterations {
+        return Err(Argon2Error::InvalidIterations);
+    }
+
+    let salt = base64_decode(salt).ok_or(Argon2Error::InvalidEncoding)?;
+    let expected = base64_decode(hash).ok_or(Argon2Error::InvalidEncoding)?;
+    if expected.len() < ARGON2_MIN_OUTPUT_LENGTH {
+        return Err(Argon2Error::InvalidOutputLength);
+    }
+
+    let mut actual = vec![0u8; expected.len()];
+    argon2(variant, &params, password, &salt, &mut actual)?;
+    let matches = bool::from(actual.ct_eq(&expected));
+    actual.zeroize();
+    Ok(matches)
+}
+
+///
+/// Reads a `name=value` field whose value is a decimal without leading
+/// zeros.
+///
+fn parse_field(field: &str, name: &str) -> Result<u32, Argon2Error> {
+    let value = field
+        .strip_prefix(name)
+        .and_then(|field| field.strip_prefix('='))
+        .ok_or(Argon2Error::InvalidEncoding)?;
+    if value.is_empty()
+        || !value.bytes().all(|byte| byte.is_ascii_digit())
+        || (value.len() > 1 && value.starts_with('0'))
+    {
+        return Err(Argon2Error::InvalidEncoding);
+    }
+    value.parse().map_err(|_| Argon2Error::InvalidEncoding)
+}
+
+///
+/// Standard base64 without padding, as PHC strings use.
+///
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            encoded.push(BASE64_ALPHABET[index as usize] as char);
+        }
+    }
+    encoded
+}
+
+///
+/// Decodes unpadded standard base64, rejecting non-canonical encodings
+/// whose unused trailing bits are set.
+///
+fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    if encoded.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.as_bytes().chunks(4) {
+        let mut bits = 0u32;
+        for (i, &character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|&c| c == character)?;
+            bits |= (value as u32) << (18 - 6 * i);
+        }
+
+        let length = chunk.len() - 1;
+        if bits & (0xffffff >> (8 * length)) != 0 {
+            return None;
+        }
+        bytes.extend_from_slice(&bits.to_be_bytes()[1..=length]);
+    }
+    Some(bytes)
+}
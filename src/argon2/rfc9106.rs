@@ -0,0 +1,501 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use super::blake2b::{Blake2b, BLAKE2B_MAX_LENGTH};
+use super::Argon2Error;
+
+/// The only version of Argon2 implemented, 1.3.
+pub const ARGON2_VERSION: u32 = 0x13;
+
+pub const ARGON2_MIN_SALT_LENGTH: usize = 8;
+pub const ARGON2_MIN_OUTPUT_LENGTH: usize = 4;
+
+/// Lanes Argon2 allows at most.
+const MAX_LANES: u32 = 0xffffff;
+
+/// Slices per pass, synchronization points between the lanes.
+const SLICES: usize = 4;
+
+/// 64-bit words per 1 KiB memory block.
+const BLOCK_WORDS: usize = 128;
+
+type Block = [u64; BLOCK_WORDS];
+
+///
+/// The three variants of Argon2, which differ in how they pick the blocks
+/// each new block depends on.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// Data-dependent memory access: the strongest against GPU cracking,
+    /// but open to side channels.
+    Argon2d,
+    /// Data-independent memory access, safe against side channels but
+    /// weaker against time-memory trade-offs.
+    Argon2i,
+    /// Argon2i for the first half of the first pass and Argon2d after
+    /// that, and the variant to use for password hashing.
+    Argon2id,
+}
+
+impl Variant {
+    ///
+    /// The name used in PHC strings.
+    ///
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Argon2d => "argon2d",
+            Variant::Argon2i => "argon2i",
+            Variant::Argon2id => "argon2id",
+        }
+    }
+
+    fn id(self) -> u32 {
+        match self {
+            Variant::Argon2d => 0,
+            Variant::Argon2i => 1,
+            Variant::Argon2id => 2,
+        }
+    }
+}
+
+///
+/// The cost parameters of Argon2: the memory in KiB, the number of passes
+/// over it, and the number of lanes it is split into.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    memory: u32,
+    iterations: u32,
+    lanes: u32,
+}
+
+impl Params {
+    ///
+    /// Checks the parameters: at least one iteration, between 1 and
+    /// 2^24 - 1 lanes, and at least 8 KiB of memory per lane.  The memory
+    /// is rounded down to a multiple of 4 KiB per lane.
+    ///
+    pub fn new(memory: u32, iterations: u32, lanes: u32) -> Result<Self, Argon2Error> {
+        if !(1..=MAX_LANES).contains(&lanes) {
+            return Err(Argon2Error::InvalidLanes);
+        }
+        if iterations == 0 {
+            return Err(Argon2Error::InvalidIterations);
+        }
+        if memory < 8 * lanes {
+            return Err(Argon2Error::InvalidMemory);
+        }
+        Ok(Params {
+            memory,
+            iterations,
+            lanes,
+        })
+    }
+
+    /// Memory in KiB.
+    pub fn memory(&self) -> u32 {
+        self.memory
+    }
+
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub fn lanes(&self) -> u32 {
+        self.lanes
+    }
+}
+
+impl Default for Params {
+    ///
+    /// The second recommended option of RFC 9106: 64 MiB of memory, three
+    /// iterations and four lanes.
+    ///
+    fn default() -> Self {
+        Params {
+            memory: 64 * 1024,
+            iterations: 3,
+            lanes: 4,
+        }
+    }
+}
+
+///
+/// Fills `output` with the Argon2 hash of `password` and `salt`.
+///
+/// The salt should be random, unique per password and at least 16 bytes;
+/// fewer than 8 fail with `Argon2Error::InvalidSaltLength`.  The output
+/// must be at least 4 bytes.  To store password hashes, `hash_password`
+/// encodes the parameters and salt along with the hash.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc9106)
+///
+pub fn argon2(
+    variant: Variant,
+    params: &Params,
+    password: &[u8],
+    salt: &[u8],
+    output: &mut [u8],
+) -> Result<(), Argon2Error> {
+    argon2_keyed(variant, params, password, salt, &[], &[], output)
+}
+
+///
+/// Like `argon2`, but also hashes a `secret` key kept apart from the
+/// stored hashes, a pepper, and `associated_data`.
+///
+pub fn argon2_keyed(
+    variant: Variant,
+    params: &Params,
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    associated_data: &[u8],
+    output: &mut [u8],
+) -> Result<(), Argon2Error> {
+    if salt.len() < ARGON2_MIN_SALT_LENGTH || salt.len() as u64 > u32::MAX as u64 {
+        return Err(Argon2Error::InvalidSaltLength);
+    }
+    if output.len() < ARGON2_MIN_OUTPUT_LENGTH || output.len() as u64 > u32::MAX as u64 {
+        return Err(Argon2Error::InvalidOutputLength);
+    }
+    if [password, secret, associated_data]
+        .iter()
+        .any(|input| input.len() as u64 > u32::MAX as u64)
+    {
+        return Err(Argon2Error::InvalidInputLength);
+    }
+
+    let lanes = params.lanes as usize;
+    let segment_length = params.memory as usize / (SLICES * lanes);
+    let lane_length = SLICES * segment_length;
+    let mut instance = Instance {
+        memory: vec![[0; BLOCK_WORDS]; lanes * lane_length],
+        variant,
+        iterations: params.iterations,
+        lanes,
+        lane_length,
+        segment_length,
+    };
+
+    // H0 binds every parameter and input
+    let mut hash = Blake2b::new(BLAKE2B_MAX_LENGTH);
+    for parameter in [
+        params.lanes,
+        output.len() as u32,
+        params.memory,
+        params.iterations,
+        ARGON2_VERSION,
+        variant.id(),
+    ] {
+        hash.update(&parameter.to_le_bytes());
+    }
+    for input in [password, salt, secret, associated_data] {
+        hash.update(&(input.len() as u32).to_le_bytes());
+        hash.update(input);
+    }
+    let mut h0 = [0u8; BLAKE2B_MAX_LENGTH];
+    hash.finalize(&mut h0);
+
+    let mut bytes = [0u8; 8 * BLOCK_WORDS];
+    for lane in 0..lanes {
+        for column in 0..2u32 {
+            variable_hash(
+                &[&h0, &column.to_le_bytes(), &(lane as u32).to_le_bytes()],
+                &mut bytes,
+            );
+            let block = &mut instance.memory[lane * lane_length + column as usize];
+            for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+                *word = u64::from_le_bytes(chunk.try_into().unwrap());
+            }
+        }
+    }
+    h0.zeroize();
+
+    for pass in 0..params.iterations {
+        for slice in 0..SLICES {
+            for lane in 0..lanes {
+                instance.fill_segment(pass, slice, lane);
+            }
+        }
+    }
+
+    // The last blocks of all lanes, XORed, give the tag
+    let mut last = instance.memory[lane_length - 1];
+    for lane in 1..lanes {
+        for (word, other) in last
+            .iter_mut()
+            .zip(&instance.memory[lane * lane_length + lane_length - 1])
+        {
+            *word ^= other;
+        }
+    }
+    for (chunk, word) in bytes.chunks_exact_mut(8).zip(last) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    variable_hash(&[&bytes], output);
+
+    last.zeroize();
+    bytes.zeroize();
+    Ok(())
+}
+
+///
+/// The memory of one hash, `lanes` rows of `lane_length` blocks, each
+/// lane split into four segments.
+///
+struct Instance {
+    memory: Vec<Block>,
+    variant: Variant,
+    iterations: u32,
+    lanes: usize,
+    lane_length: usize,
+    segment_length: usize,
+}
+
+impl Instance {
+    ///
+    /// Computes the blocks of one segment, each from the block before it
+    /// and a reference block chosen pseudo-randomly among those the other
+    /// lanes have finished.
+    ///
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc9106#section-3.4)
+    ///
+    fn fill_segment(&mut self, pass: u32, slice: usize, lane: usize) {
+        let data_independent = match self.variant {
+            Variant::Argon2d => false,
+            Variant::Argon2i => true,
+            Variant::Argon2id => pass == 0 && slice < SLICES / 2,
+        };
+
+        // Data-independent indices come from a counter run through the
+        // compression function
+        let mut input = [0u64; BLOCK_WORDS];
+        let mut addresses = [0u64; BLOCK_WORDS];
+        input[..6].copy_from_slice(&[
+            pass as u64,
+            lane as u64,
+            slice as u64,
+            self.memory.len() as u64,
+            self.iterations as u64,
+            self.variant.id() as u64,
+        ]);
+
+        // The first two blocks of each lane are already set
+        let start = if pass == 0 && slice == 0 { 2 } else { 0 };
+        if data_independent && start != 0 {
+            next_addresses(&mut input, &mut addresses);
+        }
+
+        for index in start..self.segment_length {
+            let position = slice * self.segment_length + index;
+            let current = lane * self.lane_length + position;
+            let previous = if position == 0 {
+                current + self.lane_length - 1
+            } else {
+                current - 1
+            };
+
+            let pseudo_random = if data_independent {
+                if index % BLOCK_WORDS == 0 {
+                    next_addresses(&mut input, &mut addresses);
+                }
+                addresses[index % BLOCK_WORDS]
+            } else {
+                self.memory[previous][0]
+            };
+
+            let reference_lane = if pass == 0 && slice == 0 {
+                lane
+            } else {
+                (pseudo_random >> 32) as usize % self.lanes
+            };
+            let reference = reference_lane * self.lane_length
+                + self.reference_index(pass, slice, index, reference_lane == lane, pseudo_random);
+
+            let mut block = compress(&self.memory[previous], &self.memory[reference]);
+            if pass == 0 {
+                self.memory[current] = block;
+            } else {
+                for (word, new) in self.memory[current].iter_mut().zip(&block) {
+                    *word ^= new;
+                }
+            }
+            block.zeroize();
+        }
+
+        input.zeroize();
+        addresses.zeroize();
+    }
+
+    ///
+    /// Maps the low half of `pseudo_random` to a block of the reference
+    /// lane, skewed towards recent blocks.  A block in another lane can
+    /// only be referenced once its slice is finished.
+    ///
+    fn reference_index(
+        &self,
+        pass: u32,
+        slice: usize,
+        index: usize,
+        same_lane: bool,
+        pseudo_random: u64,
+    ) -> usize {
+        let finished = if pass == 0 {
+            slice * self.segment_length
+        } else {
+            self.lane_length - self.segment_length
+        };
+        let area = if same_lane {
+            finished + index - 1
+        } else if index == 0 {
+            finished - 1
+        } else {
+            finished
+        };
+
+        let x = (pseudo_random & 0xffffffff).pow(2) >> 32;
+        let y = (area as u64 * x) >> 32;
+        let relative = area - 1 - y as usize;
+
+        let start = if pass == 0 || slice == SLICES - 1 {
+            0
+        } else {
+            (slice + 1) * self.segment_length
+        };
+        (start + relative) % self.lane_length
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        for block in &mut self.memory {
+            block.zeroize();
+        }
+    }
+}
+
+///
+/// Moves the address generator to its next block of 128 indices.
+///
+fn next_addresses(input: &mut Block, addresses: &mut Block) {
+    const ZERO: Block = [0; BLOCK_WORDS];
+    input[6] += 1;
+    *addresses = compress(&ZERO, &compress(&ZERO, input));
+}
+
+///
+/// The compression function G: the BLAKE2b round function, with
+/// multiplications added, applied to the rows and then the columns of
+/// `x ^ y` as an 8 by 8 matrix of 16-byte registers.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc9106#section-3.5)
+///
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = [0u64; BLOCK_WORDS];
+    for (word, (x, y)) in r.iter_mut().zip(x.iter().zip(y)) {
+        *word = x ^ y;
+    }
+
+    let mut z = r;
+    for row in 0..8 {
+        let indices: [usize; 16] = core::array::from_fn(|i| 16 * row + i);
+        permute(&mut z, &indices);
+    }
+    for column in 0..8 {
+        let indices: [usize; 16] = core::array::from_fn(|i| 2 * column + 16 * (i / 2) + i % 2);
+        permute(&mut z, &indices);
+    }
+
+    for (word, r) in z.iter_mut().zip(&r) {
+        *word ^= r;
+    }
+    r.zeroize();
+    z
+}
+
+///
+/// The permutation P on the sixteen words of `block` at `indices`.
+///
+fn permute(block: &mut Block, indices: &[usize; 16]) {
+    let mut v: [u64; 16] = core::array::from_fn(|i| block[indices[i]]);
+    gb(&mut v, 0, 4, 8, 12);
+    gb(&mut v, 1, 5, 9, 13);
+    gb(&mut v, 2, 6, 10, 14);
+    gb(&mut v, 3, 7, 11, 15);
+    gb(&mut v, 0, 5, 10, 15);
+    gb(&mut v, 1, 6, 11, 12);
+    gb(&mut v, 2, 7, 8, 13);
+    gb(&mut v, 3, 4, 9, 14);
+    for (&index, word) in indices.iter().zip(v) {
+        block[index] = word;
+    }
+}
+
+///
+/// The BLAKE2b quarter-round without message words, each addition
+/// strengthened with the product of the low halves of its operands.
+///
+#[inline(always)]
+fn gb(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize) {
+    v[a] = blamka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = blamka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = blamka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = blamka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+#[inline(always)]
+fn blamka(x: u64, y: u64) -> u64 {
+    let product = (x & 0xffffffff) * (y & 0xffffffff);
+    x.wrapping_add(y).wrapping_add(product.wrapping_mul(2))
+}
+
+///
+/// H', BLAKE2b extended to any output length by chaining 64-byte hashes
+/// and keeping the first half of each, over the concatenation of `parts`.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc9106#section-3.3)
+///
+fn variable_hash(parts: &[&[u8]], output: &mut [u8]) {
+    let length = (output.len() as u32).to_le_bytes();
+    if output.len() <= BLAKE2B_MAX_LENGTH {
+        let mut hash = Blake2b::new(output.len());
+        hash.update(&length);
+        for part in parts {
+            hash.update(part);
+        }
+        hash.finalize(output);
+        return;
+    }
+
+    let mut v = [0u8; BLAKE2B_MAX_LENGTH];
+    let mut hash = Blake2b::new(BLAKE2B_MAX_LENGTH);
+    hash.update(&length);
+    for part in parts {
+        hash.update(part);
+    }
+    hash.finalize(&mut v);
+
+    let half = BLAKE2B_MAX_LENGTH / 2;
+    output[..half].copy_from_slice(&v[..half]);
+    let mut position = half;
+    while output.len() - position > BLAKE2B_MAX_LENGTH {
+        let mut hash = Blake2b::new(BLAKE2B_MAX_LENGTH);
+        hash.update(&v);
+        hash.finalize(&mut v);
+        output[position..position + half].copy_from_slice(&v[..half]);
+        position += half;
+    }
+
+    let mut hash = Blake2b::new(output.len() - position);
+    hash.update(&v);
+    hash.finalize(&mut output[position..]);
+    v.zeroize();
+}
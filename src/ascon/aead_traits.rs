@@ -0,0 +1,51 @@
+//!
+//! RustCrypto `aead` trait implementations, so `Ascon128` can be used
+//! wherever a `KeyInit + AeadInPlace` (or, with `alloc`, `Aead`) type is
+//! expected, like `ChaCha20Poly1305`.
+//!
+use ::aead::consts::{U0, U16};
+use ::aead::{AeadCore, AeadInPlace, Error, KeyInit, KeySizeUser};
+
+use crate::aead::Tag;
+
+use super::Ascon128;
+
+impl KeySizeUser for Ascon128 {
+    type KeySize = U16;
+}
+
+impl KeyInit for Ascon128 {
+    fn new(key: &::aead::Key<Self>) -> Self {
+        Ascon128::new(<[u8; 16]>::from(*key))
+    }
+}
+
+impl AeadCore for Ascon128 {
+    type NonceSize = U16;
+    type TagSize = U16;
+    type CiphertextOverhead = U0;
+}
+
+impl AeadInPlace for Ascon128 {
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<::aead::Tag<Self>, Error> {
+        let tag = self.seal_in_place_detached(<[u8; 16]>::from(*nonce), associated_data, buffer);
+        Ok(tag.into_bytes().into())
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &::aead::Nonce<Self>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &::aead::Tag<Self>,
+    ) -> Result<(), Error> {
+        let tag = Tag::from(<[u8; 16]>::from(*tag));
+        self.open_in_place_detached(<[u8; 16]>::from(*nonce), associated_data, buffer, &tag)
+            .map_err(|_| Error)
+    }
+}
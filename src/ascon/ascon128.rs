@@ -0,0 +1,198 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+#[cfg(feature = "alloc")]
+use crate::aead::TAG_LENGTH;
+use crate::aead::{AeadError, Tag};
+
+use super::permutation::{pad, State, ROUNDS_A, ROUNDS_B};
+
+/// Key length, rate, and rounds of both permutations, as the first word.
+const IV: u64 = 0x80400c0600000000;
+
+/// Bytes absorbed or encrypted per permutation.
+const RATE: usize = 8;
+
+///
+/// The Ascon-128 AEAD, the primary choice of the NIST Lightweight
+/// Cryptography winner, with a 16-byte key and a 16-byte nonce.
+///
+/// This is Ascon-128 v1.2 as submitted, with an 8-byte rate and big-endian
+/// words, not the Ascon-AEAD128 of NIST SP 800-232.  The two give different
+/// ciphertexts and tags for the same inputs.
+///
+/// Ascon is a duplex sponge over a 320-bit permutation built only from
+/// bitwise operations and rotations of 64-bit words, so it is constant time
+/// and small in both code and state, which suits constrained devices.
+///
+/// A nonce must never be used twice with the same key.  A failed `open`
+/// does not reveal the plaintext.
+///
+/// [Source](https://ascon.iaik.tugraz.at/files/asconv12-nist.pdf)
+///
+pub struct Ascon128 {
+    key: [u8; 16],
+}
+
+impl Ascon128 {
+    pub fn new(key: [u8; 16]) -> Self {
+        Ascon128 { key }
+    }
+
+    ///
+    /// Encrypts `plaintext` and authenticates it together with `aad`,
+    /// returning the ciphertext with the 16-byte tag appended.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: [u8; 16], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_LENGTH);
+        output.extend_from_slice(plaintext);
+        let tag = self.seal_in_place_detached(nonce, aad, &mut output);
+        output.extend_from_slice(tag.as_ref());
+        output
+    }
+
+    ///
+    /// Verifies and decrypts a ciphertext produced by `seal`.  The plaintext
+    /// is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn open(
+        &self,
+        nonce: [u8; 16],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::TooShort);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let tag = Tag::try_from(tag).map_err(|_| AeadError::TooShort)?;
+
+        let mut buffer = ciphertext.to_vec();
+        self.open_in_place_detached(nonce, aad, &mut buffer, &tag)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Encrypts `buffer` in place and returns the tag, without allocating.
+    ///
+    pub fn seal_in_place_detached(&self, nonce: [u8; 16], aad: &[u8], buffer: &mut [u8]) -> Tag {
+        let mut state = self.initialize(&nonce);
+        absorb(&mut state, aad);
+
+        let mut blocks = buffer.chunks_exact_mut(RATE);
+        for block in &mut blocks {
+            state.0[0] ^= u64::from_be_bytes(block.try_into().unwrap());
+            block.copy_from_slice(&state.0[0].to_be_bytes());
+            state.permute(ROUNDS_B);
+        }
+        let last = blocks.into_remainder();
+        state.0[0] ^= pad(last);
+        last.copy_from_slice(&state.0[0].to_be_bytes()[..last.len()]);
+
+        self.tag(state)
+    }
+
+    ///
+    /// Decrypts `buffer` in place and verifies `tag`, without allocating.
+    /// The tag covers the plaintext, so it can only be checked after
+    /// decryption; if it is invalid, `buffer` is zeroed.
+    ///
+    pub fn open_in_place_detached(
+        &self,
+        nonce: [u8; 16],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), AeadError> {
+        let mut state = self.initialize(&nonce);
+        absorb(&mut state, aad);
+
+        let mut blocks = buffer.chunks_exact_mut(RATE);
+        for block in &mut blocks {
+            let ciphertext = u64::from_be_bytes((&*block).try_into().unwrap());
+            block.copy_from_slice(&(state.0[0] ^ ciphertext).to_be_bytes());
+            state.0[0] = ciphertext;
+            state.permute(ROUNDS_B);
+        }
+
+        // The ciphertext replaces the state bytes it covers, and the padding
+        // byte is added after it, as if the padded plaintext was absorbed
+        let last = blocks.into_remainder();
+        let mut word = state.0[0].to_be_bytes();
+        for (byte, state_byte) in last.iter_mut().zip(word.iter_mut()) {
+            let ciphertext = *byte;
+            *byte ^= *state_byte;
+            *state_byte = ciphertext;
+        }
+        word[last.len()] ^= 0x80;
+        state.0[0] = u64::from_be_bytes(word);
+
+        // Constant-time tag comparison
+        if !self.tag(state).verify(tag) {
+            buffer.zeroize();
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    fn key_words(&self) -> (u64, u64) {
+        (
+            u64::from_be_bytes(self.key[..8].try_into().unwrap()),
+            u64::from_be_bytes(self.key[8..].try_into().unwrap()),
+        )
+    }
+
+    fn initialize(&self, nonce: &[u8; 16]) -> State {
+        let (k0, k1) = self.key_words();
+        let mut state = State([
+            IV,
+            k0,
+            k1,
+            u64::from_be_bytes(nonce[..8].try_into().unwrap()),
+            u64::from_be_bytes(nonce[8..].try_into().unwrap()),
+        ]);
+        state.permute(ROUNDS_A);
+        state.0[3] ^= k0;
+        state.0[4] ^= k1;
+        state
+    }
+
+    fn tag(&self, mut state: State) -> Tag {
+        let (k0, k1) = self.key_words();
+        state.0[1] ^= k0;
+        state.0[2] ^= k1;
+        state.permute(ROUNDS_A);
+
+        let mut tag = [0u8; 16];
+        tag[..8].copy_from_slice(&(state.0[3] ^ k0).to_be_bytes());
+        tag[8..].copy_from_slice(&(state.0[4] ^ k1).to_be_bytes());
+        Tag::from(tag)
+    }
+}
+
+///
+/// Absorbs the associated data, if there is any, and separates it from
+/// the message.
+///
+fn absorb(state: &mut State, aad: &[u8]) {
+    if !aad.is_empty() {
+        let mut blocks = aad.chunks_exact(RATE);
+        for block in &mut blocks {
+            state.0[0] ^= u64::from_be_bytes(block.try_into().unwrap());
+            state.permute(ROUNDS_B);
+        }
+        state.0[0] ^= pad(blocks.remainder());
+        state.permute(ROUNDS_B);
+    }
+    state.0[4] ^= 1;
+}
+
+impl Drop for Ascon128 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
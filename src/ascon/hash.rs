@@ -0,0 +1,108 @@
+use zeroize::Zeroize;
+
+use super::permutation::{pad, State, ROUNDS_A};
+
+pub const HASH_LENGTH: usize = 32;
+
+/// The state after the permutation of the Ascon-Hash IV and zeros.
+const INITIAL_STATE: [u64; 5] = [
+    0xee9398aadb67f03d,
+    0x8bb21831c60f1002,
+    0xb48a92db98d5da62,
+    0x43189921b8f8e3e8,
+    0x348fa5c9d525e140,
+];
+
+/// Bytes absorbed or squeezed per permutation.
+const RATE: usize = 8;
+
+///
+/// Hashes `data` with Ascon-Hash in one go.
+///
+pub fn ascon_hash(data: &[u8]) -> [u8; HASH_LENGTH] {
+    let mut hash = AsconHash::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// Ascon-Hash, the 256-bit hash function of the Ascon family, as a sponge
+/// over the same permutation as Ascon-128 absorbing 8 bytes at a time.
+///
+/// Data can be fed in any number of `update` calls; only the
+/// concatenation matters.
+///
+/// This is Ascon-Hash v1.2, whose digests differ from those of
+/// Ascon-Hash256 in NIST SP 800-232.
+///
+/// [Source](https://ascon.iaik.tugraz.at/files/asconv12-nist.pdf)
+///
+pub struct AsconHash {
+    state: State,
+    buffer: [u8; RATE],
+    buffer_length: usize,
+}
+
+impl AsconHash {
+    pub fn new() -> Self {
+        AsconHash {
+            state: State(INITIAL_STATE),
+            buffer: [0u8; RATE],
+            buffer_length: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_length > 0 {
+            let length = data.len().min(RATE - self.buffer_length);
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+
+            if self.buffer_length < RATE {
+                return;
+            }
+            self.absorb(u64::from_be_bytes(self.buffer));
+            self.buffer_length = 0;
+        }
+
+        let mut blocks = data.chunks_exact(RATE);
+        for block in &mut blocks {
+            self.absorb(u64::from_be_bytes(block.try_into().unwrap()));
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; HASH_LENGTH] {
+        self.absorb(pad(&self.buffer[..self.buffer_length]));
+
+        let mut output = [0u8; HASH_LENGTH];
+        for (i, chunk) in output.chunks_exact_mut(RATE).enumerate() {
+            if i > 0 {
+                self.state.permute(ROUNDS_A);
+            }
+            chunk.copy_from_slice(&self.state.0[0].to_be_bytes());
+        }
+        output
+    }
+
+    fn absorb(&mut self, block: u64) {
+        self.state.0[0] ^= block;
+        self.state.permute(ROUNDS_A);
+    }
+}
+
+impl Default for AsconHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AsconHash {
+    fn drop(&mut self) {
+        self.buffer.zeroize();
+    }
+}
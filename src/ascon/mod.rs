@@ -0,0 +1,20 @@
+//!
+//! Ascon as submitted to the NIST Lightweight Cryptography competition,
+//! version 1.2: the Ascon-128 AEAD and Ascon-Hash.
+//!
+//! This is not the final standard.  NIST SP 800-232 renamed them
+//! Ascon-AEAD128 and Ascon-Hash256 and changed them: Ascon-AEAD128 absorbs
+//! 16 bytes at a time, uses other IVs and domain separation, and reads
+//! bytes into words little-endian.  Neither output matches v1.2, so these
+//! types do not interoperate with SP 800-232 implementations.
+//!
+//! [Source](https://csrc.nist.gov/pubs/sp/800/232/final)
+//!
+#[cfg(feature = "rustcrypto-compat")]
+mod aead_traits;
+mod ascon128;
+mod hash;
+mod permutation;
+
+pub use ascon128::*;
+pub use hash::*;
@@ -0,0 +1,78 @@
+use zeroize::Zeroize;
+
+/// Rounds of the permutation used for initialization and finalization.
+pub(super) const ROUNDS_A: usize = 12;
+
+/// Rounds of the permutation between the blocks of Ascon-128.
+pub(super) const ROUNDS_B: usize = 6;
+
+///
+/// The 320-bit Ascon state, five 64-bit words read from big-endian bytes.
+///
+/// [Source](https://ascon.iaik.tugraz.at/files/asconv12-nist.pdf)
+///
+pub(super) struct State(pub(super) [u64; 5]);
+
+impl State {
+    ///
+    /// Applies the last `rounds` rounds of the 12-round permutation, so that
+    /// p^6 uses the same round constants as the second half of p^12.
+    ///
+    pub(super) fn permute(&mut self, rounds: usize) {
+        debug_assert!(rounds <= ROUNDS_A);
+        for round in ROUNDS_A - rounds..ROUNDS_A {
+            self.round(0xf0 - 0x0f * round as u64);
+        }
+    }
+
+    fn round(&mut self, constant: u64) {
+        let [mut x0, mut x1, mut x2, mut x3, mut x4] = self.0;
+        x2 ^= constant;
+
+        // The 5-bit S-box, bitsliced across the words
+        x0 ^= x4;
+        x4 ^= x3;
+        x2 ^= x1;
+        let t0 = !x0 & x1;
+        let t1 = !x1 & x2;
+        let t2 = !x2 & x3;
+        let t3 = !x3 & x4;
+        let t4 = !x4 & x0;
+        x0 ^= t1;
+        x1 ^= t2;
+        x2 ^= t3;
+        x3 ^= t4;
+        x4 ^= t0;
+        x1 ^= x0;
+        x0 ^= x4;
+        x3 ^= x2;
+        x2 = !x2;
+
+        // The linear diffusion layer, a different pair of rotations per word
+        self.0 = [
+            x0 ^ x0.rotate_right(19) ^ x0.rotate_right(28),
+            x1 ^ x1.rotate_right(61) ^ x1.rotate_right(39),
+            x2 ^ x2.rotate_right(1) ^ x2.rotate_right(6),
+            x3 ^ x3.rotate_right(10) ^ x3.rotate_right(17),
+            x4 ^ x4.rotate_right(7) ^ x4.rotate_right(41),
+        ];
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+///
+/// Reads fewer than 8 bytes as the top of a big-endian word, followed by
+/// the 0x80 padding byte.
+///
+pub(super) fn pad(bytes: &[u8]) -> u64 {
+    debug_assert!(bytes.len() < 8);
+    let mut block = [0u8; 8];
+    block[..bytes.len()].copy_from_slice(bytes);
+    block[bytes.len()] = 0x80;
+    u64::from_be_bytes(block)
+}
@@ -0,0 +1,219 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+///
+/// The instruction set extensions armadillo can use for its ciphers, from
+/// least to most capable.  Only the ones belonging to the architecture the
+/// crate was compiled for are ever detected.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Backend {
+    /// Portable Rust, four interleaved ChaCha blocks at a time, or eight
+    /// through `std::simd` with the nightly-only `portable-simd` feature.
+    /// Used when no extension below is available.
+    Scalar,
+    /// x86 SSE2, four ChaCha blocks at a time.
+    Sse2,
+    /// x86 AVX2, eight ChaCha or four Poly1305 blocks at a time.
+    Avx2,
+    /// x86 AVX-512 alongside AVX2.  Detected, but currently served by the
+    /// AVX2 code, so a CPU with AVX-512 but no AVX2 gets `Sse2` instead.
+    Avx512,
+    /// aarch64 NEON, four ChaCha blocks at a time.
+    Neon,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Scalar => write!(f, "scalar"),
+            Backend::Sse2 => write!(f, "sse2"),
+            Backend::Avx2 => write!(f, "avx2"),
+            Backend::Avx512 => write!(f, "avx512"),
+            Backend::Neon => write!(f, "neon"),
+        }
+    }
+}
+
+impl Backend {
+    ///
+    /// Returns whether the running CPU can use this backend: the scalar
+    /// one always, the others when `backend` reports them or a more capable
+    /// backend of the same architecture.
+    ///
+    pub fn is_supported(self) -> bool {
+        let detected = backend();
+        match self {
+            Backend::Scalar => true,
+            Backend::Neon => detected == Backend::Neon,
+            _ => detected != Backend::Neon && self <= detected,
+        }
+    }
+}
+
+///
+/// Returns the best backend the running CPU supports.  Detection runs once,
+/// on first use, and every cipher dispatches on the cached result, so no
+/// compile-time target flags are needed to get the vectorized code.
+///
+/// Without the `std` feature there is no runtime detection, and the backend
+/// is picked from the target features the crate was compiled with instead
+/// (`-C target-feature` or `-C target-cpu`).
+///
+/// ChaCha keystream uses every backend here; Poly1305 only has an AVX2
+/// implementation besides the portable one.
+///
+#[cfg(feature = "std")]
+pub fn backend() -> Backend {
+    static BACKEND: OnceLock<Backend> = OnceLock::new();
+    *BACKEND.get_or_init(detect)
+}
+
+#[cfg(not(feature = "std"))]
+pub fn backend() -> Backend {
+    detect()
+}
+
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! has_feature {
+    ($feature:tt) => {
+        std::is_x86_feature_detected!($feature)
+    };
+}
+
+#[cfg(all(feature = "std", target_arch = "aarch64"))]
+macro_rules! has_feature {
+    ($feature:tt) => {
+        std::arch::is_aarch64_feature_detected!($feature)
+    };
+}
+
+#[cfg(all(
+    not(feature = "std"),
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+macro_rules! has_feature {
+    ($feature:tt) => {
+        cfg!(target_feature = $feature)
+    };
+}
+
+///
+/// Returns whether the running CPU has x86 AVX2, which the batched
+/// Poly1305 code needs whatever `backend` reports.  Detected once, like
+/// `backend`, and always false on other architectures.
+///
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_avx2() -> bool {
+    static AVX2: OnceLock<bool> = OnceLock::new();
+    *AVX2.get_or_init(|| has_feature!("avx2"))
+}
+
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_avx2() -> bool {
+    has_feature!("avx2")
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_avx2() -> bool {
+    false
+}
+
+///
+/// Returns whether the running CPU has the x86 AES-NI instructions, which
+/// AES and AEGIS use instead of their software code.  Detected once, like
+/// `backend`, and always false on other architectures.
+///
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_aes_ni() -> bool {
+    static AES_NI: OnceLock<bool> = OnceLock::new();
+    *AES_NI.get_or_init(|| has_feature!("aes") && has_feature!("sse2"))
+}
+
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_aes_ni() -> bool {
+    has_feature!("aes") && has_feature!("sse2")
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_aes_ni() -> bool {
+    false
+}
+
+///
+/// Returns whether the running CPU has the x86 carry-less multiplication
+/// instruction, which GHASH uses instead of its software code.  Detected
+/// once, like `backend`, and always false on other architectures.
+///
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_clmul() -> bool {
+    static CLMUL: OnceLock<bool> = OnceLock::new();
+    *CLMUL.get_or_init(|| has_feature!("pclmulqdq") && has_feature!("sse2"))
+}
+
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_clmul() -> bool {
+    has_feature!("pclmulqdq") && has_feature!("sse2")
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_clmul() -> bool {
+    false
+}
+
+///
+/// Returns whether the running CPU has the x86 SHA extensions, which
+/// SHA-256 uses instead of its software code.  Detected once, like
+/// `backend`, and always false on other architectures.
+///
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_sha_ni() -> bool {
+    static SHA_NI: OnceLock<bool> = OnceLock::new();
+    *SHA_NI.get_or_init(|| {
+        has_feature!("sha")
+            && has_feature!("sse2")
+            && has_feature!("ssse3")
+            && has_feature!("sse4.1")
+    })
+}
+
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_sha_ni() -> bool {
+    has_feature!("sha") && has_feature!("sse2") && has_feature!("ssse3") && has_feature!("sse4.1")
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn has_sha_ni() -> bool {
+    false
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+// Compile-time detection makes every condition a constant
+#[allow(clippy::ifs_same_cond)]
+fn detect() -> Backend {
+    // The Avx512 arms run the AVX2 kernels, so both must be present
+    if has_feature!("avx512f") && has_feature!("avx2") {
+        Backend::Avx512
+    } else if has_feature!("avx2") {
+        Backend::Avx2
+    } else if has_feature!("sse2") {
+        Backend::Sse2
+    } else {
+        Backend::Scalar
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn detect() -> Backend {
+    if has_feature!("neon") {
+        Backend::Neon
+    } else {
+        Backend::Scalar
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn detect() -> Backend {
+    Backend::Scalar
+}
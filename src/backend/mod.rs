@@ -0,0 +1,3 @@
+mod detection;
+
+pub use detection::*;
@@ -0,0 +1,211 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use super::blowfish::Blowfish;
+use super::BcryptError;
+
+pub const BCRYPT_SALT_LENGTH: usize = 16;
+pub const BCRYPT_HASH_LENGTH: usize = 23;
+/// Bytes of the password bcrypt uses; the rest are ignored.
+pub const BCRYPT_MAX_PASSWORD_LENGTH: usize = 72;
+
+pub const BCRYPT_MIN_COST: u32 = 4;
+pub const BCRYPT_MAX_COST: u32 = 31;
+
+/// Length of a hash string such as `$2b$12$<salt><hash>`.
+const ENCODED_LENGTH: usize = 60;
+const ENCODED_SALT_LENGTH: usize = 22;
+
+/// Encrypted 64 times with the expanded state to give the hash.
+const MAGIC: &[u8; 24] = b"OrpheanBeholderScryDoubt";
+
+/// bcrypt's own base64 alphabet, in a different order from the standard
+/// one.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+///
+/// Computes the raw 23-byte bcrypt hash of `password` with `salt`, at a
+/// cost of 2^`cost` key expansions.
+///
+/// Only the first 72 bytes of the password are used.  bcrypt is here to
+/// check hashes in existing password databases; new ones should use
+/// Argon2id, and rehash each password with it once it has been verified.
+///
+/// Fails with `BcryptError::InvalidCost` unless `cost` is 4 to 31.
+///
+/// [Source](https://www.usenix.org/legacy/events/usenix99/provos/provos.pdf)
+///
+pub fn bcrypt(
+    password: &[u8],
+    salt: &[u8; BCRYPT_SALT_LENGTH],
+    cost: u32,
+) -> Result<[u8; BCRYPT_HASH_LENGTH], BcryptError> {
+    if !(BCRYPT_MIN_COST..=BCRYPT_MAX_COST).contains(&cost) {
+        return Err(BcryptError::InvalidCost);
+    }
+
+    // The key is the password with its NUL terminator, cut to 72 bytes as
+    // by $2b$
+    let mut key = [0u8; BCRYPT_MAX_PASSWORD_LENGTH];
+    let length = password.len().min(BCRYPT_MAX_PASSWORD_LENGTH);
+    key[..length].copy_from_slice(&password[..length]);
+    let key_length = (password.len() + 1).min(BCRYPT_MAX_PASSWORD_LENGTH);
+    let key_bytes = &key[..key_length];
+
+    let mut salt_words = [0u32; 4];
+    for (word, chunk) in salt_words.iter_mut().zip(salt.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    // EksBlowfishSetup
+    let mut state = Blowfish::new();
+    state.expand_key(key_bytes, &salt_words);
+    for _ in 0..1u64 << cost {
+        state.expand_key(key_bytes, &[0; 4]);
+        state.expand_key(salt, &[0; 4]);
+    }
+
+    let mut text = [0u32; 6];
+    for (word, chunk) in text.iter_mut().zip(MAGIC.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    for _ in 0..64 {
+        for pair in text.chunks_exact_mut(2) {
+            (pair[0], pair[1]) = state.encrypt(pair[0], pair[1]);
+        }
+    }
+
+    // The last byte is dropped, as the hash string only has room for 23
+    let mut bytes = [0u8; 24];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(text) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    let mut hash = [0u8; BCRYPT_HASH_LENGTH];
+    hash.copy_from_slice(&bytes[..BCRYPT_HASH_LENGTH]);
+
+    key.zeroize();
+    text.zeroize();
+    bytes.zeroize();
+    Ok(hash)
+}
+
+///
+/// Hashes `password` into a `$2b$` string holding the cost, salt and hash,
+/// as stored by OpenBSD and most bcrypt libraries.
+///
+#[cfg(feature = "alloc")]
+pub fn hash_password(
+    password: &[u8],
+    salt: &[u8; BCRYPT_SALT_LENGTH],
+    cost: u32,
+) -> Result<String, BcryptError> {
+    let mut hash = bcrypt(password, salt, cost)?;
+
+    let mut encoded = [0u8; ENCODED_LENGTH];
+    encoded[..7].copy_from_slice(&[
+        b'$',
+        b'2',
+        b'b',
+        b'$',
+        b'0' + (cost / 10) as u8,
+        b'0' + (cost % 10) as u8,
+        b'$',
+    ]);
+    base64_encode(salt, &mut encoded[7..7 + ENCODED_SALT_LENGTH]);
+    base64_encode(&hash, &mut encoded[7 + ENCODED_SALT_LENGTH..]);
+    hash.zeroize();
+
+    // Every byte written comes from the alphabet or the header
+    Ok(String::from_utf8(encoded.to_vec()).unwrap())
+}
+
+///
+/// Generates a random salt for `hash_password` with the operating system's
+/// secure random number generator.
+///
+/// # Panics
+///
+/// Panics if the operating system cannot provide random bytes.
+///
+#[cfg(feature = "os_rng")]
+pub fn generate_salt() -> [u8; BCRYPT_SALT_LENGTH] {
+    let mut salt = [0u8; BCRYPT_SALT_LENGTH];
+    getrandom::getrandom(&mut salt).expect("operating system random number generator failed");
+    salt
+}
+
+///
+/// Checks `password` against a `$2a$`, `$2b$` or `$2y$` hash string,
+/// comparing the hashes in constant time.  The three versions differ only
+/// in bugs of particular implementations and hash passwords of up to 72
+/// bytes the same.
+///
+/// Fails with `BcryptError::InvalidEncoding` if the string is malformed or
+/// of another version, such as the broken `$2x$`.
+///
+pub fn verify_password(encoded: &str, password: &[u8]) -> Result<bool, BcryptError> {
+    let encoded = encoded.as_bytes();
+    if encoded.len() != ENCODED_LENGTH
+        || !matches!(&encoded[..4], b"$2a$" | b"$2b$" | b"$2y$")
+        || encoded[6] != b'$'
+        || !encoded[4..6].iter().all(u8::is_ascii_digit)
+    {
+        return Err(BcryptError::InvalidEncoding);
+    }
+    let cost = ((encoded[4] - b'0') * 10 + (encoded[5] - b'0')) as u32;
+
+    let mut salt = [0u8; BCRYPT_SALT_LENGTH];
+    let mut expected = [0u8; BCRYPT_HASH_LENGTH];
+    base64_decode(&encoded[7..7 + ENCODED_SALT_LENGTH], &mut salt)
+        .ok_or(BcryptError::InvalidEncoding)?;
+    base64_decode(&encoded[7 + ENCODED_SALT_LENGTH..], &mut expected)
+        .ok_or(BcryptError::InvalidEncoding)?;
+
+    let mut actual = bcrypt(password, &salt, cost)?;
+    let matches = bool::from(actual.ct_eq(&expected));
+    actual.zeroize();
+    Ok(matches)
+}
+
+///
+/// Writes `bytes` to `output` in bcrypt's base64, without padding; the
+/// output must be exactly long enough.
+///
+#[cfg(feature = "alloc")]
+fn base64_encode(bytes: &[u8], output: &mut [u8]) {
+    let mut characters = output.iter_mut();
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for (i, character) in characters.by_ref().take(chunk.len() + 1).enumerate() {
+            *character = BASE64_ALPHABET[(bits >> (18 - 6 * i)) as usize & 0x3f];
+        }
+    }
+}
+
+///
+/// Fills `output` from bcrypt's base64, ignoring the unused bits of the
+/// last character as OpenBSD does.
+///
+fn base64_decode(encoded: &[u8], output: &mut [u8]) -> Option<()> {
+    let mut bytes = output.iter_mut();
+    for chunk in encoded.chunks(4) {
+        let mut bits = 0u32;
+        for (i, &character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|&c| c == character)?;
+            bits |= (value as u32) << (18 - 6 * i);
+        }
+        for (value, byte) in bits.to_be_bytes()[1..chunk.len()]
+            .iter()
+            .zip(bytes.by_ref())
+        {
+            *byte = *value;
+        }
+    }
+    Some(())
+}
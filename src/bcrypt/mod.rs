@@ -0,0 +1,26 @@
+mod blowfish;
+mod hasher;
+
+pub use hasher::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BcryptError {
+    /// The cost is outside 4 to 31.
+    InvalidCost,
+    /// The hash string is malformed or of an unsupported version.
+    InvalidEncoding,
+}
+
+impl fmt::Display for BcryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BcryptError::InvalidCost => write!(f, "invalid cost"),
+            BcryptError::InvalidEncoding => write!(f, "invalid bcrypt hash string"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BcryptError {}
@@ -0,0 +1,266 @@
+use zeroize::Zeroize;
+
+/// The SHA-256 initial state, used as the key of unkeyed hashing.
+pub(super) const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+pub(super) const BLOCK_LENGTH: usize = 64;
+pub(super) const CHUNK_LENGTH: usize = 1024;
+
+// Domain flags, set on every compression they apply to
+pub(super) const CHUNK_START: u32 = 1 << 0;
+pub(super) const CHUNK_END: u32 = 1 << 1;
+pub(super) const PARENT: u32 = 1 << 2;
+pub(super) const ROOT: u32 = 1 << 3;
+pub(super) const KEYED_HASH: u32 = 1 << 4;
+pub(super) const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+pub(super) const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+/// The order message words are taken in by the next round.
+const PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+pub(super) type ChainingValue = [u32; 8];
+
+///
+/// The quarter-round of BLAKE2s, on four state words and two message
+/// words.
+///
+#[inline(always)]
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(x);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(y);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+///
+/// The compression function: seven rounds of BLAKE2s over the chaining
+/// value, the IV, the counter, the block length and the flags.  The first
+/// half of the output is the next chaining value; all of it is output at
+/// the root.
+///
+/// [Source](https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf) (Section 2.2)
+///
+pub(super) fn compress(
+    chaining_value: &ChainingValue,
+    block: &[u32; 16],
+    counter: u64,
+    block_length: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [0u32; 16];
+    state[..8].copy_from_slice(chaining_value);
+    state[8..12].copy_from_slice(&IV[..4]);
+    state[12] = counter as u32;
+    state[13] = (counter >> 32) as u32;
+    state[14] = block_length;
+    state[15] = flags;
+
+    let mut message = *block;
+    for round in 0..7 {
+        g(&mut state, 0, 4, 8, 12, message[0], message[1]);
+        g(&mut state, 1, 5, 9, 13, message[2], message[3]);
+        g(&mut state, 2, 6, 10, 14, message[4], message[5]);
+        g(&mut state, 3, 7, 11, 15, message[6], message[7]);
+        g(&mut state, 0, 5, 10, 15, message[8], message[9]);
+        g(&mut state, 1, 6, 11, 12, message[10], message[11]);
+        g(&mut state, 2, 7, 8, 13, message[12], message[13]);
+        g(&mut state, 3, 4, 9, 14, message[14], message[15]);
+
+        if round < 6 {
+            let previous = message;
+            for (word, &index) in message.iter_mut().zip(&PERMUTATION) {
+                *word = previous[index];
+            }
+        }
+    }
+
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= chaining_value[i];
+    }
+    state
+}
+
+///
+/// Reads up to 64 bytes as little-endian message words, zero-padded.
+///
+pub(super) fn block_words(bytes: &[u8]) -> [u32; 16] {
+    let mut block = [0u8; BLOCK_LENGTH];
+    block[..bytes.len()].copy_from_slice(bytes);
+    let mut words = [0u32; 16];
+    for (word, chunk) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+///
+/// Reads a 32-byte key as the eight words of a chaining value.
+///
+pub(super) fn key_words(key: &[u8; 32]) -> ChainingValue {
+    let mut words = [0u32; 8];
+    for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+///
+/// The last compression of a node, kept unevaluated: its first half is the
+/// node's chaining value, or at the root, with the `ROOT` flag and
+/// increasing counters, it produces the output.
+///
+#[derive(Clone)]
+pub(super) struct Output {
+    chaining_value: ChainingValue,
+    block: [u32; 16],
+    counter: u64,
+    block_length: u32,
+    flags: u32,
+}
+
+impl Output {
+    pub(super) fn chaining_value(&self) -> ChainingValue {
+        let state = compress(
+            &self.chaining_value,
+            &self.block,
+            self.counter,
+            self.block_length,
+            self.flags,
+        );
+        state[..8].try_into().unwrap()
+    }
+
+    ///
+    /// The 64 output bytes of block `counter` of the root output.
+    ///
+    pub(super) fn root_block(&self, counter: u64) -> [u8; 64] {
+        let state = compress(
+            &self.chaining_value,
+            &self.block,
+            counter,
+            self.block_length,
+            self.flags | ROOT,
+        );
+        let mut output = [0u8; 64];
+        for (chunk, word) in output.chunks_exact_mut(4).zip(state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        self.chaining_value.zeroize();
+        self.block.zeroize();
+    }
+}
+
+///
+/// The output of the parent node of two chaining values.
+///
+pub(super) fn parent_output(
+    left: &ChainingValue,
+    right: &ChainingValue,
+    key: &ChainingValue,
+    flags: u32,
+) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    Output {
+        chaining_value: *key,
+        block,
+        counter: 0,
+        block_length: BLOCK_LENGTH as u32,
+        flags: flags | PARENT,
+    }
+}
+
+///
+/// Hashes up to 1024 bytes as chunk `counter`, one 64-byte block at a
+/// time; the last block is left to the returned output.
+///
+#[derive(Clone)]
+pub(super) struct ChunkState {
+    chaining_value: ChainingValue,
+    pub(super) counter: u64,
+    buffer: [u8; BLOCK_LENGTH],
+    buffer_length: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    pub(super) fn new(key: &ChainingValue, counter: u64, flags: u32) -> Self {
+        ChunkState {
+            chaining_value: *key,
+            counter,
+            buffer: [0; BLOCK_LENGTH],
+            buffer_length: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    pub(super) fn length(&self) -> usize {
+        BLOCK_LENGTH * self.blocks_compressed + self.buffer_length
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    pub(super) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // A full buffer is only compressed once more data shows it is not
+            // the last block of the chunk
+            if self.buffer_length == BLOCK_LENGTH {
+                let state = compress(
+                    &self.chaining_value,
+                    &block_words(&self.buffer),
+                    self.counter,
+                    BLOCK_LENGTH as u32,
+                    self.flags | self.start_flag(),
+                );
+                self.chaining_value = state[..8].try_into().unwrap();
+                self.blocks_compressed += 1;
+                self.buffer_length = 0;
+            }
+
+            let length = (BLOCK_LENGTH - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+        }
+    }
+
+    pub(super) fn output(&self) -> Output {
+        Output {
+            chaining_value: self.chaining_value,
+            block: block_words(&self.buffer[..self.buffer_length]),
+            counter: self.counter,
+            block_length: self.buffer_length as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+impl Drop for ChunkState {
+    fn drop(&mut self) {
+        self.chaining_value.zeroize();
+        self.buffer.zeroize();
+    }
+}
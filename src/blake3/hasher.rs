@@ -0,0 +1,355 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::compress::{
+    key_words, parent_output, ChainingValue, ChunkState, Output, CHUNK_LENGTH, DERIVE_KEY_CONTEXT,
+    DERIVE_KEY_MATERIAL, IV, KEYED_HASH,
+};
+
+pub const BLAKE3_HASH_LENGTH: usize = 32;
+pub const BLAKE3_KEY_LENGTH: usize = 32;
+
+/// Chaining values the stack can hold, enough for 2^64 bytes of input.
+const MAX_DEPTH: usize = 54;
+
+/// Smallest subtree the parallel methods split between threads.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_LENGTH: usize = 16 * CHUNK_LENGTH;
+
+/// Hashes a subtree of a power-of-two number of whole chunks, or a single
+/// partial one, into its chaining value.
+type Subtree = fn(&[u8], u64, &ChainingValue, u32) -> ChainingValue;
+
+///
+/// Hashes `data` with BLAKE3 in one go.
+///
+pub fn blake3(data: &[u8]) -> [u8; BLAKE3_HASH_LENGTH] {
+    let mut hash = Blake3::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// Computes the BLAKE3 keyed hash of `data`, a MAC, in one go.
+///
+pub fn blake3_keyed(key: [u8; BLAKE3_KEY_LENGTH], data: &[u8]) -> [u8; BLAKE3_HASH_LENGTH] {
+    let mut hash = Blake3::new_keyed(key);
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// Derives a 32-byte key from `key_material` for the purpose named by
+/// `context`, in one go.
+///
+pub fn blake3_derive_key(context: &str, key_material: &[u8]) -> [u8; BLAKE3_HASH_LENGTH] {
+    let mut hash = Blake3::new_derive_key(context);
+    hash.update(key_material);
+    hash.finalize()
+}
+
+///
+/// BLAKE3: the input is split into 1024-byte chunks, each hashed with a
+/// seven-round BLAKE2s compression function, and the chunks' chaining
+/// values are combined pairwise up a binary tree whose root produces any
+/// amount of output.
+///
+/// Besides plain hashing, the hasher can be keyed, which makes it a MAC
+/// and PRF, or set up for key derivation from a context string, which
+/// should be hardcoded, globally unique and application specific, such as
+/// `"example.com 2024-01-01 session tokens v1"`.  The three modes never
+/// give related outputs.
+///
+/// Data can be fed in any number of `update` calls; only the
+/// concatenation matters.  Large inputs hash whole subtrees at once, which
+/// with the `parallel` feature `par_update` spreads over rayon's thread
+/// pool.
+///
+/// [Source](https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf)
+///
+#[derive(Clone)]
+pub struct Blake3 {
+    key: ChainingValue,
+    chunk_state: ChunkState,
+    // Chaining values of complete subtrees not yet merged, largest first
+    stack: [ChainingValue; MAX_DEPTH],
+    stack_length: usize,
+    flags: u32,
+}
+
+impl Blake3 {
+    pub fn new() -> Self {
+        Self::with_key(&IV, 0)
+    }
+
+    ///
+    /// Starts a keyed hash, for use as a MAC or PRF.
+    ///
+    pub fn new_keyed(mut key: [u8; BLAKE3_KEY_LENGTH]) -> Self {
+        let hash = Self::with_key(&key_words(&key), KEYED_HASH);
+        key.zeroize();
+        hash
+    }
+
+    ///
+    /// Starts a key derivation for the purpose named by `context`; the
+    /// key material is then passed to `update`.
+    ///
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut context_hash = Self::with_key(&IV, DERIVE_KEY_CONTEXT);
+        context_hash.update(context.as_bytes());
+        let mut context_key = context_hash.finalize();
+        let hash = Self::with_key(&key_words(&context_key), DERIVE_KEY_MATERIAL);
+        context_key.zeroize();
+        hash
+    }
+
+    fn with_key(key: &ChainingValue, flags: u32) -> Self {
+        Blake3 {
+            key: *key,
+            chunk_state: ChunkState::new(key, 0, flags),
+            stack: [[0; 8]; MAX_DEPTH],
+            stack_length: 0,
+            flags,
+        }
+    }
+
+    ///
+    /// Adds `data` to the input.
+    ///
+    pub fn update(&mut self, data: &[u8]) {
+        self.update_with(data, subtree_chaining_value);
+    }
+
+    ///
+    /// Like `update`, but hashes the subtrees of large inputs on rayon's
+    /// thread pool.  The hash is exactly that of `update`.
+    ///
+    #[cfg(feature = "parallel")]
+    pub fn par_update(&mut self, data: &[u8]) {
+        self.update_with(data, par_subtree_chaining_value);
+    }
+
+    ///
+    /// Returns the 32-byte hash of everything passed to `update`.
+    ///
+    pub fn finalize(self) -> [u8; BLAKE3_HASH_LENGTH] {
+        let mut block = self.root_output().root_block(0);
+        let mut output = [0u8; BLAKE3_HASH_LENGTH];
+        output.copy_from_slice(&block[..BLAKE3_HASH_LENGTH]);
+        block.zeroize();
+        output
+    }
+
+    ///
+    /// Ends the input and returns a reader for output of any length, whose
+    /// first 32 bytes are the hash.
+    ///
+    pub fn finalize_xof(self) -> Blake3Reader {
+        Blake3Reader {
+            output: self.root_output(),
+            counter: 0,
+            block: [0; 64],
+            position: 64,
+        }
+    }
+
+    fn update_with(&mut self, mut data: &[u8], subtree: Subtree) {
+        if self.chunk_state.length() > 0 {
+            let length = (CHUNK_LENGTH - self.chunk_state.length()).min(data.len());
+            self.chunk_state.update(&data[..length]);
+            data = &data[length..];
+            if data.is_empty() {
+                return;
+            }
+
+            // More input follows, so the chunk is complete and not the root
+            let chaining_value = self.chunk_state.output().chaining_value();
+            let counter = self.chunk_state.counter;
+            self.push(chaining_value, counter);
+            self.chunk_state = ChunkState::new(&self.key, counter + 1, self.flags);
+        }
+
+        // Whole subtrees as large as the input and their position allow go
+        // straight to the stack.  The last chunk is kept back unless a
+        // subtree of two or more ends the input, as its two halves are
+        // pushed separately, so the root is never compressed early.
+        while data.len() > CHUNK_LENGTH {
+            let counter = self.chunk_state.counter;
+            let mut chunks = 1 << (usize::BITS - 1 - (data.len() / CHUNK_LENGTH).leading_zeros());
+            while counter % chunks as u64 != 0 {
+                chunks /= 2;
+            }
+
+            let length = chunks * CHUNK_LENGTH;
+            if chunks == 1 {
+                let chaining_value = subtree(&data[..length], counter, &self.key, self.flags);
+                self.push(chaining_value, counter);
+            } else {
+                let half = length / 2;
+                let middle = counter + chunks as u64 / 2;
+                let left = subtree(&data[..half], counter, &self.key, self.flags);
+                let right = subtree(&data[half..length], middle, &self.key, self.flags);
+                self.push(left, counter);
+                self.push(right, middle);
+            }
+            self.chunk_state = ChunkState::new(&self.key, counter + chunks as u64, self.flags);
+            data = &data[length..];
+        }
+
+        if !data.is_empty() {
+            self.chunk_state.update(data);
+            self.merge(self.chunk_state.counter);
+        }
+    }
+
+    ///
+    /// Pushes the chaining value of the subtree starting at chunk
+    /// `counter`, after merging the ones before it that are complete.
+    ///
+    fn push(&mut self, chaining_value: ChainingValue, counter: u64) {
+        self.merge(counter);
+        self.stack[self.stack_length] = chaining_value;
+        self.stack_length += 1;
+    }
+
+    ///
+    /// Merges the top of the stack into parents until it holds one subtree
+    /// per set bit of `chunks`, the number of chunks hashed so far.
+    ///
+    fn merge(&mut self, chunks: u64) {
+        while self.stack_length > chunks.count_ones() as usize {
+            let (left, right) = (
+                self.stack[self.stack_length - 2],
+                self.stack[self.stack_length - 1],
+            );
+            self.stack[self.stack_length - 2] =
+                parent_output(&left, &right, &self.key, self.flags).chaining_value();
+            self.stack_length -= 1;
+        }
+    }
+
+    ///
+    /// Merges everything left into the root, right to left.
+    ///
+    fn root_output(&self) -> Output {
+        let (mut output, mut remaining) = if self.stack_length == 0 {
+            return self.chunk_state.output();
+        } else if self.chunk_state.length() > 0 {
+            (self.chunk_state.output(), self.stack_length)
+        } else {
+            // Input ending on a subtree leaves at least its two halves
+            let (left, right) = (
+                self.stack[self.stack_length - 2],
+                self.stack[self.stack_length - 1],
+            );
+            (
+                parent_output(&left, &right, &self.key, self.flags),
+                self.stack_length - 2,
+            )
+        };
+
+        while remaining > 0 {
+            remaining -= 1;
+            output = parent_output(
+                &self.stack[remaining],
+                &output.chaining_value(),
+                &self.key,
+                self.flags,
+            );
+        }
+        output
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Blake3 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.stack.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Blake3 {}
+
+///
+/// The output of BLAKE3, read in order, 64 bytes per root compression.
+///
+#[derive(Clone)]
+pub struct Blake3Reader {
+    output: Output,
+    counter: u64,
+    block: [u8; 64],
+    position: usize,
+}
+
+impl Blake3Reader {
+    ///
+    /// Fills `output` with the next bytes of output.
+    ///
+    pub fn read(&mut self, mut output: &mut [u8]) {
+        while !output.is_empty() {
+            if self.position == self.block.len() {
+                self.block = self.output.root_block(self.counter);
+                self.counter += 1;
+                self.position = 0;
+            }
+
+            let length = (self.block.len() - self.position).min(output.len());
+            output[..length].copy_from_slice(&self.block[self.position..self.position + length]);
+            self.position += length;
+            output = &mut output[length..];
+        }
+    }
+}
+
+impl Drop for Blake3Reader {
+    fn drop(&mut self) {
+        self.block.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Blake3Reader {}
+
+fn subtree_chaining_value(
+    data: &[u8],
+    counter: u64,
+    key: &ChainingValue,
+    flags: u32,
+) -> ChainingValue {
+    if data.len() <= CHUNK_LENGTH {
+        let mut chunk = ChunkState::new(key, counter, flags);
+        chunk.update(data);
+        return chunk.output().chaining_value();
+    }
+
+    let half = data.len() / 2;
+    let middle = counter + (half / CHUNK_LENGTH) as u64;
+    let left = subtree_chaining_value(&data[..half], counter, key, flags);
+    let right = subtree_chaining_value(&data[half..], middle, key, flags);
+    parent_output(&left, &right, key, flags).chaining_value()
+}
+
+#[cfg(feature = "parallel")]
+fn par_subtree_chaining_value(
+    data: &[u8],
+    counter: u64,
+    key: &ChainingValue,
+    flags: u32,
+) -> ChainingValue {
+    if data.len() <= PARALLEL_MIN_LENGTH {
+        return subtree_chaining_value(data, counter, key, flags);
+    }
+
+    let half = data.len() / 2;
+    let middle = counter + (half / CHUNK_LENGTH) as u64;
+    let (left, right) = rayon::join(
+        || par_subtree_chaining_value(&data[..half], counter, key, flags),
+        || par_subtree_chaining_value(&data[half..], middle, key, flags),
+    );
+    parent_output(&left, &right, key, flags).chaining_value()
+}
@@ -0,0 +1,4 @@
+mod compress;
+mod hasher;
+
+pub use hasher::*;
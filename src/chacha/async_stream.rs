@@ -0,0 +1,167 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::vec::Vec;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::stream::keystream_error;
+use super::ChaCha;
+
+///
+/// The asynchronous counterpart of `ChaChaReader`: wraps a tokio reader
+/// and decrypts (or encrypts) everything read through it.
+///
+/// The inner reader must be `Unpin`, like tokio's `File` and `TcpStream`
+/// are; other readers can be wrapped in `Box::pin` first.
+///
+pub struct ChaChaAsyncReader<R, const ROUNDS: usize> {
+    inner: R,
+    cipher: ChaCha<ROUNDS>,
+}
+
+pub type ChaCha20AsyncReader<R> = ChaChaAsyncReader<R, 20>;
+
+///
+/// The asynchronous counterpart of `ChaChaWriter`: wraps a tokio writer
+/// and encrypts (or decrypts) everything written through it.
+///
+/// Encrypted data the inner writer does not take at once is kept and
+/// written before any new data, and on flush and shutdown.  Unlike the
+/// synchronous writer, nothing is written on drop, so shut the writer down
+/// to be sure all data reached the inner writer.
+///
+pub struct ChaChaAsyncWriter<W, const ROUNDS: usize> {
+    inner: W,
+    cipher: ChaCha<ROUNDS>,
+    pending: Vec<u8>,
+    pending_position: usize,
+}
+
+pub type ChaCha20AsyncWriter<W> = ChaChaAsyncWriter<W, 20>;
+
+/// Most bytes a single `poll_write` call encrypts.
+const WRITE_CHUNK_LENGTH: usize = 64 * 1024;
+
+impl<R: AsyncRead + Unpin, const ROUNDS: usize> ChaChaAsyncReader<R, ROUNDS> {
+    pub fn new(inner: R, cipher: ChaCha<ROUNDS>) -> Self {
+        ChaChaAsyncReader { inner, cipher }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    ///
+    /// Reading from the inner reader directly skips the cipher, and
+    /// desynchronizes the keystream from the data.
+    ///
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin, const ROUNDS: usize> AsyncRead for ChaChaAsyncReader<R, ROUNDS> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+
+        this.cipher
+            .try_apply_keystream(&mut buf.filled_mut()[start..])
+            .map_err(keystream_error)?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin, const ROUNDS: usize> ChaChaAsyncWriter<W, ROUNDS> {
+    pub fn new(inner: W, cipher: ChaCha<ROUNDS>) -> Self {
+        ChaChaAsyncWriter {
+            inner,
+            cipher,
+            pending: Vec::new(),
+            pending_position: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    ///
+    /// Writing to the inner writer directly skips the cipher, and may
+    /// interleave with encrypted data that is still pending.
+    ///
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    ///
+    /// Returns the inner writer.  Data still pending is lost, so flush or
+    /// shut down the writer first.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.pending_position < self.pending.len() {
+            let pending = &self.pending[self.pending_position..];
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, pending))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pending_position += written;
+        }
+        self.pending.clear();
+        self.pending_position = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin, const ROUNDS: usize> AsyncWrite for ChaChaAsyncWriter<W, ROUNDS> {
+    ///
+    /// Fails with `ErrorKind::Other`, without consuming anything, if the data
+    /// runs past the end of the block counter.
+    ///
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+
+        let length = buf.len().min(WRITE_CHUNK_LENGTH);
+        this.pending.extend_from_slice(&buf[..length]);
+        if let Err(error) = this.cipher.try_apply_keystream(&mut this.pending) {
+            this.pending.clear();
+            return Poll::Ready(Err(keystream_error(error)));
+        }
+
+        // The data is accepted once it is encrypted.  Start writing it, but
+        // leave errors and back-pressure to the next call, which retries.
+        let _ = this.poll_write_pending(cx);
+        Poll::Ready(Ok(length))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,86 @@
+//!
+//! AVX2 implementation of the ChaCha block function, computing eight blocks
+//! at once.  Each 256-bit register holds the same state word for all eight
+//! blocks, so the quarter rounds are plain lane-wise operations.
+//!
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+pub(super) const BLOCKS: usize = 8;
+
+///
+/// Computes the keystream for the eight consecutive blocks starting at the
+/// counter in `state[12]`.  The counter wraps per block like the scalar
+/// implementation's 32-bit word does.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AVX2.
+///
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn keystream<const ROUNDS: usize>(state: &[u32; 16], output: &mut [u8; 512]) {
+    let mut x = [_mm256_setzero_si256(); 16];
+    x.iter_mut()
+        .zip(state)
+        .for_each(|(x, &word)| *x = _mm256_set1_epi32(word as i32));
+    x[12] = _mm256_add_epi32(x[12], _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7));
+    let original = x;
+
+    for _ in 0..ROUNDS / 2 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    // state += working_state, then transpose words back into blocks
+    let mut lanes = [[0u32; BLOCKS]; 16];
+    for ((lane, x), original) in lanes.iter_mut().zip(&x).zip(&original) {
+        let sum = _mm256_add_epi32(*x, *original);
+        _mm256_storeu_si256(lane.as_mut_ptr() as *mut __m256i, sum);
+    }
+    for (block, chunk) in output.chunks_exact_mut(64).enumerate() {
+        chunk
+            .chunks_exact_mut(4)
+            .zip(&lanes)
+            .for_each(|(bytes, lane)| bytes.copy_from_slice(&lane[block].to_le_bytes()));
+    }
+}
+
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn quarter_round(x: &mut [__m256i; 16], a: usize, b: usize, c: usize, d: usize) {
+    // Byte shuffles rotating every 32-bit lane left by 16 and 8 bits
+    let rotate_16 = _mm256_set_epi8(
+        13, 12, 15, 14, 9, 8, 11, 10, 5, 4, 7, 6, 1, 0, 3, 2, 13, 12, 15, 14, 9, 8, 11, 10, 5, 4,
+        7, 6, 1, 0, 3, 2,
+    );
+    let rotate_8 = _mm256_set_epi8(
+        14, 13, 12, 15, 10, 9, 8, 11, 6, 5, 4, 7, 2, 1, 0, 3, 14, 13, 12, 15, 10, 9, 8, 11, 6, 5,
+        4, 7, 2, 1, 0, 3,
+    );
+
+    // 1. a += b; d ^= a; d <<= 16;
+    x[a] = _mm256_add_epi32(x[a], x[b]);
+    x[d] = _mm256_shuffle_epi8(_mm256_xor_si256(x[d], x[a]), rotate_16);
+
+    // 2. c += d; b ^= c; b <<= 12;
+    x[c] = _mm256_add_epi32(x[c], x[d]);
+    let t = _mm256_xor_si256(x[b], x[c]);
+    x[b] = _mm256_or_si256(_mm256_slli_epi32(t, 12), _mm256_srli_epi32(t, 20));
+
+    // 3. a += b; d ^= a; d <<= 8;
+    x[a] = _mm256_add_epi32(x[a], x[b]);
+    x[d] = _mm256_shuffle_epi8(_mm256_xor_si256(x[d], x[a]), rotate_8);
+
+    // 4. c += d; b ^= c; b <<= 7;
+    x[c] = _mm256_add_epi32(x[c], x[d]);
+    let t = _mm256_xor_si256(x[b], x[c]);
+    x[b] = _mm256_or_si256(_mm256_slli_epi32(t, 7), _mm256_srli_epi32(t, 25));
+}
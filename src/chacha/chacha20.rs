@@ -1,21 +1,129 @@
-const KEY_LENGTH: usize = 32;
-const NONCE_LENGTH: usize = 12;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::backend::{backend, Backend};
+
+#[cfg(target_arch = "aarch64")]
+use super::neon;
+#[cfg(not(armadillo_portable_simd))]
+use super::portable;
+#[cfg(armadillo_portable_simd)]
+use super::simd;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::{avx2, sse2};
+use super::{Key, Nonce};
+
 const BLOCK_LENGTH: usize = 64;
 
-pub type Key = [u8; KEY_LENGTH];
-pub type Nonce = [u8; NONCE_LENGTH];
+/// One past the last block a 32-bit counter can address.
+const COUNTER_LIMIT: u64 = 1 << 32;
+
+/// Number of blocks of keystream `ChaCha` generates ahead at most.
+const BUFFER_BLOCKS: usize = 8;
+const BUFFER_LENGTH: usize = BUFFER_BLOCKS * BLOCK_LENGTH;
 
-pub struct ChaCha20Block {
+/// Number of blocks each thread encrypts at a time in the parallel methods.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_BLOCKS: usize = 1024;
+
+///
+/// A single ChaCha block, generic over the number of rounds.  `ROUNDS` must
+/// be even, as the rounds are run as column and diagonal pairs.
+///
+pub struct ChaChaBlock<const ROUNDS: usize> {
     state: [u32; 16],
 }
 
-pub struct ChaCha20 {
+pub type ChaCha20Block = ChaChaBlock<20>;
+pub type ChaCha12Block = ChaChaBlock<12>;
+pub type ChaCha8Block = ChaChaBlock<8>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaChaError {
+    /// The message would run past the last block of the 32-bit counter,
+    /// which would reuse keystream.
+    CounterOverflow,
+    /// A key or nonce was built from a slice of the wrong length.
+    InvalidLength,
+    /// An imported `ChaChaState` was exported from a variant with another
+    /// number of rounds, or points past the end of the block counter.
+    InvalidState,
+}
+
+impl fmt::Display for ChaChaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChaChaError::CounterOverflow => write!(f, "block counter overflow"),
+            ChaChaError::InvalidState => write!(f, "invalid cipher state"),
+            ChaChaError::InvalidLength => write!(f, "invalid key or nonce length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChaChaError {}
+
+///
+/// The ChaCha stream cipher, generic over the number of rounds.  ChaCha20
+/// is the standard variant; ChaCha12 and ChaCha8 trade security margin for
+/// speed and share the same block and quarter round code.
+///
+/// A `ChaCha` owns all of its state, so it is `Send` and `Sync`.  Cloning
+/// copies the keystream position as well: the clone and the original
+/// produce the same keystream, and must not both encrypt different data.
+/// Use `fork_at` to hand out independent parts of the stream instead.
+///
+#[derive(Clone)]
+pub struct ChaCha<const ROUNDS: usize> {
     key: Key,
     nonce: Nonce,
-    counter: u32,
+    initial_counter: u32,
+    // Kept as u64 so that an exhausted counter (2^32) can be represented
+    counter: u64,
+    keystream: [u8; BUFFER_LENGTH],
+    keystream_length: usize,
+    keystream_position: usize,
+    // Whose multi-block kernel generates the keystream
+    backend: Backend,
 }
 
-impl ChaCha20Block {
+pub type ChaCha20 = ChaCha<20>;
+pub type ChaCha12 = ChaCha<12>;
+pub type ChaCha8 = ChaCha<8>;
+
+///
+/// Everything needed to resume a `ChaCha` cipher exactly where it stopped,
+/// as returned by `ChaCha::export_state`.  Buffered keystream is not stored
+/// but regenerated from the position on import.
+///
+/// This contains the key, so it must be stored as carefully as the key
+/// itself.  With the `serde` feature it can be serialized.
+///
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChaChaState {
+    /// Number of rounds of the cipher the state was exported from.
+    pub rounds: usize,
+    pub key: Key,
+    pub nonce: Nonce,
+    /// Block counter the stream started at.
+    pub initial_counter: u32,
+    /// Byte offset in the keystream, counted from the start of the stream.
+    pub position: u64,
+}
+
+///
+/// An iterator over the raw keystream bytes of a `ChaCha` cipher, returned
+/// by `ChaCha::keystream`.  It advances the cipher's position as it goes,
+/// and ends at the end of the block counter instead of panicking.
+///
+pub struct Keystream<'a, const ROUNDS: usize> {
+    cipher: &'a mut ChaCha<ROUNDS>,
+}
+
+impl<const ROUNDS: usize> ChaChaBlock<ROUNDS> {
     ///
     /// The ChaCha20Block constructor initializes the state array with the provided
     /// key and nonce.  The key is 256-bits and the nonce is 64-bits.  The state
@@ -25,7 +133,7 @@ impl ChaCha20Block {
     /// 2. The next eight words (4-11) are taken from the 256-bit key by reading the bytes in little-endian order, in 4-byte chunks.
     /// 3. Word 12 is a block counter.  Since each block is 64 bytes, a 32-bit word allows for encrypting 2^6B * 2^32 = 2^38B = 256GB.
     /// 4. Words 13-15 are a nonce, which should not be repeated for the same key.
-    /// They are taken by reading the bytes in little-endian order, in 4-byte chunks.
+    ///    They are taken by reading the bytes in little-endian order, in 4-byte chunks.
     ///
     /// Visual representation as a matrix of the state array:
     ///
@@ -40,7 +148,15 @@ impl ChaCha20Block {
     ///
     /// [Source](https://datatracker.ietf.org/doc/html/rfc7539#section-2.3)
     ///
-    pub fn new(key: Key, nonce: Nonce, counter: u32) -> Self {
+    pub fn new(key: impl Into<Key>, nonce: impl Into<Nonce>, counter: u32) -> Self {
+        Self::with_key(&key.into(), &nonce.into(), counter)
+    }
+
+    ///
+    /// Like `new`, but borrows the key and nonce, so the hot paths do not
+    /// copy (and wipe) them for every block.
+    ///
+    pub(crate) fn with_key(key: &Key, nonce: &Nonce, counter: u32) -> Self {
         let mut state = [0u32; 16];
         // Add the constants to the state array
         state[0] = 0x61707865;
@@ -49,7 +165,7 @@ impl ChaCha20Block {
         state[3] = 0x6b206574;
 
         // Add the key to the state array
-        for (i, key_part) in key.chunks_exact(4).enumerate() {
+        for (i, key_part) in key.as_bytes().chunks_exact(4).enumerate() {
             state[4 + i] = u32::from_le_bytes(key_part.try_into().unwrap());
         }
 
@@ -57,11 +173,11 @@ impl ChaCha20Block {
         state[12] = counter;
 
         // Add the nonce to the state array
-        for (i, nonce_part) in nonce.chunks_exact(4).enumerate() {
+        for (i, nonce_part) in nonce.as_bytes().chunks_exact(4).enumerate() {
             state[13 + i] = u32::from_le_bytes(nonce_part.try_into().unwrap());
         }
 
-        ChaCha20Block { state }
+        ChaChaBlock { state }
     }
 
     ///
@@ -122,13 +238,29 @@ impl ChaCha20Block {
     /// 2.  80 rounds of quarter rounds are performed on the working state.
     /// 3.  The old state is added to the working state.
     ///
+    /// The reduced-round variants only differ in step 2: ChaCha12 performs
+    /// 48 quarter rounds and ChaCha8 performs 32.
+    ///
     /// [Source](https://datatracker.ietf.org/doc/html/rfc7539#section-2.3.1)
     ///
     pub fn block(&mut self) {
         let old_state = self.state;
 
-        // 80 rounds of quarter rounds
-        for _ in 0..10 {
+        self.rounds();
+
+        // state += working_state
+        self.state.iter_mut().zip(&old_state).for_each(|(x, y)| {
+            *x = x.wrapping_add(*y);
+        });
+    }
+
+    ///
+    /// Runs the quarter rounds of the block function on the state, without
+    /// adding the original state back.  Each iteration is one column round
+    /// and one diagonal round.
+    ///
+    pub(crate) fn rounds(&mut self) {
+        for _ in 0..ROUNDS / 2 {
             self.quarter_round(0, 4, 8, 12);
             self.quarter_round(1, 5, 9, 13);
             self.quarter_round(2, 6, 10, 14);
@@ -138,11 +270,6 @@ impl ChaCha20Block {
             self.quarter_round(2, 7, 8, 13);
             self.quarter_round(3, 4, 9, 14);
         }
-
-        // state += working_state
-        self.state.iter_mut().zip(&old_state).for_each(|(x, y)| {
-            *x = x.wrapping_add(*y);
-        });
     }
 
     ///
@@ -150,14 +277,31 @@ impl ChaCha20Block {
     ///
     pub fn get_keystream(&mut self) -> [u8; BLOCK_LENGTH] {
         self.block();
-        self.state
-            .iter()
-            .flat_map(|x| x.to_le_bytes())
-            .collect::<Vec<u8>>()
-            .try_into()
-            .unwrap()
+
+        let mut keystream = [0u8; BLOCK_LENGTH];
+        keystream
+            .chunks_exact_mut(4)
+            .zip(&self.state)
+            .for_each(|(chunk, x)| chunk.copy_from_slice(&x.to_le_bytes()));
+        keystream
     }
 
+    ///
+    /// Encrypts (or decrypts) one 64-byte block in place, by XORing it with
+    /// this block's keystream.  Like `get_keystream`, this runs the block
+    /// function on the state, so each `ChaChaBlock` is meant for one block.
+    ///
+    pub fn encrypt_block(&mut self, block: &mut [u8; BLOCK_LENGTH]) {
+        let keystream = self.get_keystream();
+        block.iter_mut().zip(&keystream).for_each(|(x, y)| *x ^= y);
+    }
+
+    ///
+    /// XORs the keystream into 16 words that hold the message bytes in
+    /// big-endian order.  Kept for compatibility; the byte order makes it
+    /// easy to misuse.
+    ///
+    #[deprecated(note = "use `encrypt_block`, which works on bytes")]
     pub fn encrypt(&mut self, data: &[u32]) -> [u32; 16] {
         self.block();
 
@@ -177,29 +321,606 @@ impl ChaCha20Block {
     }
 }
 
-impl ChaCha20 {
-    pub fn new(key: Key, nonce: Nonce) -> Self {
-        ChaCha20 {
-            key,
-            nonce,
-            counter: 1,
+impl<const ROUNDS: usize> ChaCha<ROUNDS> {
+    ///
+    /// Creates a cipher whose keystream starts at block 1, as in RFC 8439,
+    /// where block 0 is reserved for the Poly1305 key of the AEAD.
+    ///
+    pub fn new(key: impl Into<Key>, nonce: impl Into<Nonce>) -> Self {
+        Self::new_with_counter(key, nonce, 1)
+    }
+
+    ///
+    /// Creates a cipher whose keystream starts at block `counter`, for
+    /// protocols that start at 0 (like libsodium's IETF API) or resume at
+    /// an arbitrary block.  `position` and `seek` count from this block.
+    ///
+    pub fn new_with_counter(key: impl Into<Key>, nonce: impl Into<Nonce>, counter: u32) -> Self {
+        ChaCha {
+            key: key.into(),
+            nonce: nonce.into(),
+            initial_counter: counter,
+            counter: counter as u64,
+            keystream: [0u8; BUFFER_LENGTH],
+            keystream_length: 0,
+            keystream_position: 0,
+            backend: backend(),
         }
     }
 
+    ///
+    /// Like `new`, but generates the keystream with the kernels of `backend`
+    /// instead of the best one the CPU supports, to test or compare the
+    /// backends.  The keystream is the same whichever backend is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the running CPU does not support `backend`.
+    ///
+    pub fn new_with_backend(
+        key: impl Into<Key>,
+        nonce: impl Into<Nonce>,
+        backend: Backend,
+    ) -> Self {
+        assert!(backend.is_supported(), "backend not supported by this CPU");
+        let mut cipher = Self::new(key, nonce);
+        cipher.backend = backend;
+        cipher
+    }
+
+    ///
+    /// Encrypts (or decrypts) `data` by XORing it with the keystream.
+    ///
+    /// The output always has the same length as the input.  Keystream left
+    /// over from a partially used block is kept for the next call, so
+    /// encrypting a message in chunks gives the same result as encrypting it
+    /// at once.  An empty input returns an empty output and consumes nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data runs past the end of the 32-bit block counter (256
+    /// GiB per nonce).  Use `try_encrypt` to handle this as an error.
+    ///
+    #[cfg(feature = "alloc")]
     pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
-        let blocks = (data.len() + BLOCK_LENGTH - 1) / BLOCK_LENGTH;
-        let keystream = (0..blocks)
-            .flat_map(|i| {
-                let mut block = ChaCha20Block::new(self.key, self.nonce, self.counter + i as u32);
-                block.get_keystream()
-            })
-            .collect::<Vec<u8>>();
-        self.counter += blocks as u32;
+        let mut output = data.to_vec();
+        self.apply_keystream(&mut output);
+        output
+    }
 
-        keystream
-            .iter()
-            .zip(data)
-            .map(|(x, y)| x ^ y)
-            .collect::<Vec<u8>>()
+    ///
+    /// Like `encrypt`, but returns `ChaChaError::CounterOverflow` instead of
+    /// panicking when the data would run past the end of the block counter.
+    /// Nothing is consumed from the keystream in that case.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn try_encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, ChaChaError> {
+        let mut output = data.to_vec();
+        self.try_apply_keystream(&mut output)?;
+        Ok(output)
+    }
+
+    ///
+    /// XORs the keystream into `data` in place, without allocating.  This
+    /// is the same operation as `encrypt`, and shares its keystream
+    /// position.
+    ///
+    /// # Panics
+    ///
+    /// Panics on block counter overflow, like `encrypt`.
+    ///
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.try_apply_keystream(data)
+            .expect("ChaCha block counter overflow");
+    }
+
+    ///
+    /// Like `apply_keystream`, but returns `ChaChaError::CounterOverflow`
+    /// instead of panicking.  `data` is left untouched in that case.
+    ///
+    pub fn try_apply_keystream(&mut self, data: &mut [u8]) -> Result<(), ChaChaError> {
+        if data.len() as u64 > self.remaining() {
+            return Err(ChaChaError::CounterOverflow);
+        }
+
+        let mut offset = 0;
+        while offset < data.len() {
+            if self.keystream_position == self.keystream_length {
+                self.refill(data.len() - offset);
+            }
+
+            let keystream = &self.keystream[self.keystream_position..self.keystream_length];
+            let chunk = &mut data[offset..];
+            let length = keystream.len().min(chunk.len());
+            chunk[..length]
+                .iter_mut()
+                .zip(keystream)
+                .for_each(|(x, y)| *x ^= y);
+
+            offset += length;
+            self.keystream_position += length;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Overwrites `output` with the next keystream bytes, which is the same
+    /// as applying the keystream to zeroed data.  Useful to XOR against
+    /// buffers `apply_keystream` cannot take as a single slice, such as the
+    /// two halves of a ring buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics on block counter overflow, like `encrypt`.
+    ///
+    pub fn fill(&mut self, output: &mut [u8]) {
+        self.try_fill(output)
+            .expect("ChaCha block counter overflow");
+    }
+
+    ///
+    /// Like `fill`, but returns `ChaChaError::CounterOverflow` instead of
+    /// panicking.  `output` is left untouched in that case.
+    ///
+    pub fn try_fill(&mut self, output: &mut [u8]) -> Result<(), ChaChaError> {
+        if output.len() as u64 > self.remaining() {
+            return Err(ChaChaError::CounterOverflow);
+        }
+        output.fill(0);
+        self.try_apply_keystream(output)
+    }
+
+    ///
+    /// Returns an iterator over the keystream bytes, starting at the current
+    /// position.  Bytes taken from the iterator are consumed from the
+    /// cipher, as if they had been used by `apply_keystream`.
+    ///
+    pub fn keystream(&mut self) -> Keystream<'_, ROUNDS> {
+        Keystream { cipher: self }
+    }
+
+    ///
+    /// Like `encrypt`, but splits large inputs into chunks that are
+    /// encrypted on rayon's thread pool.  The result, and the keystream
+    /// position afterwards, are exactly those of `encrypt`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on block counter overflow, like `encrypt`.
+    ///
+    #[cfg(feature = "parallel")]
+    pub fn par_encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut output = data.to_vec();
+        self.par_apply_keystream(&mut output);
+        output
+    }
+
+    ///
+    /// The in-place counterpart of `par_encrypt`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on block counter overflow, like `encrypt`.
+    ///
+    #[cfg(feature = "parallel")]
+    pub fn par_apply_keystream(&mut self, data: &mut [u8]) {
+        self.try_par_apply_keystream(data)
+            .expect("ChaCha block counter overflow");
+    }
+
+    ///
+    /// Like `par_apply_keystream`, but returns `ChaChaError::CounterOverflow`
+    /// instead of panicking.  `data` is left untouched in that case.
+    ///
+    #[cfg(feature = "parallel")]
+    pub fn try_par_apply_keystream(&mut self, data: &mut [u8]) -> Result<(), ChaChaError> {
+        use rayon::prelude::*;
+
+        if data.len() as u64 > self.remaining() {
+            return Err(ChaChaError::CounterOverflow);
+        }
+
+        // Use up the buffered keystream first, so the rest starts on a block
+        let buffered = (self.keystream_length - self.keystream_position).min(data.len());
+        let (head, rest) = data.split_at_mut(buffered);
+        self.try_apply_keystream(head)?;
+
+        let blocks = rest.len() / BLOCK_LENGTH;
+        let (body, tail) = rest.split_at_mut(blocks * BLOCK_LENGTH);
+        let (key, nonce, counter) = (&self.key, &self.nonce, self.counter as u32);
+        let backend = self.backend;
+        body.par_chunks_mut(PARALLEL_CHUNK_BLOCKS * BLOCK_LENGTH)
+            .enumerate()
+            .for_each(|(i, chunk)| {
+                let counter = counter.wrapping_add((i * PARALLEL_CHUNK_BLOCKS) as u32);
+                let mut keystream = [0u8; BUFFER_LENGTH];
+                for (j, piece) in chunk.chunks_mut(BUFFER_LENGTH).enumerate() {
+                    let keystream = &mut keystream[..piece.len()];
+                    let counter = counter.wrapping_add((j * BUFFER_BLOCKS) as u32);
+                    fill_keystream::<ROUNDS>(backend, key, nonce, counter, keystream);
+                    piece
+                        .iter_mut()
+                        .zip(keystream.iter())
+                        .for_each(|(x, y)| *x ^= y);
+                }
+            });
+        self.counter += blocks as u64;
+
+        // The final partial block goes through the buffer as usual
+        self.try_apply_keystream(tail)
+    }
+
+    ///
+    /// Encrypts (or decrypts) `data` as if it sat at byte `offset` of the
+    /// stream, counted from the start, for random access such as sectors
+    /// of an encrypted disk.  The block counter and the position within the
+    /// block follow from the offset.
+    ///
+    /// Unlike `encrypt`, this does not use or move the cipher's own
+    /// position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range runs past the end of the 32-bit block counter.
+    /// Use `try_apply_keystream_at` to handle this as an error.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_at(&self, offset: u64, data: &[u8]) -> Vec<u8> {
+        let mut output = data.to_vec();
+        self.apply_keystream_at(offset, &mut output);
+        output
+    }
+
+    ///
+    /// The in-place counterpart of `encrypt_at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on block counter overflow, like `encrypt_at`.
+    ///
+    pub fn apply_keystream_at(&self, offset: u64, data: &mut [u8]) {
+        self.try_apply_keystream_at(offset, data)
+            .expect("ChaCha block counter overflow");
+    }
+
+    ///
+    /// Like `apply_keystream_at`, but returns `ChaChaError::CounterOverflow`
+    /// instead of panicking.  `data` is left untouched in that case.
+    ///
+    pub fn try_apply_keystream_at(&self, offset: u64, data: &mut [u8]) -> Result<(), ChaChaError> {
+        let limit = (COUNTER_LIMIT - self.initial_counter as u64) * BLOCK_LENGTH as u64;
+        match offset.checked_add(data.len() as u64) {
+            Some(end) if end <= limit => {}
+            _ => return Err(ChaChaError::CounterOverflow),
+        }
+
+        let mut counter = self.initial_counter as u64 + offset / BLOCK_LENGTH as u64;
+        let mut skip = (offset % BLOCK_LENGTH as u64) as usize;
+        let mut keystream = [0u8; BUFFER_LENGTH];
+        let mut done = 0;
+        while done < data.len() {
+            let blocks = (skip + data.len() - done)
+                .div_ceil(BLOCK_LENGTH)
+                .min(BUFFER_BLOCKS);
+            let keystream = &mut keystream[..blocks * BLOCK_LENGTH];
+            fill_keystream::<ROUNDS>(
+                self.backend,
+                &self.key,
+                &self.nonce,
+                counter as u32,
+                keystream,
+            );
+
+            let length = (keystream.len() - skip).min(data.len() - done);
+            data[done..done + length]
+                .iter_mut()
+                .zip(&keystream[skip..])
+                .for_each(|(x, y)| *x ^= y);
+
+            done += length;
+            skip = 0;
+            counter += blocks as u64;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Moves the keystream to byte `offset`, counted from the start of the
+    /// stream, so any byte range can be decrypted without processing what
+    /// comes before it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset lies beyond the end of the 32-bit block counter.
+    ///
+    pub fn seek(&mut self, offset: u64) {
+        let block = self.initial_counter as u64 + offset / BLOCK_LENGTH as u64;
+        let position = (offset % BLOCK_LENGTH as u64) as usize;
+        assert!(
+            block < COUNTER_LIMIT || (block == COUNTER_LIMIT && position == 0),
+            "seek offset beyond the block counter"
+        );
+
+        self.counter = block;
+        self.keystream_length = 0;
+        self.keystream_position = 0;
+        if position != 0 {
+            self.refill(1);
+            self.keystream_position = position;
+        }
+    }
+
+    ///
+    /// Moves the keystream to the start of the block with the given
+    /// (absolute) block counter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter is below the one the stream started at, where
+    /// `position` has no meaning.
+    ///
+    pub fn seek_to_block(&mut self, counter: u32) {
+        assert!(
+            counter >= self.initial_counter,
+            "block counter before the start of the stream"
+        );
+        self.counter = counter as u64;
+        self.keystream_length = 0;
+        self.keystream_position = 0;
+    }
+
+    ///
+    /// Returns a copy of this cipher positioned at the start of the block
+    /// with the given (absolute) block counter, leaving this one untouched.
+    /// Forks at counters far enough apart can encrypt independent data, for
+    /// example one per worker thread, without overlapping keystream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter is below the one the stream started at.
+    ///
+    pub fn fork_at(&self, counter: u32) -> Self {
+        let mut fork = self.clone();
+        fork.seek_to_block(counter);
+        fork
+    }
+
+    ///
+    /// Returns the current byte offset in the keystream, counted from the
+    /// start of the stream.
+    ///
+    pub fn position(&self) -> u64 {
+        // Buffered keystream has already advanced the counter
+        (self.counter - self.initial_counter as u64) * BLOCK_LENGTH as u64
+            + self.keystream_position as u64
+            - self.keystream_length as u64
+    }
+
+    ///
+    /// Captures the key, nonce and keystream position, so encryption can be
+    /// resumed later (for example after an interrupted upload) with
+    /// `import_state`.
+    ///
+    pub fn export_state(&self) -> ChaChaState {
+        ChaChaState {
+            rounds: ROUNDS,
+            key: self.key.clone(),
+            nonce: self.nonce.clone(),
+            initial_counter: self.initial_counter,
+            position: self.position(),
+        }
+    }
+
+    ///
+    /// Recreates a cipher from a state returned by `export_state`.  The
+    /// result continues the keystream exactly where the exported cipher
+    /// was.  Fails with `ChaChaError::InvalidState` if the state belongs to
+    /// a variant with a different number of rounds, or lies past the end of
+    /// the block counter.
+    ///
+    pub fn import_state(state: ChaChaState) -> Result<Self, ChaChaError> {
+        let block = state.initial_counter as u64 + state.position / BLOCK_LENGTH as u64;
+        let aligned = state.position % BLOCK_LENGTH as u64 == 0;
+        if state.rounds != ROUNDS || block > COUNTER_LIMIT || (block == COUNTER_LIMIT && !aligned) {
+            return Err(ChaChaError::InvalidState);
+        }
+
+        let mut cipher = ChaCha::new_with_counter(state.key, state.nonce, state.initial_counter);
+        cipher.seek(state.position);
+        Ok(cipher)
+    }
+
+    ///
+    /// Returns how many bytes of keystream are left before the block
+    /// counter runs out.
+    ///
+    pub(super) fn remaining(&self) -> u64 {
+        (COUNTER_LIMIT - self.counter) * BLOCK_LENGTH as u64
+            + (self.keystream_length - self.keystream_position) as u64
+    }
+
+    ///
+    /// Generates keystream for the next blocks, enough to cover `wanted`
+    /// bytes where the buffer allows, and advances the counter past them.
+    /// Never generates blocks past the end of the counter.
+    ///
+    fn refill(&mut self, wanted: usize) {
+        let blocks = wanted
+            .div_ceil(BLOCK_LENGTH)
+            .clamp(1, BUFFER_BLOCKS)
+            .min((COUNTER_LIMIT - self.counter) as usize);
+        let length = blocks * BLOCK_LENGTH;
+
+        fill_keystream::<ROUNDS>(
+            self.backend,
+            &self.key,
+            &self.nonce,
+            self.counter as u32,
+            &mut self.keystream[..length],
+        );
+        self.keystream_length = length;
+        self.keystream_position = 0;
+        self.counter += blocks as u64;
+    }
+}
+
+impl<const ROUNDS: usize> Iterator for Keystream<'_, ROUNDS> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let cipher = &mut *self.cipher;
+        if cipher.keystream_position == cipher.keystream_length {
+            if cipher.counter == COUNTER_LIMIT {
+                return None;
+            }
+            cipher.refill(BUFFER_LENGTH);
+        }
+
+        let byte = cipher.keystream[cipher.keystream_position];
+        cipher.keystream_position += 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.cipher.remaining();
+        (
+            remaining.try_into().unwrap_or(usize::MAX),
+            remaining.try_into().ok(),
+        )
+    }
+}
+
+impl<const ROUNDS: usize> FusedIterator for Keystream<'_, ROUNDS> {}
+
+///
+/// Fills `output` (a whole number of blocks) with consecutive keystream
+/// blocks starting at `counter`.  The multi-block kernel of `backend` takes
+/// as much as it can, and the single block function does the rest.
+///
+fn fill_keystream<const ROUNDS: usize>(
+    backend: Backend,
+    key: &Key,
+    nonce: &Nonce,
+    counter: u32,
+    output: &mut [u8],
+) {
+    let filled = fill_keystream_batches::<ROUNDS>(backend, key, nonce, counter, output);
+    let counter = counter.wrapping_add((filled / BLOCK_LENGTH) as u32);
+
+    for (i, chunk) in output[filled..].chunks_exact_mut(BLOCK_LENGTH).enumerate() {
+        let mut block = ChaChaBlock::<ROUNDS>::with_key(key, nonce, counter.wrapping_add(i as u32));
+        chunk.copy_from_slice(&block.get_keystream());
+    }
+}
+
+///
+/// Hands as much of `output` as pays off to the multi-block kernel of
+/// `backend`, which the CPU must support.  Returns the number of bytes
+/// filled.
+///
+fn fill_keystream_batches<const ROUNDS: usize>(
+    backend: Backend,
+    key: &Key,
+    nonce: &Nonce,
+    counter: u32,
+    output: &mut [u8],
+) -> usize {
+    // SAFETY: every kernel is only used when its extension was detected,
+    // which the constructors check
+    match backend {
+        // Below four blocks the eight-block batches do not pay off
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx2 | Backend::Avx512 => unsafe {
+            fill_batches::<ROUNDS, { avx2::BLOCKS * BLOCK_LENGTH }>(
+                key,
+                nonce,
+                counter,
+                output,
+                4 * BLOCK_LENGTH,
+                avx2::keystream::<ROUNDS>,
+            )
+        },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Sse2 => unsafe {
+            fill_batches::<ROUNDS, { sse2::BLOCKS * BLOCK_LENGTH }>(
+                key,
+                nonce,
+                counter,
+                output,
+                sse2::BLOCKS * BLOCK_LENGTH,
+                sse2::keystream::<ROUNDS>,
+            )
+        },
+        #[cfg(target_arch = "aarch64")]
+        Backend::Neon => unsafe {
+            fill_batches::<ROUNDS, { neon::BLOCKS * BLOCK_LENGTH }>(
+                key,
+                nonce,
+                counter,
+                output,
+                neon::BLOCKS * BLOCK_LENGTH,
+                neon::keystream::<ROUNDS>,
+            )
+        },
+        #[cfg(not(armadillo_portable_simd))]
+        _ => unsafe {
+            fill_batches::<ROUNDS, { portable::BLOCKS * BLOCK_LENGTH }>(
+                key,
+                nonce,
+                counter,
+                output,
+                portable::BLOCKS * BLOCK_LENGTH,
+                portable::keystream::<ROUNDS>,
+            )
+        },
+        #[cfg(armadillo_portable_simd)]
+        _ => unsafe {
+            fill_batches::<ROUNDS, { simd::BLOCKS * BLOCK_LENGTH }>(
+                key,
+                nonce,
+                counter,
+                output,
+                simd::BLOCKS * BLOCK_LENGTH,
+                simd::keystream::<ROUNDS>,
+            )
+        },
+    }
+}
+
+///
+/// Runs a `kernel` producing `N` bytes of keystream per call over `output`,
+/// for as long as at least `minimum` bytes are left.  A final partial batch
+/// is computed into a scratch buffer and truncated.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports the kernel's extension.
+///
+unsafe fn fill_batches<const ROUNDS: usize, const N: usize>(
+    key: &Key,
+    nonce: &Nonce,
+    counter: u32,
+    output: &mut [u8],
+    minimum: usize,
+    kernel: unsafe fn(&[u32; 16], &mut [u8; N]),
+) -> usize {
+    let mut filled = 0;
+    while output.len() - filled >= minimum {
+        let block_counter = counter.wrapping_add((filled / BLOCK_LENGTH) as u32);
+        let state = ChaChaBlock::<ROUNDS>::with_key(key, nonce, block_counter).state;
+
+        let length = (output.len() - filled).min(N);
+        if length == N {
+            kernel(
+                &state,
+                (&mut output[filled..filled + N]).try_into().unwrap(),
+            );
+        } else {
+            let mut batch = [0u8; N];
+            kernel(&state, &mut batch);
+            output[filled..filled + length].copy_from_slice(&batch[..length]);
+        }
+        filled += length;
     }
+    filled
 }
@@ -0,0 +1,83 @@
+//!
+//! RustCrypto `cipher` trait implementations, so `ChaCha` can be used
+//! wherever a `KeyIvInit + StreamCipher` type is expected.
+//!
+use cipher::consts::{U12, U32};
+use cipher::inout::InOutBuf;
+use cipher::{
+    IvSizeUser, KeyIvInit, KeySizeUser, OverflowError, SeekNum, StreamCipher, StreamCipherError,
+    StreamCipherSeek,
+};
+
+use super::ChaCha;
+
+const BLOCK_LENGTH: u64 = 64;
+
+/// Bytes of keystream generated on the stack at a time.
+const KEYSTREAM_CHUNK_LENGTH: usize = 512;
+
+impl<const ROUNDS: usize> KeySizeUser for ChaCha<ROUNDS> {
+    type KeySize = U32;
+}
+
+impl<const ROUNDS: usize> IvSizeUser for ChaCha<ROUNDS> {
+    type IvSize = U12;
+}
+
+impl<const ROUNDS: usize> KeyIvInit for ChaCha<ROUNDS> {
+    ///
+    /// Starts at block counter 0, like the RustCrypto `chacha20` crate does.
+    /// Note that `ChaCha::new` starts at block 1 instead.
+    ///
+    fn new(key: &cipher::Key<Self>, iv: &cipher::Iv<Self>) -> Self {
+        ChaCha::new_with_counter(<[u8; 32]>::from(*key), <[u8; 12]>::from(*iv), 0)
+    }
+}
+
+impl<const ROUNDS: usize> StreamCipher for ChaCha<ROUNDS> {
+    fn try_apply_keystream_inout(
+        &mut self,
+        mut buf: InOutBuf<'_, '_, u8>,
+    ) -> Result<(), StreamCipherError> {
+        if buf.len() as u64 > self.remaining() {
+            return Err(StreamCipherError);
+        }
+
+        let mut keystream = [0u8; KEYSTREAM_CHUNK_LENGTH];
+        while !buf.is_empty() {
+            let length = buf.len().min(KEYSTREAM_CHUNK_LENGTH);
+            let (mut chunk, rest) = buf.split_at(length);
+            self.fill(&mut keystream[..length]);
+            chunk.xor_in2out(&keystream[..length]);
+            buf = rest;
+        }
+        Ok(())
+    }
+}
+
+impl<const ROUNDS: usize> StreamCipherSeek for ChaCha<ROUNDS> {
+    fn try_current_pos<T: SeekNum>(&self) -> Result<T, OverflowError> {
+        let position = self.position();
+        let byte = position % BLOCK_LENGTH;
+        // SeekNum counts a partially used block as already passed
+        let block = position / BLOCK_LENGTH + (byte != 0) as u64;
+        T::from_block_byte(block, byte as u8, BLOCK_LENGTH as u8)
+    }
+
+    fn try_seek<T: SeekNum>(&mut self, pos: T) -> Result<(), StreamCipherError> {
+        let (block, byte): (u64, u8) = pos
+            .into_block_byte(BLOCK_LENGTH as u8)
+            .map_err(|_| StreamCipherError)?;
+        let end = self.position() + self.remaining();
+        match block
+            .checked_mul(BLOCK_LENGTH)
+            .and_then(|offset| offset.checked_add(byte as u64))
+        {
+            Some(offset) if offset <= end => {
+                self.seek(offset);
+                Ok(())
+            }
+            _ => Err(StreamCipherError),
+        }
+    }
+}
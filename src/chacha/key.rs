@@ -0,0 +1,161 @@
+use core::fmt;
+
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::ChaChaError;
+
+pub(super) const KEY_LENGTH: usize = 32;
+pub(super) const NONCE_LENGTH: usize = 12;
+
+///
+/// A 256-bit ChaCha key.  The bytes are wiped from memory when the key is
+/// dropped, compared in constant time, and never shown by `Debug`.
+///
+/// Functions taking a key accept anything that converts into one, so a
+/// plain `[u8; 32]` can still be passed directly.
+///
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key([u8; KEY_LENGTH]);
+
+///
+/// A 96-bit ChaCha nonce, as used by the IETF variant.  Like `Key`, it is
+/// wiped on drop and redacted in `Debug`.
+///
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nonce([u8; NONCE_LENGTH]);
+
+impl Key {
+    ///
+    /// Generates a new random key with the operating system's secure random
+    /// number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    #[cfg(feature = "os_rng")]
+    pub fn generate() -> Self {
+        let mut key = Key([0u8; KEY_LENGTH]);
+        getrandom::getrandom(&mut key.0).expect("operating system random number generator failed");
+        key
+    }
+
+    pub fn as_bytes(&self) -> &[u8; KEY_LENGTH] {
+        &self.0
+    }
+}
+
+impl Nonce {
+    ///
+    /// Generates a random nonce with the operating system's secure random
+    /// number generator.
+    ///
+    /// A 96-bit nonce is only just large enough to be picked at random: keep
+    /// the number of messages per key well below 2^32, or use `XChaCha20`
+    /// and its 192-bit nonce instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    #[cfg(feature = "os_rng")]
+    pub fn generate() -> Self {
+        let mut nonce = Nonce([0u8; NONCE_LENGTH]);
+        getrandom::getrandom(&mut nonce.0)
+            .expect("operating system random number generator failed");
+        nonce
+    }
+
+    pub fn as_bytes(&self) -> &[u8; NONCE_LENGTH] {
+        &self.0
+    }
+}
+
+impl From<[u8; KEY_LENGTH]> for Key {
+    fn from(bytes: [u8; KEY_LENGTH]) -> Self {
+        Key(bytes)
+    }
+}
+
+impl From<[u8; NONCE_LENGTH]> for Nonce {
+    fn from(bytes: [u8; NONCE_LENGTH]) -> Self {
+        Nonce(bytes)
+    }
+}
+
+///
+/// Fails with `ChaChaError::InvalidLength` unless the slice is exactly 32
+/// bytes long.
+///
+impl TryFrom<&[u8]> for Key {
+    type Error = ChaChaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; KEY_LENGTH] = bytes.try_into().map_err(|_| ChaChaError::InvalidLength)?;
+        Ok(Key(bytes))
+    }
+}
+
+///
+/// Fails with `ChaChaError::InvalidLength` unless the slice is exactly 12
+/// bytes long.
+///
+impl TryFrom<&[u8]> for Nonce {
+    type Error = ChaChaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; NONCE_LENGTH] = bytes.try_into().map_err(|_| ChaChaError::InvalidLength)?;
+        Ok(Nonce(bytes))
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        // Compare in constant time, so no timing information is leaked
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for Key {}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key([REDACTED])")
+    }
+}
+
+impl fmt::Debug for Nonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Nonce([REDACTED])")
+    }
+}
+
+impl Zeroize for Key {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Zeroize for Nonce {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Drop for Nonce {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Key {}
+impl ZeroizeOnDrop for Nonce {}
@@ -0,0 +1,111 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::{ChaChaBlock, Key, Nonce};
+
+const LEGACY_NONCE_LENGTH: usize = 8;
+const BLOCK_LENGTH: usize = 64;
+
+pub type LegacyNonce = [u8; LEGACY_NONCE_LENGTH];
+
+///
+/// The original ChaCha layout from Bernstein's paper, generic over the
+/// number of rounds.  It has a 64-bit block counter and a 64-bit nonce
+/// instead of the IETF 32-bit counter and 96-bit nonce:
+///
+/// ```notrust
+/// cccccccc  cccccccc  cccccccc  cccccccc
+/// kkkkkkkk  kkkkkkkk  kkkkkkkk  kkkkkkkk
+/// kkkkkkkk  kkkkkkkk  kkkkkkkk  kkkkkkkk
+/// bbbbbbbb  bbbbbbbb  nnnnnnnn  nnnnnnnn
+/// ```
+///
+/// c=constant k=key b=blockcount n=nonce
+///
+/// The counter starts at 0, as in the original specification and in
+/// libsodium's `crypto_stream_chacha20`.
+///
+/// [Source](https://cr.yp.to/chacha/chacha-20080128.pdf)
+///
+#[derive(Clone)]
+pub struct ChaChaLegacy<const ROUNDS: usize> {
+    key: Key,
+    nonce: LegacyNonce,
+    counter: u64,
+    keystream: [u8; BLOCK_LENGTH],
+    keystream_position: usize,
+}
+
+pub type ChaCha20Legacy = ChaChaLegacy<20>;
+
+impl<const ROUNDS: usize> ChaChaLegacy<ROUNDS> {
+    pub fn new(key: impl Into<Key>, nonce: LegacyNonce) -> Self {
+        ChaChaLegacy {
+            key: key.into(),
+            nonce,
+            counter: 0,
+            keystream: [0u8; BLOCK_LENGTH],
+            keystream_position: BLOCK_LENGTH,
+        }
+    }
+
+    ///
+    /// Encrypts (or decrypts) `data` by XORing it with the keystream.  Like
+    /// `ChaCha20::encrypt`, unused keystream is carried over to the next
+    /// call.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut output = data.to_vec();
+        self.apply_keystream(&mut output);
+        output
+    }
+
+    ///
+    /// XORs the keystream into `data` in place, without allocating.
+    ///
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        let mut offset = 0;
+        while offset < data.len() {
+            if self.keystream_position == BLOCK_LENGTH {
+                self.next_block();
+            }
+
+            let keystream = &self.keystream[self.keystream_position..];
+            let chunk = &mut data[offset..];
+            let length = keystream.len().min(chunk.len());
+            chunk[..length]
+                .iter_mut()
+                .zip(keystream)
+                .for_each(|(x, y)| *x ^= y);
+
+            offset += length;
+            self.keystream_position += length;
+        }
+    }
+
+    ///
+    /// Moves the keystream to the start of the block with the given block
+    /// counter.
+    ///
+    pub fn seek_to_block(&mut self, counter: u64) {
+        self.counter = counter;
+        self.keystream_position = BLOCK_LENGTH;
+    }
+
+    ///
+    /// The IETF block takes a 32-bit counter and a 96-bit nonce; the high
+    /// counter word simply takes the place of the first nonce word.
+    ///
+    fn next_block(&mut self) {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&((self.counter >> 32) as u32).to_le_bytes());
+        nonce[4..].copy_from_slice(&self.nonce);
+
+        let nonce = Nonce::from(nonce);
+        let mut block = ChaChaBlock::<ROUNDS>::with_key(&self.key, &nonce, self.counter as u32);
+        self.keystream = block.get_keystream();
+        self.keystream_position = 0;
+        self.counter += 1;
+    }
+}
@@ -1,3 +1,29 @@
+#[cfg(feature = "async")]
+mod async_stream;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
 mod chacha20;
+#[cfg(feature = "rustcrypto-compat")]
+mod cipher_traits;
+mod key;
+mod legacy;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+#[cfg(not(armadillo_portable_simd))]
+mod portable;
+#[cfg(armadillo_portable_simd)]
+mod simd;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sse2;
+#[cfg(feature = "std")]
+mod stream;
+mod xchacha20;
 
+#[cfg(feature = "async")]
+pub use async_stream::*;
 pub use chacha20::*;
+pub use key::*;
+pub use legacy::*;
+#[cfg(feature = "std")]
+pub use stream::*;
+pub use xchacha20::*;
@@ -0,0 +1,75 @@
+//!
+//! NEON implementation of the ChaCha block function, computing four blocks
+//! at once.  Each 128-bit register holds the same state word for all four
+//! blocks, so the quarter rounds are plain lane-wise operations.
+//!
+use core::arch::aarch64::*;
+
+pub(super) const BLOCKS: usize = 4;
+
+///
+/// Computes the keystream for the four consecutive blocks starting at the
+/// counter in `state[12]`.  The counter wraps per block like the scalar
+/// implementation's 32-bit word does.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports NEON.
+///
+#[target_feature(enable = "neon")]
+pub(super) unsafe fn keystream<const ROUNDS: usize>(state: &[u32; 16], output: &mut [u8; 256]) {
+    let mut x = [vdupq_n_u32(0); 16];
+    x.iter_mut()
+        .zip(state)
+        .for_each(|(x, &word)| *x = vdupq_n_u32(word));
+    let offsets = [0u32, 1, 2, 3];
+    x[12] = vaddq_u32(x[12], vld1q_u32(offsets.as_ptr()));
+    let original = x;
+
+    for _ in 0..ROUNDS / 2 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    // state += working_state, then transpose words back into blocks
+    let mut lanes = [[0u32; BLOCKS]; 16];
+    for ((lane, x), original) in lanes.iter_mut().zip(&x).zip(&original) {
+        vst1q_u32(lane.as_mut_ptr(), vaddq_u32(*x, *original));
+    }
+    for (block, chunk) in output.chunks_exact_mut(64).enumerate() {
+        chunk
+            .chunks_exact_mut(4)
+            .zip(&lanes)
+            .for_each(|(bytes, lane)| bytes.copy_from_slice(&lane[block].to_le_bytes()));
+    }
+}
+
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn quarter_round(x: &mut [uint32x4_t; 16], a: usize, b: usize, c: usize, d: usize) {
+    // 1. a += b; d ^= a; d <<= 16;
+    x[a] = vaddq_u32(x[a], x[b]);
+    let t = veorq_u32(x[d], x[a]);
+    x[d] = vreinterpretq_u32_u16(vrev32q_u16(vreinterpretq_u16_u32(t)));
+
+    // 2. c += d; b ^= c; b <<= 12;
+    x[c] = vaddq_u32(x[c], x[d]);
+    let t = veorq_u32(x[b], x[c]);
+    x[b] = vsriq_n_u32::<20>(vshlq_n_u32::<12>(t), t);
+
+    // 3. a += b; d ^= a; d <<= 8;
+    x[a] = vaddq_u32(x[a], x[b]);
+    let t = veorq_u32(x[d], x[a]);
+    x[d] = vsriq_n_u32::<24>(vshlq_n_u32::<8>(t), t);
+
+    // 4. c += d; b ^= c; b <<= 7;
+    x[c] = vaddq_u32(x[c], x[d]);
+    let t = veorq_u32(x[b], x[c]);
+    x[b] = vsriq_n_u32::<25>(vshlq_n_u32::<7>(t), t);
+}
@@ -0,0 +1,92 @@
+//!
+//! Portable implementation of the ChaCha block function, computing four
+//! blocks at once.  The blocks are interleaved word by word, so every step of
+//! a quarter round is the same operation on four independent lanes, which
+//! the compiler can vectorize without any intrinsics.
+//!
+pub(super) const BLOCKS: usize = 4;
+
+type Lanes = [u32; BLOCKS];
+
+///
+/// Computes the keystream for the four consecutive blocks starting at the
+/// counter in `state[12]`.  The counter wraps per block like the single
+/// block function's 32-bit word does.
+///
+pub(super) fn keystream<const ROUNDS: usize>(state: &[u32; 16], output: &mut [u8; 256]) {
+    let mut x = [[0u32; BLOCKS]; 16];
+    x.iter_mut()
+        .zip(state)
+        .for_each(|(x, &word)| *x = [word; BLOCKS]);
+    x[12]
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, counter)| *counter = counter.wrapping_add(i as u32));
+    let original = x;
+
+    for _ in 0..ROUNDS / 2 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    // state += working_state, then transpose words back into blocks
+    for (block, chunk) in output.chunks_exact_mut(64).enumerate() {
+        chunk
+            .chunks_exact_mut(4)
+            .zip(x.iter().zip(&original))
+            .for_each(|(bytes, (x, original))| {
+                bytes.copy_from_slice(&x[block].wrapping_add(original[block]).to_le_bytes())
+            });
+    }
+}
+
+#[inline(always)]
+fn quarter_round(x: &mut [Lanes; 16], a: usize, b: usize, c: usize, d: usize) {
+    let (mut xa, mut xb, mut xc, mut xd) = (x[a], x[b], x[c], x[d]);
+
+    // 1. a += b; d ^= a; d <<= 16;
+    xa = add(xa, xb);
+    xd = xor_rotate::<16>(xd, xa);
+
+    // 2. c += d; b ^= c; b <<= 12;
+    xc = add(xc, xd);
+    xb = xor_rotate::<12>(xb, xc);
+
+    // 3. a += b; d ^= a; d <<= 8;
+    xa = add(xa, xb);
+    xd = xor_rotate::<8>(xd, xa);
+
+    // 4. c += d; b ^= c; b <<= 7;
+    xc = add(xc, xd);
+    xb = xor_rotate::<7>(xb, xc);
+
+    (x[a], x[b], x[c], x[d]) = (xa, xb, xc, xd);
+}
+
+/// a + b, lane by lane.
+#[inline(always)]
+fn add(a: Lanes, b: Lanes) -> Lanes {
+    [
+        a[0].wrapping_add(b[0]),
+        a[1].wrapping_add(b[1]),
+        a[2].wrapping_add(b[2]),
+        a[3].wrapping_add(b[3]),
+    ]
+}
+
+/// (a ^ b) <<< N, lane by lane.
+#[inline(always)]
+fn xor_rotate<const N: u32>(a: Lanes, b: Lanes) -> Lanes {
+    [
+        (a[0] ^ b[0]).rotate_left(N),
+        (a[1] ^ b[1]).rotate_left(N),
+        (a[2] ^ b[2]).rotate_left(N),
+        (a[3] ^ b[3]).rotate_left(N),
+    ]
+}
@@ -0,0 +1,70 @@
+//!
+//! `std::simd` implementation of the ChaCha block function, computing eight
+//! blocks at once.  It has the same vertical layout as the AVX2 kernel, but
+//! leaves the choice of instructions to the compiler, so it vectorizes on
+//! any architecture with SIMD registers.  Only built on nightly, with the
+//! `portable-simd` feature.
+//!
+use core::simd::u32x8;
+
+pub(super) const BLOCKS: usize = 8;
+
+///
+/// Computes the keystream for the eight consecutive blocks starting at the
+/// counter in `state[12]`.  The counter wraps per block like the single
+/// block function's 32-bit word does.
+///
+pub(super) fn keystream<const ROUNDS: usize>(state: &[u32; 16], output: &mut [u8; 512]) {
+    let mut x = state.map(u32x8::splat);
+    x[12] += u32x8::from_array([0, 1, 2, 3, 4, 5, 6, 7]);
+    let original = x;
+
+    for _ in 0..ROUNDS / 2 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    // state += working_state, then transpose words back into blocks
+    let words = core::array::from_fn::<_, 16, _>(|i| (x[i] + original[i]).to_array());
+    for (block, chunk) in output.chunks_exact_mut(64).enumerate() {
+        chunk
+            .chunks_exact_mut(4)
+            .zip(&words)
+            .for_each(|(bytes, word)| bytes.copy_from_slice(&word[block].to_le_bytes()));
+    }
+}
+
+#[inline(always)]
+fn quarter_round(x: &mut [u32x8; 16], a: usize, b: usize, c: usize, d: usize) {
+    let (mut xa, mut xb, mut xc, mut xd) = (x[a], x[b], x[c], x[d]);
+
+    // 1. a += b; d ^= a; d <<= 16;
+    xa += xb;
+    xd = rotate::<16>(xd ^ xa);
+
+    // 2. c += d; b ^= c; b <<= 12;
+    xc += xd;
+    xb = rotate::<12>(xb ^ xc);
+
+    // 3. a += b; d ^= a; d <<= 8;
+    xa += xb;
+    xd = rotate::<8>(xd ^ xa);
+
+    // 4. c += d; b ^= c; b <<= 7;
+    xc += xd;
+    xb = rotate::<7>(xb ^ xc);
+
+    (x[a], x[b], x[c], x[d]) = (xa, xb, xc, xd);
+}
+
+/// x <<< N, lane by lane.
+#[inline(always)]
+fn rotate<const N: u32>(x: u32x8) -> u32x8 {
+    (x << u32x8::splat(N)) | (x >> u32x8::splat(32 - N))
+}
@@ -0,0 +1,79 @@
+//!
+//! SSE2 implementation of the ChaCha block function, computing four blocks
+//! at once.  Each 128-bit register holds the same state word for all four
+//! blocks, so the quarter rounds are plain lane-wise operations.
+//!
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+pub(super) const BLOCKS: usize = 4;
+
+///
+/// Computes the keystream for the four consecutive blocks starting at the
+/// counter in `state[12]`.  The counter wraps per block like the scalar
+/// implementation's 32-bit word does.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports SSE2.
+///
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn keystream<const ROUNDS: usize>(state: &[u32; 16], output: &mut [u8; 256]) {
+    let mut x = [_mm_setzero_si128(); 16];
+    x.iter_mut()
+        .zip(state)
+        .for_each(|(x, &word)| *x = _mm_set1_epi32(word as i32));
+    x[12] = _mm_add_epi32(x[12], _mm_setr_epi32(0, 1, 2, 3));
+    let original = x;
+
+    for _ in 0..ROUNDS / 2 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    // state += working_state, then transpose words back into blocks
+    let mut lanes = [[0u32; BLOCKS]; 16];
+    for ((lane, x), original) in lanes.iter_mut().zip(&x).zip(&original) {
+        let sum = _mm_add_epi32(*x, *original);
+        _mm_storeu_si128(lane.as_mut_ptr() as *mut __m128i, sum);
+    }
+    for (block, chunk) in output.chunks_exact_mut(64).enumerate() {
+        chunk
+            .chunks_exact_mut(4)
+            .zip(&lanes)
+            .for_each(|(bytes, lane)| bytes.copy_from_slice(&lane[block].to_le_bytes()));
+    }
+}
+
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn quarter_round(x: &mut [__m128i; 16], a: usize, b: usize, c: usize, d: usize) {
+    // 1. a += b; d ^= a; d <<= 16;
+    x[a] = _mm_add_epi32(x[a], x[b]);
+    let t = _mm_xor_si128(x[d], x[a]);
+    // Swapping the 16-bit halves of every lane is a rotation by 16
+    x[d] = _mm_shufflehi_epi16(_mm_shufflelo_epi16(t, 0b10_11_00_01), 0b10_11_00_01);
+
+    // 2. c += d; b ^= c; b <<= 12;
+    x[c] = _mm_add_epi32(x[c], x[d]);
+    let t = _mm_xor_si128(x[b], x[c]);
+    x[b] = _mm_or_si128(_mm_slli_epi32(t, 12), _mm_srli_epi32(t, 20));
+
+    // 3. a += b; d ^= a; d <<= 8;
+    x[a] = _mm_add_epi32(x[a], x[b]);
+    let t = _mm_xor_si128(x[d], x[a]);
+    x[d] = _mm_or_si128(_mm_slli_epi32(t, 8), _mm_srli_epi32(t, 24));
+
+    // 4. c += d; b ^= c; b <<= 7;
+    x[c] = _mm_add_epi32(x[c], x[d]);
+    let t = _mm_xor_si128(x[b], x[c]);
+    x[b] = _mm_or_si128(_mm_slli_epi32(t, 7), _mm_srli_epi32(t, 25));
+}
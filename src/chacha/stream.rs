@@ -0,0 +1,182 @@
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use super::{ChaCha, ChaChaError};
+
+///
+/// Wraps a reader and decrypts (or encrypts) everything read through it
+/// with a `ChaCha` cipher, so a `File` or `TcpStream` can be wrapped
+/// without chunking the data by hand.
+///
+pub struct ChaChaReader<R, const ROUNDS: usize> {
+    inner: R,
+    cipher: ChaCha<ROUNDS>,
+}
+
+pub type ChaCha20Reader<R> = ChaChaReader<R, 20>;
+
+///
+/// Wraps a writer and encrypts (or decrypts) everything written through it
+/// with a `ChaCha` cipher.
+///
+/// Data accepted by `write` is encrypted right away.  Whatever the inner
+/// writer does not take at once, for example because it would block, is
+/// kept and written before any new data, and on `flush`, `into_inner` or
+/// drop.
+///
+pub struct ChaChaWriter<W: Write, const ROUNDS: usize> {
+    inner: Option<W>,
+    cipher: ChaCha<ROUNDS>,
+    pending: Vec<u8>,
+    pending_position: usize,
+}
+
+pub type ChaCha20Writer<W> = ChaChaWriter<W, 20>;
+
+/// Most bytes a single `write` call encrypts.
+const WRITE_CHUNK_LENGTH: usize = 64 * 1024;
+
+pub(super) fn keystream_error(error: ChaChaError) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+impl<R: Read, const ROUNDS: usize> ChaChaReader<R, ROUNDS> {
+    pub fn new(inner: R, cipher: ChaCha<ROUNDS>) -> Self {
+        ChaChaReader { inner, cipher }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    ///
+    /// Reading from the inner reader directly skips the cipher, and
+    /// desynchronizes the keystream from the data.
+    ///
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, const ROUNDS: usize> Read for ChaChaReader<R, ROUNDS> {
+    ///
+    /// Fails with `ErrorKind::Other` if the data runs past the end of the
+    /// block counter.
+    ///
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        self.cipher
+            .try_apply_keystream(&mut buf[..length])
+            .map_err(keystream_error)?;
+        Ok(length)
+    }
+}
+
+impl<W: Write, const ROUNDS: usize> ChaChaWriter<W, ROUNDS> {
+    pub fn new(inner: W, cipher: ChaCha<ROUNDS>) -> Self {
+        ChaChaWriter {
+            inner: Some(inner),
+            cipher,
+            pending: Vec::new(),
+            pending_position: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    ///
+    /// Writing to the inner writer directly skips the cipher, and may
+    /// interleave with encrypted data that is still pending.
+    ///
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    ///
+    /// Writes out any pending encrypted data, and returns the inner writer.
+    ///
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.write_pending()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    ///
+    /// Writes out the pending data.  What the inner writer accepts before
+    /// an error is dropped from it, so a retry continues where this left
+    /// off instead of writing those bytes twice.
+    ///
+    fn write_pending(&mut self) -> io::Result<()> {
+        let inner = self.inner.as_mut().unwrap();
+        while self.pending_position < self.pending.len() {
+            match inner.write(&self.pending[self.pending_position..]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => self.pending_position += written,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        self.pending.clear();
+        self.pending_position = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write, const ROUNDS: usize> Write for ChaChaWriter<W, ROUNDS> {
+    ///
+    /// Fails with `ErrorKind::Other`, without consuming anything, if the data
+    /// runs past the end of the block counter.  Errors of the inner writer
+    /// also consume nothing, except `WouldBlock` and `Interrupted`: the data
+    /// is then accepted and kept pending.
+    ///
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_pending()?;
+
+        let length = buf.len().min(WRITE_CHUNK_LENGTH);
+        let position = self.cipher.position();
+        let mut encrypted = buf[..length].to_vec();
+        self.cipher
+            .try_apply_keystream(&mut encrypted)
+            .map_err(keystream_error)?;
+
+        let written = match self.inner.as_mut().unwrap().write(&encrypted) {
+            Ok(written) => written,
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+                ) =>
+            {
+                0
+            }
+            Err(error) => {
+                // Nothing was written, so rewind the keystream for the
+                // caller to retry the same data
+                self.cipher.seek(position);
+                return Err(error);
+            }
+        };
+        self.pending.extend_from_slice(&encrypted[written..]);
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write, const ROUNDS: usize> Drop for ChaChaWriter<W, ROUNDS> {
+    fn drop(&mut self) {
+        // Errors cannot be reported from drop; use `into_inner` or `flush`
+        // to see them
+        if self.inner.is_some() {
+            let _ = self.write_pending();
+        }
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::{ChaCha, ChaChaBlock, ChaChaError, Key, Nonce};
+
+const EXTENDED_NONCE_LENGTH: usize = 24;
+
+pub type ExtendedNonce = [u8; EXTENDED_NONCE_LENGTH];
+
+///
+/// ChaCha with a 192-bit nonce, generic over the number of rounds like
+/// `ChaCha`.  The subkey is derived with the same number of rounds.
+///
+#[derive(Clone)]
+pub struct XChaCha<const ROUNDS: usize> {
+    cipher: ChaCha<ROUNDS>,
+}
+
+pub type XChaCha20 = XChaCha<20>;
+pub type XChaCha12 = XChaCha<12>;
+pub type XChaCha8 = XChaCha<8>;
+
+///
+/// HChaCha20 derives a 256-bit subkey from a key and a 128-bit nonce.  The
+/// nonce takes the place of the block counter and nonce words, the 80
+/// quarter rounds are run without the final addition, and the first and
+/// last rows of the state form the output.
+///
+/// The output is a uniformly random key for any other construction, which
+/// makes it usable for XChaCha-style nonce extension and subkey derivation.
+/// The key it is derived from must itself be secret and uniformly random.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.2)
+///
+pub fn hchacha20(key: impl Into<Key>, nonce: [u8; 16]) -> [u8; 32] {
+    hchacha::<20>(&key.into(), nonce)
+}
+
+///
+/// HChaCha with any number of rounds, as used by the reduced-round XChaCha
+/// variants.
+///
+pub(crate) fn hchacha<const ROUNDS: usize>(key: &Key, nonce: [u8; 16]) -> [u8; 32] {
+    let counter = u32::from_le_bytes(nonce[0..4].try_into().unwrap());
+    let nonce = Nonce::try_from(&nonce[4..]).unwrap();
+    let mut block = ChaChaBlock::<ROUNDS>::with_key(key, &nonce, counter);
+
+    block.rounds();
+
+    let state = block.get_state();
+    let mut output = [0u8; 32];
+    output
+        .chunks_exact_mut(4)
+        .zip(state[0..4].iter().chain(&state[12..16]))
+        .for_each(|(bytes, word)| bytes.copy_from_slice(&word.to_le_bytes()));
+    output
+}
+
+impl<const ROUNDS: usize> XChaCha<ROUNDS> {
+    ///
+    /// XChaCha20 runs HChaCha20 over the key and the first 16 nonce bytes,
+    /// and uses the result as the key of a regular ChaCha20.  Its nonce is
+    /// four zero bytes followed by the last 8 bytes of the extended nonce.
+    ///
+    /// The 192-bit nonce is large enough to be picked at random for every
+    /// message.  Like `ChaCha20::new`, the block counter starts at 1.
+    ///
+    /// [Source](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.3)
+    ///
+    pub fn new(key: impl Into<Key>, nonce: ExtendedNonce) -> Self {
+        Self::new_with_counter(key, nonce, 1)
+    }
+
+    ///
+    /// Like `new`, but the keystream starts at block `counter`, for
+    /// constructions such as Adiantum that start at 0.
+    ///
+    pub fn new_with_counter(key: impl Into<Key>, nonce: ExtendedNonce, counter: u32) -> Self {
+        let subkey = hchacha::<ROUNDS>(&key.into(), nonce[..16].try_into().unwrap());
+
+        let mut chacha_nonce = [0u8; 12];
+        chacha_nonce[4..].copy_from_slice(&nonce[16..]);
+
+        XChaCha {
+            cipher: ChaCha::new_with_counter(subkey, chacha_nonce, counter),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        self.cipher.encrypt(data)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn try_encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, ChaChaError> {
+        self.cipher.try_encrypt(data)
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.cipher.apply_keystream(data)
+    }
+
+    pub fn try_apply_keystream(&mut self, data: &mut [u8]) -> Result<(), ChaChaError> {
+        self.cipher.try_apply_keystream(data)
+    }
+}
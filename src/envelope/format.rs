@@ -0,0 +1,184 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::aead::{AeadError, ChaCha20Poly1305, TAG_LENGTH};
+
+use super::{DataKey, EnvelopeError, KeyWrapper};
+
+/// Version byte every serialized envelope starts with.
+const VERSION: u8 = 1;
+
+///
+/// A payload sealed under its own data key, together with the data key
+/// wrapped by a `KeyWrapper` and that wrapper's key id.
+///
+/// Serialized, it is the version byte, the key id and the wrapped key,
+/// each after its length (one byte, then two bytes big-endian), and
+/// finally the ChaCha20-Poly1305 ciphertext and tag.  Everything before the
+/// ciphertext is authenticated along with the caller's associated data, so
+/// no part of the envelope can be swapped for another's.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    key_id: String,
+    wrapped_key: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl Envelope {
+    ///
+    /// Seals `plaintext` and `aad` under a new random data key, wrapped by
+    /// `wrapper`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    #[cfg(feature = "os_rng")]
+    pub fn seal<W: KeyWrapper + ?Sized>(
+        wrapper: &W,
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Self, EnvelopeError> {
+        Self::seal_with_data_key(wrapper, DataKey::generate(), aad, plaintext)
+    }
+
+    ///
+    /// Seals `plaintext` and `aad` under `data_key`, wrapped by `wrapper`.
+    /// The data key must be new and random, such as one a key management
+    /// service generated, and is used for this envelope only.
+    ///
+    /// Fails with `EnvelopeError::InvalidEncoding` if the key id is longer
+    /// than 255 bytes or the wrapped key longer than 65535, and with what
+    /// the wrapper returns if wrapping fails.
+    ///
+    pub fn seal_with_data_key<W: KeyWrapper + ?Sized>(
+        wrapper: &W,
+        data_key: DataKey,
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Self, EnvelopeError> {
+        let mut envelope = Envelope {
+            key_id: String::from(wrapper.key_id()),
+            wrapped_key: wrapper.wrap(&data_key)?,
+            ciphertext: Vec::new(),
+        };
+        let header = envelope.header()?;
+
+        let aead = ChaCha20Poly1305::new(data_key.key().clone());
+        envelope.ciphertext = aead.seal([0u8; 12], &associated_data(&header, aad), plaintext);
+        Ok(envelope)
+    }
+
+    ///
+    /// Unwraps the data key with `wrapper` and opens the payload.  The
+    /// plaintext is only returned if the envelope and `aad` are authentic.
+    ///
+    /// Fails with `EnvelopeError::KeyIdMismatch` if the envelope names
+    /// another key than the wrapper's, with what the wrapper returns if
+    /// unwrapping fails, and with `EnvelopeError::InvalidTag` if the
+    /// payload was changed.
+    ///
+    pub fn open<W: KeyWrapper + ?Sized>(
+        &self,
+        wrapper: &W,
+        aad: &[u8],
+    ) -> Result<Vec<u8>, EnvelopeError> {
+        if wrapper.key_id() != self.key_id {
+            return Err(EnvelopeError::KeyIdMismatch);
+        }
+        let header = self.header()?;
+        let data_key = wrapper.unwrap(&self.wrapped_key)?;
+
+        let aead = ChaCha20Poly1305::new(data_key.key().clone());
+        aead.open([0u8; 12], &associated_data(&header, aad), &self.ciphertext)
+            .map_err(|error| match error {
+                AeadError::InvalidTag => EnvelopeError::InvalidTag,
+                _ => EnvelopeError::InvalidEncoding,
+            })
+    }
+
+    ///
+    /// The id of the key the data key is wrapped with, which tells the
+    /// reader which wrapper to open the envelope with.
+    ///
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn wrapped_key(&self) -> &[u8] {
+        &self.wrapped_key
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self
+            .header()
+            .expect("lengths were checked when the envelope was made");
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    ///
+    /// Parses a serialized envelope.  Fails with
+    /// `EnvelopeError::InvalidEncoding` if it is cut short, has another
+    /// version, or its key id is not UTF-8.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let (&version, rest) = bytes.split_first().ok_or(EnvelopeError::InvalidEncoding)?;
+        if version != VERSION {
+            return Err(EnvelopeError::InvalidEncoding);
+        }
+
+        let (&key_id_length, rest) = rest.split_first().ok_or(EnvelopeError::InvalidEncoding)?;
+        let (key_id, rest) = split(rest, key_id_length as usize)?;
+        let key_id = core::str::from_utf8(key_id).map_err(|_| EnvelopeError::InvalidEncoding)?;
+
+        let (wrapped_key_length, rest) = split(rest, 2)?;
+        let wrapped_key_length = u16::from_be_bytes([wrapped_key_length[0], wrapped_key_length[1]]);
+        let (wrapped_key, ciphertext) = split(rest, wrapped_key_length as usize)?;
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(EnvelopeError::InvalidEncoding);
+        }
+
+        Ok(Envelope {
+            key_id: String::from(key_id),
+            wrapped_key: wrapped_key.to_vec(),
+            ciphertext: ciphertext.to_vec(),
+        })
+    }
+
+    ///
+    /// Serializes everything before the ciphertext.
+    ///
+    fn header(&self) -> Result<Vec<u8>, EnvelopeError> {
+        let key_id_length =
+            u8::try_from(self.key_id.len()).map_err(|_| EnvelopeError::InvalidEncoding)?;
+        let wrapped_key_length =
+            u16::try_from(self.wrapped_key.len()).map_err(|_| EnvelopeError::InvalidEncoding)?;
+
+        let mut header = Vec::with_capacity(4 + self.key_id.len() + self.wrapped_key.len());
+        header.push(VERSION);
+        header.push(key_id_length);
+        header.extend_from_slice(self.key_id.as_bytes());
+        header.extend_from_slice(&wrapped_key_length.to_be_bytes());
+        header.extend_from_slice(&self.wrapped_key);
+        Ok(header)
+    }
+}
+
+///
+/// The payload's associated data: the envelope header, then the caller's.
+///
+fn associated_data(header: &[u8], aad: &[u8]) -> Vec<u8> {
+    let mut associated_data = Vec::with_capacity(header.len() + aad.len());
+    associated_data.extend_from_slice(header);
+    associated_data.extend_from_slice(aad);
+    associated_data
+}
+
+fn split(bytes: &[u8], length: usize) -> Result<(&[u8], &[u8]), EnvelopeError> {
+    if bytes.len() < length {
+        return Err(EnvelopeError::InvalidEncoding);
+    }
+    Ok(bytes.split_at(length))
+}
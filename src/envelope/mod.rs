@@ -0,0 +1,44 @@
+//!
+//! Envelope encryption, as key management services use it: every payload
+//! is sealed under a fresh data key, and only the data key is wrapped by a
+//! key encryption key that never leaves the `KeyWrapper`, which can be a
+//! local key or a remote service.
+//!
+mod format;
+mod wrapper;
+
+pub use format::*;
+pub use wrapper::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The serialized envelope is malformed, or its key id is longer than
+    /// 255 bytes.
+    InvalidEncoding,
+    /// The envelope was wrapped by a key other than the wrapper's.
+    KeyIdMismatch,
+    /// The key wrapper could not wrap the data key.
+    WrapFailed,
+    /// The key wrapper could not unwrap the data key, because it was not
+    /// wrapped under this key or was changed.
+    UnwrapFailed,
+    /// The tag did not match the payload, header and associated data.
+    InvalidTag,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::InvalidEncoding => write!(f, "invalid envelope encoding"),
+            EnvelopeError::KeyIdMismatch => write!(f, "envelope key id mismatch"),
+            EnvelopeError::WrapFailed => write!(f, "data key wrapping failed"),
+            EnvelopeError::UnwrapFailed => write!(f, "data key unwrapping failed"),
+            EnvelopeError::InvalidTag => write!(f, "envelope authentication failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EnvelopeError {}
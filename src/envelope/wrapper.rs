@@ -0,0 +1,114 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::aead::ChaCha20Poly1305Siv;
+use crate::chacha::Key;
+
+use super::EnvelopeError;
+
+pub const DATA_KEY_LENGTH: usize = 32;
+
+///
+/// The 256-bit key a single envelope's payload is sealed with.  Like `Key`,
+/// it is wiped on drop, compared in constant time and redacted in `Debug`.
+///
+/// It is not `Clone`, and sealing an envelope consumes it: the payload is
+/// encrypted under a fixed nonce, which is only safe for a key that seals
+/// one payload.
+///
+#[derive(Debug, PartialEq, Eq)]
+pub struct DataKey(Key);
+
+impl DataKey {
+    ///
+    /// Generates a new random data key with the operating system's secure
+    /// random number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    #[cfg(feature = "os_rng")]
+    pub fn generate() -> Self {
+        DataKey(Key::generate())
+    }
+
+    pub fn as_bytes(&self) -> &[u8; DATA_KEY_LENGTH] {
+        self.0.as_bytes()
+    }
+
+    pub(super) fn key(&self) -> &Key {
+        &self.0
+    }
+}
+
+impl From<[u8; DATA_KEY_LENGTH]> for DataKey {
+    fn from(bytes: [u8; DATA_KEY_LENGTH]) -> Self {
+        DataKey(Key::from(bytes))
+    }
+}
+
+///
+/// Wraps and unwraps data keys under a key encryption key, the part of
+/// envelope encryption that a key management service takes over.
+///
+/// Implementations for remote services map their own failures, such as a
+/// denied request, onto `EnvelopeError::WrapFailed` and
+/// `EnvelopeError::UnwrapFailed`.
+///
+pub trait KeyWrapper {
+    ///
+    /// Names the key encryption key.  It is stored in the envelope, so the
+    /// reader can pick the wrapper, and must be at most 255 bytes long.
+    ///
+    fn key_id(&self) -> &str;
+
+    fn wrap(&self, data_key: &DataKey) -> Result<Vec<u8>, EnvelopeError>;
+
+    fn unwrap(&self, wrapped_key: &[u8]) -> Result<DataKey, EnvelopeError>;
+}
+
+///
+/// A `KeyWrapper` holding the key encryption key locally.  Data keys are
+/// wrapped with `ChaCha20Poly1305Siv`, which needs no nonce to be safe,
+/// with the key id as associated data.
+///
+pub struct LocalKeyWrapper {
+    key_id: String,
+    siv: ChaCha20Poly1305Siv,
+}
+
+impl LocalKeyWrapper {
+    pub fn new(key_id: impl Into<String>, key_encryption_key: impl Into<Key>) -> Self {
+        LocalKeyWrapper {
+            key_id: key_id.into(),
+            siv: ChaCha20Poly1305Siv::new(key_encryption_key),
+        }
+    }
+}
+
+impl KeyWrapper for LocalKeyWrapper {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn wrap(&self, data_key: &DataKey) -> Result<Vec<u8>, EnvelopeError> {
+        Ok(self
+            .siv
+            .seal([0u8; 12], self.key_id.as_bytes(), data_key.as_bytes()))
+    }
+
+    fn unwrap(&self, wrapped_key: &[u8]) -> Result<DataKey, EnvelopeError> {
+        let mut bytes = self
+            .siv
+            .open([0u8; 12], self.key_id.as_bytes(), wrapped_key)
+            .map_err(|_| EnvelopeError::UnwrapFailed)?;
+        let data_key = <[u8; DATA_KEY_LENGTH]>::try_from(&bytes[..])
+            .map(DataKey::from)
+            .map_err(|_| EnvelopeError::UnwrapFailed);
+        bytes.zeroize();
+        data_key
+    }
+}
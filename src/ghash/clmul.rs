@@ -0,0 +1,48 @@
+//!
+//! GHASH with the x86 carry-less multiplication instruction, which gives
+//! the four 64-bit partial products of a field multiplication directly.
+//! The reduction is the software one, on 128-bit integers.
+//!
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::gf128::reduce;
+
+///
+/// Absorbs `data`, a whole number of 16-byte blocks, into `accumulator`.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports PCLMULQDQ and SSE2.
+///
+#[target_feature(enable = "pclmulqdq,sse2")]
+pub(super) unsafe fn blocks(h: u128, accumulator: &mut u128, data: &[u8]) {
+    let h = load(h);
+    for block in data.chunks_exact(16) {
+        let x = load(*accumulator ^ u128::from_be_bytes(block.try_into().unwrap()));
+        let low = store(_mm_clmulepi64_si128(x, h, 0x00));
+        let high = store(_mm_clmulepi64_si128(x, h, 0x11));
+        let middle = store(_mm_xor_si128(
+            _mm_clmulepi64_si128(x, h, 0x01),
+            _mm_clmulepi64_si128(x, h, 0x10),
+        ));
+        *accumulator = reduce(high ^ (middle >> 64), low ^ (middle << 64));
+    }
+}
+
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn load(value: u128) -> __m128i {
+    let bytes = value.to_le_bytes();
+    _mm_loadu_si128(bytes.as_ptr() as *const __m128i)
+}
+
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn store(value: __m128i) -> u128 {
+    let mut bytes = [0u8; 16];
+    _mm_storeu_si128(bytes.as_mut_ptr() as *mut __m128i, value);
+    u128::from_le_bytes(bytes)
+}
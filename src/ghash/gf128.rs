@@ -0,0 +1,101 @@
+//!
+//! Constant-time multiplication in GHASH's field, GF(2^128) modulo
+//! x^128 + x^7 + x^2 + x + 1, without carry-less multiply instructions.
+//!
+//! Blocks are read as big-endian integers, in which GHASH puts the
+//! coefficient of x^0 in the top bit.  The carry-less product of two such
+//! bit-reflected values is the reflected product shifted right by one, so
+//! it is shifted back before the reduction, which works on reflected
+//! values as well.
+//!
+//! The 64-bit carry-less products use integer multiplications on masked
+//! operands, with holes between the bits so that carries never reach a bit
+//! that is kept.  Unlike table-based GHASH, nothing depends on secret
+//! memory accesses.
+//!
+//! [Source](https://www.bearssl.org/constanttime.html#ghash-for-gcm)
+//!
+
+/// Every fourth bit, starting at bit 0.
+const HOLES: u64 = 0x1111111111111111;
+
+///
+/// Absorbs `data`, a whole number of 16-byte blocks, into `accumulator`.
+///
+pub(super) fn blocks(h: u128, accumulator: &mut u128, data: &[u8]) {
+    for block in data.chunks_exact(16) {
+        *accumulator = multiply(
+            *accumulator ^ u128::from_be_bytes(block.try_into().unwrap()),
+            h,
+        );
+    }
+}
+
+///
+/// x * y in the field, both bit-reflected.
+///
+fn multiply(x: u128, y: u128) -> u128 {
+    let (x1, x0) = ((x >> 64) as u64, x as u64);
+    let (y1, y0) = ((y >> 64) as u64, y as u64);
+
+    let low = clmul64(x0, y0);
+    let high = clmul64(x1, y1);
+    let middle = clmul64(x0, y1) ^ clmul64(x1, y0);
+    reduce(high ^ (middle >> 64), low ^ (middle << 64))
+}
+
+///
+/// Reduces the 255-bit carry-less product `high:low` of two bit-reflected
+/// values, giving the bit-reflected product in the field.
+///
+pub(super) fn reduce(high: u128, low: u128) -> u128 {
+    // Realign the product, so that bit 255 holds the coefficient of x^0
+    let high = (high << 1) | (low >> 127);
+    let low = low << 1;
+
+    // x^128 = x^7 + x^2 + x + 1, and in reflected order multiplying by x
+    // shifts right.  The low half is folded into the high half, and the
+    // few bits the shifts push back into the low half are folded with it.
+    let low = low ^ (low << 127) ^ (low << 126) ^ (low << 121);
+    high ^ low ^ (low >> 1) ^ (low >> 2) ^ (low >> 7)
+}
+
+///
+/// The 127-bit carry-less product of `x` and `y`.  The high half is the low
+/// half of the product of the bit-reversed operands, reversed.
+///
+fn clmul64(x: u64, y: u64) -> u128 {
+    let low = bmul64(x, y);
+    let high = bmul64(x.reverse_bits(), y.reverse_bits()).reverse_bits() >> 1;
+    ((high as u128) << 64) | low as u128
+}
+
+///
+/// The low 64 bits of the carry-less product of `x` and `y`.  Each operand
+/// is split into four parts with three-bit holes; a column of one integer
+/// product then sums at most 15 bits below bit 64, which fits in the hole
+/// above it.
+///
+fn bmul64(x: u64, y: u64) -> u64 {
+    let [x0, x1, x2, x3] = [
+        x & HOLES,
+        x & (HOLES << 1),
+        x & (HOLES << 2),
+        x & (HOLES << 3),
+    ];
+    let [y0, y1, y2, y3] = [
+        y & HOLES,
+        y & (HOLES << 1),
+        y & (HOLES << 2),
+        y & (HOLES << 3),
+    ];
+
+    let product =
+        |pairs: [(u64, u64); 4]| pairs.iter().fold(0, |acc, &(a, b)| acc ^ a.wrapping_mul(b));
+    let z0 = product([(x0, y0), (x1, y3), (x2, y2), (x3, y1)]);
+    let z1 = product([(x0, y1), (x1, y0), (x2, y3), (x3, y2)]);
+    let z2 = product([(x0, y2), (x1, y1), (x2, y0), (x3, y3)]);
+    let z3 = product([(x0, y3), (x1, y2), (x2, y1), (x3, y0)]);
+
+    (z0 & HOLES) | (z1 & (HOLES << 1)) | (z2 & (HOLES << 2)) | (z3 & (HOLES << 3))
+}
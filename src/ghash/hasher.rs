@@ -0,0 +1,144 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::backend::has_clmul;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::clmul;
+use super::gf128;
+
+pub const BLOCK_LENGTH: usize = 16;
+
+pub type Block = [u8; BLOCK_LENGTH];
+
+///
+/// GHASH, the universal hash of AES-GCM: the message in 16-byte blocks
+/// evaluated as a polynomial in the hash key `H` over GF(2^128).  It is
+/// not a MAC by itself; GCM encrypts its output with a one-time block.
+///
+/// Feeding the message to `update` in any split and calling `finalize`
+/// gives the same result.  GHASH is only defined on whole blocks, so a
+/// partial last block is padded with zeros.
+///
+/// With PCLMULQDQ, detected at runtime, each multiplication takes a few
+/// instructions.  The software fallback is constant time as well, built on
+/// integer multiplications instead of the usual key-dependent tables.
+///
+/// [Source](https://csrc.nist.gov/pubs/sp/800/38/d/final) (Section 6.4)
+///
+#[derive(Clone)]
+pub struct GHash {
+    h: u128,
+    accumulator: u128,
+    // Bytes of an incomplete block, waiting for more data
+    buffer: Block,
+    buffer_length: usize,
+    // Skips PCLMULQDQ even where it is detected
+    #[cfg_attr(
+        not(any(target_arch = "x86", target_arch = "x86_64")),
+        allow(dead_code)
+    )]
+    soft: bool,
+}
+
+impl GHash {
+    ///
+    /// Starts a hash under the key `h`, which GCM derives by encrypting the
+    /// zero block.
+    ///
+    pub fn new(h: Block) -> Self {
+        GHash {
+            h: u128::from_be_bytes(h),
+            accumulator: 0,
+            buffer: [0; BLOCK_LENGTH],
+            buffer_length: 0,
+            soft: false,
+        }
+    }
+
+    ///
+    /// Like `new`, but always runs the constant-time software code, even
+    /// where PCLMULQDQ is detected.  Meant for testing and benchmarking the
+    /// fallback.
+    ///
+    pub fn new_soft(h: Block) -> Self {
+        let mut hash = Self::new(h);
+        hash.soft = true;
+        hash
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_length > 0 {
+            let length = (BLOCK_LENGTH - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+            if self.buffer_length < BLOCK_LENGTH {
+                return;
+            }
+
+            let block = self.buffer;
+            self.blocks(&block);
+            self.buffer_length = 0;
+        }
+
+        let length = data.len() - data.len() % BLOCK_LENGTH;
+        self.blocks(&data[..length]);
+
+        let remainder = &data[length..];
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    ///
+    /// Adds `data` followed by zeros up to a multiple of 16 bytes, counted
+    /// from the start of the message, as GCM does for the associated data
+    /// and the ciphertext.
+    ///
+    pub fn update_padded(&mut self, data: &[u8]) {
+        self.update(data);
+        self.pad();
+    }
+
+    ///
+    /// Returns the hash of everything passed to `update`, zero-padded to a
+    /// whole number of blocks.
+    ///
+    pub fn finalize(mut self) -> Block {
+        self.pad();
+        self.accumulator.to_be_bytes()
+    }
+
+    fn pad(&mut self) {
+        if self.buffer_length > 0 {
+            self.buffer[self.buffer_length..].fill(0);
+            let block = self.buffer;
+            self.blocks(&block);
+            self.buffer_length = 0;
+        }
+    }
+
+    fn blocks(&mut self, data: &[u8]) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if !self.soft && has_clmul() {
+            // SAFETY: PCLMULQDQ was detected above
+            unsafe { clmul::blocks(self.h, &mut self.accumulator, data) };
+            return;
+        }
+        gf128::blocks(self.h, &mut self.accumulator, data);
+    }
+}
+
+impl Drop for GHash {
+    fn drop(&mut self) {
+        self.h.zeroize();
+        self.accumulator.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for GHash {}
@@ -0,0 +1,6 @@
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod clmul;
+mod gf128;
+mod hasher;
+
+pub use hasher::*;
@@ -0,0 +1,99 @@
+use crate::blake3::{Blake3, BLAKE3_HASH_LENGTH};
+use crate::sha2::{Sha256, Sha512, SHA256_BLOCK_LENGTH, SHA512_BLOCK_LENGTH};
+use crate::sha3::Sha3;
+
+///
+/// A streaming hash function, the interface HMAC and the key derivation
+/// functions are written against.  SHA-256, SHA-512, the SHA-3 hashes and
+/// BLAKE3 implement it.
+///
+/// Cloning a hasher forks it: both continue from the data absorbed so far,
+/// which HMAC uses to key a hash once and reuse it for many messages.
+///
+pub trait Digest: Clone {
+    /// Bytes the hash absorbs per compression, which HMAC pads keys to.
+    const BLOCK_LENGTH: usize;
+
+    /// The hash, an array of `OUTPUT_LENGTH` bytes.
+    type Output: AsRef<[u8]> + AsMut<[u8]> + Copy;
+
+    const OUTPUT_LENGTH: usize;
+
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> Self::Output;
+}
+
+impl Digest for Sha256 {
+    const BLOCK_LENGTH: usize = SHA256_BLOCK_LENGTH;
+    type Output = [u8; 32];
+    const OUTPUT_LENGTH: usize = 32;
+
+    fn new() -> Self {
+        Sha256::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        Sha256::finalize(self)
+    }
+}
+
+impl Digest for Sha512 {
+    const BLOCK_LENGTH: usize = SHA512_BLOCK_LENGTH;
+    type Output = [u8; 64];
+    const OUTPUT_LENGTH: usize = 64;
+
+    fn new() -> Self {
+        Sha512::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha512::update(self, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        Sha512::finalize(self)
+    }
+}
+
+impl<const RATE: usize, const HASH_LENGTH: usize> Digest for Sha3<RATE, HASH_LENGTH> {
+    const BLOCK_LENGTH: usize = RATE;
+    type Output = [u8; HASH_LENGTH];
+    const OUTPUT_LENGTH: usize = HASH_LENGTH;
+
+    fn new() -> Self {
+        Sha3::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3::update(self, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        Sha3::finalize(self)
+    }
+}
+
+impl Digest for Blake3 {
+    const BLOCK_LENGTH: usize = 64;
+    type Output = [u8; BLAKE3_HASH_LENGTH];
+    const OUTPUT_LENGTH: usize = BLAKE3_HASH_LENGTH;
+
+    fn new() -> Self {
+        Blake3::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Blake3::update(self, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        Blake3::finalize(self)
+    }
+}
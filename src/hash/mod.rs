@@ -0,0 +1,3 @@
+mod digest;
+
+pub use digest::*;
@@ -0,0 +1,25 @@
+mod rfc5869;
+
+pub use rfc5869::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HkdfError {
+    /// The requested output is longer than 255 hash outputs.
+    InvalidOutputLength,
+    /// The pseudorandom key is shorter than one hash output.
+    InvalidPrkLength,
+}
+
+impl fmt::Display for HkdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HkdfError::InvalidOutputLength => write!(f, "invalid output length"),
+            HkdfError::InvalidPrkLength => write!(f, "invalid pseudorandom key length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HkdfError {}
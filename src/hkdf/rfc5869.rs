@@ -0,0 +1,110 @@
+use zeroize::Zeroize;
+
+use crate::hash::Digest;
+use crate::mac::Hmac;
+
+use super::HkdfError;
+
+/// Hash outputs `expand` can produce at most, as the block counter is a
+/// single byte.
+const MAX_BLOCKS: usize = 255;
+
+///
+/// The extract step of HKDF: concentrates the entropy of the input keying
+/// material `ikm`, such as a Diffie-Hellman shared secret, into a
+/// pseudorandom key of one hash output, `HMAC(salt, ikm)`.
+///
+/// The salt should be random or fixed per application, and need not be
+/// secret; an empty salt acts as a hash output of zeros.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc5869#section-2.2)
+///
+pub fn extract<D: Digest>(salt: &[u8], ikm: &[u8]) -> D::Output {
+    let mut mac = Hmac::<D>::new(salt);
+    mac.update(ikm);
+    mac.finalize()
+}
+
+///
+/// The expand step of HKDF: fills `output` with key material derived from
+/// the pseudorandom key `prk` for the purpose described by `info`.
+/// Different `info` values give independent outputs, so one key from
+/// `extract` can serve for every subkey.
+///
+/// Fails with `HkdfError::InvalidPrkLength` if `prk` is shorter than a hash
+/// output, or `HkdfError::InvalidOutputLength` if `output` is longer than
+/// 255 of them, leaving `output` untouched.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc5869#section-2.3)
+///
+pub fn expand<D: Digest>(prk: &[u8], info: &[u8], output: &mut [u8]) -> Result<(), HkdfError> {
+    check::<D>(prk, output.len())?;
+
+    // T(i) = HMAC(PRK, T(i - 1) || info || i), with an empty T(0)
+    let keyed = Hmac::<D>::new(prk);
+    let mut previous: Option<D::Output> = None;
+    for (counter, chunk) in (1..=MAX_BLOCKS as u8).zip(output.chunks_mut(D::OUTPUT_LENGTH)) {
+        let mut mac = keyed.clone();
+        if let Some(previous) = &previous {
+            mac.update(previous.as_ref());
+        }
+        mac.update(info);
+        mac.update(&[counter]);
+
+        let block = previous.insert(mac.finalize());
+        chunk.copy_from_slice(&block.as_ref()[..chunk.len()]);
+    }
+    if let Some(previous) = &mut previous {
+        previous.as_mut().zeroize();
+    }
+    Ok(())
+}
+
+///
+/// Runs both steps of HKDF: extracts a pseudorandom key from `ikm` and
+/// `salt` and expands it with `info` into `output`.
+///
+/// Fails with `HkdfError::InvalidOutputLength` if `output` is longer than
+/// 255 hash outputs.
+///
+pub fn derive<D: Digest>(
+    salt: &[u8],
+    ikm: &[u8],
+    info: &[u8],
+    output: &mut [u8],
+) -> Result<(), HkdfError> {
+    let mut prk = extract::<D>(salt, ikm);
+    let result = expand::<D>(prk.as_ref(), info, output);
+    prk.as_mut().zeroize();
+    result
+}
+
+///
+/// Expands `prk` once per `(label, output)` pair, with the label as the
+/// `info`, to derive several independent keys of any lengths in one call,
+/// such as a key and an IV per direction.
+///
+/// Fails like `expand`, before any output is written.
+///
+pub fn expand_multi<D: Digest>(
+    prk: &[u8],
+    outputs: &mut [(&[u8], &mut [u8])],
+) -> Result<(), HkdfError> {
+    for (_, output) in outputs.iter() {
+        check::<D>(prk, output.len())?;
+    }
+    for (label, output) in outputs.iter_mut() {
+        expand::<D>(prk, label, output)?;
+    }
+    Ok(())
+}
+
+fn check<D: Digest>(prk: &[u8], output_length: usize) -> Result<(), HkdfError> {
+    if prk.len() < D::OUTPUT_LENGTH {
+        return Err(HkdfError::InvalidPrkLength);
+    }
+    if output_length > MAX_BLOCKS * D::OUTPUT_LENGTH {
+        return Err(HkdfError::InvalidOutputLength);
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::aead::{AeadError, ChaCha20Poly1305, Tag, TAG_LENGTH};
+use crate::chacha::{hchacha20, Key};
+
+use super::{ContentEncryption, JweError, JweHeader};
+
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+///
+/// Encrypts `plaintext` into a JWE in compact serialization: the protected
+/// header, an empty encrypted key, the IV, the ciphertext and the tag,
+/// base64url-encoded and joined by dots.  The ASCII of the encoded header is
+/// the associated data.
+///
+/// `key` is the content encryption key itself, as `dir` prescribes.  `iv`
+/// must be as long as `header.enc` needs, and never be used twice with the
+/// same key.  Fails with `JweError::InvalidIvLength` otherwise.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7516#section-5.1)
+///
+pub fn encrypt_compact(
+    key: impl Into<Key>,
+    header: &JweHeader,
+    iv: &[u8],
+    plaintext: &[u8],
+) -> Result<String, JweError> {
+    if iv.len() != header.enc.iv_length() {
+        return Err(JweError::InvalidIvLength);
+    }
+    let encoded_header = base64url_encode(header.to_json().as_bytes());
+
+    let (aead, nonce) = content_cipher(key.into(), header.enc, iv);
+    let (ciphertext, tag) = aead.seal_detached(nonce, encoded_header.as_bytes(), plaintext);
+
+    let mut token = encoded_header;
+    token.push_str("..");
+    token.push_str(&base64url_encode(iv));
+    token.push('.');
+    token.push_str(&base64url_encode(&ciphertext));
+    token.push('.');
+    token.push_str(&base64url_encode(tag.as_ref()));
+    Ok(token)
+}
+
+///
+/// Verifies and decrypts a JWE in compact serialization made with `dir` and
+/// `C20P` or `XC20P`, such as `encrypt_compact` produces.  The plaintext is
+/// only returned if the tag is valid.
+///
+/// Fails with `JweError::InvalidEncoding` if the token is malformed or has
+/// an encrypted key, with `JweError::UnsupportedAlgorithm` if its header
+/// asks for anything else, with `JweError::InvalidIvLength` if the IV does
+/// not fit the algorithm, and with `JweError::InvalidTag` if the token is
+/// not authentic.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7516#section-5.2)
+///
+pub fn decrypt_compact(key: impl Into<Key>, token: &str) -> Result<Vec<u8>, JweError> {
+    let [encoded_header, encrypted_key, iv, ciphertext, tag] = split(token)?;
+    let header = JweHeader::from_json(&base64url_decode(encoded_header)?)?;
+    if !encrypted_key.is_empty() {
+        return Err(JweError::InvalidEncoding);
+    }
+
+    let iv = base64url_decode(iv)?;
+    if iv.len() != header.enc.iv_length() {
+        return Err(JweError::InvalidIvLength);
+    }
+    let ciphertext = base64url_decode(ciphertext)?;
+    let tag = Tag::try_from(&base64url_decode(tag)?[..]).map_err(|_| JweError::InvalidEncoding)?;
+
+    let (aead, nonce) = content_cipher(key.into(), header.enc, &iv);
+    aead.open_detached(nonce, encoded_header.as_bytes(), &ciphertext, &tag)
+        .map_err(|error| match error {
+            AeadError::InvalidTag => JweError::InvalidTag,
+            _ => JweError::InvalidEncoding,
+        })
+}
+
+///
+/// Reads the protected header of a compact JWE without decrypting it, for
+/// example to pick the key named by `kid`.  The header is not authentic
+/// until `decrypt_compact` succeeds.
+///
+pub fn decode_header(token: &str) -> Result<JweHeader, JweError> {
+    let [encoded_header, ..] = split(token)?;
+    JweHeader::from_json(&base64url_decode(encoded_header)?)
+}
+
+///
+/// Splits a compact JWE into its five parts.
+///
+fn split(token: &str) -> Result<[&str; 5], JweError> {
+    let mut parts = token.split('.');
+    let mut next = || parts.next().ok_or(JweError::InvalidEncoding);
+    let split = [next()?, next()?, next()?, next()?, next()?];
+    if parts.next().is_some() || split[4].len() != (TAG_LENGTH * 4).div_ceil(3) {
+        return Err(JweError::InvalidEncoding);
+    }
+    Ok(split)
+}
+
+///
+/// Returns the ChaCha20-Poly1305 instance and nonce for the content
+/// encryption.  XC20P derives a subkey from the first 16 IV bytes with
+/// HChaCha20 and uses the last 8 as the nonce, like XChaCha20.
+///
+fn content_cipher(key: Key, enc: ContentEncryption, iv: &[u8]) -> (ChaCha20Poly1305, [u8; 12]) {
+    match enc {
+        ContentEncryption::C20P => (ChaCha20Poly1305::new(key), iv.try_into().unwrap()),
+        ContentEncryption::XC20P => {
+            let subkey = hchacha20(key, iv[..16].try_into().unwrap());
+            let mut nonce = [0u8; 12];
+            nonce[4..].copy_from_slice(&iv[16..]);
+            (ChaCha20Poly1305::new(subkey), nonce)
+        }
+    }
+}
+
+///
+/// Base64url without padding, as JOSE uses.
+///
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            encoded.push(BASE64URL_ALPHABET[index as usize] as char);
+        }
+    }
+    encoded
+}
+
+///
+/// Decodes unpadded base64url, rejecting non-canonical encodings whose
+/// unused trailing bits are set.
+///
+fn base64url_decode(encoded: &str) -> Result<Vec<u8>, JweError> {
+    if encoded.len() % 4 == 1 {
+        return Err(JweError::InvalidEncoding);
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.as_bytes().chunks(4) {
+        let mut bits = 0u32;
+        for (i, &character) in chunk.iter().enumerate() {
+            let value = BASE64URL_ALPHABET
+                .iter()
+                .position(|&c| c == character)
+                .ok_or(JweError::InvalidEncoding)?;
+            bits |= (value as u32) << (18 - 6 * i);
+        }
+
+        let length = chunk.len() - 1;
+        if bits & (0xffffff >> (8 * length)) != 0 {
+            return Err(JweError::InvalidEncoding);
+        }
+        bytes.extend_from_slice(&bits.to_be_bytes()[1..=length]);
+    }
+    Ok(bytes)
+}
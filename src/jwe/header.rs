@@ -0,0 +1,380 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::JweError;
+
+/// Deepest nesting of arrays and objects accepted in a header.
+const MAX_DEPTH: usize = 16;
+
+///
+/// The content encryption algorithms of draft-amringer-jose-chacha.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncryption {
+    /// ChaCha20-Poly1305 with a 96-bit IV.
+    C20P,
+    /// XChaCha20-Poly1305 with a 192-bit IV, large enough to be random.
+    XC20P,
+}
+
+impl ContentEncryption {
+    ///
+    /// The name used for the algorithm in the `enc` header.
+    ///
+    pub fn name(self) -> &'static str {
+        match self {
+            ContentEncryption::C20P => "C20P",
+            ContentEncryption::XC20P => "XC20P",
+        }
+    }
+
+    pub fn iv_length(self) -> usize {
+        match self {
+            ContentEncryption::C20P => 12,
+            ContentEncryption::XC20P => 24,
+        }
+    }
+
+    ///
+    /// Generates a random IV with the operating system's secure random
+    /// number generator.  Prefer `XC20P` for random IVs; with `C20P` keep
+    /// the number of tokens per key well below 2^32.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    #[cfg(feature = "os_rng")]
+    pub fn generate_iv(self) -> Vec<u8> {
+        let mut iv = alloc::vec![0u8; self.iv_length()];
+        getrandom::getrandom(&mut iv).expect("operating system random number generator failed");
+        iv
+    }
+}
+
+///
+/// The parts of a JWE protected header this crate understands: the content
+/// encryption, and the optional key id and content type that tell the
+/// recipient which key to use and what the plaintext is.  The key
+/// management algorithm is always `dir`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JweHeader {
+    pub enc: ContentEncryption,
+    pub kid: Option<String>,
+    pub cty: Option<String>,
+}
+
+impl JweHeader {
+    pub fn new(enc: ContentEncryption) -> Self {
+        JweHeader {
+            enc,
+            kid: None,
+            cty: None,
+        }
+    }
+
+    ///
+    /// Serializes the header as compact JSON, with `alg` and `enc` first.
+    ///
+    pub(super) fn to_json(&self) -> String {
+        let mut json = String::new();
+        json.push_str("{\"alg\":\"dir\",\"enc\":\"");
+        json.push_str(self.enc.name());
+        json.push('"');
+        for (name, value) in [("kid", &self.kid), ("cty", &self.cty)] {
+            if let Some(value) = value {
+                json.push_str(",\"");
+                json.push_str(name);
+                json.push_str("\":");
+                write_string(&mut json, value);
+            }
+        }
+        json.push('}');
+        json
+    }
+
+    ///
+    /// Parses a protected header.  Members other than `alg`, `enc`, `kid`,
+    /// `cty`, `zip` and `crit` are ignored.  Duplicate members are rejected,
+    /// as are `zip` and `crit`, since neither compression nor extensions are
+    /// supported.
+    ///
+    pub(super) fn from_json(json: &[u8]) -> Result<Self, JweError> {
+        let mut parser = Parser { json, position: 0 };
+        let members = parser.object()?;
+        parser.whitespace();
+        if parser.position != json.len() {
+            return Err(JweError::InvalidEncoding);
+        }
+
+        for (i, (name, _)) in members.iter().enumerate() {
+            if members[..i].iter().any(|(other, _)| other == name) {
+                return Err(JweError::InvalidEncoding);
+            }
+        }
+        let member = |name: &str| {
+            members
+                .iter()
+                .find(|(other, _)| other == name)
+                .map(|(_, value)| value)
+        };
+
+        if member("zip").is_some() || member("crit").is_some() {
+            return Err(JweError::UnsupportedAlgorithm);
+        }
+        let string = |name: &str| match member(name) {
+            None => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(Value::Other) => Err(JweError::InvalidEncoding),
+        };
+
+        if string("alg")?.as_deref() != Some("dir") {
+            return Err(JweError::UnsupportedAlgorithm);
+        }
+        let enc = match string("enc")?.as_deref() {
+            Some("C20P") => ContentEncryption::C20P,
+            Some("XC20P") => ContentEncryption::XC20P,
+            _ => return Err(JweError::UnsupportedAlgorithm),
+        };
+        Ok(JweHeader {
+            enc,
+            kid: string("kid")?,
+            cty: string("cty")?,
+        })
+    }
+}
+
+///
+/// Writes `value` as a JSON string, escaping quotes, backslashes and
+/// control characters.
+///
+fn write_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+///
+/// A member value of the header: a string, or anything else, which is
+/// checked but not kept.
+///
+enum Value {
+    String(String),
+    Other,
+}
+
+///
+/// Just enough of a JSON parser for a protected header.
+///
+struct Parser<'a> {
+    json: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.json.get(self.position) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JweError> {
+        self.whitespace();
+        if self.json.get(self.position) != Some(&byte) {
+            return Err(JweError::InvalidEncoding);
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    ///
+    /// Reads an object and returns its members.
+    ///
+    fn object(&mut self) -> Result<Vec<(String, Value)>, JweError> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.whitespace();
+        if self.json.get(self.position) == Some(&b'}') {
+            self.position += 1;
+            return Ok(members);
+        }
+        loop {
+            self.whitespace();
+            let name = self.string()?;
+            self.expect(b':')?;
+            let value = self.value(1)?;
+            members.push((name, value));
+
+            self.whitespace();
+            match self.json.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(members);
+                }
+                _ => return Err(JweError::InvalidEncoding),
+            }
+        }
+    }
+
+    ///
+    /// Reads any value.  Nested arrays and objects are checked and skipped.
+    ///
+    fn value(&mut self, depth: usize) -> Result<Value, JweError> {
+        self.whitespace();
+        match self.json.get(self.position) {
+            Some(b'"') => return self.string().map(Value::String),
+            Some(b'{' | b'[') if depth < MAX_DEPTH => self.container(depth)?,
+            Some(b't') => self.literal(b"true")?,
+            Some(b'f') => self.literal(b"false")?,
+            Some(b'n') => self.literal(b"null")?,
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            _ => return Err(JweError::InvalidEncoding),
+        }
+        Ok(Value::Other)
+    }
+
+    fn container(&mut self, depth: usize) -> Result<(), JweError> {
+        let close = if self.json[self.position] == b'{' {
+            b'}'
+        } else {
+            b']'
+        };
+        self.position += 1;
+        self.whitespace();
+        if self.json.get(self.position) == Some(&close) {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            if close == b'}' {
+                self.whitespace();
+                self.string()?;
+                self.expect(b':')?;
+            }
+            self.value(depth + 1)?;
+
+            self.whitespace();
+            match self.json.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(&byte) if byte == close => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(JweError::InvalidEncoding),
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &[u8]) -> Result<(), JweError> {
+        if !self.json[self.position..].starts_with(literal) {
+            return Err(JweError::InvalidEncoding);
+        }
+        self.position += literal.len();
+        Ok(())
+    }
+
+    ///
+    /// Skips a number, checking only that it is made of number characters.
+    ///
+    fn number(&mut self) -> Result<(), JweError> {
+        let start = self.position;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') =
+            self.json.get(self.position)
+        {
+            self.position += 1;
+        }
+        if self.position == start {
+            return Err(JweError::InvalidEncoding);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Reads a string and decodes its escapes, including UTF-16 surrogate
+    /// pairs.
+    ///
+    fn string(&mut self) -> Result<String, JweError> {
+        if self.json.get(self.position) != Some(&b'"') {
+            return Err(JweError::InvalidEncoding);
+        }
+        self.position += 1;
+
+        let mut bytes = Vec::new();
+        loop {
+            let byte = *self
+                .json
+                .get(self.position)
+                .ok_or(JweError::InvalidEncoding)?;
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = *self
+                        .json
+                        .get(self.position)
+                        .ok_or(JweError::InvalidEncoding)?;
+                    self.position += 1;
+                    let c = match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(JweError::InvalidEncoding),
+                    };
+                    let mut buffer = [0u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                0..=0x1f => return Err(JweError::InvalidEncoding),
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| JweError::InvalidEncoding)
+    }
+
+    ///
+    /// Decodes the code point of a `\u` escape whose `\u` was read already.
+    ///
+    fn unicode_escape(&mut self) -> Result<char, JweError> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            self.literal(b"\\u")?;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(JweError::InvalidEncoding);
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or(JweError::InvalidEncoding)
+    }
+
+    fn hex4(&mut self) -> Result<u32, JweError> {
+        let digits = self
+            .json
+            .get(self.position..self.position + 4)
+            .ok_or(JweError::InvalidEncoding)?;
+        self.position += 4;
+        digits.iter().try_fold(0, |value, &digit| {
+            let digit = (digit as char)
+                .to_digit(16)
+                .ok_or(JweError::InvalidEncoding)?;
+            Ok(value << 4 | digit)
+        })
+    }
+}
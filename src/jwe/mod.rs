@@ -0,0 +1,43 @@
+//!
+//! JWE compact serialization with the ChaCha20-Poly1305 content encryption
+//! algorithms of draft-amringer-jose-chacha: `C20P` and `XC20P`.
+//!
+//! Only direct encryption (`"alg":"dir"`) with a shared 256-bit key is
+//! supported.  ECDH-ES key agreement needs X25519, which this crate does not
+//! implement.
+//!
+mod compact;
+mod header;
+
+pub use compact::*;
+pub use header::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JweError {
+    /// The token or its protected header is malformed.
+    InvalidEncoding,
+    /// The header asks for a key management or content encryption algorithm
+    /// other than `dir` with `C20P` or `XC20P`, for compression, or for
+    /// critical extensions.
+    UnsupportedAlgorithm,
+    /// The IV does not have the length the content encryption needs.
+    InvalidIvLength,
+    /// The tag did not match the ciphertext and protected header.
+    InvalidTag,
+}
+
+impl fmt::Display for JweError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JweError::InvalidEncoding => write!(f, "invalid JWE encoding"),
+            JweError::UnsupportedAlgorithm => write!(f, "unsupported JWE algorithm"),
+            JweError::InvalidIvLength => write!(f, "invalid IV length"),
+            JweError::InvalidTag => write!(f, "JWE authentication failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JweError {}
@@ -0,0 +1,51 @@
+//!
+//! A password-protected file of named long-term keys.  Needs the `std` and
+//! `os_rng` features.
+//!
+mod store;
+
+pub use store::*;
+
+use core::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStoreError {
+    /// Reading or writing the file failed.
+    Io(io::ErrorKind),
+    /// The file is not a keystore, or is cut short.
+    InvalidEncoding,
+    /// The password is wrong, or the file was changed.
+    InvalidPassword,
+    /// The Argon2 parameters are invalid or above the keystore's limits.
+    InvalidParams,
+    /// A key with this name exists already.
+    KeyExists,
+    /// No key has this name.
+    KeyNotFound,
+    /// A name is empty or longer than 255 bytes, or metadata or a key is
+    /// longer than 65535.
+    InvalidLength,
+}
+
+impl fmt::Display for KeyStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyStoreError::Io(kind) => write!(f, "keystore I/O failed: {}", kind),
+            KeyStoreError::InvalidEncoding => write!(f, "invalid keystore encoding"),
+            KeyStoreError::InvalidPassword => write!(f, "wrong keystore password"),
+            KeyStoreError::InvalidParams => write!(f, "invalid Argon2 parameters"),
+            KeyStoreError::KeyExists => write!(f, "key already exists"),
+            KeyStoreError::KeyNotFound => write!(f, "key not found"),
+            KeyStoreError::InvalidLength => write!(f, "invalid name, metadata or key length"),
+        }
+    }
+}
+
+impl std::error::Error for KeyStoreError {}
+
+impl From<io::Error> for KeyStoreError {
+    fn from(error: io::Error) -> Self {
+        KeyStoreError::Io(error.kind())
+    }
+}
@@ -0,0 +1,497 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::string::String;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::aead::{ChaCha20Poly1305, TAG_LENGTH};
+use crate::argon2::{argon2, Params, Variant};
+use crate::chacha::Key;
+use crate::hkdf::expand;
+use crate::mac::HmacSha256;
+use crate::sha2::Sha256;
+
+use super::KeyStoreError;
+
+/// Bytes every keystore file starts with.
+const MAGIC: &[u8; 4] = b"AKS\x01";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const MAC_LENGTH: usize = 32;
+
+/// Most memory, in KiB, a keystore may ask Argon2 for: 4 GiB.
+pub const MAX_MEMORY: u32 = 4 * 1024 * 1024;
+
+/// Most Argon2 iterations a keystore may ask for.
+pub const MAX_ITERATIONS: u32 = 64;
+
+///
+/// Key material returned by a `KeyStore`, wiped from memory when dropped.
+///
+pub struct KeyMaterial(Vec<u8>);
+
+impl KeyMaterial {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Drop for KeyMaterial {
+    fn drop(&mut self) {
+        self.0.as_mut_slice().zeroize();
+    }
+}
+
+///
+/// A named key in a `KeyStore`, wrapped under the store's wrapping key.
+/// Its name, creation time and metadata are readable without the key.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEntry {
+    name: String,
+    // Seconds since the Unix epoch
+    created: u64,
+    metadata: String,
+    nonce: [u8; NONCE_LENGTH],
+    wrapped_key: Vec<u8>,
+}
+
+impl KeyEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    ///
+    /// When the key was added or last rotated, to the second.
+    ///
+    pub fn created(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.created)
+    }
+
+    pub fn metadata(&self) -> &str {
+        &self.metadata
+    }
+
+    ///
+    /// The associated data the key is wrapped with, so an entry's key
+    /// cannot be moved to another name, date or metadata.
+    ///
+    fn associated_data(&self) -> Vec<u8> {
+        let mut aad = Vec::new();
+        write_entry_header(&mut aad, &self.name, self.created, &self.metadata);
+        aad
+    }
+}
+
+///
+/// A file of named long-term keys, protected by a password.
+///
+/// The password and a random salt go through Argon2id to give a master
+/// key, from which HKDF-SHA256 derives a key wrapping the stored keys one
+/// by one with ChaCha20-Poly1305, and a key for an HMAC-SHA256 over the
+/// whole file.  A wrong password and a changed file, including one with
+/// entries removed, both fail with `KeyStoreError::InvalidPassword`.
+///
+/// The file is the magic bytes `AKS\x01`, the Argon2 memory, iterations and
+/// lanes as 32-bit big-endian integers, the salt, the number of entries and
+/// the entries, then the HMAC.  An entry is its name, creation time in
+/// seconds and metadata, then the nonce and the wrapped key.  Names,
+/// metadata and wrapped keys are each preceded by their length, in one,
+/// two and two bytes.
+///
+/// Changes only reach the file through `save`, which replaces it
+/// atomically.
+///
+pub struct KeyStore {
+    params: Params,
+    salt: [u8; SALT_LENGTH],
+    wrapping_key: Key,
+    mac_key: [u8; 32],
+    entries: Vec<KeyEntry>,
+}
+
+impl KeyStore {
+    ///
+    /// Creates an empty keystore protected by `password`, with a new
+    /// random salt.  Fails with `KeyStoreError::InvalidParams` if `params`
+    /// asks for more than `MAX_MEMORY` or `MAX_ITERATIONS`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    pub fn create(password: &[u8], params: Params) -> Result<Self, KeyStoreError> {
+        check_params(&params)?;
+        let mut salt = [0u8; SALT_LENGTH];
+        fill_random(&mut salt);
+
+        let (wrapping_key, mac_key) = derive_keys(password, &params, &salt)?;
+        Ok(KeyStore {
+            params,
+            salt,
+            wrapping_key,
+            mac_key,
+            entries: Vec::new(),
+        })
+    }
+
+    ///
+    /// Reads and opens the keystore at `path`.
+    ///
+    pub fn load(path: impl AsRef<Path>, password: &[u8]) -> Result<Self, KeyStoreError> {
+        Self::from_bytes(&fs::read(path)?, password)
+    }
+
+    ///
+    /// Opens a keystore serialized by `to_bytes`.  Fails with
+    /// `KeyStoreError::InvalidEncoding` if it is malformed, with
+    /// `KeyStoreError::InvalidParams` if its Argon2 parameters are invalid
+    /// or above the limits, and with `KeyStoreError::InvalidPassword` if
+    /// the password is wrong or the keystore was changed.
+    ///
+    pub fn from_bytes(bytes: &[u8], password: &[u8]) -> Result<Self, KeyStoreError> {
+        if bytes.len() < MAC_LENGTH {
+            return Err(KeyStoreError::InvalidEncoding);
+        }
+        let (contents, mac) = bytes.split_at(bytes.len() - MAC_LENGTH);
+        let mut reader = Reader(contents);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(KeyStoreError::InvalidEncoding);
+        }
+        let memory = reader.u32()?;
+        let iterations = reader.u32()?;
+        let lanes = reader.u32()?;
+        let params =
+            Params::new(memory, iterations, lanes).map_err(|_| KeyStoreError::InvalidParams)?;
+        check_params(&params)?;
+        let salt: [u8; SALT_LENGTH] = reader.take(SALT_LENGTH)?.try_into().unwrap();
+
+        let (wrapping_key, mac_key) = derive_keys(password, &params, &salt)?;
+        let mut hmac = HmacSha256::new(&mac_key);
+        hmac.update(contents);
+        if !hmac.verify(mac) {
+            return Err(KeyStoreError::InvalidPassword);
+        }
+
+        let count = reader.u32()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let name_length = reader.take(1)?[0] as usize;
+            let name = reader.string(name_length)?;
+            let created = u64::from_be_bytes(reader.take(8)?.try_into().unwrap());
+            let metadata_length = reader.u16()? as usize;
+            let metadata = reader.string(metadata_length)?;
+            let nonce = reader.take(NONCE_LENGTH)?.try_into().unwrap();
+            let wrapped_key_length = reader.u16()? as usize;
+            let wrapped_key = reader.take(wrapped_key_length)?.to_vec();
+            entries.push(KeyEntry {
+                name,
+                created,
+                metadata,
+                nonce,
+                wrapped_key,
+            });
+        }
+        if !reader.0.is_empty() {
+            return Err(KeyStoreError::InvalidEncoding);
+        }
+
+        Ok(KeyStore {
+            params,
+            salt,
+            wrapping_key,
+            mac_key,
+            entries,
+        })
+    }
+
+    ///
+    /// Writes the keystore to `path` atomically: to a temporary file next
+    /// to it first, which is synced and then renamed over `path`, so a
+    /// crash leaves either the old file or the new one.
+    ///
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), KeyStoreError> {
+        let path = path.as_ref();
+        let mut temporary_name = path
+            .file_name()
+            .ok_or(KeyStoreError::Io(std::io::ErrorKind::InvalidInput))?
+            .to_os_string();
+        temporary_name.push(".tmp");
+        let temporary_path = path.with_file_name(temporary_name);
+
+        let result = (|| {
+            let mut file = File::create(&temporary_path)?;
+            file.write_all(&self.to_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temporary_path, path)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary_path);
+        }
+        result.map_err(KeyStoreError::from)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        for value in [
+            self.params.memory(),
+            self.params.iterations(),
+            self.params.lanes(),
+        ] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes.extend_from_slice(&self.salt);
+
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_be_bytes());
+        for entry in &self.entries {
+            write_entry_header(&mut bytes, &entry.name, entry.created, &entry.metadata);
+            bytes.extend_from_slice(&entry.nonce);
+            bytes.extend_from_slice(&(entry.wrapped_key.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(&entry.wrapped_key);
+        }
+
+        let mut hmac = HmacSha256::new(&self.mac_key);
+        hmac.update(&bytes);
+        bytes.extend_from_slice(&hmac.finalize());
+        bytes
+    }
+
+    ///
+    /// The stored keys, in the order they were added.
+    ///
+    pub fn entries(&self) -> &[KeyEntry] {
+        &self.entries
+    }
+
+    ///
+    /// Stores `key` under `name`, dated now.  Fails with
+    /// `KeyStoreError::KeyExists` if the name is taken, and with
+    /// `KeyStoreError::InvalidLength` if the name is empty or longer than
+    /// 255 bytes, or the metadata or key longer than 65535.
+    ///
+    pub fn add(&mut self, name: &str, key: &[u8], metadata: &str) -> Result<(), KeyStoreError> {
+        if self.position(name).is_some() {
+            return Err(KeyStoreError::KeyExists);
+        }
+        let entry = self.seal_entry(name, now(), key, metadata)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    ///
+    /// Stores a new random key of `length` bytes under `name`, and fails
+    /// like `add`.
+    ///
+    pub fn generate(
+        &mut self,
+        name: &str,
+        length: usize,
+        metadata: &str,
+    ) -> Result<(), KeyStoreError> {
+        let key = random_key(length);
+        self.add(name, key.as_bytes(), metadata)
+    }
+
+    ///
+    /// Unwraps the key stored under `name`.
+    ///
+    pub fn get(&self, name: &str) -> Result<KeyMaterial, KeyStoreError> {
+        let entry = &self.entries[self.position(name).ok_or(KeyStoreError::KeyNotFound)?];
+        ChaCha20Poly1305::new(self.wrapping_key.clone())
+            .open(entry.nonce, &entry.associated_data(), &entry.wrapped_key)
+            .map(KeyMaterial)
+            .map_err(|_| KeyStoreError::InvalidPassword)
+    }
+
+    ///
+    /// Removes the key stored under `name`.
+    ///
+    pub fn remove(&mut self, name: &str) -> Result<(), KeyStoreError> {
+        let position = self.position(name).ok_or(KeyStoreError::KeyNotFound)?;
+        self.entries.remove(position);
+        Ok(())
+    }
+
+    ///
+    /// Replaces the key stored under `name` with a new random key of the
+    /// same length, dated now, keeping its metadata.  Returns the new key.
+    ///
+    pub fn rotate(&mut self, name: &str) -> Result<KeyMaterial, KeyStoreError> {
+        let position = self.position(name).ok_or(KeyStoreError::KeyNotFound)?;
+        let length = self.get(name)?.as_bytes().len();
+
+        let key = random_key(length);
+        let metadata = self.entries[position].metadata.clone();
+        self.entries[position] = self.seal_entry(name, now(), key.as_bytes(), &metadata)?;
+        Ok(key)
+    }
+
+    ///
+    /// Protects the keystore with a new password and parameters, with a new
+    /// salt, and wraps every key again.  Fails like `create`.
+    ///
+    pub fn change_password(
+        &mut self,
+        password: &[u8],
+        params: Params,
+    ) -> Result<(), KeyStoreError> {
+        let mut replacement = KeyStore::create(password, params)?;
+        for entry in &self.entries {
+            let key = self.get(&entry.name)?;
+            let rewrapped = replacement.seal_entry(
+                &entry.name,
+                entry.created,
+                key.as_bytes(),
+                &entry.metadata,
+            )?;
+            replacement.entries.push(rewrapped);
+        }
+        *self = replacement;
+        Ok(())
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.name == name)
+    }
+
+    ///
+    /// Wraps `key` into an entry created at `created`, under a new random
+    /// nonce.
+    ///
+    fn seal_entry(
+        &self,
+        name: &str,
+        created: u64,
+        key: &[u8],
+        metadata: &str,
+    ) -> Result<KeyEntry, KeyStoreError> {
+        if name.is_empty()
+            || name.len() > u8::MAX as usize
+            || metadata.len() > u16::MAX as usize
+            || key.len() + TAG_LENGTH > u16::MAX as usize
+        {
+            return Err(KeyStoreError::InvalidLength);
+        }
+
+        let mut entry = KeyEntry {
+            name: String::from(name),
+            created,
+            metadata: String::from(metadata),
+            nonce: [0u8; NONCE_LENGTH],
+            wrapped_key: Vec::new(),
+        };
+        fill_random(&mut entry.nonce);
+        entry.wrapped_key = ChaCha20Poly1305::new(self.wrapping_key.clone()).seal(
+            entry.nonce,
+            &entry.associated_data(),
+            key,
+        );
+        Ok(entry)
+    }
+}
+
+impl Drop for KeyStore {
+    fn drop(&mut self) {
+        self.mac_key.zeroize();
+    }
+}
+
+///
+/// Writes the parts of an entry that are authenticated with its key.
+///
+fn write_entry_header(bytes: &mut Vec<u8>, name: &str, created: u64, metadata: &str) {
+    bytes.push(name.len() as u8);
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.extend_from_slice(&created.to_be_bytes());
+    bytes.extend_from_slice(&(metadata.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(metadata.as_bytes());
+}
+
+///
+/// The current time in seconds since the Unix epoch, or zero for a clock
+/// set before it.
+///
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+fn check_params(params: &Params) -> Result<(), KeyStoreError> {
+    if params.memory() > MAX_MEMORY || params.iterations() > MAX_ITERATIONS {
+        return Err(KeyStoreError::InvalidParams);
+    }
+    Ok(())
+}
+
+///
+/// Derives the wrapping key and the HMAC key from the password.
+///
+fn derive_keys(
+    password: &[u8],
+    params: &Params,
+    salt: &[u8],
+) -> Result<(Key, [u8; 32]), KeyStoreError> {
+    let mut master_key = [0u8; 32];
+    argon2(Variant::Argon2id, params, password, salt, &mut master_key)
+        .map_err(|_| KeyStoreError::InvalidParams)?;
+
+    let mut wrapping_key = [0u8; 32];
+    let mut mac_key = [0u8; 32];
+    expand::<Sha256>(
+        &master_key,
+        b"armadillo keystore wrapping key",
+        &mut wrapping_key,
+    )
+    .unwrap();
+    expand::<Sha256>(&master_key, b"armadillo keystore mac key", &mut mac_key).unwrap();
+    master_key.zeroize();
+
+    let wrapping_key = Key::from(wrapping_key);
+    Ok((wrapping_key, mac_key))
+}
+
+fn fill_random(bytes: &mut [u8]) {
+    getrandom::getrandom(bytes).expect("operating system random number generator failed");
+}
+
+fn random_key(length: usize) -> KeyMaterial {
+    let mut key = KeyMaterial(std::vec![0u8; length]);
+    fill_random(&mut key.0);
+    key
+}
+
+///
+/// Reads the fields of a serialized keystore in order.
+///
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], KeyStoreError> {
+        if self.0.len() < length {
+            return Err(KeyStoreError::InvalidEncoding);
+        }
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> Result<u16, KeyStoreError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, KeyStoreError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self, length: usize) -> Result<String, KeyStoreError> {
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| KeyStoreError::InvalidEncoding)
+    }
+}
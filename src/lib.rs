@@ -1,2 +1,56 @@
+//!
+//! Without the default `std` feature the crate is `no_std`.  The ciphers
+//! and their modes, the hashes, MACs, key derivation functions and DRBGs,
+//! and the AEADs then work on caller-provided buffers only; the `alloc`
+//! feature adds back the functions returning a `Vec`, and with them
+//! Argon2, scrypt, JWE, OpenPGP SEIPD packets, envelope encryption and the
+//! secretbox and SSH constructions.  The password-protected keystore needs
+//! both `std` and `os_rng`.
+//!
+#![no_std]
+#![cfg_attr(armadillo_portable_simd, feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod aead;
+pub mod aes;
+#[cfg(feature = "alloc")]
+pub mod argon2;
+pub mod ascon;
+pub mod backend;
+pub mod bcrypt;
+pub mod blake3;
 pub mod chacha;
+#[cfg(feature = "alloc")]
+pub mod envelope;
+pub mod ghash;
+pub mod hash;
+pub mod hkdf;
+#[cfg(feature = "alloc")]
+pub mod jwe;
+#[cfg(all(feature = "std", feature = "os_rng"))]
+pub mod keystore;
+pub mod mac;
+pub mod modes;
+#[cfg(feature = "alloc")]
+pub mod openpgp;
+pub mod padding;
+pub mod pbkdf2;
 pub mod poly;
+pub mod rng;
+pub mod salsa;
+#[cfg(feature = "alloc")]
+pub mod scrypt;
+#[cfg(feature = "alloc")]
+pub mod secretbox;
+pub mod sha2;
+pub mod sha3;
+pub mod srtp;
+#[cfg(feature = "alloc")]
+pub mod ssh;
+pub mod tls13;
+#[cfg(feature = "wasm")]
+pub mod wasm;
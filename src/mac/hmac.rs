@@ -0,0 +1,116 @@
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::hash::Digest;
+use crate::sha2::{Sha256, Sha512};
+
+/// Bytes of the padded key absorbed at a time.
+const PAD_CHUNK_LENGTH: usize = 64;
+
+const INNER_PAD: u8 = 0x36;
+const OUTER_PAD: u8 = 0x5c;
+
+///
+/// Computes the HMAC of `data` under `key` in one go.
+///
+pub fn hmac<D: Digest>(key: &[u8], data: &[u8]) -> D::Output {
+    let mut mac = Hmac::<D>::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+///
+/// HMAC over any `Digest`: `H((K ^ opad) || H((K ^ ipad) || message))`,
+/// with the key padded to the hash's block length, or hashed first if it
+/// is longer.  Unlike a plain hash of the key and message, it is not open
+/// to length extension.
+///
+/// Both hashes are keyed when the MAC is created, so cloning a fresh
+/// `Hmac` reuses the key for many messages without padding it again.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc2104)
+///
+#[derive(Clone)]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer: D,
+}
+
+pub type HmacSha256 = Hmac<Sha256>;
+pub type HmacSha512 = Hmac<Sha512>;
+
+impl<D: Digest> Hmac<D> {
+    ///
+    /// Starts a MAC under `key`, which can have any length but should be
+    /// at least as long as the hash output.
+    ///
+    pub fn new(key: &[u8]) -> Self {
+        if key.len() > D::BLOCK_LENGTH {
+            let mut hash = D::new();
+            hash.update(key);
+            let mut hashed_key = hash.finalize();
+            let mac = Self::new(hashed_key.as_ref());
+            hashed_key.as_mut().zeroize();
+            return mac;
+        }
+
+        Hmac {
+            inner: padded_hash::<D>(key, INNER_PAD),
+            outer: padded_hash::<D>(key, OUTER_PAD),
+        }
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    ///
+    /// Returns the tag of everything passed to `update`.
+    ///
+    pub fn finalize(self) -> D::Output {
+        let Hmac { inner, mut outer } = self;
+        let mut inner_hash = inner.finalize();
+        outer.update(inner_hash.as_ref());
+        inner_hash.as_mut().zeroize();
+        outer.finalize()
+    }
+
+    ///
+    /// Checks in constant time that `tag` is the tag of everything passed
+    /// to `update`.  Protocols that truncate HMAC tags can pass the leading
+    /// bytes they keep, which should be at least half the hash output.
+    ///
+    #[must_use]
+    pub fn verify(self, tag: &[u8]) -> bool {
+        let expected = self.finalize();
+        let expected = expected.as_ref();
+        !tag.is_empty()
+            && tag.len() <= expected.len()
+            && bool::from(expected[..tag.len()].ct_eq(tag))
+    }
+}
+
+impl<D: Digest + ZeroizeOnDrop> ZeroizeOnDrop for Hmac<D> {}
+
+///
+/// A hash that has absorbed `key`, zero-padded to the block length and
+/// XORed with `pad`.
+///
+fn padded_hash<D: Digest>(key: &[u8], pad: u8) -> D {
+    let mut hash = D::new();
+    let mut chunk = [0u8; PAD_CHUNK_LENGTH];
+    let mut position = 0;
+    while position < D::BLOCK_LENGTH {
+        let length = PAD_CHUNK_LENGTH.min(D::BLOCK_LENGTH - position);
+        for (i, byte) in chunk[..length].iter_mut().enumerate() {
+            *byte = key.get(position + i).copied().unwrap_or(0) ^ pad;
+        }
+        hash.update(&chunk[..length]);
+        position += length;
+    }
+    chunk.zeroize();
+    hash
+}
@@ -0,0 +1,5 @@
+mod hmac;
+mod siphash;
+
+pub use hmac::*;
+pub use siphash::*;
@@ -0,0 +1,222 @@
+use core::hash::{BuildHasher, Hasher};
+
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const SIPHASH_KEY_LENGTH: usize = 16;
+
+/// "somepseudorandomlygeneratedbytes", XORed with the key halves.
+const INITIAL_STATE: [u64; 4] = [
+    0x736f6d6570736575,
+    0x646f72616e646f6d,
+    0x6c7967656e657261,
+    0x7465646279746573,
+];
+
+///
+/// Computes the SipHash-2-4 of `data` under `key` in one go.
+///
+pub fn siphash(key: &[u8; SIPHASH_KEY_LENGTH], data: &[u8]) -> u64 {
+    let mut hash = SipHash::new(key);
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// SipHash-2-4: a keyed 64-bit PRF, fast on short inputs, with two ARX
+/// rounds per 8-byte word and four to finalize.
+///
+/// Keyed with a random secret, it keeps attackers from picking inputs that
+/// collide in a hash table, and it authenticates short messages such as
+/// tokens.  The 64-bit output is too short for a general-purpose MAC;
+/// prefer HMAC or Poly1305 where forgeries can be tried offline.
+///
+/// `SipHash` implements `core::hash::Hasher`, and `SipHashBuilder` builds
+/// it for `HashMap` and other std collections.  Note that `Hash`
+/// implementations add their own framing, such as the length of a slice,
+/// so hashing a value through `Hasher` is not the same as `siphash` of its
+/// bytes.
+///
+/// [Source](https://www.aumasson.jp/siphash/siphash.pdf)
+///
+#[derive(Clone)]
+pub struct SipHash {
+    state: [u64; 4],
+    buffer: [u8; 8],
+    buffer_length: usize,
+    // Bytes hashed so far; only the lowest byte is used
+    length: u64,
+}
+
+impl SipHash {
+    pub fn new(key: &[u8; SIPHASH_KEY_LENGTH]) -> Self {
+        let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+        let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+        SipHash {
+            state: [
+                INITIAL_STATE[0] ^ k0,
+                INITIAL_STATE[1] ^ k1,
+                INITIAL_STATE[2] ^ k0,
+                INITIAL_STATE[3] ^ k1,
+            ],
+            buffer: [0; 8],
+            buffer_length: 0,
+            length: 0,
+        }
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffer_length > 0 {
+            let length = (8 - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+            if self.buffer_length < 8 {
+                return;
+            }
+
+            self.compress(u64::from_le_bytes(self.buffer));
+            self.buffer_length = 0;
+        }
+
+        let mut words = data.chunks_exact(8);
+        for word in &mut words {
+            self.compress(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+
+        let remainder = words.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    ///
+    /// Returns the 64-bit hash of everything passed to `update`.
+    ///
+    pub fn finalize(self) -> u64 {
+        self.result()
+    }
+
+    ///
+    /// Checks in constant time that `tag` is the hash of everything passed
+    /// to `update`.
+    ///
+    #[must_use]
+    pub fn verify(self, tag: u64) -> bool {
+        bool::from(self.result().ct_eq(&tag))
+    }
+
+    fn compress(&mut self, word: u64) {
+        self.state[3] ^= word;
+        sip_round(&mut self.state);
+        sip_round(&mut self.state);
+        self.state[0] ^= word;
+    }
+
+    ///
+    /// Pads the last word with zeros and the length byte, and runs the
+    /// finalization rounds on a copy of the state.
+    ///
+    fn result(&self) -> u64 {
+        let mut hash = self.clone();
+        let mut last = [0u8; 8];
+        last[..self.buffer_length].copy_from_slice(&self.buffer[..self.buffer_length]);
+        last[7] = self.length as u8;
+        hash.compress(u64::from_le_bytes(last));
+
+        hash.state[2] ^= 0xff;
+        for _ in 0..4 {
+            sip_round(&mut hash.state);
+        }
+        last.zeroize();
+        hash.state.iter().fold(0, |result, word| result ^ word)
+    }
+}
+
+impl Hasher for SipHash {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.result()
+    }
+}
+
+impl Drop for SipHash {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SipHash {}
+
+///
+/// Builds `SipHash` hashers under one key, for hash tables such as
+/// `HashMap::with_hasher`.  The key should be random and secret, and can
+/// differ per table.
+///
+#[derive(Clone)]
+pub struct SipHashBuilder {
+    key: [u8; SIPHASH_KEY_LENGTH],
+}
+
+impl SipHashBuilder {
+    pub fn new(key: [u8; SIPHASH_KEY_LENGTH]) -> Self {
+        SipHashBuilder { key }
+    }
+
+    ///
+    /// Creates a builder with a random key from the operating system's
+    /// secure random number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes.
+    ///
+    #[cfg(feature = "os_rng")]
+    pub fn generate() -> Self {
+        let mut key = [0u8; SIPHASH_KEY_LENGTH];
+        getrandom::getrandom(&mut key).expect("operating system random number generator failed");
+        SipHashBuilder { key }
+    }
+}
+
+impl BuildHasher for SipHashBuilder {
+    type Hasher = SipHash;
+
+    fn build_hasher(&self) -> SipHash {
+        SipHash::new(&self.key)
+    }
+}
+
+impl Drop for SipHashBuilder {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SipHashBuilder {}
+
+///
+/// The SipRound, an add-rotate-XOR mix of the four state words.
+///
+#[inline(always)]
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
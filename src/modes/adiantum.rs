@@ -0,0 +1,245 @@
+use core::fmt;
+
+use zeroize::Zeroize;
+
+use crate::aes::{Aes256, Block, BLOCK_LENGTH};
+use crate::chacha::{Key, XChaCha12};
+use crate::poly::Poly1305;
+
+pub const ADIANTUM_TWEAK_LENGTH: usize = 32;
+
+/// Bytes of message NH compresses at a time.
+const NH_MESSAGE_LENGTH: usize = 1024;
+
+/// 32-bit words of NH key: one per message word, plus 12 more for the
+/// three passes after the first, which start 16 bytes further on each.
+const NH_KEY_WORDS: usize = NH_MESSAGE_LENGTH / 4 + 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdiantumError {
+    /// The message is shorter than one block.
+    InvalidLength,
+}
+
+impl fmt::Display for AdiantumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdiantumError::InvalidLength => write!(f, "message shorter than one block"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AdiantumError {}
+
+///
+/// Adiantum, the length-preserving wide-block cipher built from XChaCha12,
+/// AES-256 and the NH and Poly1305 hashes, for disk sectors on devices
+/// without AES instructions.  Only one AES block is encrypted per message,
+/// however long.
+///
+/// The message is split into its last 16 bytes `R` and the rest `L`.  `R`
+/// plus a hash of the tweak and `L` is encrypted with AES-256, the result
+/// is the nonce of the XChaCha12 keystream that encrypts `L`, and that
+/// block minus a hash of the tweak and the encrypted `L` becomes the last
+/// 16 bytes of the ciphertext.  Every ciphertext bit depends on every
+/// plaintext bit, so unlike XTS a change anywhere in a sector changes the
+/// whole sector.
+///
+/// The subkeys are the XChaCha12 keystream under the nonce `1 || 0^23`.
+/// The tweak is 32 bytes, as in Linux.  Like XTS, Adiantum does not
+/// authenticate anything.
+///
+/// [Source](https://doi.org/10.13154/tosc.v2018.i4.39-61)
+///
+pub struct Adiantum {
+    key: Key,
+    aes: Aes256,
+    // The r halves of the Poly1305 keys; their s halves stay zero
+    header_hash_key: [u8; 16],
+    message_hash_key: [u8; 16],
+    nh_key: [u32; NH_KEY_WORDS],
+}
+
+impl Adiantum {
+    pub fn new(key: impl Into<Key>) -> Self {
+        let key = key.into();
+        let mut subkeys = [0u8; 32 + 16 + 16 + NH_KEY_WORDS * 4];
+        let mut nonce = [0u8; 24];
+        nonce[0] = 1;
+        XChaCha12::new_with_counter(key.clone(), nonce, 0).apply_keystream(&mut subkeys);
+
+        let mut nh_key = [0u32; NH_KEY_WORDS];
+        nh_key
+            .iter_mut()
+            .zip(subkeys[64..].chunks_exact(4))
+            .for_each(|(word, bytes)| *word = u32::from_le_bytes(bytes.try_into().unwrap()));
+        let adiantum = Adiantum {
+            key,
+            aes: Aes256::new(subkeys[..32].try_into().unwrap()),
+            header_hash_key: subkeys[32..48].try_into().unwrap(),
+            message_hash_key: subkeys[48..64].try_into().unwrap(),
+            nh_key,
+        };
+        subkeys.zeroize();
+        adiantum
+    }
+
+    ///
+    /// Encrypts `data` in place under `tweak`.  Fails with
+    /// `AdiantumError::InvalidLength` if it is shorter than 16 bytes.
+    ///
+    pub fn encrypt(
+        &self,
+        tweak: &[u8; ADIANTUM_TWEAK_LENGTH],
+        data: &mut [u8],
+    ) -> Result<(), AdiantumError> {
+        let (left, right) = split(data)?;
+
+        let mut block = add(right, self.hash(tweak, left));
+        self.aes.encrypt_block(&mut block);
+        self.apply_keystream(&block, left);
+        let last = subtract(&block, self.hash(tweak, left));
+        right.copy_from_slice(&last);
+        block.zeroize();
+        Ok(())
+    }
+
+    ///
+    /// Decrypts `data` in place under `tweak`.  Fails with
+    /// `AdiantumError::InvalidLength` if it is shorter than 16 bytes.
+    ///
+    pub fn decrypt(
+        &self,
+        tweak: &[u8; ADIANTUM_TWEAK_LENGTH],
+        data: &mut [u8],
+    ) -> Result<(), AdiantumError> {
+        let (left, right) = split(data)?;
+
+        let block = add(right, self.hash(tweak, left));
+        self.apply_keystream(&block, left);
+        let mut decrypted = block;
+        self.aes.decrypt_block(&mut decrypted);
+        let last = subtract(&decrypted, self.hash(tweak, left));
+        right.copy_from_slice(&last);
+        decrypted.zeroize();
+        Ok(())
+    }
+
+    ///
+    /// Encrypts the sector with number `sector` in place, with the sector
+    /// number as a 64-bit little-endian integer followed by zeros as the
+    /// tweak, like dm-crypt's `plain64` IV.
+    ///
+    pub fn encrypt_sector(&self, sector: u64, data: &mut [u8]) -> Result<(), AdiantumError> {
+        self.encrypt(&sector_tweak(sector), data)
+    }
+
+    ///
+    /// Decrypts the sector with number `sector` in place, like
+    /// `encrypt_sector`.
+    ///
+    pub fn decrypt_sector(&self, sector: u64, data: &mut [u8]) -> Result<(), AdiantumError> {
+        self.decrypt(&sector_tweak(sector), data)
+    }
+
+    ///
+    /// Encrypts or decrypts `left` with XChaCha12, under the nonce made of
+    /// the AES block, then `1` as a 32-bit little-endian integer and zeros.
+    ///
+    fn apply_keystream(&self, block: &Block, left: &mut [u8]) {
+        let mut nonce = [0u8; 24];
+        nonce[..BLOCK_LENGTH].copy_from_slice(block);
+        nonce[BLOCK_LENGTH] = 1;
+        XChaCha12::new_with_counter(self.key.clone(), nonce, 0).apply_keystream(left);
+    }
+
+    ///
+    /// The hash of the tweak and the left part, `Poly1305(bin(|L|) || T) +
+    /// Poly1305(NH(L))` modulo 2^128, both without the final addition of
+    /// `s`.  NH works on 1024-byte pieces of `L`, padded with zeros to a
+    /// multiple of 16 bytes.
+    ///
+    fn hash(&self, tweak: &[u8; ADIANTUM_TWEAK_LENGTH], left: &[u8]) -> u128 {
+        let mut header = Poly1305::new(poly1305_key(&self.header_hash_key));
+        header.update(&(left.len() as u128 * 8).to_le_bytes());
+        header.update(tweak);
+
+        let mut message = Poly1305::new(poly1305_key(&self.message_hash_key));
+        for piece in left.chunks(NH_MESSAGE_LENGTH) {
+            message.update(&self.nh(piece));
+        }
+
+        let header = u128::from_le_bytes(header.finalize().into_bytes());
+        let message = u128::from_le_bytes(message.finalize().into_bytes());
+        header.wrapping_add(message)
+    }
+
+    ///
+    /// NH with four passes over up to 1024 bytes, zero-padded to a multiple
+    /// of 16 bytes.  Each 16-byte unit adds `(m0 + k0) * (m2 + k2) +
+    /// (m1 + k1) * (m3 + k3)` to every pass's sum, with the key moved on by
+    /// 16 bytes per pass.
+    ///
+    fn nh(&self, piece: &[u8]) -> [u8; 32] {
+        let mut sums = [0u64; 4];
+        for (unit, bytes) in piece.chunks(16).enumerate() {
+            let mut padded = [0u8; 16];
+            padded[..bytes.len()].copy_from_slice(bytes);
+            let m: [u32; 4] = core::array::from_fn(|i| {
+                u32::from_le_bytes(padded[4 * i..4 * i + 4].try_into().unwrap())
+            });
+            let key = &self.nh_key[4 * unit..];
+            for (pass, sum) in sums.iter_mut().enumerate() {
+                let k = &key[4 * pass..4 * pass + 4];
+                *sum = sum
+                    .wrapping_add(m[0].wrapping_add(k[0]) as u64 * m[2].wrapping_add(k[2]) as u64)
+                    .wrapping_add(m[1].wrapping_add(k[1]) as u64 * m[3].wrapping_add(k[3]) as u64);
+            }
+            padded.zeroize();
+        }
+
+        let mut output = [0u8; 32];
+        output
+            .chunks_exact_mut(8)
+            .zip(sums)
+            .for_each(|(bytes, sum)| bytes.copy_from_slice(&sum.to_le_bytes()));
+        output
+    }
+}
+
+impl Drop for Adiantum {
+    fn drop(&mut self) {
+        self.header_hash_key.zeroize();
+        self.message_hash_key.zeroize();
+        self.nh_key.zeroize();
+    }
+}
+
+fn split(data: &mut [u8]) -> Result<(&mut [u8], &mut Block), AdiantumError> {
+    if data.len() < BLOCK_LENGTH {
+        return Err(AdiantumError::InvalidLength);
+    }
+    let (left, right) = data.split_at_mut(data.len() - BLOCK_LENGTH);
+    Ok((left, right.try_into().unwrap()))
+}
+
+fn add(block: &Block, hash: u128) -> Block {
+    u128::from_le_bytes(*block).wrapping_add(hash).to_le_bytes()
+}
+
+fn subtract(block: &Block, hash: u128) -> Block {
+    u128::from_le_bytes(*block).wrapping_sub(hash).to_le_bytes()
+}
+
+fn poly1305_key(r: &[u8; 16]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[..16].copy_from_slice(r);
+    key
+}
+
+fn sector_tweak(sector: u64) -> [u8; ADIANTUM_TWEAK_LENGTH] {
+    let mut tweak = [0u8; ADIANTUM_TWEAK_LENGTH];
+    tweak[..8].copy_from_slice(&sector.to_le_bytes());
+    tweak
+}
@@ -0,0 +1,69 @@
+use crate::aes::{Aes, Block};
+
+///
+/// A block cipher with 128-bit blocks, the interface the modes of operation
+/// are written against.  `Aes` implements it; another cipher only needs to
+/// encrypt and decrypt single blocks to get every mode.
+///
+/// Ciphers that are faster on several blocks at once, like AES with its
+/// eight-block batches, override `encrypt_blocks` and `decrypt_blocks`.
+///
+pub trait BlockCipher {
+    fn encrypt_block(&self, block: &mut Block);
+
+    fn decrypt_block(&self, block: &mut Block);
+
+    ///
+    /// Encrypts every block independently.
+    ///
+    fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        for block in blocks {
+            self.encrypt_block(block);
+        }
+    }
+
+    ///
+    /// Decrypts every block independently.
+    ///
+    fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        for block in blocks {
+            self.decrypt_block(block);
+        }
+    }
+}
+
+impl<B: BlockCipher + ?Sized> BlockCipher for &B {
+    fn encrypt_block(&self, block: &mut Block) {
+        (**self).encrypt_block(block);
+    }
+
+    fn decrypt_block(&self, block: &mut Block) {
+        (**self).decrypt_block(block);
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        (**self).encrypt_blocks(blocks);
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        (**self).decrypt_blocks(blocks);
+    }
+}
+
+impl<const KEY_LENGTH: usize> BlockCipher for Aes<KEY_LENGTH> {
+    fn encrypt_block(&self, block: &mut Block) {
+        Aes::encrypt_block(self, block);
+    }
+
+    fn decrypt_block(&self, block: &mut Block) {
+        Aes::decrypt_block(self, block);
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        Aes::encrypt_blocks(self, blocks);
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        Aes::decrypt_blocks(self, blocks);
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use zeroize::Zeroize;
+
+use crate::aes::{Block, BLOCK_LENGTH};
+#[cfg(feature = "alloc")]
+use crate::padding::pkcs7_pad;
+use crate::padding::pkcs7_unpad;
+
+use super::BlockCipher;
+
+/// Blocks decrypted side by side, as decryption does not chain.
+const PARALLEL_BLOCKS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CbcError {
+    /// The data is not a whole number of blocks, or is empty where padding
+    /// is expected.  This depends only on the public length.
+    InvalidLength,
+    /// The decrypted padding is malformed.  Deliberately carries no detail
+    /// about what was wrong, so callers cannot leak it.
+    InvalidPadding,
+}
+
+impl fmt::Display for CbcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CbcError::InvalidLength => write!(f, "invalid ciphertext length"),
+            CbcError::InvalidPadding => write!(f, "invalid padding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CbcError {}
+
+///
+/// Cipher block chaining over any `BlockCipher`: every plaintext block is
+/// XORed with the previous ciphertext block, or the IV for the first one,
+/// before it is encrypted.
+///
+/// CBC is here to read and write legacy formats.  It does not authenticate
+/// anything, and a server that reveals whether decrypted padding was valid,
+/// through its errors or its timing, lets an attacker decrypt messages.
+/// The padded functions therefore check PKCS#7 padding in constant time
+/// and report every malformed padding with the same error; data should
+/// still be authenticated before it is decrypted.  New designs should use
+/// an AEAD.
+///
+/// The IV must be unpredictable, and fresh for every message.
+///
+/// [Source](https://csrc.nist.gov/pubs/sp/800/38/a/final) (Section 6.2)
+///
+pub struct Cbc<B: BlockCipher> {
+    cipher: B,
+}
+
+impl<B: BlockCipher> Cbc<B> {
+    pub fn new(cipher: B) -> Self {
+        Cbc { cipher }
+    }
+
+    ///
+    /// Pads `plaintext` with PKCS#7 and encrypts it, returning a ciphertext
+    /// of one to sixteen bytes more.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_padded(&self, iv: Block, plaintext: &[u8]) -> Vec<u8> {
+        let mut buffer = pkcs7_pad(plaintext, BLOCK_LENGTH).unwrap();
+        self.encrypt_aligned(iv, &mut buffer);
+        buffer
+    }
+
+    ///
+    /// Decrypts a ciphertext produced by `encrypt_padded` and removes its
+    /// padding.  Every malformed padding gives `CbcError::InvalidPadding`,
+    /// after the same amount of work.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn decrypt_padded(&self, iv: Block, ciphertext: &[u8]) -> Result<Vec<u8>, CbcError> {
+        let mut buffer = ciphertext.to_vec();
+        let length = self.decrypt_padded_in_place(iv, &mut buffer)?.len();
+        buffer.truncate(length);
+        Ok(buffer)
+    }
+
+    ///
+    /// Like `decrypt_padded`, but decrypts `buffer` in place and returns the
+    /// part of it holding the plaintext.  If the padding is invalid,
+    /// `buffer` is zeroed.
+    ///
+    pub fn decrypt_padded_in_place<'a>(
+        &self,
+        iv: Block,
+        buffer: &'a mut [u8],
+    ) -> Result<&'a mut [u8], CbcError> {
+        if buffer.is_empty() {
+            return Err(CbcError::InvalidLength);
+        }
+        self.decrypt_in_place(iv, buffer)?;
+
+        match pkcs7_unpad(buffer, BLOCK_LENGTH) {
+            Ok(plaintext) => {
+                let length = plaintext.len();
+                Ok(&mut buffer[..length])
+            }
+            Err(_) => {
+                buffer.zeroize();
+                Err(CbcError::InvalidPadding)
+            }
+        }
+    }
+
+    ///
+    /// Encrypts `buffer` in place, without padding.  Fails with
+    /// `CbcError::InvalidLength` unless it is a whole number of blocks.
+    ///
+    pub fn encrypt_in_place(&self, iv: Block, buffer: &mut [u8]) -> Result<(), CbcError> {
+        if buffer.len() % BLOCK_LENGTH != 0 {
+            return Err(CbcError::InvalidLength);
+        }
+        self.encrypt_aligned(iv, buffer);
+        Ok(())
+    }
+
+    ///
+    /// Decrypts `buffer` in place, without removing padding.  Fails with
+    /// `CbcError::InvalidLength` unless it is a whole number of blocks.
+    ///
+    pub fn decrypt_in_place(&self, iv: Block, buffer: &mut [u8]) -> Result<(), CbcError> {
+        if buffer.len() % BLOCK_LENGTH != 0 {
+            return Err(CbcError::InvalidLength);
+        }
+
+        // Each plaintext block needs the ciphertext block before it, which
+        // is kept from the previous batch before the buffer is overwritten
+        let mut previous = iv;
+        let mut blocks = [[0u8; BLOCK_LENGTH]; PARALLEL_BLOCKS];
+        for chunk in buffer.chunks_mut(PARALLEL_BLOCKS * BLOCK_LENGTH) {
+            let blocks = &mut blocks[..chunk.len() / BLOCK_LENGTH];
+            for (block, ciphertext) in blocks.iter_mut().zip(chunk.chunks_exact(BLOCK_LENGTH)) {
+                block.copy_from_slice(ciphertext);
+            }
+            self.cipher.decrypt_blocks(blocks);
+
+            for (block, output) in blocks.iter_mut().zip(chunk.chunks_exact_mut(BLOCK_LENGTH)) {
+                let ciphertext: Block = (&*output).try_into().unwrap();
+                for ((output, byte), previous) in output.iter_mut().zip(&*block).zip(previous) {
+                    *output = byte ^ previous;
+                }
+                previous = ciphertext;
+            }
+        }
+        blocks.zeroize();
+        Ok(())
+    }
+
+    fn encrypt_aligned(&self, iv: Block, buffer: &mut [u8]) {
+        let mut previous = iv;
+        for chunk in buffer.chunks_exact_mut(BLOCK_LENGTH) {
+            for (byte, previous) in previous.iter_mut().zip(&*chunk) {
+                *byte ^= previous;
+            }
+            self.cipher.encrypt_block(&mut previous);
+            chunk.copy_from_slice(&previous);
+        }
+    }
+}
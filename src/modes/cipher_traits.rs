@@ -0,0 +1,53 @@
+//!
+//! RustCrypto `cipher` trait implementations, so `Ctr` can be used wherever
+//! a `StreamCipher` type is expected.  It is built from a `BlockCipher`
+//! value rather than a key, so it does not implement `KeyIvInit`.
+//!
+use cipher::inout::InOutBuf;
+use cipher::{OverflowError, SeekNum, StreamCipher, StreamCipherError, StreamCipherSeek};
+
+use crate::aes::BLOCK_LENGTH;
+
+use super::{BlockCipher, Ctr};
+
+/// Bytes of keystream generated on the stack at a time.
+const KEYSTREAM_CHUNK_LENGTH: usize = 512;
+
+impl<B: BlockCipher> StreamCipher for Ctr<B> {
+    fn try_apply_keystream_inout(
+        &mut self,
+        mut buf: InOutBuf<'_, '_, u8>,
+    ) -> Result<(), StreamCipherError> {
+        let mut keystream = [0u8; KEYSTREAM_CHUNK_LENGTH];
+        while !buf.is_empty() {
+            let length = buf.len().min(KEYSTREAM_CHUNK_LENGTH);
+            let (mut chunk, rest) = buf.split_at(length);
+            self.fill(&mut keystream[..length]);
+            chunk.xor_in2out(&keystream[..length]);
+            buf = rest;
+        }
+        Ok(())
+    }
+}
+
+impl<B: BlockCipher> StreamCipherSeek for Ctr<B> {
+    fn try_current_pos<T: SeekNum>(&self) -> Result<T, OverflowError> {
+        let position = self.position();
+        let byte = position % BLOCK_LENGTH as u64;
+        // SeekNum counts a partially used block as already passed
+        let block = position / BLOCK_LENGTH as u64 + (byte != 0) as u64;
+        T::from_block_byte(block, byte as u8, BLOCK_LENGTH as u8)
+    }
+
+    fn try_seek<T: SeekNum>(&mut self, pos: T) -> Result<(), StreamCipherError> {
+        let (block, byte): (u64, u8) = pos
+            .into_block_byte(BLOCK_LENGTH as u8)
+            .map_err(|_| StreamCipherError)?;
+        let offset = block
+            .checked_mul(BLOCK_LENGTH as u64)
+            .and_then(|offset| offset.checked_add(byte as u64))
+            .ok_or(StreamCipherError)?;
+        self.seek(offset);
+        Ok(())
+    }
+}
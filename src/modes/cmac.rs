@@ -0,0 +1,126 @@
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::aes::{Block, BLOCK_LENGTH};
+
+use super::BlockCipher;
+
+///
+/// CMAC over any `BlockCipher`: a CBC-MAC whose last block is masked with
+/// one of two subkeys derived from the cipher, depending on whether it was
+/// padded, which makes it secure for messages of any length.
+///
+/// Feeding the message to `update` in any split and calling `finalize`
+/// gives the same tag.  Unlike Poly1305, a CMAC key can authenticate any
+/// number of messages.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc4493)
+///
+#[derive(Clone)]
+pub struct Cmac<B: BlockCipher> {
+    cipher: B,
+    // Masks for a complete and a padded last block
+    subkeys: [Block; 2],
+    state: Block,
+    // The last block seen so far, which is only absorbed once more data
+    // shows it is not the last block of the message
+    buffer: Block,
+    buffer_length: usize,
+}
+
+impl<B: BlockCipher> Cmac<B> {
+    pub fn new(cipher: B) -> Self {
+        let mut first = [0u8; BLOCK_LENGTH];
+        cipher.encrypt_block(&mut first);
+        let first = double(&first);
+        let second = double(&first);
+
+        Cmac {
+            cipher,
+            subkeys: [first, second],
+            state: [0; BLOCK_LENGTH],
+            buffer: [0; BLOCK_LENGTH],
+            buffer_length: 0,
+        }
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buffer_length == BLOCK_LENGTH {
+                let block = self.buffer;
+                self.absorb(&block);
+                self.buffer_length = 0;
+            }
+
+            let length = (BLOCK_LENGTH - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+        }
+    }
+
+    ///
+    /// Returns the 16-byte tag of everything passed to `update`.
+    ///
+    pub fn finalize(mut self) -> Block {
+        let mut last = self.buffer;
+        let subkey = if self.buffer_length == BLOCK_LENGTH {
+            self.subkeys[0]
+        } else {
+            // 10* padding
+            last[self.buffer_length] = 0x80;
+            last[self.buffer_length + 1..].fill(0);
+            self.subkeys[1]
+        };
+        for (byte, key) in last.iter_mut().zip(subkey) {
+            *byte ^= key;
+        }
+        self.absorb(&last);
+        last.zeroize();
+        self.state
+    }
+
+    ///
+    /// Checks in constant time that `tag` is the tag of everything passed
+    /// to `update`.  Protocols that truncate CMAC tags can pass the leading
+    /// bytes they keep, which should be at least eight.
+    ///
+    #[must_use]
+    pub fn verify(self, tag: &[u8]) -> bool {
+        let expected = self.finalize();
+        !tag.is_empty() && tag.len() <= BLOCK_LENGTH && bool::from(expected[..tag.len()].ct_eq(tag))
+    }
+
+    fn absorb(&mut self, block: &Block) {
+        for (byte, input) in self.state.iter_mut().zip(block) {
+            *byte ^= input;
+        }
+        self.cipher.encrypt_block(&mut self.state);
+    }
+}
+
+impl<B: BlockCipher> Drop for Cmac<B> {
+    fn drop(&mut self) {
+        self.subkeys.zeroize();
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl<B: BlockCipher> ZeroizeOnDrop for Cmac<B> {}
+
+///
+/// Multiplication by x in GF(2^128), on the big-endian representation CMAC
+/// uses, without branching on the top bit.
+///
+fn double(block: &Block) -> Block {
+    let value = u128::from_be_bytes(*block);
+    let carry = (value >> 127) as u8;
+    let mut doubled = (value << 1).to_be_bytes();
+    doubled[BLOCK_LENGTH - 1] ^= 0x87 & carry.wrapping_neg();
+    doubled
+}
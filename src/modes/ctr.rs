@@ -0,0 +1,207 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::aes::{Block, BLOCK_LENGTH};
+
+use super::BlockCipher;
+
+/// Number of blocks of keystream `Ctr` generates ahead at most.
+const BUFFER_BLOCKS: usize = 8;
+
+const BUFFER_LENGTH: usize = BUFFER_BLOCKS * BLOCK_LENGTH;
+
+///
+/// Counter mode over any `BlockCipher`, which turns it into a stream
+/// cipher: the keystream is the encryption of the initial counter block,
+/// then of that block plus one, and so on, as a 128-bit big-endian integer
+/// that wraps around.
+///
+/// Like `ChaCha`, it keeps the unused keystream of a partial block for the
+/// next call, so data can be encrypted in any number of pieces, and it can
+/// seek to any byte of the stream.  Keystream is generated eight blocks at
+/// a time, so AES encrypts them side by side.
+///
+/// An initial counter block must never be used twice with the same key,
+/// and neither may the ranges of blocks two streams go through overlap.
+///
+/// [Source](https://csrc.nist.gov/pubs/sp/800/38/a/final) (Section 6.5)
+///
+#[derive(Clone)]
+pub struct Ctr<B: BlockCipher> {
+    cipher: B,
+    initial_counter: u128,
+    // Blocks generated so far, counted from the initial counter block
+    blocks: u64,
+    keystream: [u8; BUFFER_LENGTH],
+    keystream_length: usize,
+    keystream_position: usize,
+}
+
+impl<B: BlockCipher> Ctr<B> {
+    pub fn new(cipher: B, initial_counter: Block) -> Self {
+        Ctr {
+            cipher,
+            initial_counter: u128::from_be_bytes(initial_counter),
+            blocks: 0,
+            keystream: [0u8; BUFFER_LENGTH],
+            keystream_length: 0,
+            keystream_position: 0,
+        }
+    }
+
+    ///
+    /// Encrypts (or decrypts) `data` by XORing it with the keystream.
+    ///
+    /// Keystream left over from a partially used block is kept for the
+    /// next call, so encrypting a message in chunks gives the same result as
+    /// encrypting it at once.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut output = data.to_vec();
+        self.apply_keystream(&mut output);
+        output
+    }
+
+    ///
+    /// XORs the keystream into `data` in place, without allocating.  This
+    /// is the same operation as `encrypt`, and shares its keystream
+    /// position.
+    ///
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        let mut offset = 0;
+        while offset < data.len() {
+            if self.keystream_position == self.keystream_length {
+                self.refill(data.len() - offset);
+            }
+
+            let keystream = &self.keystream[self.keystream_position..self.keystream_length];
+            let chunk = &mut data[offset..];
+            let length = keystream.len().min(chunk.len());
+            chunk[..length]
+                .iter_mut()
+                .zip(keystream)
+                .for_each(|(x, y)| *x ^= y);
+
+            offset += length;
+            self.keystream_position += length;
+        }
+    }
+
+    ///
+    /// Overwrites `output` with the next keystream bytes, which is the same
+    /// as applying the keystream to zeroed data.
+    ///
+    pub fn fill(&mut self, output: &mut [u8]) {
+        output.fill(0);
+        self.apply_keystream(output);
+    }
+
+    ///
+    /// XORs the keystream starting at byte `offset` of the stream into
+    /// `data`, without moving this cipher's position, so separate ranges
+    /// can be processed independently, for example by several threads.
+    ///
+    pub fn apply_keystream_at(&self, offset: u64, data: &mut [u8]) {
+        let mut block = offset / BLOCK_LENGTH as u64;
+        let mut skip = (offset % BLOCK_LENGTH as u64) as usize;
+        let mut keystream = [0u8; BUFFER_LENGTH];
+        let mut done = 0;
+        while done < data.len() {
+            let blocks = (skip + data.len() - done)
+                .div_ceil(BLOCK_LENGTH)
+                .min(BUFFER_BLOCKS);
+            let keystream = &mut keystream[..blocks * BLOCK_LENGTH];
+            Self::generate(&self.cipher, self.initial_counter, block, keystream);
+
+            let length = (keystream.len() - skip).min(data.len() - done);
+            data[done..done + length]
+                .iter_mut()
+                .zip(&keystream[skip..])
+                .for_each(|(x, y)| *x ^= y);
+
+            done += length;
+            skip = 0;
+            block += blocks as u64;
+        }
+        keystream.zeroize();
+    }
+
+    ///
+    /// Moves the keystream to byte `offset`, counted from the start of the
+    /// stream, so any byte range can be decrypted without processing what
+    /// comes before it.
+    ///
+    pub fn seek(&mut self, offset: u64) {
+        self.blocks = offset / BLOCK_LENGTH as u64;
+        self.keystream_length = 0;
+        self.keystream_position = 0;
+
+        let position = (offset % BLOCK_LENGTH as u64) as usize;
+        if position != 0 {
+            self.refill(1);
+            self.keystream_position = position;
+        }
+    }
+
+    ///
+    /// Returns the current byte offset in the keystream, counted from the
+    /// start of the stream.
+    ///
+    pub fn position(&self) -> u64 {
+        // Buffered keystream has already advanced the block count
+        self.blocks * BLOCK_LENGTH as u64 + self.keystream_position as u64
+            - self.keystream_length as u64
+    }
+
+    ///
+    /// Generates keystream for the next blocks, enough to cover `wanted`
+    /// bytes where the buffer allows, and advances the block count past
+    /// them.
+    ///
+    fn refill(&mut self, wanted: usize) {
+        let blocks = wanted.div_ceil(BLOCK_LENGTH).clamp(1, BUFFER_BLOCKS);
+        let length = blocks * BLOCK_LENGTH;
+
+        Self::generate(
+            &self.cipher,
+            self.initial_counter,
+            self.blocks,
+            &mut self.keystream[..length],
+        );
+
+        self.keystream_length = length;
+        self.keystream_position = 0;
+        self.blocks += blocks as u64;
+    }
+
+    ///
+    /// Writes the encrypted counter blocks from `block` on into `output`,
+    /// a whole number of blocks no longer than the buffer.
+    ///
+    fn generate(cipher: &B, initial_counter: u128, block: u64, output: &mut [u8]) {
+        let mut counters = [[0u8; BLOCK_LENGTH]; BUFFER_BLOCKS];
+        let counters = &mut counters[..output.len() / BLOCK_LENGTH];
+        for (i, counter) in counters.iter_mut().enumerate() {
+            *counter = initial_counter
+                .wrapping_add(block as u128 + i as u128)
+                .to_be_bytes();
+        }
+        cipher.encrypt_blocks(counters);
+        for (output, counter) in output
+            .chunks_exact_mut(BLOCK_LENGTH)
+            .zip(counters.iter_mut())
+        {
+            output.copy_from_slice(counter);
+            counter.zeroize();
+        }
+    }
+}
+
+impl<B: BlockCipher> Drop for Ctr<B> {
+    fn drop(&mut self) {
+        self.keystream.zeroize();
+    }
+}
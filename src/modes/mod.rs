@@ -0,0 +1,17 @@
+mod adiantum;
+mod block_cipher;
+mod cbc;
+#[cfg(feature = "rustcrypto-compat")]
+mod cipher_traits;
+mod cmac;
+mod ctr;
+mod ff1;
+mod xts;
+
+pub use adiantum::*;
+pub use block_cipher::*;
+pub use cbc::*;
+pub use cmac::*;
+pub use ctr::*;
+pub use ff1::*;
+pub use xts::*;
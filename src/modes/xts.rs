@@ -0,0 +1,261 @@
+use core::fmt;
+
+use zeroize::Zeroize;
+
+use crate::aes::{Aes128, Aes256, Block, BLOCK_LENGTH};
+
+use super::BlockCipher;
+
+/// Blocks processed side by side within a data unit.
+const PARALLEL_BLOCKS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XtsError {
+    /// A data unit is shorter than one block, or the data is not a whole
+    /// number of data units.
+    InvalidLength,
+}
+
+impl fmt::Display for XtsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XtsError::InvalidLength => write!(f, "invalid data unit length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for XtsError {}
+
+///
+/// The XTS mode of IEEE 1619 over any `BlockCipher`, for encrypting
+/// storage in place: every data unit, typically a disk sector, is
+/// encrypted on its own under a tweak derived from its number, so the
+/// ciphertext is exactly as long as the plaintext and any sector can be
+/// read or written independently.
+///
+/// Within a data unit, block j is XORed with `E_K2(sector) * x^j` in
+/// GF(2^128) before and after encryption with the first key.  A last
+/// partial block borrows the tail of the one before it (ciphertext
+/// stealing).  The two keys must be independent; a key half repeated in
+/// both places is rejected by FIPS implementations.
+///
+/// XTS does not authenticate anything, and the same plaintext at the same
+/// sector always gives the same ciphertext, so an attacker who sees the
+/// disk more than once learns which blocks changed.
+///
+/// [Source](https://doi.org/10.1109/IEEESTD.2019.8637988)
+///
+pub struct Xts<B: BlockCipher> {
+    cipher: B,
+    tweak_cipher: B,
+}
+
+pub type Aes128Xts = Xts<Aes128>;
+pub type Aes256Xts = Xts<Aes256>;
+
+impl<B: BlockCipher> Xts<B> {
+    ///
+    /// Creates the mode from the data cipher, keyed with the first half of
+    /// the XTS key, and the tweak cipher, keyed with the second half.
+    ///
+    pub fn new(cipher: B, tweak_cipher: B) -> Self {
+        Xts {
+            cipher,
+            tweak_cipher,
+        }
+    }
+
+    ///
+    /// Encrypts the data unit with number `sector` in place.  Fails with
+    /// `XtsError::InvalidLength` if it is shorter than one block.
+    ///
+    pub fn encrypt_sector(&self, sector: u128, data: &mut [u8]) -> Result<(), XtsError> {
+        if data.len() < BLOCK_LENGTH {
+            return Err(XtsError::InvalidLength);
+        }
+
+        let full = data.len() / BLOCK_LENGTH;
+        let remainder = data.len() % BLOCK_LENGTH;
+        let mut tweak = self.initial_tweak(sector);
+        if remainder == 0 {
+            self.process_blocks(&mut tweak, data, true);
+            tweak.zeroize();
+            return Ok(());
+        }
+
+        // Ciphertext stealing: the last full block is encrypted as usual,
+        // its head becomes the partial last block, and its tail pads the
+        // partial plaintext into a block encrypted with the next tweak
+        let stolen = (full - 1) * BLOCK_LENGTH;
+        self.process_blocks(&mut tweak, &mut data[..stolen + BLOCK_LENGTH], true);
+        let (last_full, partial) = data[stolen..].split_at_mut(BLOCK_LENGTH);
+
+        let mut block: Block = last_full.try_into().unwrap();
+        block[..remainder].swap_with_slice(partial);
+        self.process_block(&tweak, &mut block, true);
+        last_full.copy_from_slice(&block);
+        block.zeroize();
+        tweak.zeroize();
+        Ok(())
+    }
+
+    ///
+    /// Decrypts the data unit with number `sector` in place.  Fails with
+    /// `XtsError::InvalidLength` if it is shorter than one block.
+    ///
+    pub fn decrypt_sector(&self, sector: u128, data: &mut [u8]) -> Result<(), XtsError> {
+        if data.len() < BLOCK_LENGTH {
+            return Err(XtsError::InvalidLength);
+        }
+
+        let full = data.len() / BLOCK_LENGTH;
+        let remainder = data.len() % BLOCK_LENGTH;
+        let mut tweak = self.initial_tweak(sector);
+        if remainder == 0 {
+            self.process_blocks(&mut tweak, data, false);
+            tweak.zeroize();
+            return Ok(());
+        }
+
+        // The last full block was encrypted with the tweak after its own,
+        // so it is decrypted first, before the stolen bytes are put back
+        let stolen = (full - 1) * BLOCK_LENGTH;
+        self.process_blocks(&mut tweak, &mut data[..stolen], false);
+        let (last_full, partial) = data[stolen..].split_at_mut(BLOCK_LENGTH);
+
+        let mut next_tweak = tweak;
+        multiply_by_x(&mut next_tweak);
+        let mut block: Block = last_full.try_into().unwrap();
+        self.process_block(&next_tweak, &mut block, false);
+        block[..remainder].swap_with_slice(partial);
+        self.process_block(&tweak, &mut block, false);
+        last_full.copy_from_slice(&block);
+        block.zeroize();
+        tweak.zeroize();
+        next_tweak.zeroize();
+        Ok(())
+    }
+
+    ///
+    /// Encrypts consecutive data units of `sector_size` bytes in place, the
+    /// first one numbered `first_sector`.  Fails with
+    /// `XtsError::InvalidLength`, without touching `data`, unless it is a
+    /// whole number of data units of at least one block.
+    ///
+    pub fn encrypt_sectors(
+        &self,
+        first_sector: u128,
+        sector_size: usize,
+        data: &mut [u8],
+    ) -> Result<(), XtsError> {
+        check_sectors(sector_size, data)?;
+        for (sector, chunk) in (first_sector..).zip(data.chunks_mut(sector_size)) {
+            self.encrypt_sector(sector, chunk)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Decrypts consecutive data units of `sector_size` bytes in place, the
+    /// first one numbered `first_sector`.  Fails like `encrypt_sectors`.
+    ///
+    pub fn decrypt_sectors(
+        &self,
+        first_sector: u128,
+        sector_size: usize,
+        data: &mut [u8],
+    ) -> Result<(), XtsError> {
+        check_sectors(sector_size, data)?;
+        for (sector, chunk) in (first_sector..).zip(data.chunks_mut(sector_size)) {
+            self.decrypt_sector(sector, chunk)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// The tweak of block 0: the sector number as a 128-bit little-endian
+    /// integer, encrypted with the second key.
+    ///
+    fn initial_tweak(&self, sector: u128) -> Block {
+        let mut tweak = sector.to_le_bytes();
+        self.tweak_cipher.encrypt_block(&mut tweak);
+        tweak
+    }
+
+    ///
+    /// Encrypts or decrypts whole blocks, starting with `tweak`, which is
+    /// left as the tweak of the block after them.
+    ///
+    fn process_blocks(&self, tweak: &mut Block, data: &mut [u8], encrypt: bool) {
+        let mut blocks = [[0u8; BLOCK_LENGTH]; PARALLEL_BLOCKS];
+        let mut tweaks = [[0u8; BLOCK_LENGTH]; PARALLEL_BLOCKS];
+        for chunk in data.chunks_mut(PARALLEL_BLOCKS * BLOCK_LENGTH) {
+            let count = chunk.len() / BLOCK_LENGTH;
+            let blocks = &mut blocks[..count];
+            for ((block, input), block_tweak) in blocks
+                .iter_mut()
+                .zip(chunk.chunks_exact(BLOCK_LENGTH))
+                .zip(tweaks.iter_mut())
+            {
+                *block_tweak = *tweak;
+                multiply_by_x(tweak);
+                for ((byte, input), tweak) in block.iter_mut().zip(input).zip(&*block_tweak) {
+                    *byte = input ^ tweak;
+                }
+            }
+
+            if encrypt {
+                self.cipher.encrypt_blocks(blocks);
+            } else {
+                self.cipher.decrypt_blocks(blocks);
+            }
+
+            for ((block, output), block_tweak) in blocks
+                .iter()
+                .zip(chunk.chunks_exact_mut(BLOCK_LENGTH))
+                .zip(&tweaks)
+            {
+                for ((output, byte), tweak) in output.iter_mut().zip(block).zip(block_tweak) {
+                    *output = byte ^ tweak;
+                }
+            }
+        }
+        blocks.zeroize();
+        tweaks.zeroize();
+    }
+
+    fn process_block(&self, tweak: &Block, block: &mut Block, encrypt: bool) {
+        for (byte, tweak) in block.iter_mut().zip(tweak) {
+            *byte ^= tweak;
+        }
+        if encrypt {
+            self.cipher.encrypt_block(block);
+        } else {
+            self.cipher.decrypt_block(block);
+        }
+        for (byte, tweak) in block.iter_mut().zip(tweak) {
+            *byte ^= tweak;
+        }
+    }
+}
+
+fn check_sectors(sector_size: usize, data: &[u8]) -> Result<(), XtsError> {
+    if sector_size < BLOCK_LENGTH || data.len() % sector_size != 0 {
+        return Err(XtsError::InvalidLength);
+    }
+    Ok(())
+}
+
+///
+/// Multiplication by x in GF(2^128) modulo x^128 + x^7 + x^2 + x + 1, on
+/// the little-endian representation XTS uses, without branching on the
+/// top bit.
+///
+fn multiply_by_x(tweak: &mut Block) {
+    let value = u128::from_le_bytes(*tweak);
+    let carry = (value >> 127) as u8;
+    let mut doubled = (value << 1).to_le_bytes();
+    doubled[0] ^= 0x87 & carry.wrapping_neg();
+    *tweak = doubled;
+}
@@ -0,0 +1,47 @@
+//!
+//! Version 2 Symmetrically Encrypted and Integrity Protected Data (SEIPD)
+//! packets of OpenPGP, as specified in RFC 9580, for exchanging encrypted
+//! data with OpenPGP implementations without a full OpenPGP stack.
+//!
+//! Only the packet itself is handled: the session key comes from the
+//! caller, who also builds and parses the packets it encrypts, such as a
+//! Literal Data packet.  Of the AEAD modes only GCM is supported, as this
+//! crate implements neither OCB nor EAX.
+//!
+mod seipd;
+
+pub use seipd::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenPgpError {
+    /// The packet is malformed or cut short, or is not a version 2 SEIPD
+    /// packet.
+    InvalidEncoding,
+    /// The packet uses a cipher other than AES or an AEAD mode other than
+    /// GCM.
+    UnsupportedAlgorithm,
+    /// The session key does not have the length the cipher needs.
+    InvalidKeyLength,
+    /// The chunk size octet is larger than 16.
+    InvalidChunkSize,
+    /// A chunk or the final tag did not match, or chunks were removed or
+    /// reordered.
+    InvalidTag,
+}
+
+impl fmt::Display for OpenPgpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenPgpError::InvalidEncoding => write!(f, "invalid SEIPD packet encoding"),
+            OpenPgpError::UnsupportedAlgorithm => write!(f, "unsupported OpenPGP algorithm"),
+            OpenPgpError::InvalidKeyLength => write!(f, "invalid session key length"),
+            OpenPgpError::InvalidChunkSize => write!(f, "invalid chunk size"),
+            OpenPgpError::InvalidTag => write!(f, "SEIPD authentication failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpenPgpError {}
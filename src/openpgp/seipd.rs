@@ -0,0 +1,365 @@
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::aead::{Aes128Gcm, Aes256Gcm, AesGcm, Tag, GCM_NONCE_LENGTH, TAG_LENGTH};
+use crate::hkdf::derive;
+use crate::sha2::Sha256;
+
+use super::OpenPgpError;
+
+/// The packet type ID 18 in OpenPGP format encoding, which starts every
+/// SEIPD packet.
+const PACKET_TAG: u8 = 0xc0 | 18;
+
+const VERSION: u8 = 2;
+
+/// The AEAD algorithm ID of GCM.
+const GCM_ID: u8 = 3;
+
+pub const SEIPD_SALT_LENGTH: usize = 32;
+
+/// Largest chunk size octet, for chunks of 4 MiB.
+pub const MAX_CHUNK_SIZE_OCTET: u8 = 16;
+
+/// The part of the nonce derived with the message key; the rest is the
+/// chunk index.
+const IV_LENGTH: usize = GCM_NONCE_LENGTH - 8;
+
+///
+/// The symmetric ciphers a SEIPD packet can use with this crate, and their
+/// OpenPGP algorithm IDs.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymmetricAlgorithm {
+    Aes128 = 7,
+    Aes192 = 8,
+    Aes256 = 9,
+}
+
+impl SymmetricAlgorithm {
+    pub fn key_length(&self) -> usize {
+        match self {
+            SymmetricAlgorithm::Aes128 => 16,
+            SymmetricAlgorithm::Aes192 => 24,
+            SymmetricAlgorithm::Aes256 => 32,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, OpenPgpError> {
+        match id {
+            7 => Ok(SymmetricAlgorithm::Aes128),
+            8 => Ok(SymmetricAlgorithm::Aes192),
+            9 => Ok(SymmetricAlgorithm::Aes256),
+            _ => Err(OpenPgpError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+///
+/// Encrypts `plaintext` into a version 2 SEIPD packet with AES-GCM, under a
+/// new random salt.  See `encrypt_seipd_with_salt`.
+///
+/// # Panics
+///
+/// Panics if the operating system cannot provide random bytes.
+///
+#[cfg(feature = "os_rng")]
+pub fn encrypt_seipd(
+    session_key: &[u8],
+    cipher: SymmetricAlgorithm,
+    chunk_size_octet: u8,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, OpenPgpError> {
+    let mut salt = [0u8; SEIPD_SALT_LENGTH];
+    getrandom::getrandom(&mut salt).expect("operating system random number generator failed");
+    encrypt_seipd_with_salt(session_key, cipher, chunk_size_octet, salt, plaintext)
+}
+
+///
+/// Encrypts `plaintext`, normally a sequence of OpenPGP packets, into a
+/// version 2 SEIPD packet with AES-GCM, including the packet header.
+///
+/// HKDF-SHA256 derives a message key and the first 4 bytes of every nonce
+/// from `session_key` and `salt`.  The plaintext is split into chunks of
+/// `1 << (chunk_size_octet + 6)` bytes, each sealed under the nonce ending
+/// in its index, and a final tag over the total length closes the packet,
+/// so chunks cannot be removed, reordered or cut off.
+///
+/// The salt must be random and never be used twice with the same session
+/// key.  Fails with `OpenPgpError::InvalidKeyLength` if `session_key` does
+/// not fit `cipher`, and with `OpenPgpError::InvalidChunkSize` if
+/// `chunk_size_octet` is larger than 16.
+///
+/// [Source](https://www.rfc-editor.org/rfc/rfc9580#section-5.13.2)
+///
+pub fn encrypt_seipd_with_salt(
+    session_key: &[u8],
+    cipher: SymmetricAlgorithm,
+    chunk_size_octet: u8,
+    salt: [u8; SEIPD_SALT_LENGTH],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, OpenPgpError> {
+    let associated_data = [PACKET_TAG, VERSION, cipher as u8, GCM_ID, chunk_size_octet];
+    let chunk_size = chunk_size(chunk_size_octet)?;
+    let (gcm, iv) = message_cipher(session_key, cipher, &salt, &associated_data)?;
+
+    let chunk_count = plaintext.len().div_ceil(chunk_size);
+    let mut body = Vec::with_capacity(
+        4 + SEIPD_SALT_LENGTH + plaintext.len() + (chunk_count + 1) * TAG_LENGTH,
+    );
+    body.extend_from_slice(&associated_data[1..]);
+    body.extend_from_slice(&salt);
+
+    for (index, chunk) in plaintext.chunks(chunk_size).enumerate() {
+        let start = body.len();
+        body.extend_from_slice(chunk);
+        let tag = gcm.seal(
+            nonce(&iv, index as u64),
+            &associated_data,
+            &mut body[start..],
+        );
+        body.extend_from_slice(tag.as_ref());
+    }
+    let tag = gcm.seal(
+        nonce(&iv, chunk_count as u64),
+        &final_associated_data(&associated_data, plaintext.len() as u64),
+        &mut [],
+    );
+    body.extend_from_slice(tag.as_ref());
+
+    let mut packet = Vec::with_capacity(body.len() + 6);
+    packet.push(PACKET_TAG);
+    write_length(&mut packet, body.len());
+    packet.extend_from_slice(&body);
+    Ok(packet)
+}
+
+///
+/// Verifies and decrypts a version 2 SEIPD packet, including its header,
+/// such as `encrypt_seipd` produces.  Both definite and partial body
+/// lengths are read.  The plaintext is only returned once every chunk and
+/// the final tag are verified.
+///
+/// Fails with `OpenPgpError::InvalidEncoding` if the packet is malformed,
+/// with `OpenPgpError::UnsupportedAlgorithm` if it uses another cipher than
+/// AES or another mode than GCM, with `OpenPgpError::InvalidKeyLength` if
+/// `session_key` does not fit its cipher, with
+/// `OpenPgpError::InvalidChunkSize` if its chunk size octet is larger than
+/// 16, and with `OpenPgpError::InvalidTag` if it is not authentic.
+///
+/// [Source](https://www.rfc-editor.org/rfc/rfc9580#section-5.13.2)
+///
+pub fn decrypt_seipd(session_key: &[u8], packet: &[u8]) -> Result<Vec<u8>, OpenPgpError> {
+    let body = read_body(packet)?;
+    if body.len() < 4 + SEIPD_SALT_LENGTH + TAG_LENGTH || body[0] != VERSION {
+        return Err(OpenPgpError::InvalidEncoding);
+    }
+    let cipher = SymmetricAlgorithm::from_id(body[1])?;
+    if body[2] != GCM_ID {
+        return Err(OpenPgpError::UnsupportedAlgorithm);
+    }
+    let associated_data = [PACKET_TAG, VERSION, body[1], body[2], body[3]];
+    let chunk_size = chunk_size(body[3])?;
+    let salt = &body[4..4 + SEIPD_SALT_LENGTH];
+    let (gcm, iv) = message_cipher(session_key, cipher, salt, &associated_data)?;
+
+    let (chunks, final_tag) =
+        body[4 + SEIPD_SALT_LENGTH..].split_at(body.len() - 4 - SEIPD_SALT_LENGTH - TAG_LENGTH);
+    let mut plaintext = Vec::with_capacity(chunks.len());
+    let mut chunk_count = 0;
+    for chunk in chunks.chunks(chunk_size + TAG_LENGTH) {
+        // An empty chunk is never written, so one is a sign of tampering.
+        if chunk.len() <= TAG_LENGTH {
+            plaintext.zeroize();
+            return Err(OpenPgpError::InvalidEncoding);
+        }
+        let (ciphertext, tag) = chunk.split_at(chunk.len() - TAG_LENGTH);
+        let start = plaintext.len();
+        plaintext.extend_from_slice(ciphertext);
+        if gcm
+            .open(
+                nonce(&iv, chunk_count),
+                &associated_data,
+                &mut plaintext[start..],
+                tag,
+            )
+            .is_err()
+        {
+            plaintext.zeroize();
+            return Err(OpenPgpError::InvalidTag);
+        }
+        chunk_count += 1;
+    }
+    let final_associated_data = final_associated_data(&associated_data, plaintext.len() as u64);
+    if gcm
+        .open(
+            nonce(&iv, chunk_count),
+            &final_associated_data,
+            &mut [],
+            final_tag,
+        )
+        .is_err()
+    {
+        plaintext.zeroize();
+        return Err(OpenPgpError::InvalidTag);
+    }
+    Ok(plaintext)
+}
+
+///
+/// AES-GCM with the key length of the packet's cipher.
+///
+enum MessageCipher {
+    Aes128(Aes128Gcm),
+    Aes192(AesGcm<24>),
+    Aes256(Aes256Gcm),
+}
+
+impl MessageCipher {
+    fn seal(&self, nonce: [u8; GCM_NONCE_LENGTH], aad: &[u8], buffer: &mut [u8]) -> Tag {
+        match self {
+            MessageCipher::Aes128(gcm) => gcm.seal_in_place_detached(nonce, aad, buffer),
+            MessageCipher::Aes192(gcm) => gcm.seal_in_place_detached(nonce, aad, buffer),
+            MessageCipher::Aes256(gcm) => gcm.seal_in_place_detached(nonce, aad, buffer),
+        }
+    }
+
+    fn open(
+        &self,
+        nonce: [u8; GCM_NONCE_LENGTH],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), OpenPgpError> {
+        let tag = Tag::try_from(tag).map_err(|_| OpenPgpError::InvalidEncoding)?;
+        match self {
+            MessageCipher::Aes128(gcm) => gcm.open_in_place_detached(nonce, aad, buffer, &tag),
+            MessageCipher::Aes192(gcm) => gcm.open_in_place_detached(nonce, aad, buffer, &tag),
+            MessageCipher::Aes256(gcm) => gcm.open_in_place_detached(nonce, aad, buffer, &tag),
+        }
+        .map_err(|_| OpenPgpError::InvalidTag)
+    }
+}
+
+///
+/// Derives the message key and the nonce's IV from the session key, with
+/// the packet's first five bytes as the HKDF info.
+///
+fn message_cipher(
+    session_key: &[u8],
+    cipher: SymmetricAlgorithm,
+    salt: &[u8],
+    info: &[u8],
+) -> Result<(MessageCipher, [u8; IV_LENGTH]), OpenPgpError> {
+    let key_length = cipher.key_length();
+    if session_key.len() != key_length {
+        return Err(OpenPgpError::InvalidKeyLength);
+    }
+
+    let mut okm = [0u8; 32 + IV_LENGTH];
+    let okm = &mut okm[..key_length + IV_LENGTH];
+    derive::<Sha256>(salt, session_key, info, okm).unwrap();
+    let (key, iv) = okm.split_at(key_length);
+    let iv = iv.try_into().unwrap();
+    let gcm = match cipher {
+        SymmetricAlgorithm::Aes128 => MessageCipher::Aes128(AesGcm::new(key.try_into().unwrap())),
+        SymmetricAlgorithm::Aes192 => MessageCipher::Aes192(AesGcm::new(key.try_into().unwrap())),
+        SymmetricAlgorithm::Aes256 => MessageCipher::Aes256(AesGcm::new(key.try_into().unwrap())),
+    };
+    okm.zeroize();
+    Ok((gcm, iv))
+}
+
+fn chunk_size(octet: u8) -> Result<usize, OpenPgpError> {
+    if octet > MAX_CHUNK_SIZE_OCTET {
+        return Err(OpenPgpError::InvalidChunkSize);
+    }
+    Ok(1 << (octet + 6))
+}
+
+fn nonce(iv: &[u8; IV_LENGTH], index: u64) -> [u8; GCM_NONCE_LENGTH] {
+    let mut nonce = [0u8; GCM_NONCE_LENGTH];
+    nonce[..IV_LENGTH].copy_from_slice(iv);
+    nonce[IV_LENGTH..].copy_from_slice(&index.to_be_bytes());
+    nonce
+}
+
+///
+/// The final tag's associated data: the chunks', then the total plaintext
+/// length as a 64-bit big-endian integer.
+///
+fn final_associated_data(associated_data: &[u8; 5], length: u64) -> [u8; 13] {
+    let mut final_associated_data = [0u8; 13];
+    final_associated_data[..5].copy_from_slice(associated_data);
+    final_associated_data[5..].copy_from_slice(&length.to_be_bytes());
+    final_associated_data
+}
+
+///
+/// Writes a definite body length in the one-, two- or five-octet form.
+///
+fn write_length(packet: &mut Vec<u8>, length: usize) {
+    match length {
+        0..=191 => packet.push(length as u8),
+        192..=8383 => {
+            let length = length - 192;
+            packet.extend_from_slice(&[(length >> 8) as u8 + 192, length as u8]);
+        }
+        _ => {
+            packet.push(0xff);
+            packet.extend_from_slice(&(length as u32).to_be_bytes());
+        }
+    }
+}
+
+///
+/// Checks the packet header and returns the body, joining the parts of a
+/// body with partial lengths.
+///
+/// [Source](https://www.rfc-editor.org/rfc/rfc9580#section-4.2.1)
+///
+fn read_body(packet: &[u8]) -> Result<Vec<u8>, OpenPgpError> {
+    let (&tag, mut rest) = packet.split_first().ok_or(OpenPgpError::InvalidEncoding)?;
+    if tag != PACKET_TAG {
+        return Err(OpenPgpError::InvalidEncoding);
+    }
+
+    let mut body = Vec::with_capacity(rest.len());
+    loop {
+        let (&first, after) = rest.split_first().ok_or(OpenPgpError::InvalidEncoding)?;
+        let (length, partial, after) = match first {
+            0..=191 => (first as usize, false, after),
+            192..=223 => {
+                let (&second, after) = after.split_first().ok_or(OpenPgpError::InvalidEncoding)?;
+                (
+                    (((first - 192) as usize) << 8) + second as usize + 192,
+                    false,
+                    after,
+                )
+            }
+            224..=254 => (1 << (first & 0x1f), true, after),
+            255 => {
+                if after.len() < 4 {
+                    return Err(OpenPgpError::InvalidEncoding);
+                }
+                let (length, after) = after.split_at(4);
+                let length = u32::from_be_bytes(length.try_into().unwrap());
+                (length as usize, false, after)
+            }
+        };
+        if after.len() < length {
+            return Err(OpenPgpError::InvalidEncoding);
+        }
+        let (part, after) = after.split_at(length);
+        body.extend_from_slice(part);
+        rest = after;
+        if !partial {
+            break;
+        }
+    }
+    if !rest.is_empty() {
+        return Err(OpenPgpError::InvalidEncoding);
+    }
+    Ok(body)
+}
@@ -0,0 +1,29 @@
+//!
+//! Branch-free helpers for the unpadding routines.  Masks are `0xff` for
+//! true and `0x00` for false.
+//!
+
+///
+/// Returns `0xff` if `a == b`, `0x00` otherwise.
+///
+pub(super) fn eq(a: u8, b: u8) -> u8 {
+    let difference = (a ^ b) as u32;
+    // Only a zero difference wraps around to set the top bit
+    ((difference.wrapping_sub(1) >> 31) as u8).wrapping_neg()
+}
+
+///
+/// Returns `0xff` if `a < b`, `0x00` otherwise.  Both values must be below
+/// 2^31, which holds for every length the padding schemes deal with.
+///
+pub(super) fn lt(a: usize, b: usize) -> u8 {
+    (((a as u32).wrapping_sub(b as u32) >> 31) as u8).wrapping_neg()
+}
+
+///
+/// Returns `a` if `mask` is `0xff`, `b` if it is `0x00`.
+///
+pub(super) fn select(mask: u8, a: usize, b: usize) -> usize {
+    let mask = ((mask & 1) as usize).wrapping_neg();
+    (a & mask) | (b & !mask)
+}
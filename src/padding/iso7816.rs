@@ -0,0 +1,66 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::constant_time;
+use super::PaddingError;
+
+///
+/// ISO/IEC 7816-4 padding appends a single `0x80` byte followed by as many
+/// zero bytes as needed to reach a multiple of the block size.
+///
+/// [Source](https://www.iso.org/standard/77180.html)
+///
+#[cfg(feature = "alloc")]
+pub fn iso7816_pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, PaddingError> {
+    if block_size == 0 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    let padding = block_size - data.len() % block_size;
+
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    padded.resize(data.len() + padding, 0);
+    Ok(padded)
+}
+
+///
+/// Removes ISO/IEC 7816-4 padding in constant time with respect to the
+/// contents of the last block.
+///
+pub fn iso7816_unpad(data: &[u8], block_size: usize) -> Result<&[u8], PaddingError> {
+    if block_size == 0 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    if data.is_empty() || data.len() % block_size != 0 {
+        return Err(PaddingError::InvalidLength);
+    }
+
+    let length = marker_position(data, block_size)?;
+    Ok(&data[..length])
+}
+
+///
+/// Finds the position of the `0x80` marker within the last `window` bytes
+/// of `data`, checking that only zero bytes follow it.  Every byte of the
+/// window is inspected, whatever the padding length turns out to be.
+///
+pub(super) fn marker_position(data: &[u8], window: usize) -> Result<usize, PaddingError> {
+    let start = data.len() - window;
+
+    let mut found = 0u8;
+    let mut invalid = 0u8;
+    let mut position = 0usize;
+    for (i, &byte) in data[start..].iter().enumerate().rev() {
+        let is_marker = constant_time::eq(byte, 0x80) & !found;
+        let is_zero = constant_time::eq(byte, 0);
+        invalid |= !found & !is_zero & !is_marker;
+        position = constant_time::select(is_marker, start + i, position);
+        found |= is_marker;
+    }
+    invalid |= !found;
+
+    if invalid != 0 {
+        return Err(PaddingError::InvalidPadding);
+    }
+    Ok(position)
+}
@@ -0,0 +1,34 @@
+mod constant_time;
+mod iso7816;
+mod padme;
+mod pkcs7;
+
+pub use iso7816::*;
+pub use padme::*;
+pub use pkcs7::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingError {
+    /// The block size is zero, or too large for the scheme.
+    InvalidBlockSize,
+    /// The padded data is empty or not a multiple of the block size.
+    InvalidLength,
+    /// The padding bytes are malformed.  Deliberately carries no detail
+    /// about what was wrong, so callers cannot leak it.
+    InvalidPadding,
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaddingError::InvalidBlockSize => write!(f, "invalid block size"),
+            PaddingError::InvalidLength => write!(f, "invalid padded length"),
+            PaddingError::InvalidPadding => write!(f, "invalid padding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PaddingError {}
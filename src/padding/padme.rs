@@ -0,0 +1,50 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::iso7816::marker_position;
+use super::PaddingError;
+
+///
+/// Computes the PADMÉ padded length of a message of `length` bytes.
+///
+/// PADMÉ rounds the length up so that only its top `log2(log2(length))`
+/// bits are kept, which leaks at most O(log log L) bits about the length
+/// while costing at most 12% overhead.
+///
+/// [Source](https://petsymposium.org/popets/2019/popets-2019-0056.pdf)
+///
+pub fn padme_length(length: usize) -> usize {
+    if length < 2 {
+        return length;
+    }
+    let exponent = usize::BITS - 1 - length.leading_zeros();
+    let significant_bits = u32::BITS - exponent.leading_zeros();
+    let last_bits = exponent - significant_bits;
+    let bit_mask = (1usize << last_bits) - 1;
+    (length + bit_mask) & !bit_mask
+}
+
+///
+/// Pads `data` to a PADMÉ length.  A `0x80` marker followed by zeros is
+/// used as filler (as in ISO/IEC 7816-4), so the padding can be removed
+/// without storing the original length.
+///
+#[cfg(feature = "alloc")]
+pub fn padme_pad(data: &[u8]) -> Vec<u8> {
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    padded.resize(padme_length(data.len() + 1), 0);
+    padded
+}
+
+///
+/// Removes padding added by `padme_pad`.  The whole input is scanned, so the
+/// running time only depends on the padded length.
+///
+pub fn padme_unpad(data: &[u8]) -> Result<&[u8], PaddingError> {
+    if data.is_empty() {
+        return Err(PaddingError::InvalidLength);
+    }
+    let length = marker_position(data, data.len())?;
+    Ok(&data[..length])
+}
@@ -0,0 +1,54 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::constant_time;
+use super::PaddingError;
+
+///
+/// PKCS#7 padding appends `n` bytes of value `n`, where `n` is between 1
+/// and the block size.  A full block of padding is added when the data is
+/// already block aligned, so the padding can always be removed.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3)
+///
+#[cfg(feature = "alloc")]
+pub fn pkcs7_pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    let padding = block_size - data.len() % block_size;
+
+    let mut padded = data.to_vec();
+    padded.resize(data.len() + padding, padding as u8);
+    Ok(padded)
+}
+
+///
+/// Removes PKCS#7 padding in constant time.  Every byte of the last block is
+/// inspected regardless of the padding length, and all malformed paddings
+/// produce the same `InvalidPadding` error.
+///
+pub fn pkcs7_unpad(data: &[u8], block_size: usize) -> Result<&[u8], PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    if data.is_empty() || data.len() % block_size != 0 {
+        return Err(PaddingError::InvalidLength);
+    }
+
+    let padding = data[data.len() - 1];
+    // The padding length must be in 1..=block_size
+    let mut invalid =
+        constant_time::eq(padding, 0) | !constant_time::lt(padding as usize, block_size + 1);
+
+    let last_block = &data[data.len() - block_size..];
+    for (i, &byte) in last_block.iter().rev().enumerate() {
+        let in_padding = constant_time::lt(i, padding as usize);
+        invalid |= in_padding & !constant_time::eq(byte, padding);
+    }
+
+    if invalid != 0 {
+        return Err(PaddingError::InvalidPadding);
+    }
+    Ok(&data[..data.len() - padding as usize])
+}
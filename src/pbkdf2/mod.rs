@@ -0,0 +1,25 @@
+mod rfc8018;
+
+pub use rfc8018::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pbkdf2Error {
+    /// The iteration count is zero.
+    InvalidIterations,
+    /// The requested output is longer than 2^32 - 1 hash outputs.
+    InvalidOutputLength,
+}
+
+impl fmt::Display for Pbkdf2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pbkdf2Error::InvalidIterations => write!(f, "invalid iteration count"),
+            Pbkdf2Error::InvalidOutputLength => write!(f, "invalid output length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Pbkdf2Error {}
@@ -0,0 +1,123 @@
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use zeroize::Zeroize;
+
+use crate::hash::Digest;
+use crate::mac::Hmac;
+use crate::sha2::{Sha256, Sha512};
+
+use super::Pbkdf2Error;
+
+/// Iterations `calibrate` starts measuring with.
+#[cfg(feature = "std")]
+const CALIBRATION_ITERATIONS: u32 = 1024;
+
+/// Shortest measurement `calibrate` extrapolates from, in milliseconds.
+#[cfg(feature = "std")]
+const CALIBRATION_MIN_MS: u128 = 20;
+
+///
+/// PBKDF2 with HMAC over any `Digest`: fills `output` with a key derived
+/// from `password` and `salt`, each output block the XOR of `iterations`
+/// chained HMACs.
+///
+/// PBKDF2 is here to read and write existing password stores.  It only
+/// costs time, which GPUs and ASICs parallelize cheaply, so new designs
+/// should prefer a memory-hard function.  Where PBKDF2 is required, OWASP
+/// recommends 600,000 iterations of HMAC-SHA256 or 210,000 of
+/// HMAC-SHA512, and `calibrate` measures what the running machine affords.
+/// The salt should be random and unique per password.
+///
+/// Fails with `Pbkdf2Error::InvalidIterations` if `iterations` is zero.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8018#section-5.2)
+///
+pub fn pbkdf2<D: Digest>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    output: &mut [u8],
+) -> Result<(), Pbkdf2Error> {
+    if iterations == 0 {
+        return Err(Pbkdf2Error::InvalidIterations);
+    }
+    if output.len().div_ceil(D::OUTPUT_LENGTH) as u64 > u32::MAX as u64 {
+        return Err(Pbkdf2Error::InvalidOutputLength);
+    }
+
+    let keyed = Hmac::<D>::new(password);
+    for (index, chunk) in (1u32..).zip(output.chunks_mut(D::OUTPUT_LENGTH)) {
+        // U_1 = HMAC(P, S || INT(i)), U_j = HMAC(P, U_(j - 1)), and the
+        // block is their XOR
+        let mut mac = keyed.clone();
+        mac.update(salt);
+        mac.update(&index.to_be_bytes());
+        let mut u = mac.finalize();
+        let mut block = u;
+
+        for _ in 1..iterations {
+            let mut mac = keyed.clone();
+            mac.update(u.as_ref());
+            u = mac.finalize();
+            for (byte, u) in block.as_mut().iter_mut().zip(u.as_ref()) {
+                *byte ^= u;
+            }
+        }
+
+        chunk.copy_from_slice(&block.as_ref()[..chunk.len()]);
+        u.as_mut().zeroize();
+        block.as_mut().zeroize();
+    }
+    Ok(())
+}
+
+///
+/// PBKDF2-HMAC-SHA256, as `pbkdf2`.
+///
+pub fn pbkdf2_hmac_sha256(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    output: &mut [u8],
+) -> Result<(), Pbkdf2Error> {
+    pbkdf2::<Sha256>(password, salt, iterations, output)
+}
+
+///
+/// PBKDF2-HMAC-SHA512, as `pbkdf2`.
+///
+pub fn pbkdf2_hmac_sha512(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    output: &mut [u8],
+) -> Result<(), Pbkdf2Error> {
+    pbkdf2::<Sha512>(password, salt, iterations, output)
+}
+
+///
+/// Returns the iteration count for which deriving one hash output of
+/// PBKDF2 over `D` takes about `target_ms` milliseconds on this machine,
+/// at least 1.  The count is extrapolated from a short benchmark, so it
+/// varies between runs and with the load on the machine; store it with
+/// each hash rather than recomputing it.
+///
+#[cfg(feature = "std")]
+pub fn calibrate<D: Digest>(target_ms: u64) -> u32 {
+    let mut output = [0u8; 64];
+    let output = &mut output[..D::OUTPUT_LENGTH.min(64)];
+
+    // Double the work until the measurement is long enough to trust
+    let mut iterations = CALIBRATION_ITERATIONS;
+    loop {
+        let start = Instant::now();
+        pbkdf2::<D>(b"calibration", b"calibration salt", iterations, output).unwrap();
+        let elapsed = start.elapsed().as_micros().max(1);
+        if elapsed >= CALIBRATION_MIN_MS * 1000 || iterations >= u32::MAX / 2 {
+            let scaled = iterations as u128 * target_ms as u128 * 1000 / elapsed;
+            return scaled.clamp(1, u32::MAX as u128) as u32;
+        }
+        iterations *= 2;
+    }
+}
@@ -0,0 +1,235 @@
+//!
+//! AVX2 implementation of Poly1305, absorbing four blocks at once.  Each
+//! 256-bit register holds the same 26-bit limb of four accumulators, one
+//! per block of a 64-byte group, which are all multiplied by r^4 per group
+//! with Horner's rule.  At the end the accumulators are multiplied by r^4,
+//! r^3, r^2 and r and summed, which gives the same value as absorbing the
+//! blocks one at a time.
+//!
+//! Follows the vectorized Poly1305 by Shay Gueron and Martin Goll.
+//! [Source](https://eprint.iacr.org/2015/565)
+//!
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use zeroize::Zeroize;
+
+use super::R;
+
+pub(super) const BLOCKS: usize = 4;
+
+/// Mask of the low 26 bits, the width of one limb.
+const LIMB_MASK: u32 = 0x3ffffff;
+
+/// The 0x01 byte appended to every full block, as seen from the top limb.
+const FULL_BLOCK_BIT: u32 = 1 << 24;
+
+///
+/// r, r^2, r^3 and r^4 on five 26-bit limbs, computed once per MAC.
+///
+#[derive(Clone)]
+pub(super) struct Powers {
+    powers: [[u32; 5]; BLOCKS],
+}
+
+impl Powers {
+    pub(super) fn new(r: R) -> Self {
+        let mut powers = [[0u32; 5]; BLOCKS];
+        for (i, limb) in powers[0].iter_mut().enumerate() {
+            *limb = (r >> (26 * i)) as u32 & LIMB_MASK;
+        }
+        for i in 1..BLOCKS {
+            powers[i] = multiply(&powers[i - 1], &powers[0]);
+        }
+        Powers { powers }
+    }
+}
+
+impl Zeroize for Powers {
+    fn zeroize(&mut self) {
+        self.powers.zeroize();
+    }
+}
+
+///
+/// a * b % p, not fully reduced, for the precomputation.
+///
+fn multiply(a: &[u32; 5], b: &[u32; 5]) -> [u32; 5] {
+    let [a0, a1, a2, a3, a4] = a.map(|a| a as u64);
+    let [b0, b1, b2, b3, b4] = b.map(|b| b as u64);
+    // 2^130 = 5 (mod p), so limbs wrapping past the top come back times 5
+    let [s1, s2, s3, s4] = [b1 * 5, b2 * 5, b3 * 5, b4 * 5];
+
+    let mut d = [
+        a0 * b0 + a1 * s4 + a2 * s3 + a3 * s2 + a4 * s1,
+        a0 * b1 + a1 * b0 + a2 * s4 + a3 * s3 + a4 * s2,
+        a0 * b2 + a1 * b1 + a2 * b0 + a3 * s4 + a4 * s3,
+        a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0 + a4 * s4,
+        a0 * b4 + a1 * b3 + a2 * b2 + a3 * b1 + a4 * b0,
+    ];
+    let mut result = [0u32; 5];
+    for i in 0..4 {
+        d[i + 1] += d[i] >> 26;
+        result[i] = d[i] as u32 & LIMB_MASK;
+    }
+    result[4] = d[4] as u32 & LIMB_MASK;
+    result[0] += (d[4] >> 26) as u32 * 5;
+    result[1] += result[0] >> 26;
+    result[0] &= LIMB_MASK;
+    result
+}
+
+///
+/// Absorbs `data`, a whole number of 64-byte groups of full blocks, into
+/// the accumulator `h`, given on five 26-bit limbs.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AVX2.
+///
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn blocks(powers: &Powers, h: &mut [u32; 5], data: &[u8]) {
+    debug_assert!(!data.is_empty() && data.len() % (16 * BLOCKS) == 0);
+    let mut groups = data.chunks_exact(16 * BLOCKS);
+
+    // The running accumulator joins the first block's lane
+    let mut acc = load(groups.next().unwrap());
+    for (limb, &h) in acc.iter_mut().zip(h.iter()) {
+        *limb = _mm256_add_epi64(*limb, _mm256_setr_epi64x(h as i64, 0, 0, 0));
+    }
+
+    let r4 = broadcast(&powers.powers[3]);
+    for group in groups {
+        acc = add(&multiply_reduce(&acc, &r4), &load(group));
+    }
+
+    // The first block's lane still owes r^4, the last block's only r
+    let mut tail = [[0u32; 5]; BLOCKS];
+    for (lane, power) in tail.iter_mut().zip(powers.powers.iter().rev()) {
+        *lane = *power;
+    }
+    acc = multiply_reduce(&acc, &interleave(&tail));
+
+    let mut sums = [0u64; 5];
+    for (sum, limb) in sums.iter_mut().zip(&acc) {
+        let mut lanes = [0u64; BLOCKS];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, *limb);
+        *sum = lanes.iter().sum();
+    }
+
+    // The sums of four lanes are a few bits over 26, so carry them
+    // through once to get the accumulator back in shape
+    for i in 0..4 {
+        sums[i + 1] += sums[i] >> 26;
+        h[i] = sums[i] as u32 & LIMB_MASK;
+    }
+    h[4] = sums[4] as u32 & LIMB_MASK;
+    h[0] += (sums[4] >> 26) as u32 * 5;
+    h[1] += h[0] >> 26;
+    h[0] &= LIMB_MASK;
+}
+
+///
+/// Splits four consecutive blocks into limbs, one block per lane, with the
+/// 0x01 byte above each.
+///
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn load(group: &[u8]) -> [__m256i; 5] {
+    let first = _mm256_loadu_si256(group.as_ptr() as *const __m256i);
+    let second = _mm256_loadu_si256(group[32..].as_ptr() as *const __m256i);
+
+    // The unpacks work within 128-bit halves, which leaves the blocks in
+    // the order 0, 2, 1, 3; the permutes put them back in order
+    let low = _mm256_permute4x64_epi64(_mm256_unpacklo_epi64(first, second), 0b11_01_10_00);
+    let high = _mm256_permute4x64_epi64(_mm256_unpackhi_epi64(first, second), 0b11_01_10_00);
+
+    let mask = _mm256_set1_epi64x(LIMB_MASK as i64);
+    [
+        _mm256_and_si256(low, mask),
+        _mm256_and_si256(_mm256_srli_epi64(low, 26), mask),
+        _mm256_and_si256(
+            _mm256_or_si256(_mm256_srli_epi64(low, 52), _mm256_slli_epi64(high, 12)),
+            mask,
+        ),
+        _mm256_and_si256(_mm256_srli_epi64(high, 14), mask),
+        _mm256_or_si256(
+            _mm256_srli_epi64(high, 40),
+            _mm256_set1_epi64x(FULL_BLOCK_BIT as i64),
+        ),
+    ]
+}
+
+///
+/// The same field element in all four lanes.
+///
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn broadcast(limbs: &[u32; 5]) -> [__m256i; 5] {
+    limbs.map(|limb| _mm256_set1_epi64x(limb as i64))
+}
+
+///
+/// One field element per lane.
+///
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn interleave(elements: &[[u32; 5]; BLOCKS]) -> [__m256i; 5] {
+    let mut limbs = [_mm256_setzero_si256(); 5];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let [a, b, c, d] = elements.map(|element| element[i] as i64);
+        *limb = _mm256_setr_epi64x(a, b, c, d);
+    }
+    limbs
+}
+
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn add(a: &[__m256i; 5], b: &[__m256i; 5]) -> [__m256i; 5] {
+    let mut sum = *a;
+    for (sum, b) in sum.iter_mut().zip(b) {
+        *sum = _mm256_add_epi64(*sum, *b);
+    }
+    sum
+}
+
+///
+/// a * b % p in every lane, with the same partial carry propagation as the
+/// scalar code.  The limbs of `a` may be a bit over 26 bits; the products,
+/// and the sums of five of them, still fit in 64 bits.
+///
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn multiply_reduce(a: &[__m256i; 5], b: &[__m256i; 5]) -> [__m256i; 5] {
+    let [b0, b1, b2, b3, b4] = *b;
+    let times_5 = |x: __m256i| _mm256_add_epi64(x, _mm256_slli_epi64(x, 2));
+    let [s1, s2, s3, s4] = [times_5(b1), times_5(b2), times_5(b3), times_5(b4)];
+
+    let sum = |terms: [(__m256i, __m256i); 5]| {
+        terms.iter().fold(_mm256_setzero_si256(), |acc, &(x, y)| {
+            _mm256_add_epi64(acc, _mm256_mul_epu32(x, y))
+        })
+    };
+    let [a0, a1, a2, a3, a4] = *a;
+    let mut d = [
+        sum([(a0, b0), (a1, s4), (a2, s3), (a3, s2), (a4, s1)]),
+        sum([(a0, b1), (a1, b0), (a2, s4), (a3, s3), (a4, s2)]),
+        sum([(a0, b2), (a1, b1), (a2, b0), (a3, s4), (a4, s3)]),
+        sum([(a0, b3), (a1, b2), (a2, b1), (a3, b0), (a4, s4)]),
+        sum([(a0, b4), (a1, b3), (a2, b2), (a3, b1), (a4, b0)]),
+    ];
+
+    let mask = _mm256_set1_epi64x(LIMB_MASK as i64);
+    for i in 0..4 {
+        d[i + 1] = _mm256_add_epi64(d[i + 1], _mm256_srli_epi64(d[i], 26));
+        d[i] = _mm256_and_si256(d[i], mask);
+    }
+    let carry = _mm256_srli_epi64(d[4], 26);
+    d[4] = _mm256_and_si256(d[4], mask);
+    d[0] = _mm256_add_epi64(d[0], times_5(carry));
+    d[1] = _mm256_add_epi64(d[1], _mm256_srli_epi64(d[0], 26));
+    d[0] = _mm256_and_si256(d[0], mask);
+    d
+}
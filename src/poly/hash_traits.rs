@@ -0,0 +1,57 @@
+//!
+//! RustCrypto `universal-hash` trait implementations, so `Poly1305` can be
+//! used wherever a `KeyInit + UniversalHash` type is expected, such as in
+//! generic AEAD constructions.
+//!
+use universal_hash::consts::{U16, U32};
+use universal_hash::crypto_common::{BlockSizeUser, KeySizeUser, ParBlocksSizeUser};
+use universal_hash::{Block, KeyInit, ParBlocks, UhfBackend, UhfClosure, UniversalHash};
+
+use super::Poly1305;
+
+const BLOCK_LENGTH: usize = 16;
+
+impl KeySizeUser for Poly1305 {
+    type KeySize = U32;
+}
+
+impl KeyInit for Poly1305 {
+    fn new(key: &universal_hash::Key<Self>) -> Self {
+        Poly1305::new(<[u8; 32]>::from(*key))
+    }
+}
+
+impl BlockSizeUser for Poly1305 {
+    type BlockSize = U16;
+}
+
+///
+/// Sixteen blocks at a time, enough for the vectorized code to take them.
+///
+impl ParBlocksSizeUser for Poly1305 {
+    type ParBlocksSize = U16;
+}
+
+impl UhfBackend for Poly1305 {
+    fn proc_block(&mut self, block: &Block<Self>) {
+        self.update(block);
+    }
+
+    fn proc_par_blocks(&mut self, blocks: &ParBlocks<Self>) {
+        let mut data = [0u8; 16 * BLOCK_LENGTH];
+        for (chunk, block) in data.chunks_exact_mut(BLOCK_LENGTH).zip(blocks) {
+            chunk.copy_from_slice(block);
+        }
+        self.update(&data);
+    }
+}
+
+impl UniversalHash for Poly1305 {
+    fn update_with_backend(&mut self, f: impl UhfClosure<BlockSize = Self::BlockSize>) {
+        f.call(self);
+    }
+
+    fn finalize(self) -> Block<Self> {
+        Poly1305::finalize(self).into_bytes().into()
+    }
+}
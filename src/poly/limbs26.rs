@@ -0,0 +1,179 @@
+//!
+//! Poly1305 arithmetic on five 26-bit limbs with 64-bit products, for
+//! targets without fast 128-bit multiplication.
+//!
+//! Follows the 32-bit poly1305-donna implementation by Andrew Moon.
+//! [Source](https://github.com/floodyberry/poly1305-donna)
+//!
+use zeroize::Zeroize;
+
+use super::{Key, R, TAG_LENGTH};
+
+/// Mask of the low 26 bits, the width of one limb.
+const LIMB_MASK: u32 = 0x3ffffff;
+
+/// The 0x01 byte appended to every full block, as seen from the top limb.
+const FULL_BLOCK_BIT: u32 = 1 << 24;
+
+///
+/// The key and accumulator.  Products of two limbs fit in 64 bits with room
+/// to add five of them, so carries only need to be propagated once per
+/// block.
+///
+#[derive(Clone)]
+pub(super) struct State {
+    r: [u32; 5],
+    s: [u32; 4],
+    h: [u32; 5],
+}
+
+fn le32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+impl State {
+    pub(super) fn new(key: &Key) -> Self {
+        // r is clamped while it is split into limbs
+        let r = [
+            le32(&key[0..]) & 0x3ffffff,
+            (le32(&key[3..]) >> 2) & 0x3ffff03,
+            (le32(&key[6..]) >> 4) & 0x3ffc0ff,
+            (le32(&key[9..]) >> 6) & 0x3f03fff,
+            (le32(&key[12..]) >> 8) & 0x00fffff,
+        ];
+        let s = [
+            le32(&key[16..]),
+            le32(&key[20..]),
+            le32(&key[24..]),
+            le32(&key[28..]),
+        ];
+        State { r, s, h: [0; 5] }
+    }
+
+    ///
+    /// a = ((a + n) * r) % p for one 16-byte block.  `full` adds the 0x01
+    /// byte above the block; the padded final block carries its own.
+    ///
+    pub(super) fn block(&mut self, block: &[u8; 16], full: bool) {
+        let high_bit = if full { FULL_BLOCK_BIT } else { 0 };
+        let [r0, r1, r2, r3, r4] = self.r.map(|r| r as u64);
+        // 2^130 = 5 (mod p), so limbs wrapping past the top come back times 5
+        let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+
+        let h = &mut self.h;
+        h[0] += le32(&block[0..]) & LIMB_MASK;
+        h[1] += (le32(&block[3..]) >> 2) & LIMB_MASK;
+        h[2] += (le32(&block[6..]) >> 4) & LIMB_MASK;
+        h[3] += (le32(&block[9..]) >> 6) & LIMB_MASK;
+        h[4] += (le32(&block[12..]) >> 8) | high_bit;
+
+        let [h0, h1, h2, h3, h4] = h.map(|h| h as u64);
+        let mut d = [
+            h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1,
+            h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2,
+            h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3,
+            h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4,
+            h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0,
+        ];
+
+        // Partial carry propagation, leaving h only slightly above p
+        for i in 0..4 {
+            d[i + 1] += d[i] >> 26;
+            h[i] = d[i] as u32 & LIMB_MASK;
+        }
+        h[4] = d[4] as u32 & LIMB_MASK;
+        h[0] += (d[4] >> 26) as u32 * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= LIMB_MASK;
+    }
+
+    ///
+    /// The clamped r, for the vectorized code.
+    ///
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(super) fn r(&self) -> R {
+        self.r
+            .iter()
+            .rev()
+            .fold(0, |r, &limb| (r << 26) | limb as R)
+    }
+
+    ///
+    /// The accumulator on five 26-bit limbs, for the vectorized code.
+    ///
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(super) fn accumulator(&self) -> [u32; 5] {
+        self.h
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(super) fn set_accumulator(&mut self, h: [u32; 5]) {
+        self.h = h;
+    }
+
+    ///
+    /// Fully reduces the accumulator modulo p, adds s, and returns the low
+    /// 128 bits.
+    ///
+    pub(super) fn finish(&self) -> [u8; TAG_LENGTH] {
+        let mut h = self.h;
+
+        // Full carry propagation
+        let mut carry;
+        carry = h[1] >> 26;
+        h[1] &= LIMB_MASK;
+        h[2] += carry;
+        carry = h[2] >> 26;
+        h[2] &= LIMB_MASK;
+        h[3] += carry;
+        carry = h[3] >> 26;
+        h[3] &= LIMB_MASK;
+        h[4] += carry;
+        carry = h[4] >> 26;
+        h[4] &= LIMB_MASK;
+        h[0] += carry * 5;
+        carry = h[0] >> 26;
+        h[0] &= LIMB_MASK;
+        h[1] += carry;
+
+        // g = h - p = h + 5 - 2^130
+        let mut g = [0u32; 5];
+        carry = 5;
+        for i in 0..4 {
+            g[i] = h[i] + carry;
+            carry = g[i] >> 26;
+            g[i] &= LIMB_MASK;
+        }
+        g[4] = h[4].wrapping_add(carry).wrapping_sub(1 << 26);
+
+        // Select h if h < p (g went negative), or g otherwise
+        let use_g = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !use_g) | (g[i] & use_g);
+        }
+
+        // Pack into four 32-bit words and add s, modulo 2^128
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+        let mut tag = [0u8; 16];
+        let mut carry = 0u64;
+        for ((chunk, word), s) in tag.chunks_exact_mut(4).zip(words).zip(self.s) {
+            let sum = word as u64 + s as u64 + carry;
+            chunk.copy_from_slice(&(sum as u32).to_le_bytes());
+            carry = sum >> 32;
+        }
+        tag
+    }
+}
+
+impl Zeroize for State {
+    fn zeroize(&mut self) {
+        self.r.zeroize();
+        self.s.zeroize();
+        self.h.zeroize();
+    }
+}
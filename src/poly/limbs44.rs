@@ -0,0 +1,180 @@
+//!
+//! Poly1305 arithmetic on three 44-bit limbs (the top one 42 bits) with
+//! 128-bit products, for 64-bit targets.  A block takes nine
+//! multiplications instead of the 26-bit version's twenty-five.
+//!
+//! Follows the 64-bit poly1305-donna implementation by Andrew Moon.
+//! [Source](https://github.com/floodyberry/poly1305-donna)
+//!
+use zeroize::Zeroize;
+
+use super::{Key, R, TAG_LENGTH};
+
+/// Mask of the low 44 bits, the width of the two lower limbs.
+const LIMB_MASK: u64 = 0xfffffffffff;
+/// Mask of the low 42 bits, the width of the top limb.
+const TOP_LIMB_MASK: u64 = 0x3ffffffffff;
+
+/// The 0x01 byte appended to every full block, as seen from the top limb.
+const FULL_BLOCK_BIT: u64 = 1 << 40;
+
+///
+/// The key and accumulator.  Products of two limbs fit in 128 bits with
+/// plenty of room to add three of them, so carries only need to be
+/// propagated once per block.
+///
+#[derive(Clone)]
+pub(super) struct State {
+    r: [u64; 3],
+    s: [u64; 2],
+    h: [u64; 3],
+}
+
+fn le64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+impl State {
+    pub(super) fn new(key: &Key) -> Self {
+        let (t0, t1) = (le64(&key[0..]), le64(&key[8..]));
+
+        // r is clamped while it is split into limbs
+        let r = [
+            t0 & 0xffc0fffffff,
+            ((t0 >> 44) | (t1 << 20)) & 0xfffffc0ffff,
+            (t1 >> 24) & 0x00ffffffc0f,
+        ];
+        let s = [le64(&key[16..]), le64(&key[24..])];
+        State { r, s, h: [0; 3] }
+    }
+
+    ///
+    /// a = ((a + n) * r) % p for one 16-byte block.  `full` adds the 0x01
+    /// byte above the block; the padded final block carries its own.
+    ///
+    pub(super) fn block(&mut self, block: &[u8; 16], full: bool) {
+        let high_bit = if full { FULL_BLOCK_BIT } else { 0 };
+        let [r0, r1, r2] = self.r.map(|r| r as u128);
+        // 2^130 = 5 (mod p), and the limbs are 44 bits apart while 2^130 is
+        // 2^132 / 4, so wrapped products come back times 20
+        let (s1, s2) = (r1 * 20, r2 * 20);
+
+        let (t0, t1) = (le64(&block[0..]), le64(&block[8..]));
+        let h = &mut self.h;
+        h[0] += t0 & LIMB_MASK;
+        h[1] += ((t0 >> 44) | (t1 << 20)) & LIMB_MASK;
+        h[2] += ((t1 >> 24) & TOP_LIMB_MASK) | high_bit;
+
+        let [h0, h1, h2] = h.map(|h| h as u128);
+        let d0 = h0 * r0 + h1 * s2 + h2 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0;
+
+        // Partial carry propagation, leaving h only slightly above p
+        d1 += d0 >> 44;
+        h[0] = d0 as u64 & LIMB_MASK;
+        d2 += d1 >> 44;
+        h[1] = d1 as u64 & LIMB_MASK;
+        h[2] = d2 as u64 & TOP_LIMB_MASK;
+        h[0] += (d2 >> 42) as u64 * 5;
+        h[1] += h[0] >> 44;
+        h[0] &= LIMB_MASK;
+    }
+
+    ///
+    /// The clamped r, for the vectorized code.
+    ///
+    #[cfg(target_arch = "x86_64")]
+    pub(super) fn r(&self) -> R {
+        let [r0, r1, r2] = self.r.map(|r| r as R);
+        r0 | (r1 << 44) | (r2 << 88)
+    }
+
+    ///
+    /// The accumulator on five 26-bit limbs, for the vectorized code.  The
+    /// lower limbs are carried first, so each one fits its new width.
+    ///
+    #[cfg(target_arch = "x86_64")]
+    pub(super) fn accumulator(&self) -> [u32; 5] {
+        let mut h = self.h;
+        h[1] += h[0] >> 44;
+        h[0] &= LIMB_MASK;
+        h[2] += h[1] >> 44;
+        h[1] &= LIMB_MASK;
+
+        let mask = 0x3ffffff;
+        [
+            (h[0] & mask) as u32,
+            (((h[0] >> 26) | (h[1] << 18)) & mask) as u32,
+            ((h[1] >> 8) & mask) as u32,
+            (((h[1] >> 34) | (h[2] << 10)) & mask) as u32,
+            (h[2] >> 16) as u32,
+        ]
+    }
+
+    ///
+    /// Sets the accumulator from five 26-bit limbs, which may each be a few
+    /// bits over.
+    ///
+    #[cfg(target_arch = "x86_64")]
+    pub(super) fn set_accumulator(&mut self, h: [u32; 5]) {
+        let [h0, h1, h2, h3, h4] = h.map(|h| h as u64);
+        let t0 = h0 + (h1 << 26);
+        let t1 = (t0 >> 44) + (h2 << 8) + (h3 << 34);
+        self.h = [t0 & LIMB_MASK, t1 & LIMB_MASK, (t1 >> 44) + (h4 << 16)];
+    }
+
+    ///
+    /// Fully reduces the accumulator modulo p, adds s, and returns the low
+    /// 128 bits.
+    ///
+    pub(super) fn finish(&self) -> [u8; TAG_LENGTH] {
+        let mut h = self.h;
+
+        // Full carry propagation, twice around to settle every limb
+        for _ in 0..2 {
+            h[2] += h[1] >> 44;
+            h[1] &= LIMB_MASK;
+            h[0] += (h[2] >> 42) * 5;
+            h[2] &= TOP_LIMB_MASK;
+            h[1] += h[0] >> 44;
+            h[0] &= LIMB_MASK;
+        }
+
+        // g = h - p = h + 5 - 2^130
+        let mut g = [0u64; 3];
+        g[0] = h[0] + 5;
+        g[1] = h[1] + (g[0] >> 44);
+        g[0] &= LIMB_MASK;
+        g[2] = (h[2] + (g[1] >> 44)).wrapping_sub(1 << 42);
+        g[1] &= LIMB_MASK;
+
+        // Select h if h < p (g went negative), or g otherwise
+        let use_g = (g[2] >> 63).wrapping_sub(1);
+        for i in 0..3 {
+            h[i] = (h[i] & !use_g) | (g[i] & use_g);
+        }
+
+        // h + s, modulo 2^128
+        let [s0, s1] = self.s;
+        h[0] += s0 & LIMB_MASK;
+        h[1] += (((s0 >> 44) | (s1 << 20)) & LIMB_MASK) + (h[0] >> 44);
+        h[0] &= LIMB_MASK;
+        h[2] += ((s1 >> 24) & TOP_LIMB_MASK) + (h[1] >> 44);
+        h[1] &= LIMB_MASK;
+        h[2] &= TOP_LIMB_MASK;
+
+        let mut tag = [0u8; 16];
+        tag[..8].copy_from_slice(&(h[0] | (h[1] << 44)).to_le_bytes());
+        tag[8..].copy_from_slice(&((h[1] >> 20) | (h[2] << 24)).to_le_bytes());
+        tag
+    }
+}
+
+impl Zeroize for State {
+    fn zeroize(&mut self) {
+        self.r.zeroize();
+        self.s.zeroize();
+        self.h.zeroize();
+    }
+}
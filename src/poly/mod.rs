@@ -1,3 +1,17 @@
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+#[cfg(feature = "rustcrypto-compat")]
+mod hash_traits;
+#[cfg(not(target_pointer_width = "64"))]
+mod limbs26;
+#[cfg(target_pointer_width = "64")]
+mod limbs44;
 mod poly1305;
+mod poly1305_aes;
+#[cfg(feature = "std")]
+mod stream;
+mod tag;
 
 pub use poly1305::*;
+pub use poly1305_aes::*;
+pub use tag::*;
@@ -1,20 +1,41 @@
-use std::ops::Sub;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
-use num_bigint::{BigUint, ToBigUint};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::backend::has_avx2;
+use crate::chacha::{self, ChaCha20Block};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::avx2;
+#[cfg(not(target_pointer_width = "64"))]
+use super::limbs26::State;
+#[cfg(target_pointer_width = "64")]
+use super::limbs44::State;
+use super::{Poly1305Error, Tag};
 
 pub type R = u128;
 pub type S = u128;
 pub type Key = [u8; 32];
 
+/// Fewest bytes worth handing to the vectorized code.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const BATCH_MINIMUM: usize = 256;
+
+/// Fewest messages each thread verifies at a time in the parallel batch.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_ITEMS: usize = 64;
+
 ///
 /// R must be clamped before it is used in the Poly1305 function.
 ///
 /// This is done using the following steps:
 ///
 /// * r(3), r(7), r(11), and r(15) are required to have their top four
-/// bits clear (be smaller than 16)
+///   bits clear (be smaller than 16)
 /// * r(4), r(8), and r(12) are required to have their bottom two bits
-/// clear (be divisible by 4)
+///   clear (be divisible by 4)
 ///
 /// This method was adapted from poly1305aes_test_clamp.c version 20050207
 /// D. J. Bernstein
@@ -44,31 +65,241 @@ pub fn poly1305_r_clamp(r: R) -> R {
 //    a += s
 //    return num_to_16_le_bytes(a)
 //    end
-pub fn poly1305_mac(key: Key, data: &[u8]) -> [u8; 16] {
-    let r = poly1305_r_clamp(u128::from_le_bytes(key[0..16].try_into().unwrap()))
-        .to_biguint()
-        .unwrap();
-    let s = u128::from_le_bytes(key[16..32].try_into().unwrap())
-        .to_biguint()
-        .unwrap();
-
-    let mut accumulator = 0.to_biguint().unwrap();
-    let p = 2
-        .to_biguint()
-        .unwrap()
-        .pow(130)
-        .sub(5.to_biguint().unwrap());
-    for i in 1..=(data.len().div_ceil(16)) {
-        let mut n_bytes = data[((i - 1) * 16)..(i * 16).min(data.len())].to_vec();
-        n_bytes.push(0x01u8);
-
-        let n = BigUint::from_bytes_le(&n_bytes);
-        accumulator += n;
-        accumulator = (r.clone() * accumulator) % p.clone();
+///
+/// Computes the 16-byte Poly1305 tag of `data` under the one-time `key`.
+///
+/// Every input length is accepted: an empty message yields `s` (the second
+/// half of the key), and a trailing block shorter than 16 bytes is padded
+/// with the `0x01` marker exactly like the full blocks are.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7539#section-2.5.1)
+///
+pub fn poly1305_mac(key: Key, data: &[u8]) -> Tag {
+    let mut mac = Poly1305::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+///
+/// Generates the one-time Poly1305 key for a ChaCha20 `key` and `nonce`:
+/// the first 32 bytes of keystream block 0.  This is how ChaCha20-Poly1305
+/// keys its MAC, with the message itself encrypted from block 1 on.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.6)
+///
+pub fn poly1305_key_gen(key: impl Into<chacha::Key>, nonce: impl Into<chacha::Nonce>) -> Key {
+    let mut keystream = ChaCha20Block::with_key(&key.into(), &nonce.into(), 0).get_keystream();
+    let poly_key: Key = keystream[..32].try_into().unwrap();
+    keystream.zeroize();
+    poly_key
+}
+
+///
+/// Checks that `tag` is the Poly1305 tag of `data` under `key`.  The tags
+/// are compared in constant time, so a forger learns nothing from how long
+/// the check takes.
+///
+#[must_use]
+pub fn poly1305_verify(key: Key, data: &[u8], tag: &Tag) -> bool {
+    let mut mac = Poly1305::new(key);
+    mac.update(data);
+    mac.verify(tag)
+}
+
+///
+/// Verifies a batch of messages, each under its own one-time key, and
+/// returns for each one whether its tag is valid.  Every tag is compared in
+/// constant time, and a failure does not stop the rest from being checked.
+///
+#[cfg(feature = "alloc")]
+pub fn poly1305_verify_batch(items: &[(Key, &[u8], Tag)]) -> Vec<bool> {
+    items
+        .iter()
+        .map(|(key, data, tag)| poly1305_verify(*key, data, tag))
+        .collect()
+}
+
+///
+/// Like `poly1305_verify_batch`, but spreads the messages over rayon's
+/// thread pool, in chunks so small messages do not drown in scheduling.
+///
+#[cfg(feature = "parallel")]
+pub fn par_poly1305_verify_batch(items: &[(Key, &[u8], Tag)]) -> Vec<bool> {
+    use rayon::prelude::*;
+
+    items
+        .par_iter()
+        .with_min_len(PARALLEL_CHUNK_ITEMS)
+        .map(|(key, data, tag)| poly1305_verify(*key, data, tag))
+        .collect()
+}
+
+///
+/// Compares a computed tag with received bytes in constant time.  Slices of
+/// another length are never equal.
+///
+#[cfg(feature = "alloc")]
+pub(crate) fn tags_equal(a: &Tag, b: &[u8]) -> bool {
+    subtle::ConstantTimeEq::ct_eq(a.as_ref(), b).into()
+}
+
+///
+/// A streaming Poly1305 MAC, for messages that arrive in pieces.  Feeding
+/// the message to `update` in any split and calling `finalize` gives the
+/// same tag as `poly1305_mac` over the whole message.
+///
+/// The arithmetic uses fixed-size limbs: three 44-bit limbs with 128-bit
+/// products on 64-bit targets, and five 26-bit limbs with 64-bit products
+/// elsewhere.  There is no heap allocation, no branch on secret data and no
+/// secret-dependent table lookup, and the key and state are wiped on drop.
+///
+/// On x86 CPUs with AVX2, long messages are absorbed four blocks at a time
+/// with precomputed powers of r instead, picked at runtime like the ChaCha
+/// backends.
+///
+#[derive(Clone)]
+pub struct Poly1305 {
+    state: State,
+    // r to r^4 for the vectorized code, computed on first use
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    powers: Option<avx2::Powers>,
+    // Bytes of an incomplete block, waiting for more data
+    buffer: [u8; 16],
+    buffer_length: usize,
+}
+
+impl Poly1305 {
+    ///
+    /// Starts a MAC under the one-time `key`: `r` is its first 16 bytes,
+    /// clamped, and `s` the last 16.  A key must never authenticate more
+    /// than one message.
+    ///
+    pub fn new(key: Key) -> Self {
+        Poly1305 {
+            state: State::new(&key),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            powers: None,
+            buffer: [0; 16],
+            buffer_length: 0,
+        }
+    }
+
+    ///
+    /// Like `new`, but takes the key as a slice, for keys cut from a larger
+    /// buffer.  Fails with `Poly1305Error::InvalidLength` unless the slice
+    /// is exactly 32 bytes long.
+    ///
+    pub fn new_from_slice(key: &[u8]) -> Result<Self, Poly1305Error> {
+        let key: Key = key.try_into().map_err(|_| Poly1305Error::InvalidLength)?;
+        Ok(Poly1305::new(key))
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_length > 0 {
+            let length = (16 - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+            if self.buffer_length < 16 {
+                return;
+            }
+
+            let block = self.buffer;
+            self.state.block(&block, true);
+            self.buffer_length = 0;
+        }
+
+        data = self.update_batches(data);
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.state.block(block.try_into().unwrap(), true);
+        }
+
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
     }
 
-    // Only the last 16 bytes are needed
-    let code = ((accumulator + s) & 0xffffffffffffffffffffffffffffffffu128.to_biguint().unwrap())
-        .to_bytes_le();
-    code.try_into().unwrap()
+    ///
+    /// Hands as many full blocks from the start of `data` as pay off to the
+    /// vectorized code, if there is one.  Returns the rest.
+    ///
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn update_batches<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        let length = data.len() - data.len() % (16 * avx2::BLOCKS);
+        // Below four groups the setup and final sum do not pay off
+        if length < BATCH_MINIMUM || !has_avx2() {
+            return data;
+        }
+
+        let powers = self
+            .powers
+            .get_or_insert_with(|| avx2::Powers::new(self.state.r()));
+        let mut h = self.state.accumulator();
+        // SAFETY: AVX2 was detected above
+        unsafe { avx2::blocks(powers, &mut h, &data[..length]) };
+        self.state.set_accumulator(h);
+        h.zeroize();
+        &data[length..]
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    fn update_batches<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        data
+    }
+
+    ///
+    /// Adds `data` followed by zeros up to a multiple of 16 bytes, counted
+    /// from the start of the message, as the ChaCha20-Poly1305 AEAD does
+    /// for the associated data and the ciphertext.
+    ///
+    pub fn update_padded(&mut self, data: &[u8]) {
+        self.update(data);
+        if self.buffer_length > 0 {
+            self.buffer[self.buffer_length..].fill(0);
+            let block = self.buffer;
+            self.state.block(&block, true);
+            self.buffer_length = 0;
+        }
+    }
+
+    ///
+    /// Returns the tag of everything passed to `update`.
+    ///
+    pub fn finalize(mut self) -> Tag {
+        // The final partial block carries its 0x01 marker within the 16 bytes
+        if self.buffer_length > 0 {
+            let mut block = [0u8; 16];
+            block[..self.buffer_length].copy_from_slice(&self.buffer[..self.buffer_length]);
+            block[self.buffer_length] = 0x01;
+            self.state.block(&block, false);
+        }
+        Tag::from(self.state.finish())
+    }
+
+    ///
+    /// Checks in constant time that `tag` is the tag of everything passed to
+    /// `update`.
+    ///
+    #[must_use]
+    pub fn verify(self, tag: &Tag) -> bool {
+        self.finalize().verify(tag)
+    }
 }
+
+impl Drop for Poly1305 {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(powers) = &mut self.powers {
+            powers.zeroize();
+        }
+        self.buffer.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Poly1305 {}
@@ -0,0 +1,70 @@
+use zeroize::Zeroize;
+
+use crate::aes::Aes128;
+
+use super::{poly1305_mac, poly1305_verify, Key, Tag};
+
+/// The AES-128 key `k` followed by Poly1305's `r`.
+pub const POLY1305_AES_KEY_LENGTH: usize = 32;
+pub const POLY1305_AES_NONCE_LENGTH: usize = 16;
+
+///
+/// Computes the Poly1305-AES tag of `data`: Poly1305 keyed with `r` and
+/// with `s` = AES-128_k(`nonce`), as in Bernstein's original paper.  This
+/// predates ChaCha20-Poly1305 and is here for interoperating with systems
+/// that still use it.
+///
+/// `key` is `k` followed by `r`, as the paper lays them out.  `r` is
+/// clamped before use, so keys the paper rejects are accepted here.  A
+/// nonce must never be used twice with the same key.
+///
+/// [Source](https://cr.yp.to/mac/poly1305-20050329.pdf)
+///
+pub fn poly1305_aes_mac(
+    key: [u8; POLY1305_AES_KEY_LENGTH],
+    nonce: [u8; POLY1305_AES_NONCE_LENGTH],
+    data: &[u8],
+) -> Tag {
+    let mut one_time_key = one_time_key(key, nonce);
+    let tag = poly1305_mac(one_time_key, data);
+    one_time_key.zeroize();
+    tag
+}
+
+///
+/// Checks in constant time that `tag` is the Poly1305-AES tag of `data`
+/// under `key` and `nonce`.
+///
+#[must_use]
+pub fn poly1305_aes_verify(
+    key: [u8; POLY1305_AES_KEY_LENGTH],
+    nonce: [u8; POLY1305_AES_NONCE_LENGTH],
+    data: &[u8],
+    tag: &Tag,
+) -> bool {
+    let mut one_time_key = one_time_key(key, nonce);
+    let valid = poly1305_verify(one_time_key, data, tag);
+    one_time_key.zeroize();
+    valid
+}
+
+///
+/// Builds the Poly1305 key `r || s`, with `s` the AES-128 encryption of
+/// the nonce under `k`.
+///
+fn one_time_key(
+    mut key: [u8; POLY1305_AES_KEY_LENGTH],
+    nonce: [u8; POLY1305_AES_NONCE_LENGTH],
+) -> Key {
+    let (k, r) = key.split_at(16);
+    let aes = Aes128::new(k.try_into().unwrap());
+    let mut s = nonce;
+    aes.encrypt_block(&mut s);
+
+    let mut one_time_key = [0u8; 32];
+    one_time_key[..16].copy_from_slice(r);
+    one_time_key[16..].copy_from_slice(&s);
+    key.zeroize();
+    s.zeroize();
+    one_time_key
+}
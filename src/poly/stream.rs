@@ -0,0 +1,32 @@
+use std::io::{self, Read, Write};
+
+use super::{Key, Poly1305, Tag};
+
+impl Poly1305 {
+    ///
+    /// Computes the tag of everything `reader` yields until end of file.
+    /// The data is read in small chunks and fed to the MAC as it arrives, so
+    /// a file of any size can be authenticated in constant memory.
+    ///
+    pub fn mac_reader<R: Read>(key: Key, mut reader: R) -> io::Result<Tag> {
+        let mut mac = Poly1305::new(key);
+        io::copy(&mut reader, &mut mac)?;
+        Ok(mac.finalize())
+    }
+}
+
+///
+/// Writing to a `Poly1305` adds the data to the message, so it can sit at
+/// the end of `io::copy` or behind any other writer.  Writes never fail and
+/// always take all of the data.
+///
+impl Write for Poly1305 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use core::fmt;
+use core::str::FromStr;
+
+use subtle::{Choice, ConstantTimeEq};
+
+pub const TAG_LENGTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Poly1305Error {
+    /// A key or tag was built from a slice or hex string of the wrong
+    /// length.
+    InvalidLength,
+    /// A hex string held something other than hex digits.
+    InvalidHex,
+}
+
+impl fmt::Display for Poly1305Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Poly1305Error::InvalidLength => write!(f, "invalid key or tag length"),
+            Poly1305Error::InvalidHex => write!(f, "invalid hex digit in tag"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Poly1305Error {}
+
+///
+/// A 16-byte Poly1305 tag.  `==` compares tags in constant time, so
+/// checking a received tag against a computed one leaks nothing about how
+/// many bytes matched.
+///
+/// Tags are not secret, so unlike keys they are shown in full by `Debug`
+/// and `Display`, as lowercase hex.
+///
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tag([u8; TAG_LENGTH]);
+
+impl Tag {
+    pub fn as_bytes(&self) -> &[u8; TAG_LENGTH] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> [u8; TAG_LENGTH] {
+        self.0
+    }
+
+    ///
+    /// Checks in constant time that `other` is this tag.  The same as `==`,
+    /// but the result cannot be dropped by accident.
+    ///
+    #[must_use]
+    pub fn verify(&self, other: &Tag) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl From<[u8; TAG_LENGTH]> for Tag {
+    fn from(bytes: [u8; TAG_LENGTH]) -> Self {
+        Tag(bytes)
+    }
+}
+
+impl From<Tag> for [u8; TAG_LENGTH] {
+    fn from(tag: Tag) -> Self {
+        tag.0
+    }
+}
+
+///
+/// Fails with `Poly1305Error::InvalidLength` unless the slice is exactly 16
+/// bytes long.
+///
+impl TryFrom<&[u8]> for Tag {
+    type Error = Poly1305Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; TAG_LENGTH] = bytes.try_into().map_err(|_| Poly1305Error::InvalidLength)?;
+        Ok(Tag(bytes))
+    }
+}
+
+impl AsRef<[u8]> for Tag {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ConstantTimeEq for Tag {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.verify(other)
+    }
+}
+
+impl PartialEq<[u8; TAG_LENGTH]> for Tag {
+    fn eq(&self, other: &[u8; TAG_LENGTH]) -> bool {
+        self.0.ct_eq(other).into()
+    }
+}
+
+impl Eq for Tag {}
+
+///
+/// Parses 32 hex digits, in either case.
+///
+impl FromStr for Tag {
+    type Err = Poly1305Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.as_bytes();
+        if digits.len() != 2 * TAG_LENGTH {
+            return Err(Poly1305Error::InvalidLength);
+        }
+
+        let mut tag = [0u8; TAG_LENGTH];
+        for (byte, pair) in tag.iter_mut().zip(digits.chunks_exact(2)) {
+            *byte = (hex_digit(pair[0])? << 4) | hex_digit(pair[1])?;
+        }
+        Ok(Tag(tag))
+    }
+}
+
+fn hex_digit(digit: u8) -> Result<u8, Poly1305Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Poly1305Error::InvalidHex),
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl fmt::Debug for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tag({})", self)
+    }
+}
@@ -0,0 +1,307 @@
+use zeroize::Zeroize;
+
+use super::{check_input, EntropySource, RngError, MAX_REQUEST_LENGTH, MAX_RESEED_INTERVAL};
+use crate::aes::{Aes, Block, BLOCK_LENGTH};
+
+/// Seed length of AES-256, the longest key plus one block.
+const MAX_SEED_LENGTH: usize = 32 + BLOCK_LENGTH;
+
+///
+/// CTR_DRBG from NIST SP 800-90A with the derivation function: an AES key
+/// and a counter block `V`, where output is AES in counter mode from
+/// `V + 1`, and every request ends by replacing the key and `V` with more
+/// of that keystream.
+///
+/// Seeds and additional input of any length are condensed by the
+/// derivation function, Block_Cipher_df, which is built on AES CBC-MAC.
+/// The security strength is the AES key length, which is also the least
+/// entropy it is seeded with.
+///
+/// [Source](https://csrc.nist.gov/pubs/sp/800/90/a/r1/final)
+///
+pub struct CtrDrbg<const KEY_LENGTH: usize> {
+    cipher: Aes<KEY_LENGTH>,
+    value: Block,
+    reseed_counter: u64,
+    reseed_interval: u64,
+}
+
+pub type CtrDrbgAes128 = CtrDrbg<16>;
+pub type CtrDrbgAes192 = CtrDrbg<24>;
+pub type CtrDrbgAes256 = CtrDrbg<32>;
+
+impl<const KEY_LENGTH: usize> CtrDrbg<KEY_LENGTH> {
+    const SEED_LENGTH: usize = KEY_LENGTH + BLOCK_LENGTH;
+
+    ///
+    /// Instantiates the generator from `entropy`, a `nonce` and an optional
+    /// `personalization` string that sets it apart from other instances.
+    /// Fails with `RngError::InsufficientEntropy` if `entropy` is shorter
+    /// than the AES key.
+    ///
+    /// The same inputs always give the same output, so outside of tests
+    /// prefer `from_entropy_source`.
+    ///
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, RngError> {
+        check_entropy::<KEY_LENGTH>(entropy)?;
+        check_input(nonce)?;
+        check_input(personalization)?;
+
+        let mut seed = [0u8; MAX_SEED_LENGTH];
+        derive::<KEY_LENGTH>(
+            &[entropy, nonce, personalization],
+            &mut seed[..Self::SEED_LENGTH],
+        )?;
+        let mut drbg = CtrDrbg {
+            cipher: Aes::new([0; KEY_LENGTH]),
+            value: [0; BLOCK_LENGTH],
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+        };
+        drbg.update(&seed[..Self::SEED_LENGTH]);
+        seed.zeroize();
+        Ok(drbg)
+    }
+
+    ///
+    /// Instantiates the generator with entropy and a nonce drawn from
+    /// `source`.
+    ///
+    pub fn from_entropy_source(
+        source: &mut impl EntropySource,
+        personalization: &[u8],
+    ) -> Result<Self, RngError> {
+        let mut seed = [0u8; 48];
+        let seed_length = KEY_LENGTH * 3 / 2;
+        let result = source
+            .fill_entropy(&mut seed[..seed_length])
+            .and_then(|()| {
+                let (entropy, nonce) = seed[..seed_length].split_at(KEY_LENGTH);
+                Self::new(entropy, nonce, personalization)
+            });
+        seed.zeroize();
+        result
+    }
+
+    ///
+    /// Sets the number of generate calls allowed between reseeds, after
+    /// which `generate` fails with `RngError::ReseedRequired`.  It is
+    /// clamped to between 1 and `MAX_RESEED_INTERVAL`, which is also the
+    /// default.
+    ///
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = interval.clamp(1, MAX_RESEED_INTERVAL);
+    }
+
+    ///
+    /// Mixes fresh `entropy` and optional `additional` input into the
+    /// state and restarts the reseed interval.
+    ///
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), RngError> {
+        check_entropy::<KEY_LENGTH>(entropy)?;
+        check_input(additional)?;
+
+        let mut seed = [0u8; MAX_SEED_LENGTH];
+        derive::<KEY_LENGTH>(&[entropy, additional], &mut seed[..Self::SEED_LENGTH])?;
+        self.update(&seed[..Self::SEED_LENGTH]);
+        self.reseed_counter = 1;
+        seed.zeroize();
+        Ok(())
+    }
+
+    ///
+    /// Reseeds the generator with entropy drawn from `source`.
+    ///
+    pub fn reseed_from(
+        &mut self,
+        source: &mut impl EntropySource,
+        additional: &[u8],
+    ) -> Result<(), RngError> {
+        let mut entropy = [0u8; KEY_LENGTH];
+        let result = source
+            .fill_entropy(&mut entropy)
+            .and_then(|()| self.reseed(&entropy, additional));
+        entropy.zeroize();
+        result
+    }
+
+    ///
+    /// Fills `dest` with random bytes, after mixing in the optional
+    /// `additional` input.  Fails with `RngError::RequestTooLarge` for
+    /// more than `MAX_REQUEST_LENGTH` bytes, and with
+    /// `RngError::ReseedRequired` once the reseed interval has passed.
+    ///
+    pub fn generate(&mut self, dest: &mut [u8], additional: &[u8]) -> Result<(), RngError> {
+        if dest.len() > MAX_REQUEST_LENGTH {
+            return Err(RngError::RequestTooLarge);
+        }
+        check_input(additional)?;
+        if self.reseed_counter > self.reseed_interval {
+            return Err(RngError::ReseedRequired);
+        }
+
+        // Without additional input the update uses zeros instead
+        let mut seed = [0u8; MAX_SEED_LENGTH];
+        if !additional.is_empty() {
+            derive::<KEY_LENGTH>(&[additional], &mut seed[..Self::SEED_LENGTH])?;
+            self.update(&seed[..Self::SEED_LENGTH]);
+        }
+        self.keystream(dest);
+        self.update(&seed[..Self::SEED_LENGTH]);
+        self.reseed_counter += 1;
+        seed.zeroize();
+        Ok(())
+    }
+
+    ///
+    /// Like `generate`, but reseeds from `source` first, so the output
+    /// stays unpredictable even to someone who learned the state before
+    /// the call.
+    ///
+    pub fn generate_with_prediction_resistance(
+        &mut self,
+        source: &mut impl EntropySource,
+        dest: &mut [u8],
+        additional: &[u8],
+    ) -> Result<(), RngError> {
+        if dest.len() > MAX_REQUEST_LENGTH {
+            return Err(RngError::RequestTooLarge);
+        }
+        self.reseed_from(source, additional)?;
+        self.generate(dest, &[])
+    }
+
+    ///
+    /// The CTR_DRBG update function: `SEED_LENGTH` bytes of keystream,
+    /// XORed with `provided`, become the new key and `V`.
+    ///
+    fn update(&mut self, provided: &[u8]) {
+        let mut temp = [0u8; MAX_SEED_LENGTH];
+        self.keystream(&mut temp[..Self::SEED_LENGTH]);
+        temp.iter_mut()
+            .zip(provided)
+            .for_each(|(byte, provided)| *byte ^= provided);
+
+        self.cipher = Aes::new(temp[..KEY_LENGTH].try_into().unwrap());
+        self.value
+            .copy_from_slice(&temp[KEY_LENGTH..Self::SEED_LENGTH]);
+        temp.zeroize();
+    }
+
+    ///
+    /// Fills `dest` with AES in counter mode, incrementing `V` as a 128-bit
+    /// big-endian integer before every block.
+    ///
+    fn keystream(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(BLOCK_LENGTH) {
+            self.value = u128::from_be_bytes(self.value)
+                .wrapping_add(1)
+                .to_be_bytes();
+            let mut block = self.value;
+            self.cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            block.zeroize();
+        }
+    }
+}
+
+impl<const KEY_LENGTH: usize> Drop for CtrDrbg<KEY_LENGTH> {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+fn check_entropy<const KEY_LENGTH: usize>(entropy: &[u8]) -> Result<(), RngError> {
+    if entropy.len() < KEY_LENGTH {
+        return Err(RngError::InsufficientEntropy);
+    }
+    check_input(entropy)
+}
+
+///
+/// Block_Cipher_df: condenses the concatenation of `input` into `output`.
+/// CBC-MACs of `L || N || input || 0x80`, zero-padded and each preceded by
+/// a block holding its index, under a fixed key give a key and block `X`,
+/// and `output` is then `X` encrypted again and again under that key.
+///
+fn derive<const KEY_LENGTH: usize>(input: &[&[u8]], output: &mut [u8]) -> Result<(), RngError> {
+    let input_length: usize = input.iter().map(|data| data.len()).sum();
+    let input_length = u32::try_from(input_length).map_err(|_| RngError::InputTooLong)?;
+
+    let mut key = [0u8; KEY_LENGTH];
+    key.iter_mut()
+        .enumerate()
+        .for_each(|(i, byte)| *byte = i as u8);
+    let cipher = Aes::<KEY_LENGTH>::new(key);
+
+    let mut temp = [0u8; MAX_SEED_LENGTH];
+    for (i, chunk) in temp[..KEY_LENGTH + BLOCK_LENGTH]
+        .chunks_mut(BLOCK_LENGTH)
+        .enumerate()
+    {
+        let mut mac = CbcMac::new(&cipher);
+        let mut iv = [0u8; BLOCK_LENGTH];
+        iv[..4].copy_from_slice(&(i as u32).to_be_bytes());
+        mac.update(&iv);
+        mac.update(&input_length.to_be_bytes());
+        mac.update(&(output.len() as u32).to_be_bytes());
+        for data in input {
+            mac.update(data);
+        }
+        mac.update(&[0x80]);
+        let tag = mac.finalize();
+        chunk.copy_from_slice(&tag[..chunk.len()]);
+    }
+
+    let cipher = Aes::<KEY_LENGTH>::new(temp[..KEY_LENGTH].try_into().unwrap());
+    let mut block: Block = temp[KEY_LENGTH..KEY_LENGTH + BLOCK_LENGTH]
+        .try_into()
+        .unwrap();
+    for chunk in output.chunks_mut(BLOCK_LENGTH) {
+        cipher.encrypt_block(&mut block);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    temp.zeroize();
+    block.zeroize();
+    Ok(())
+}
+
+///
+/// CBC-MAC with a zero IV, the BCC function of Block_Cipher_df.  The
+/// message is zero-padded to a whole number of blocks.
+///
+struct CbcMac<'a, const KEY_LENGTH: usize> {
+    cipher: &'a Aes<KEY_LENGTH>,
+    chain: Block,
+    position: usize,
+}
+
+impl<'a, const KEY_LENGTH: usize> CbcMac<'a, KEY_LENGTH> {
+    fn new(cipher: &'a Aes<KEY_LENGTH>) -> Self {
+        CbcMac {
+            cipher,
+            chain: [0; BLOCK_LENGTH],
+            position: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.chain[self.position] ^= byte;
+            self.position += 1;
+            if self.position == BLOCK_LENGTH {
+                self.cipher.encrypt_block(&mut self.chain);
+                self.position = 0;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> Block {
+        if self.position > 0 {
+            self.cipher.encrypt_block(&mut self.chain);
+        }
+        let tag = self.chain;
+        self.chain.zeroize();
+        tag
+    }
+}
@@ -0,0 +1,27 @@
+use super::RngError;
+
+///
+/// A source of full-entropy bytes that the generators seed and reseed
+/// from.
+///
+/// Implementations fail with `RngError::EntropySourceFailed` rather than
+/// return fewer or weaker bytes than asked for.
+///
+pub trait EntropySource {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), RngError>;
+}
+
+///
+/// The operating system's secure random number generator as an entropy
+/// source.
+///
+#[cfg(feature = "os_rng")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEntropy;
+
+#[cfg(feature = "os_rng")]
+impl EntropySource for OsEntropy {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        getrandom::getrandom(dest).map_err(|_| RngError::EntropySourceFailed)
+    }
+}
@@ -0,0 +1,281 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use zeroize::Zeroize;
+
+use super::{EntropySource, RngError};
+use crate::chacha::ChaCha20;
+use crate::sha2::{sha256, Sha256};
+
+/// Number of entropy pools.
+pub const POOL_COUNT: usize = 32;
+
+/// Most bytes of data one event may carry.
+pub const MAX_EVENT_LENGTH: usize = 32;
+
+/// Most bytes one `random_data` call may return.
+pub const MAX_FORTUNA_REQUEST_LENGTH: usize = 1 << 20;
+
+/// Length of a seed file.
+pub const SEED_FILE_LENGTH: usize = 64;
+
+/// Bytes pool 0 must have absorbed before it triggers a reseed.
+const MIN_POOL_LENGTH: usize = 64;
+
+/// Least time between two reseeds.
+const RESEED_DELAY: Duration = Duration::from_millis(100);
+
+///
+/// The Fortuna accumulator and generator of Ferguson and Schneier.  Events
+/// from many sources are spread over 32 SHA-256 pools, and the generator
+/// is reseeded from them once pool 0 holds enough data.  Reseed `r` uses
+/// pool `i` only if `2^i` divides `r`, so the higher pools collect entropy
+/// for longer and eventually outpace an attacker who can predict or
+/// inject some of the events.
+///
+/// The generator is ChaCha20 with fast key erasure instead of the original
+/// AES in counter mode: every request takes a new key from the first 32
+/// bytes of the keystream and returns the keystream from the next block
+/// on, so earlier output cannot be recomputed from the state.  Reseeding
+/// sets the key to `SHA-256(SHA-256(key || seed))`.
+///
+/// A seed file carries entropy across restarts.  Load it before the first
+/// request, and write it again periodically and at shutdown.
+///
+/// [Source](https://www.schneier.com/wp-content/uploads/2015/12/fortuna.pdf)
+///
+pub struct Fortuna {
+    key: [u8; 32],
+    seeded: bool,
+    pools: [Sha256; POOL_COUNT],
+    pool0_length: usize,
+    reseed_count: u64,
+    last_reseed: Option<Instant>,
+}
+
+impl Fortuna {
+    ///
+    /// Creates an unseeded accumulator.  Output is only available once
+    /// events have filled pool 0 or a seed file has been loaded.
+    ///
+    pub fn new() -> Self {
+        Fortuna {
+            key: [0; 32],
+            seeded: false,
+            pools: core::array::from_fn(|_| Sha256::new()),
+            pool0_length: 0,
+            reseed_count: 0,
+            last_reseed: None,
+        }
+    }
+
+    ///
+    /// Adds an event from `source` to pool `pool`.  Fails with
+    /// `RngError::InvalidEvent` if `pool` is not below `POOL_COUNT` or
+    /// `data` is empty or longer than `MAX_EVENT_LENGTH` bytes.
+    ///
+    /// Every source should spread its events over the pools in turn,
+    /// which `EventSource` does.
+    ///
+    pub fn add_random_event(
+        &mut self,
+        source: u8,
+        pool: usize,
+        data: &[u8],
+    ) -> Result<(), RngError> {
+        if pool >= POOL_COUNT || data.is_empty() || data.len() > MAX_EVENT_LENGTH {
+            return Err(RngError::InvalidEvent);
+        }
+
+        self.pools[pool].update(&[source, data.len() as u8]);
+        self.pools[pool].update(data);
+        if pool == 0 {
+            self.pool0_length += 2 + data.len();
+        }
+        Ok(())
+    }
+
+    ///
+    /// Fills `dest` with random bytes, reseeding from the pools first if
+    /// pool 0 holds at least 64 bytes and the last reseed was over 100 ms
+    /// ago.  Fails with `RngError::NotSeeded` before the first reseed, and
+    /// with `RngError::RequestTooLarge` for more than
+    /// `MAX_FORTUNA_REQUEST_LENGTH` bytes.
+    ///
+    pub fn random_data(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        if dest.len() > MAX_FORTUNA_REQUEST_LENGTH {
+            return Err(RngError::RequestTooLarge);
+        }
+
+        let now = Instant::now();
+        let delay_passed = self
+            .last_reseed
+            .map_or(true, |last| now.duration_since(last) >= RESEED_DELAY);
+        if self.pool0_length >= MIN_POOL_LENGTH && delay_passed {
+            self.reseed_from_pools();
+            self.last_reseed = Some(now);
+        }
+
+        if !self.seeded {
+            return Err(RngError::NotSeeded);
+        }
+        self.generate(dest);
+        Ok(())
+    }
+
+    ///
+    /// The number of reseeds from the pools so far.
+    ///
+    pub fn reseed_count(&self) -> u64 {
+        self.reseed_count
+    }
+
+    ///
+    /// Reseeds the generator from the seed file at `path`, then replaces
+    /// the file with fresh output so the same seed is never used twice.
+    /// Fails with `io::ErrorKind::InvalidData` if the file is not
+    /// `SEED_FILE_LENGTH` bytes long.
+    ///
+    pub fn load_seed_file(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut seed = Vec::with_capacity(SEED_FILE_LENGTH + 1);
+        File::open(path)?
+            .take(SEED_FILE_LENGTH as u64 + 1)
+            .read_to_end(&mut seed)?;
+        if seed.len() != SEED_FILE_LENGTH {
+            seed.zeroize();
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+
+        self.reseed(&seed);
+        seed.zeroize();
+        self.write_seed_file(path)
+    }
+
+    ///
+    /// Writes `SEED_FILE_LENGTH` bytes of output to `path`, replacing it
+    /// atomically.  Fails with `io::ErrorKind::Other` if the generator is
+    /// not seeded yet.
+    ///
+    pub fn write_seed_file(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut seed = [0u8; SEED_FILE_LENGTH];
+        self.random_data(&mut seed)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+
+        let mut temporary_name = path
+            .file_name()
+            .ok_or(io::ErrorKind::InvalidInput)?
+            .to_os_string();
+        temporary_name.push(".tmp");
+        let temporary_path = path.with_file_name(temporary_name);
+
+        let result = (|| {
+            let mut file = File::create(&temporary_path)?;
+            file.write_all(&seed)?;
+            file.sync_all()?;
+            fs::rename(&temporary_path, path)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary_path);
+        }
+        seed.zeroize();
+        result
+    }
+
+    ///
+    /// Concatenates the SHA-256 hashes of the pools this reseed uses,
+    /// empties them and reseeds the generator with the result.
+    ///
+    fn reseed_from_pools(&mut self) {
+        self.reseed_count += 1;
+        let mut seed = [0u8; 32 * POOL_COUNT];
+        let mut seed_length = 0;
+        for (i, pool) in self.pools.iter_mut().enumerate() {
+            if i > 0 && self.reseed_count % (1 << i) != 0 {
+                break;
+            }
+            let hash = core::mem::take(pool).finalize();
+            seed[seed_length..seed_length + 32].copy_from_slice(&sha256(&hash));
+            seed_length += 32;
+        }
+        self.pool0_length = 0;
+        self.reseed(&seed[..seed_length]);
+        seed.zeroize();
+    }
+
+    fn reseed(&mut self, seed: &[u8]) {
+        let mut hash = Sha256::new();
+        hash.update(&self.key);
+        hash.update(seed);
+        let mut inner = hash.finalize();
+        self.key = sha256(&inner);
+        inner.zeroize();
+        self.seeded = true;
+    }
+
+    ///
+    /// Takes a new key from keystream block 0 and fills `dest` from block
+    /// 1 on.
+    ///
+    fn generate(&mut self, dest: &mut [u8]) {
+        let mut new_key = [0u8; 32];
+        ChaCha20::new_with_counter(self.key, [0; 12], 0).apply_keystream(&mut new_key);
+        dest.fill(0);
+        ChaCha20::new_with_counter(self.key, [0; 12], 1).apply_keystream(dest);
+        self.key = new_key;
+        new_key.zeroize();
+    }
+}
+
+impl Default for Fortuna {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Fortuna {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl EntropySource for Fortuna {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        self.random_data(dest)
+            .map_err(|_| RngError::EntropySourceFailed)
+    }
+}
+
+///
+/// One source of events for a `Fortuna` accumulator, which spreads its
+/// events over the pools in turn.
+///
+#[derive(Debug, Clone)]
+pub struct EventSource {
+    id: u8,
+    next_pool: usize,
+}
+
+impl EventSource {
+    ///
+    /// Creates the source numbered `id`, which should be unique among the
+    /// sources feeding one accumulator.
+    ///
+    pub fn new(id: u8) -> Self {
+        EventSource { id, next_pool: 0 }
+    }
+
+    ///
+    /// Adds an event carrying `data` to the next pool.  Fails like
+    /// `Fortuna::add_random_event`.
+    ///
+    pub fn add_event(&mut self, fortuna: &mut Fortuna, data: &[u8]) -> Result<(), RngError> {
+        fortuna.add_random_event(self.id, self.next_pool, data)?;
+        self.next_pool = (self.next_pool + 1) % POOL_COUNT;
+        Ok(())
+    }
+}
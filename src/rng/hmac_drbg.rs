@@ -0,0 +1,218 @@
+use zeroize::Zeroize;
+
+use super::{check_input, EntropySource, RngError, MAX_REQUEST_LENGTH, MAX_RESEED_INTERVAL};
+use crate::hash::Digest;
+use crate::mac::Hmac;
+use crate::sha2::{Sha256, Sha512};
+
+///
+/// HMAC_DRBG from NIST SP 800-90A: a key `K` and value `V` the size of the
+/// hash output, where output blocks are `V = HMAC(K, V)` and every request
+/// ends by rekeying with `K = HMAC(K, V || 0x00 || additional input)`, so
+/// earlier output cannot be recomputed from the state.
+///
+/// Its security strength is 256 bits, or the hash length for shorter
+/// hashes, which is also the least entropy it is seeded with.
+///
+/// [Source](https://csrc.nist.gov/pubs/sp/800/90/a/r1/final)
+///
+pub struct HmacDrbg<D: Digest> {
+    key: D::Output,
+    value: D::Output,
+    reseed_counter: u64,
+    reseed_interval: u64,
+}
+
+pub type HmacDrbgSha256 = HmacDrbg<Sha256>;
+pub type HmacDrbgSha512 = HmacDrbg<Sha512>;
+
+impl<D: Digest> HmacDrbg<D> {
+    const SECURITY_STRENGTH: usize = if D::OUTPUT_LENGTH < 32 {
+        D::OUTPUT_LENGTH
+    } else {
+        32
+    };
+
+    ///
+    /// Instantiates the generator from `entropy`, a `nonce` and an optional
+    /// `personalization` string that sets it apart from other instances.
+    /// Fails with `RngError::InsufficientEntropy` if `entropy` is shorter
+    /// than the security strength.
+    ///
+    /// The same inputs always give the same output, so outside of tests
+    /// prefer `from_entropy_source`.
+    ///
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, RngError> {
+        check_entropy::<D>(entropy)?;
+        check_input(nonce)?;
+        check_input(personalization)?;
+
+        let mut drbg = HmacDrbg {
+            key: filled::<D>(0x00),
+            value: filled::<D>(0x01),
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        Ok(drbg)
+    }
+
+    ///
+    /// Instantiates the generator with entropy and a nonce drawn from
+    /// `source`.
+    ///
+    pub fn from_entropy_source(
+        source: &mut impl EntropySource,
+        personalization: &[u8],
+    ) -> Result<Self, RngError> {
+        let mut seed = [0u8; 48];
+        let seed_length = Self::SECURITY_STRENGTH * 3 / 2;
+        let result = source
+            .fill_entropy(&mut seed[..seed_length])
+            .and_then(|()| {
+                let (entropy, nonce) = seed[..seed_length].split_at(Self::SECURITY_STRENGTH);
+                Self::new(entropy, nonce, personalization)
+            });
+        seed.zeroize();
+        result
+    }
+
+    ///
+    /// Sets the number of generate calls allowed between reseeds, after
+    /// which `generate` fails with `RngError::ReseedRequired`.  It is
+    /// clamped to between 1 and `MAX_RESEED_INTERVAL`, which is also the
+    /// default.
+    ///
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = interval.clamp(1, MAX_RESEED_INTERVAL);
+    }
+
+    ///
+    /// Mixes fresh `entropy` and optional `additional` input into the
+    /// state and restarts the reseed interval.
+    ///
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), RngError> {
+        check_entropy::<D>(entropy)?;
+        check_input(additional)?;
+
+        self.update(&[entropy, additional]);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    ///
+    /// Reseeds the generator with entropy drawn from `source`.
+    ///
+    pub fn reseed_from(
+        &mut self,
+        source: &mut impl EntropySource,
+        additional: &[u8],
+    ) -> Result<(), RngError> {
+        let mut entropy = [0u8; 32];
+        let result = source
+            .fill_entropy(&mut entropy[..Self::SECURITY_STRENGTH])
+            .and_then(|()| self.reseed(&entropy[..Self::SECURITY_STRENGTH], additional));
+        entropy.zeroize();
+        result
+    }
+
+    ///
+    /// Fills `dest` with random bytes, after mixing in the optional
+    /// `additional` input.  Fails with `RngError::RequestTooLarge` for
+    /// more than `MAX_REQUEST_LENGTH` bytes, and with
+    /// `RngError::ReseedRequired` once the reseed interval has passed.
+    ///
+    pub fn generate(&mut self, dest: &mut [u8], additional: &[u8]) -> Result<(), RngError> {
+        if dest.len() > MAX_REQUEST_LENGTH {
+            return Err(RngError::RequestTooLarge);
+        }
+        check_input(additional)?;
+        if self.reseed_counter > self.reseed_interval {
+            return Err(RngError::ReseedRequired);
+        }
+
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        for chunk in dest.chunks_mut(D::OUTPUT_LENGTH) {
+            self.value = self.next_value();
+            chunk.copy_from_slice(&self.value.as_ref()[..chunk.len()]);
+        }
+        self.update(&[additional]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    ///
+    /// Like `generate`, but reseeds from `source` first, so the output
+    /// stays unpredictable even to someone who learned the state before
+    /// the call.
+    ///
+    pub fn generate_with_prediction_resistance(
+        &mut self,
+        source: &mut impl EntropySource,
+        dest: &mut [u8],
+        additional: &[u8],
+    ) -> Result<(), RngError> {
+        if dest.len() > MAX_REQUEST_LENGTH {
+            return Err(RngError::RequestTooLarge);
+        }
+        self.reseed_from(source, additional)?;
+        self.generate(dest, &[])
+    }
+
+    ///
+    /// The HMAC_DRBG update function: `K = HMAC(K, V || 0x00 || data)` and
+    /// `V = HMAC(K, V)`, and the same again with `0x01` unless the provided
+    /// data is empty.
+    ///
+    fn update(&mut self, provided: &[&[u8]]) {
+        self.update_round(0x00, provided);
+        if provided.iter().any(|data| !data.is_empty()) {
+            self.update_round(0x01, provided);
+        }
+    }
+
+    fn update_round(&mut self, round: u8, provided: &[&[u8]]) {
+        let mut mac = Hmac::<D>::new(self.key.as_ref());
+        mac.update(self.value.as_ref());
+        mac.update(&[round]);
+        for data in provided {
+            mac.update(data);
+        }
+        self.key = mac.finalize();
+        self.value = self.next_value();
+    }
+
+    ///
+    /// `HMAC(K, V)`.
+    ///
+    fn next_value(&self) -> D::Output {
+        let mut mac = Hmac::<D>::new(self.key.as_ref());
+        mac.update(self.value.as_ref());
+        mac.finalize()
+    }
+}
+
+impl<D: Digest> Drop for HmacDrbg<D> {
+    fn drop(&mut self) {
+        self.key.as_mut().zeroize();
+        self.value.as_mut().zeroize();
+    }
+}
+
+fn check_entropy<D: Digest>(entropy: &[u8]) -> Result<(), RngError> {
+    if entropy.len() < HmacDrbg::<D>::SECURITY_STRENGTH {
+        return Err(RngError::InsufficientEntropy);
+    }
+    check_input(entropy)
+}
+
+///
+/// A hash output with every byte set to `byte`.
+///
+fn filled<D: Digest>(byte: u8) -> D::Output {
+    let mut output = D::new().finalize();
+    output.as_mut().fill(byte);
+    output
+}
@@ -0,0 +1,80 @@
+//!
+//! Deterministic random bit generators from NIST SP 800-90A, for callers
+//! that need an approved DRBG rather than the operating system's generator
+//! directly.  CTR_DRBG is built on AES with the derivation function, and
+//! HMAC_DRBG on HMAC over any `Digest`.
+//!
+//! Both draw their seeds from an `EntropySource`, which `OsEntropy`
+//! implements on top of the operating system's generator.  Sources of
+//! their own, such as a hardware generator, implement the same trait.
+//!
+//! With `std`, the Fortuna accumulator pools entropy from an application's
+//! own events for long-running processes, and is an `EntropySource` too.
+//!
+mod ctr_drbg;
+mod entropy;
+#[cfg(feature = "std")]
+mod fortuna;
+mod hmac_drbg;
+
+pub use ctr_drbg::*;
+pub use entropy::*;
+#[cfg(feature = "std")]
+pub use fortuna::*;
+pub use hmac_drbg::*;
+
+use core::fmt;
+
+/// Most bytes one generate call may return, 2^19 bits.
+pub const MAX_REQUEST_LENGTH: usize = 1 << 16;
+
+/// Most generate calls between two reseeds that SP 800-90A allows.
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Most bytes of personalization string or additional input, 2^35 bits.
+const MAX_INPUT_LENGTH: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngError {
+    /// The entropy input is shorter than the security strength.
+    InsufficientEntropy,
+    /// The entropy source could not provide entropy.
+    EntropySourceFailed,
+    /// The personalization string or additional input is longer than
+    /// 2^32 bytes.
+    InputTooLong,
+    /// More than `MAX_REQUEST_LENGTH` bytes were requested at once.
+    RequestTooLarge,
+    /// Fortuna has not been seeded from its pools or a seed file yet.
+    NotSeeded,
+    /// A Fortuna event names a pool that does not exist, or its data is
+    /// empty or longer than 32 bytes.
+    InvalidEvent,
+    /// The reseed interval has passed, and the generator must be reseeded
+    /// before it returns more output.
+    ReseedRequired,
+}
+
+impl fmt::Display for RngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RngError::InsufficientEntropy => write!(f, "insufficient entropy input"),
+            RngError::EntropySourceFailed => write!(f, "entropy source failed"),
+            RngError::InputTooLong => write!(f, "DRBG input too long"),
+            RngError::RequestTooLarge => write!(f, "random bytes request too large"),
+            RngError::NotSeeded => write!(f, "generator not seeded"),
+            RngError::InvalidEvent => write!(f, "invalid entropy event"),
+            RngError::ReseedRequired => write!(f, "generator must be reseeded"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RngError {}
+
+fn check_input(input: &[u8]) -> Result<(), RngError> {
+    if input.len() as u64 > MAX_INPUT_LENGTH {
+        return Err(RngError::InputTooLong);
+    }
+    Ok(())
+}
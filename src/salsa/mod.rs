@@ -0,0 +1,3 @@
+mod salsa20;
+
+pub use salsa20::*;
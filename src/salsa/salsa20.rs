@@ -0,0 +1,271 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 8;
+const EXTENDED_NONCE_LENGTH: usize = 24;
+const BLOCK_LENGTH: usize = 64;
+
+pub type Key = [u8; KEY_LENGTH];
+pub type Nonce = [u8; NONCE_LENGTH];
+pub type ExtendedNonce = [u8; EXTENDED_NONCE_LENGTH];
+
+pub struct Salsa20Block {
+    state: [u32; 16],
+}
+
+pub struct Salsa20 {
+    key: Key,
+    nonce: Nonce,
+    counter: u64,
+    keystream: [u8; BLOCK_LENGTH],
+    keystream_position: usize,
+}
+
+pub struct XSalsa20 {
+    cipher: Salsa20,
+}
+
+impl Salsa20Block {
+    ///
+    /// The Salsa20Block constructor initializes the state array with the
+    /// provided key, nonce, and block counter.  The constants are spread
+    /// along the diagonal, and the key is split around them:
+    ///
+    /// ```notrust
+    /// cccccccc  kkkkkkkk  kkkkkkkk  kkkkkkkk
+    /// kkkkkkkk  cccccccc  nnnnnnnn  nnnnnnnn
+    /// bbbbbbbb  bbbbbbbb  cccccccc  kkkkkkkk
+    /// kkkkkkkk  kkkkkkkk  kkkkkkkk  cccccccc
+    /// ```
+    ///
+    /// c=constant k=key b=blockcount n=nonce
+    ///
+    /// [Source](https://cr.yp.to/snuffle/spec.pdf)
+    ///
+    pub fn new(key: Key, nonce: Nonce, counter: u64) -> Self {
+        let mut state = Self::key_setup(key);
+
+        // Add the nonce to the state array
+        state[6] = u32::from_le_bytes(nonce[0..4].try_into().unwrap());
+        state[7] = u32::from_le_bytes(nonce[4..8].try_into().unwrap());
+
+        // Add the block counter to the state array
+        state[8] = counter as u32;
+        state[9] = (counter >> 32) as u32;
+
+        Salsa20Block { state }
+    }
+
+    ///
+    /// Places the constants and the key, leaving words 6-9 to the caller.
+    ///
+    fn key_setup(key: Key) -> [u32; 16] {
+        let mut state = [0u32; 16];
+        // Add the constants to the state array
+        state[0] = 0x61707865;
+        state[5] = 0x3320646e;
+        state[10] = 0x79622d32;
+        state[15] = 0x6b206574;
+
+        // Add the key to the state array
+        for (i, key_part) in key.chunks_exact(4).enumerate() {
+            let index = if i < 4 { 1 + i } else { 7 + i };
+            state[index] = u32::from_le_bytes(key_part.try_into().unwrap());
+        }
+        state
+    }
+
+    ///
+    /// The Salsa20 quarter round operates on four 32-bit words:
+    ///
+    /// 1.  b ^= (a + d) <<< 7;
+    /// 2.  c ^= (b + a) <<< 9;
+    /// 3.  d ^= (c + b) <<< 13;
+    /// 4.  a ^= (d + c) <<< 18;
+    ///
+    /// [Source](https://cr.yp.to/snuffle/spec.pdf)
+    ///
+    pub fn quarter_round(&mut self, a: usize, b: usize, c: usize, d: usize) {
+        self.state[b] ^= self.state[a].wrapping_add(self.state[d]).rotate_left(7);
+        self.state[c] ^= self.state[b].wrapping_add(self.state[a]).rotate_left(9);
+        self.state[d] ^= self.state[c].wrapping_add(self.state[b]).rotate_left(13);
+        self.state[a] ^= self.state[d].wrapping_add(self.state[c]).rotate_left(18);
+    }
+
+    ///
+    /// Runs `double_rounds` pairs of rounds of the permutation, a column
+    /// round then a row round, without adding the original state back.
+    ///
+    fn rounds(&mut self, double_rounds: usize) {
+        for _ in 0..double_rounds {
+            // Column round
+            self.quarter_round(0, 4, 8, 12);
+            self.quarter_round(5, 9, 13, 1);
+            self.quarter_round(10, 14, 2, 6);
+            self.quarter_round(15, 3, 7, 11);
+            // Row round
+            self.quarter_round(0, 1, 2, 3);
+            self.quarter_round(5, 6, 7, 4);
+            self.quarter_round(10, 11, 8, 9);
+            self.quarter_round(15, 12, 13, 14);
+        }
+    }
+
+    ///
+    /// The Salsa20 block function: 20 rounds followed by adding the original
+    /// state to the working state.
+    ///
+    pub fn block(&mut self) {
+        let old_state = self.state;
+
+        self.rounds(10);
+
+        // state += working_state
+        self.state.iter_mut().zip(&old_state).for_each(|(x, y)| {
+            *x = x.wrapping_add(*y);
+        });
+    }
+
+    ///
+    /// Generates the keystream from the state by running Salsa20.
+    ///
+    pub fn get_keystream(&mut self) -> [u8; BLOCK_LENGTH] {
+        self.block();
+
+        let mut keystream = [0u8; BLOCK_LENGTH];
+        keystream
+            .chunks_exact_mut(4)
+            .zip(&self.state)
+            .for_each(|(chunk, x)| chunk.copy_from_slice(&x.to_le_bytes()));
+        keystream
+    }
+
+    ///
+    /// Gets the current state of the Salsa20 cipher.
+    ///
+    pub fn get_state(&self) -> &[u32; 16] {
+        &self.state
+    }
+}
+
+///
+/// Salsa20/8 core, the eight-round block function scrypt mixes its memory
+/// with, applied to `state` in place.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7914#section-3)
+///
+#[cfg(feature = "alloc")]
+pub(crate) fn salsa20_8(state: &mut [u32; 16]) {
+    let mut block = Salsa20Block { state: *state };
+    block.rounds(4);
+    state.iter_mut().zip(&block.state).for_each(|(x, y)| {
+        *x = x.wrapping_add(*y);
+    });
+}
+
+///
+/// HSalsa20 derives a 256-bit subkey from a key and a 128-bit nonce.  The
+/// nonce fills words 6-9 of the state, the 20 rounds are run without the
+/// final addition, and the diagonal and nonce words form the output.
+///
+/// [Source](https://cr.yp.to/snuffle/xsalsa-20081128.pdf)
+///
+pub fn hsalsa20(key: Key, nonce: [u8; 16]) -> [u8; 32] {
+    let mut block = Salsa20Block {
+        state: Salsa20Block::key_setup(key),
+    };
+    for (i, nonce_part) in nonce.chunks_exact(4).enumerate() {
+        block.state[6 + i] = u32::from_le_bytes(nonce_part.try_into().unwrap());
+    }
+
+    block.rounds(10);
+
+    let mut output = [0u8; 32];
+    output
+        .chunks_exact_mut(4)
+        .zip([0, 5, 10, 15, 6, 7, 8, 9])
+        .for_each(|(bytes, i)| bytes.copy_from_slice(&block.state[i].to_le_bytes()));
+    output
+}
+
+impl Salsa20 {
+    pub fn new(key: Key, nonce: Nonce) -> Self {
+        Salsa20 {
+            key,
+            nonce,
+            counter: 0,
+            keystream: [0u8; BLOCK_LENGTH],
+            keystream_position: BLOCK_LENGTH,
+        }
+    }
+
+    ///
+    /// Encrypts (or decrypts) `data` by XORing it with the keystream.  Like
+    /// `ChaCha20::encrypt`, unused keystream is carried over to the next
+    /// call.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut output = data.to_vec();
+        self.apply_keystream(&mut output);
+        output
+    }
+
+    ///
+    /// XORs the keystream into `data` in place, without allocating.  This
+    /// is the same operation as `encrypt`, and shares its keystream
+    /// position.
+    ///
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        let mut offset = 0;
+        while offset < data.len() {
+            if self.keystream_position == BLOCK_LENGTH {
+                self.next_block();
+            }
+
+            let keystream = &self.keystream[self.keystream_position..];
+            let chunk = &mut data[offset..];
+            let length = keystream.len().min(chunk.len());
+            chunk[..length]
+                .iter_mut()
+                .zip(keystream)
+                .for_each(|(x, y)| *x ^= y);
+
+            offset += length;
+            self.keystream_position += length;
+        }
+    }
+
+    fn next_block(&mut self) {
+        let mut block = Salsa20Block::new(self.key, self.nonce, self.counter);
+        self.keystream = block.get_keystream();
+        self.keystream_position = 0;
+        self.counter += 1;
+    }
+}
+
+impl XSalsa20 {
+    ///
+    /// XSalsa20 runs HSalsa20 over the key and the first 16 nonce bytes, and
+    /// uses the result as the key of a plain Salsa20 with the last 8 bytes
+    /// as its nonce.
+    ///
+    /// [Source](https://cr.yp.to/snuffle/xsalsa-20081128.pdf)
+    ///
+    pub fn new(key: Key, nonce: ExtendedNonce) -> Self {
+        let subkey = hsalsa20(key, nonce[..16].try_into().unwrap());
+        XSalsa20 {
+            cipher: Salsa20::new(subkey, nonce[16..].try_into().unwrap()),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        self.cipher.encrypt(data)
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.cipher.apply_keystream(data)
+    }
+}
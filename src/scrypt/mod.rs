@@ -0,0 +1,32 @@
+mod rfc7914;
+
+pub use rfc7914::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScryptError {
+    /// The cost N is not a power of two greater than one, is too large for
+    /// the block size, or needs more memory than can be addressed.
+    InvalidCost,
+    /// The block size r is zero.
+    InvalidBlockSize,
+    /// The parallelization p is zero, or r * p is at least 2^30.
+    InvalidParallelism,
+    /// The output is empty or longer than 2^32 - 1 SHA-256 outputs.
+    InvalidOutputLength,
+}
+
+impl fmt::Display for ScryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScryptError::InvalidCost => write!(f, "invalid cost parameter"),
+            ScryptError::InvalidBlockSize => write!(f, "invalid block size"),
+            ScryptError::InvalidParallelism => write!(f, "invalid parallelization parameter"),
+            ScryptError::InvalidOutputLength => write!(f, "invalid output length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ScryptError {}
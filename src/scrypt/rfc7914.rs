@@ -0,0 +1,225 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use zeroize::Zeroize;
+
+use crate::pbkdf2::pbkdf2;
+use crate::salsa::salsa20_8;
+use crate::sha2::Sha256;
+
+use super::ScryptError;
+
+/// Words of one Salsa20/8 block.
+const SALSA_WORDS: usize = 16;
+
+/// Limit on r * p.
+const MAX_WORK: u64 = 1 << 30;
+
+/// Mixes the p blocks of 128 * r bytes in place.
+type Mix = fn(&mut [u8], &Params);
+
+///
+/// The cost parameters of scrypt: the CPU and memory cost N, the block
+/// size r and the parallelization p.  Each of the p blocks takes
+/// 128 * r * N bytes of memory to mix.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    n: u64,
+    r: u32,
+    p: u32,
+}
+
+impl Params {
+    ///
+    /// Checks the parameters: N a power of two greater than one and below
+    /// 2^(16 * r), and r and p at least one with r * p below 2^30.
+    ///
+    pub fn new(n: u64, r: u32, p: u32) -> Result<Self, ScryptError> {
+        if r == 0 {
+            return Err(ScryptError::InvalidBlockSize);
+        }
+        if p == 0 || r as u64 * p as u64 >= MAX_WORK {
+            return Err(ScryptError::InvalidParallelism);
+        }
+        if n < 2 || !n.is_power_of_two() || (r < 4 && n >= 1 << (16 * r)) {
+            return Err(ScryptError::InvalidCost);
+        }
+        let memory = usize::try_from(n)
+            .ok()
+            .and_then(|n| n.checked_mul(128 * r as usize));
+        if memory.is_none() {
+            return Err(ScryptError::InvalidCost);
+        }
+        Ok(Params { n, r, p })
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn r(&self) -> u32 {
+        self.r
+    }
+
+    pub fn p(&self) -> u32 {
+        self.p
+    }
+}
+
+impl Default for Params {
+    ///
+    /// OWASP's minimum for password storage: N = 2^17, r = 8 and p = 1,
+    /// which takes 128 MiB.
+    ///
+    fn default() -> Self {
+        Params {
+            n: 1 << 17,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+///
+/// Fills `output` with the scrypt key derived from `password` and `salt`.
+///
+/// scrypt stretches the password with PBKDF2-HMAC-SHA256, mixes each of
+/// the p resulting blocks through N blocks of memory with Salsa20/8, and
+/// runs PBKDF2 once more over the mixed blocks.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7914)
+///
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    output: &mut [u8],
+) -> Result<(), ScryptError> {
+    scrypt_with(password, salt, params, output, mix_blocks)
+}
+
+///
+/// Like `scrypt`, but mixes the p blocks on rayon's thread pool, each with
+/// its own 128 * r * N bytes of memory.  The output is exactly that of
+/// `scrypt`.
+///
+#[cfg(feature = "parallel")]
+pub fn par_scrypt(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    output: &mut [u8],
+) -> Result<(), ScryptError> {
+    scrypt_with(password, salt, params, output, par_mix_blocks)
+}
+
+fn scrypt_with(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    output: &mut [u8],
+    mix: Mix,
+) -> Result<(), ScryptError> {
+    if output.is_empty() {
+        return Err(ScryptError::InvalidOutputLength);
+    }
+
+    let mut blocks = vec![0u8; params.p as usize * 128 * params.r as usize];
+    pbkdf2::<Sha256>(password, salt, 1, &mut blocks).unwrap();
+    mix(&mut blocks, params);
+
+    let result = pbkdf2::<Sha256>(password, &blocks, 1, output)
+        .map_err(|_| ScryptError::InvalidOutputLength);
+    blocks.zeroize();
+    result
+}
+
+fn mix_blocks(blocks: &mut [u8], params: &Params) {
+    for block in blocks.chunks_exact_mut(128 * params.r as usize) {
+        ro_mix(block, params);
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn par_mix_blocks(blocks: &mut [u8], params: &Params) {
+    blocks
+        .par_chunks_exact_mut(128 * params.r as usize)
+        .for_each(|block| ro_mix(block, params));
+}
+
+///
+/// ROMix: writes N successive BlockMix outputs to memory, then reads them
+/// back in an order that depends on the data, so that computing the result
+/// with less memory costs more time.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7914#section-5)
+///
+fn ro_mix(block: &mut [u8], params: &Params) {
+    let words = 32 * params.r as usize;
+    let n = params.n as usize;
+
+    let mut x: Vec<u32> = block
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    let mut y = vec![0u32; words];
+    let mut memory = vec![0u32; words * n];
+
+    for stored in memory.chunks_exact_mut(words) {
+        stored.copy_from_slice(&x);
+        block_mix(&x, &mut y);
+        core::mem::swap(&mut x, &mut y);
+    }
+    for _ in 0..n {
+        let j = integerify(&x) as usize & (n - 1);
+        for (word, stored) in x.iter_mut().zip(&memory[j * words..(j + 1) * words]) {
+            *word ^= stored;
+        }
+        block_mix(&x, &mut y);
+        core::mem::swap(&mut x, &mut y);
+    }
+
+    for (chunk, word) in block.chunks_exact_mut(4).zip(&x) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    x.zeroize();
+    y.zeroize();
+    memory.zeroize();
+}
+
+///
+/// BlockMix: chains Salsa20/8 through the 2 * r 64-byte blocks of `input`,
+/// writing the even-numbered outputs followed by the odd-numbered ones.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7914#section-4)
+///
+fn block_mix(input: &[u32], output: &mut [u32]) {
+    let blocks = input.len() / SALSA_WORDS;
+    let mut state: [u32; SALSA_WORDS] = input[input.len() - SALSA_WORDS..].try_into().unwrap();
+    for (i, block) in input.chunks_exact(SALSA_WORDS).enumerate() {
+        for (word, input) in state.iter_mut().zip(block) {
+            *word ^= input;
+        }
+        salsa20_8(&mut state);
+
+        let target = if i % 2 == 0 {
+            i / 2
+        } else {
+            blocks / 2 + i / 2
+        };
+        output[target * SALSA_WORDS..(target + 1) * SALSA_WORDS].copy_from_slice(&state);
+    }
+    state.zeroize();
+}
+
+///
+/// Reads the last 64-byte block of `x` as a little-endian integer, of
+/// which only the low 64 bits are needed.
+///
+fn integerify(x: &[u32]) -> u64 {
+    let last = x.len() - SALSA_WORDS;
+    x[last] as u64 | (x[last + 1] as u64) << 32
+}
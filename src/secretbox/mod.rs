@@ -0,0 +1,3 @@
+mod xsalsa20poly1305;
+
+pub use xsalsa20poly1305::*;
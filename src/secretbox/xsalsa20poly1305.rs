@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use zeroize::Zeroize;
+
+use crate::poly::{poly1305_mac, poly1305_verify};
+use crate::salsa::{ExtendedNonce, XSalsa20};
+
+pub const KEY_LENGTH: usize = 32;
+pub const NONCE_LENGTH: usize = 24;
+pub const TAG_LENGTH: usize = 16;
+
+pub type Key = [u8; KEY_LENGTH];
+pub type Nonce = ExtendedNonce;
+
+pub use crate::poly::Tag;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretBoxError {
+    /// The box is shorter than its tag.
+    TooShort,
+    /// The Poly1305 tag did not match the box.
+    InvalidTag,
+}
+
+impl fmt::Display for SecretBoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretBoxError::TooShort => write!(f, "secretbox is too short"),
+            SecretBoxError::InvalidTag => write!(f, "secretbox authentication failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SecretBoxError {}
+
+///
+/// Encrypts and authenticates `message`, producing the libsodium
+/// `crypto_secretbox_easy` layout: the 16-byte tag followed by the
+/// ciphertext.
+///
+/// The first 32 bytes of the XSalsa20 keystream become the Poly1305 key,
+/// and the message is encrypted with the keystream that follows.
+///
+/// [Source](https://nacl.cr.yp.to/secretbox.html)
+///
+pub fn seal(key: Key, nonce: Nonce, message: &[u8]) -> Vec<u8> {
+    let (ciphertext, tag) = seal_detached(key, nonce, message);
+    let mut output = Vec::with_capacity(TAG_LENGTH + ciphertext.len());
+    output.extend_from_slice(tag.as_ref());
+    output.extend_from_slice(&ciphertext);
+    output
+}
+
+///
+/// Verifies and decrypts a box produced by `seal`.  The plaintext is only
+/// returned if the tag is valid.
+///
+pub fn open(key: Key, nonce: Nonce, sealed: &[u8]) -> Result<Vec<u8>, SecretBoxError> {
+    if sealed.len() < TAG_LENGTH {
+        return Err(SecretBoxError::TooShort);
+    }
+    let (tag, ciphertext) = sealed.split_at(TAG_LENGTH);
+    let tag = Tag::try_from(tag).map_err(|_| SecretBoxError::TooShort)?;
+    open_detached(key, nonce, ciphertext, &tag)
+}
+
+///
+/// Like `seal`, but returns the ciphertext and the tag separately, as
+/// libsodium's `crypto_secretbox_detached` does.
+///
+pub fn seal_detached(key: Key, nonce: Nonce, message: &[u8]) -> (Vec<u8>, Tag) {
+    let mut cipher = XSalsa20::new(key, nonce);
+    let mut poly_key = [0u8; 32];
+    cipher.apply_keystream(&mut poly_key);
+
+    let ciphertext = cipher.encrypt(message);
+    let tag = poly1305_mac(poly_key, &ciphertext);
+    poly_key.zeroize();
+    (ciphertext, tag)
+}
+
+///
+/// Verifies and decrypts a ciphertext and tag produced by `seal_detached`,
+/// as libsodium's `crypto_secretbox_open_detached` does.  Nothing is
+/// decrypted unless the tag is valid.
+///
+pub fn open_detached(
+    key: Key,
+    nonce: Nonce,
+    ciphertext: &[u8],
+    tag: &Tag,
+) -> Result<Vec<u8>, SecretBoxError> {
+    // Decrypting the zero prefix recovers the Poly1305 key
+    let mut cipher = XSalsa20::new(key, nonce);
+    let mut poly_key = [0u8; 32];
+    cipher.apply_keystream(&mut poly_key);
+
+    let verified = poly1305_verify(poly_key, ciphertext, tag);
+    poly_key.zeroize();
+    if !verified {
+        return Err(SecretBoxError::InvalidTag);
+    }
+
+    Ok(cipher.encrypt(ciphertext))
+}
@@ -0,0 +1,7 @@
+mod sha256;
+mod sha512;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod shani;
+
+pub use sha256::*;
+pub use sha512::*;
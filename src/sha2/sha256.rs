@@ -0,0 +1,216 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::backend::has_sha_ni;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::shani;
+
+pub const SHA256_HASH_LENGTH: usize = 32;
+pub const SHA256_BLOCK_LENGTH: usize = 64;
+
+/// The first 32 bits of the fractional parts of the square roots of the
+/// first eight primes.
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The first 32 bits of the fractional parts of the cube roots of the
+/// first 64 primes.
+pub(super) const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+///
+/// Hashes `data` with SHA-256 in one go.
+///
+pub fn sha256(data: &[u8]) -> [u8; SHA256_HASH_LENGTH] {
+    let mut hash = Sha256::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// SHA-256, the Merkle-Damgård hash of FIPS 180-4 over 64-byte blocks with
+/// a 256-bit state.
+///
+/// Data can be fed in any number of `update` calls; only the
+/// concatenation matters.  Like every Merkle-Damgård hash, SHA-256 allows
+/// length extension: `H(secret || message)` is not a MAC, HMAC is.
+///
+/// With the x86 SHA extensions, detected at runtime, the compression
+/// function runs on them instead of the portable code.
+///
+/// [Source](https://csrc.nist.gov/pubs/fips/180-4/upd1/final)
+///
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; SHA256_BLOCK_LENGTH],
+    buffer_length: usize,
+    // Bytes hashed so far
+    length: u64,
+    // Skips the SHA extensions even where they are detected
+    #[cfg_attr(
+        not(any(target_arch = "x86", target_arch = "x86_64")),
+        allow(dead_code)
+    )]
+    soft: bool,
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Sha256 {
+            state: INITIAL_STATE,
+            buffer: [0; SHA256_BLOCK_LENGTH],
+            buffer_length: 0,
+            length: 0,
+            soft: false,
+        }
+    }
+
+    ///
+    /// Like `new`, but always runs the portable compression function, even
+    /// where the SHA extensions are detected.  Meant for testing and
+    /// benchmarking the fallback.
+    ///
+    pub fn new_soft() -> Self {
+        let mut hash = Self::new();
+        hash.soft = true;
+        hash
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffer_length > 0 {
+            let length = (SHA256_BLOCK_LENGTH - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+            if self.buffer_length < SHA256_BLOCK_LENGTH {
+                return;
+            }
+
+            let block = self.buffer;
+            self.compress(&block);
+            self.buffer_length = 0;
+        }
+
+        let length = data.len() - data.len() % SHA256_BLOCK_LENGTH;
+        self.compress(&data[..length]);
+
+        let remainder = &data[length..];
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    ///
+    /// Returns the hash of everything passed to `update`.
+    ///
+    pub fn finalize(mut self) -> [u8; SHA256_HASH_LENGTH] {
+        // A one bit, zeros, and the message length in bits, spilling into
+        // a second block if the length does not fit after the one bit
+        let bit_length = self.length.wrapping_mul(8);
+        self.buffer[self.buffer_length] = 0x80;
+        self.buffer[self.buffer_length + 1..].fill(0);
+        if self.buffer_length >= SHA256_BLOCK_LENGTH - 8 {
+            let block = self.buffer;
+            self.compress(&block);
+            self.buffer.fill(0);
+        }
+        self.buffer[SHA256_BLOCK_LENGTH - 8..].copy_from_slice(&bit_length.to_be_bytes());
+        let block = self.buffer;
+        self.compress(&block);
+
+        let mut output = [0u8; SHA256_HASH_LENGTH];
+        for (chunk, word) in output.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output
+    }
+
+    fn compress(&mut self, blocks: &[u8]) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if !self.soft && has_sha_ni() {
+            // SAFETY: the SHA extensions were detected above
+            unsafe { shani::compress(&mut self.state, blocks) };
+            return;
+        }
+        compress(&mut self.state, blocks);
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Sha256 {}
+
+///
+/// The portable compression function, over whole 64-byte blocks.
+///
+fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    let mut w = [0u32; 64];
+    for block in blocks.chunks_exact(SHA256_BLOCK_LENGTH) {
+        for (word, chunk) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (&k, &w) in K.iter().zip(&w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    w.zeroize();
+}
@@ -0,0 +1,258 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const SHA512_HASH_LENGTH: usize = 64;
+pub const SHA512_BLOCK_LENGTH: usize = 128;
+
+/// The first 64 bits of the fractional parts of the square roots of the
+/// first eight primes.
+const INITIAL_STATE: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// The first 64 bits of the fractional parts of the cube roots of the
+/// first 80 primes.
+const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+///
+/// Hashes `data` with SHA-512 in one go.
+///
+pub fn sha512(data: &[u8]) -> [u8; SHA512_HASH_LENGTH] {
+    let mut hash = Sha512::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// SHA-512, the 64-bit sibling of SHA-256 in FIPS 180-4, over 128-byte
+/// blocks with a 512-bit state and 80 rounds.  It is often faster than
+/// SHA-256 on 64-bit CPUs without the SHA extensions, which only cover
+/// SHA-256.
+///
+/// Data can be fed in any number of `update` calls; only the
+/// concatenation matters.  SHA-512 allows length extension like SHA-256.
+///
+/// [Source](https://csrc.nist.gov/pubs/fips/180-4/upd1/final)
+///
+#[derive(Clone)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buffer: [u8; SHA512_BLOCK_LENGTH],
+    buffer_length: usize,
+    // Bytes hashed so far
+    length: u128,
+}
+
+impl Sha512 {
+    pub fn new() -> Self {
+        Sha512 {
+            state: INITIAL_STATE,
+            buffer: [0; SHA512_BLOCK_LENGTH],
+            buffer_length: 0,
+            length: 0,
+        }
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u128);
+
+        if self.buffer_length > 0 {
+            let length = (SHA512_BLOCK_LENGTH - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + length]
+                .copy_from_slice(&data[..length]);
+            self.buffer_length += length;
+            data = &data[length..];
+            if self.buffer_length < SHA512_BLOCK_LENGTH {
+                return;
+            }
+
+            let block = self.buffer;
+            compress(&mut self.state, &block);
+            self.buffer_length = 0;
+        }
+
+        let length = data.len() - data.len() % SHA512_BLOCK_LENGTH;
+        compress(&mut self.state, &data[..length]);
+
+        let remainder = &data[length..];
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    ///
+    /// Returns the hash of everything passed to `update`.
+    ///
+    pub fn finalize(mut self) -> [u8; SHA512_HASH_LENGTH] {
+        // As for SHA-256, but with a 128-bit length
+        let bit_length = self.length.wrapping_mul(8);
+        self.buffer[self.buffer_length] = 0x80;
+        self.buffer[self.buffer_length + 1..].fill(0);
+        if self.buffer_length >= SHA512_BLOCK_LENGTH - 16 {
+            let block = self.buffer;
+            compress(&mut self.state, &block);
+            self.buffer.fill(0);
+        }
+        self.buffer[SHA512_BLOCK_LENGTH - 16..].copy_from_slice(&bit_length.to_be_bytes());
+        let block = self.buffer;
+        compress(&mut self.state, &block);
+
+        let mut output = [0u8; SHA512_HASH_LENGTH];
+        for (chunk, word) in output.chunks_exact_mut(8).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Sha512 {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Sha512 {}
+
+///
+/// The compression function, over whole 128-byte blocks.
+///
+fn compress(state: &mut [u64; 8], blocks: &[u8]) {
+    let mut w = [0u64; 80];
+    for block in blocks.chunks_exact(SHA512_BLOCK_LENGTH) {
+        for (word, chunk) in w.iter_mut().zip(block.chunks_exact(8)) {
+            *word = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (&k, &w) in K.iter().zip(&w) {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    w.zeroize();
+}
@@ -0,0 +1,78 @@
+//!
+//! The SHA-256 compression function with the x86 SHA extensions, which run
+//! two rounds or a step of the message schedule in one instruction.
+//!
+//! The state is kept as the ABEF and CDGH halves the round instruction
+//! expects, and only rearranged into the usual order around a whole run of
+//! blocks.
+//!
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::sha256::{K, SHA256_BLOCK_LENGTH};
+
+///
+/// Compresses every whole 64-byte block of `blocks` into `state`.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports the SHA extensions, SSE2, SSSE3
+/// and SSE4.1.
+///
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+pub(super) unsafe fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    // Turns the big-endian message words into native ones
+    let byte_swap = _mm_set_epi64x(0x0c0d0e0f08090a0b, 0x0405060700010203);
+
+    let dcba = _mm_loadu_si128(state.as_ptr() as *const __m128i);
+    let hgfe = _mm_loadu_si128(state.as_ptr().add(4) as *const __m128i);
+    let cdab = _mm_shuffle_epi32(dcba, 0xb1);
+    let efgh = _mm_shuffle_epi32(hgfe, 0x1b);
+    let mut abef = _mm_alignr_epi8(cdab, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, cdab, 0xf0);
+
+    for block in blocks.chunks_exact(SHA256_BLOCK_LENGTH) {
+        let abef_saved = abef;
+        let cdgh_saved = cdgh;
+
+        // The last sixteen message words, four per vector
+        let mut w = [_mm_setzero_si128(); 4];
+        for (i, word) in w.iter_mut().enumerate() {
+            let input = _mm_loadu_si128(block.as_ptr().add(16 * i) as *const __m128i);
+            *word = _mm_shuffle_epi8(input, byte_swap);
+        }
+
+        for i in 0..16 {
+            if i >= 4 {
+                w[i % 4] = schedule(w[i % 4], w[(i + 1) % 4], w[(i + 2) % 4], w[(i + 3) % 4]);
+            }
+            let k = _mm_loadu_si128(K.as_ptr().add(4 * i) as *const __m128i);
+            let words = _mm_add_epi32(w[i % 4], k);
+            cdgh = _mm_sha256rnds2_epu32(cdgh, abef, words);
+            abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32(words, 0x0e));
+        }
+
+        abef = _mm_add_epi32(abef, abef_saved);
+        cdgh = _mm_add_epi32(cdgh, cdgh_saved);
+    }
+
+    let feba = _mm_shuffle_epi32(abef, 0x1b);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xb1);
+    let dcba = _mm_blend_epi16(feba, dchg, 0xf0);
+    let hgfe = _mm_alignr_epi8(dchg, feba, 8);
+    _mm_storeu_si128(state.as_mut_ptr() as *mut __m128i, dcba);
+    _mm_storeu_si128(state.as_mut_ptr().add(4) as *mut __m128i, hgfe);
+}
+
+///
+/// The next four message words from the previous sixteen, oldest first.
+///
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3")]
+unsafe fn schedule(w0: __m128i, w1: __m128i, w2: __m128i, w3: __m128i) -> __m128i {
+    let partial = _mm_sha256msg1_epu32(w0, w1);
+    let partial = _mm_add_epi32(partial, _mm_alignr_epi8(w3, w2, 4));
+    _mm_sha256msg2_epu32(partial, w3)
+}
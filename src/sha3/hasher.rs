@@ -0,0 +1,84 @@
+use zeroize::ZeroizeOnDrop;
+
+use super::keccak::Sponge;
+
+/// Domain bits of the SHA-3 hashes, `01`, and the first padding bit.
+const SHA3_DOMAIN: u8 = 0x06;
+
+///
+/// Hashes `data` with SHA3-256 in one go.
+///
+pub fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut hash = Sha3_256::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// Hashes `data` with SHA3-512 in one go.
+///
+pub fn sha3_512(data: &[u8]) -> [u8; 64] {
+    let mut hash = Sha3_512::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+///
+/// A SHA-3 hash of FIPS 202 with `HASH_LENGTH` bytes of output: the Keccak
+/// sponge with a capacity of twice the output, absorbing the rest of the
+/// 200-byte state, `RATE` bytes, per permutation.  Use the `Sha3_256` and
+/// `Sha3_512` aliases.
+///
+/// Data can be fed in any number of `update` calls; only the
+/// concatenation matters.  Unlike SHA-2, SHA-3 is not open to length
+/// extension.
+///
+/// [Source](https://csrc.nist.gov/pubs/fips/202/final)
+///
+#[derive(Clone)]
+pub struct Sha3<const RATE: usize, const HASH_LENGTH: usize> {
+    sponge: Sponge<RATE, SHA3_DOMAIN>,
+}
+
+pub type Sha3_256 = Sha3<136, 32>;
+pub type Sha3_512 = Sha3<72, 64>;
+
+impl<const RATE: usize, const HASH_LENGTH: usize> Sha3<RATE, HASH_LENGTH> {
+    const VALID_PARAMETERS: () = assert!(
+        RATE + 2 * HASH_LENGTH == 200 && RATE % 8 == 0,
+        "the SHA-3 capacity is twice the output length"
+    );
+
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_PARAMETERS;
+        Sha3 {
+            sponge: Sponge::new(),
+        }
+    }
+
+    ///
+    /// Adds `data` to the message.
+    ///
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.absorb(data);
+    }
+
+    ///
+    /// Returns the hash of everything passed to `update`.
+    ///
+    pub fn finalize(mut self) -> [u8; HASH_LENGTH] {
+        self.sponge.pad();
+        let mut output = [0u8; HASH_LENGTH];
+        self.sponge.squeeze(&mut output);
+        output
+    }
+}
+
+impl<const RATE: usize, const HASH_LENGTH: usize> Default for Sha3<RATE, HASH_LENGTH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const RATE: usize, const HASH_LENGTH: usize> ZeroizeOnDrop for Sha3<RATE, HASH_LENGTH> {}
@@ -0,0 +1,160 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Rounds of Keccak-f[1600].
+const ROUNDS: usize = 24;
+
+/// Round constants, XORed into the first lane by the iota step.
+const ROUND_CONSTANTS: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation of each lane in the rho step, in the order the pi step visits
+/// them.
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// Lane the pi step moves each lane to, starting from lane 1.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+///
+/// The Keccak-f[1600] permutation on 25 lanes, lane `x + 5 * y` holding
+/// the bits at column x and row y.
+///
+/// [Source](https://keccak.team/files/Keccak-reference-3.0.pdf)
+///
+pub(super) fn keccak_f1600(state: &mut [u64; 25]) {
+    for round_constant in ROUND_CONSTANTS {
+        // Theta: every column's parity spreads to its two neighbours
+        let mut parity = [0u64; 5];
+        for (x, parity) in parity.iter_mut().enumerate() {
+            *parity = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let mix = parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= mix;
+            }
+        }
+
+        // Rho and pi: every lane rotates and moves along the same cycle
+        let mut lane = state[1];
+        for (&rotation, &target) in RHO.iter().zip(&PI) {
+            let next = state[target];
+            state[target] = lane.rotate_left(rotation);
+            lane = next;
+        }
+
+        // Chi: the only nonlinear step, row by row
+        for y in 0..5 {
+            let row: [u64; 5] = state[5 * y..5 * y + 5].try_into().unwrap();
+            for x in 0..5 {
+                state[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        state[0] ^= round_constant;
+    }
+}
+
+///
+/// A Keccak sponge absorbing and squeezing `RATE` bytes per permutation.
+/// `DOMAIN` holds the suffix bits that separate the functions built on it,
+/// with the first bit of the padding after them.
+///
+#[derive(Clone)]
+pub(super) struct Sponge<const RATE: usize, const DOMAIN: u8> {
+    state: [u64; 25],
+    // Bytes absorbed into, or squeezed from, the current block
+    position: usize,
+}
+
+impl<const RATE: usize, const DOMAIN: u8> Sponge<RATE, DOMAIN> {
+    pub(super) fn new() -> Self {
+        Sponge {
+            state: [0; 25],
+            position: 0,
+        }
+    }
+
+    pub(super) fn absorb(&mut self, mut data: &[u8]) {
+        // Unaligned bytes go one at a time, whole lanes eight at a time
+        while !data.is_empty() {
+            if self.position % 8 == 0 && data.len() >= 8 && self.position + 8 <= RATE {
+                let lane = u64::from_le_bytes(data[..8].try_into().unwrap());
+                self.state[self.position / 8] ^= lane;
+                self.position += 8;
+                data = &data[8..];
+            } else {
+                self.xor_byte(self.position, data[0]);
+                self.position += 1;
+                data = &data[1..];
+            }
+
+            if self.position == RATE {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+        }
+    }
+
+    ///
+    /// Ends absorption with the domain bits and pad10*1, leaving the
+    /// sponge ready to squeeze.
+    ///
+    pub(super) fn pad(&mut self) {
+        self.xor_byte(self.position, DOMAIN);
+        self.xor_byte(RATE - 1, 0x80);
+        keccak_f1600(&mut self.state);
+        self.position = 0;
+    }
+
+    pub(super) fn squeeze(&mut self, output: &mut [u8]) {
+        for byte in output {
+            if self.position == RATE {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+            *byte = (self.state[self.position / 8] >> (8 * (self.position % 8))) as u8;
+            self.position += 1;
+        }
+    }
+
+    fn xor_byte(&mut self, position: usize, byte: u8) {
+        self.state[position / 8] ^= (byte as u64) << (8 * (position % 8));
+    }
+}
+
+impl<const RATE: usize, const DOMAIN: u8> Drop for Sponge<RATE, DOMAIN> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+impl<const RATE: usize, const DOMAIN: u8> ZeroizeOnDrop for Sponge<RATE, DOMAIN> {}
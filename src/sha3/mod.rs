@@ -0,0 +1,6 @@
+mod hasher;
+mod keccak;
+mod shake;
+
+pub use hasher::*;
+pub use shake::*;
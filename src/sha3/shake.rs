@@ -0,0 +1,79 @@
+use super::keccak::Sponge;
+
+/// Domain bits of the SHAKE functions, `1111`, and the first padding bit.
+const SHAKE_DOMAIN: u8 = 0x1f;
+
+///
+/// A SHAKE extendable-output function of FIPS 202: the Keccak sponge
+/// absorbing `RATE` bytes per permutation, whose output can be read to
+/// any length.  Use the `Shake128` and `Shake256` aliases, named after
+/// their security strength in bits.
+///
+/// Feed the input with `update`, then call `finalize_xof` and read as much
+/// output as needed, in any number of calls; a shorter output is always a
+/// prefix of a longer one.
+///
+/// [Source](https://csrc.nist.gov/pubs/fips/202/final)
+///
+#[derive(Clone)]
+pub struct Shake<const RATE: usize> {
+    sponge: Sponge<RATE, SHAKE_DOMAIN>,
+}
+
+pub type Shake128 = Shake<168>;
+pub type Shake256 = Shake<136>;
+
+impl<const RATE: usize> Shake<RATE> {
+    const VALID_PARAMETERS: () = assert!(
+        RATE < 200 && RATE % 8 == 0,
+        "the rate is a whole number of lanes and leaves some capacity"
+    );
+
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_PARAMETERS;
+        Shake {
+            sponge: Sponge::new(),
+        }
+    }
+
+    ///
+    /// Adds `data` to the input.
+    ///
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.absorb(data);
+    }
+
+    ///
+    /// Ends the input and returns the reader for the output.
+    ///
+    pub fn finalize_xof(mut self) -> ShakeReader<RATE> {
+        self.sponge.pad();
+        ShakeReader {
+            sponge: self.sponge,
+        }
+    }
+}
+
+impl<const RATE: usize> Default for Shake<RATE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// The output of a SHAKE function, read in order.
+///
+#[derive(Clone)]
+pub struct ShakeReader<const RATE: usize> {
+    sponge: Sponge<RATE, SHAKE_DOMAIN>,
+}
+
+impl<const RATE: usize> ShakeReader<RATE> {
+    ///
+    /// Fills `output` with the next bytes of output.
+    ///
+    pub fn read(&mut self, output: &mut [u8]) {
+        self.sponge.squeeze(output);
+    }
+}
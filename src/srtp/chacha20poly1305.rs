@@ -0,0 +1,313 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::aead::{AeadError, ChaCha20Poly1305, Tag, TAG_LENGTH};
+
+use super::{srtp_kdf, KeyLabel, SrtpError};
+
+pub const MASTER_KEY_LENGTH: usize = 32;
+pub const MASTER_SALT_LENGTH: usize = 12;
+
+/// Length of the RTP header without CSRCs and extension.
+const RTP_HEADER_LENGTH: usize = 12;
+/// Length of the RTCP header and sender SSRC, which stay in the clear.
+const RTCP_HEADER_LENGTH: usize = 8;
+/// Length of the E flag and SRTCP index that follow the tag.
+const RTCP_INDEX_LENGTH: usize = 4;
+/// The E flag, set on every SRTCP packet this profile produces.
+const RTCP_ENCRYPTED: u32 = 1 << 31;
+
+///
+/// Builds the nonce of an SRTP packet: two zero bytes, the SSRC, the ROC
+/// and the sequence number, XORed with the session salt.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7714#section-8.1)
+///
+pub fn rtp_nonce(
+    session_salt: &[u8; MASTER_SALT_LENGTH],
+    ssrc: u32,
+    roc: u32,
+    sequence: u16,
+) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[2..6].copy_from_slice(&ssrc.to_be_bytes());
+    nonce[6..10].copy_from_slice(&roc.to_be_bytes());
+    nonce[10..].copy_from_slice(&sequence.to_be_bytes());
+    nonce
+        .iter_mut()
+        .zip(session_salt)
+        .for_each(|(x, y)| *x ^= y);
+    nonce
+}
+
+///
+/// Builds the nonce of an SRTCP packet: two zero bytes, the SSRC, two more
+/// zero bytes and the 31-bit SRTCP index, XORed with the session salt.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc7714#section-9.1)
+///
+pub fn rtcp_nonce(session_salt: &[u8; MASTER_SALT_LENGTH], ssrc: u32, index: u32) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[2..6].copy_from_slice(&ssrc.to_be_bytes());
+    nonce[8..].copy_from_slice(&index.to_be_bytes());
+    nonce
+        .iter_mut()
+        .zip(session_salt)
+        .for_each(|(x, y)| *x ^= y);
+    nonce
+}
+
+///
+/// Returns the length of an RTP packet's header: the fixed 12 bytes, the
+/// CSRC list and the header extension, which are all authenticated but
+/// not encrypted.  Fails with `SrtpError::InvalidHeader` if the packet is
+/// not RTP version 2 or ends inside its header.
+///
+pub fn rtp_header_length(packet: &[u8]) -> Result<usize, SrtpError> {
+    if packet.len() < RTP_HEADER_LENGTH || packet[0] >> 6 != 2 {
+        return Err(SrtpError::InvalidHeader);
+    }
+    let mut length = RTP_HEADER_LENGTH + 4 * (packet[0] & 0x0f) as usize;
+    if packet[0] & 0x10 != 0 {
+        let words = packet
+            .get(length + 2..length + 4)
+            .ok_or(SrtpError::InvalidHeader)?;
+        length += 4 + 4 * u16::from_be_bytes([words[0], words[1]]) as usize;
+    }
+    if packet.len() < length {
+        return Err(SrtpError::InvalidHeader);
+    }
+    Ok(length)
+}
+
+///
+/// SRTP and SRTCP packet protection with ChaCha20-Poly1305, following the
+/// AEAD packet layout of RFC 7714 with a 256-bit master key, a 96-bit
+/// master salt and a 16-byte tag.
+///
+/// The session keys and salts for both protocols are derived from the
+/// master key and salt with `srtp_kdf` over AES-256.  RTP headers are
+/// authenticated and the payload is encrypted, with the tag appended.
+/// SRTCP packets are always encrypted past the first 8 bytes, and carry
+/// the tag followed by the E flag and 31-bit SRTCP index.
+///
+/// Senders count the ROC up whenever the sequence number wraps, and must
+/// never protect two packets with the same SSRC and index.  Receivers
+/// learn the ROC from an `RtpIndexTracker`.
+///
+pub struct SrtpChaCha20Poly1305 {
+    rtp: ChaCha20Poly1305,
+    rtp_salt: [u8; MASTER_SALT_LENGTH],
+    rtcp: ChaCha20Poly1305,
+    rtcp_salt: [u8; MASTER_SALT_LENGTH],
+}
+
+impl SrtpChaCha20Poly1305 {
+    pub fn new(master_key: [u8; MASTER_KEY_LENGTH], master_salt: [u8; MASTER_SALT_LENGTH]) -> Self {
+        let derive = |label| {
+            let mut key = [0u8; 32];
+            srtp_kdf(master_key, &master_salt, label, &mut key);
+            key
+        };
+        let salt = |label| {
+            let mut salt = [0u8; MASTER_SALT_LENGTH];
+            srtp_kdf(master_key, &master_salt, label, &mut salt);
+            salt
+        };
+
+        SrtpChaCha20Poly1305 {
+            rtp: ChaCha20Poly1305::new(derive(KeyLabel::RtpEncryption)),
+            rtp_salt: salt(KeyLabel::RtpSalt),
+            rtcp: ChaCha20Poly1305::new(derive(KeyLabel::RtcpEncryption)),
+            rtcp_salt: salt(KeyLabel::RtcpSalt),
+        }
+    }
+
+    ///
+    /// Encrypts the payload of the RTP packet in `packet` in place, and
+    /// returns the tag to append to it.  `roc` is the sender's rollover
+    /// counter for the packet's sequence number.
+    ///
+    pub fn protect_rtp_in_place(&self, roc: u32, packet: &mut [u8]) -> Result<Tag, SrtpError> {
+        let header_length = rtp_header_length(packet)?;
+        let nonce = self.rtp_packet_nonce(roc, packet);
+        let (header, payload) = packet.split_at_mut(header_length);
+        Ok(self.rtp.seal_in_place_detached(nonce, header, payload))
+    }
+
+    ///
+    /// Verifies and decrypts, in place, an SRTP packet with its tag
+    /// appended, as `protect_rtp` produces it.  Returns the part of
+    /// `packet` holding the RTP packet.  If the tag is invalid, `packet` is
+    /// left untouched.
+    ///
+    pub fn unprotect_rtp_in_place<'a>(
+        &self,
+        roc: u32,
+        packet: &'a mut [u8],
+    ) -> Result<&'a mut [u8], SrtpError> {
+        let header_length = rtp_header_length(packet)?;
+        if packet.len() < header_length + TAG_LENGTH {
+            return Err(SrtpError::TooShort);
+        }
+        let nonce = self.rtp_packet_nonce(roc, packet);
+
+        let length = packet.len() - TAG_LENGTH;
+        let (rtp, tag) = packet.split_at_mut(length);
+        let tag = Tag::try_from(&tag[..]).map_err(|_| SrtpError::TooShort)?;
+        let (header, payload) = rtp.split_at_mut(header_length);
+        self.rtp
+            .open_in_place_detached(nonce, header, payload, &tag)
+            .map_err(tag_error)?;
+        Ok(rtp)
+    }
+
+    ///
+    /// Protects an RTP packet, returning the SRTP packet: the header, the
+    /// encrypted payload and the tag.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn protect_rtp(&self, roc: u32, packet: &[u8]) -> Result<Vec<u8>, SrtpError> {
+        let mut output = Vec::with_capacity(packet.len() + TAG_LENGTH);
+        output.extend_from_slice(packet);
+        let tag = self.protect_rtp_in_place(roc, &mut output)?;
+        output.extend_from_slice(tag.as_ref());
+        Ok(output)
+    }
+
+    ///
+    /// Verifies and decrypts an SRTP packet produced by `protect_rtp`.  The
+    /// RTP packet is only returned if the tag is valid.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn unprotect_rtp(&self, roc: u32, packet: &[u8]) -> Result<Vec<u8>, SrtpError> {
+        let mut buffer = packet.to_vec();
+        let length = self.unprotect_rtp_in_place(roc, &mut buffer)?.len();
+        buffer.truncate(length);
+        Ok(buffer)
+    }
+
+    ///
+    /// Encrypts the RTCP packet in `packet` in place, past its first 8
+    /// bytes, and returns the 20 bytes to append to it: the tag, then the E
+    /// flag and `index`.  The sender counts `index` up from zero for every
+    /// packet.  Fails with `SrtpError::IndexOverflow` if `index` does not
+    /// fit in 31 bits.
+    ///
+    pub fn protect_rtcp_in_place(
+        &self,
+        index: u32,
+        packet: &mut [u8],
+    ) -> Result<[u8; TAG_LENGTH + RTCP_INDEX_LENGTH], SrtpError> {
+        if index & RTCP_ENCRYPTED != 0 {
+            return Err(SrtpError::IndexOverflow);
+        }
+        if packet.len() < RTCP_HEADER_LENGTH || packet[0] >> 6 != 2 {
+            return Err(SrtpError::InvalidHeader);
+        }
+        let e_index = (RTCP_ENCRYPTED | index).to_be_bytes();
+        let nonce = self.rtcp_packet_nonce(index, packet);
+
+        let (header, payload) = packet.split_at_mut(RTCP_HEADER_LENGTH);
+        let aad = rtcp_aad(header, e_index);
+        let tag = self.rtcp.seal_in_place_detached(nonce, &aad, payload);
+
+        let mut trailer = [0u8; TAG_LENGTH + RTCP_INDEX_LENGTH];
+        trailer[..TAG_LENGTH].copy_from_slice(tag.as_ref());
+        trailer[TAG_LENGTH..].copy_from_slice(&e_index);
+        Ok(trailer)
+    }
+
+    ///
+    /// Verifies and decrypts, in place, an SRTCP packet as `protect_rtcp`
+    /// produces it.  Returns the part of `packet` holding the RTCP packet
+    /// and the SRTCP index, which the caller checks against replays.  If
+    /// the tag is invalid, `packet` is left untouched.
+    ///
+    /// Packets without the E flag, whose payload was not encrypted, are
+    /// rejected with `SrtpError::InvalidHeader`.
+    ///
+    pub fn unprotect_rtcp_in_place<'a>(
+        &self,
+        packet: &'a mut [u8],
+    ) -> Result<(&'a mut [u8], u32), SrtpError> {
+        if packet.len() < RTCP_HEADER_LENGTH || packet[0] >> 6 != 2 {
+            return Err(SrtpError::InvalidHeader);
+        }
+        if packet.len() < RTCP_HEADER_LENGTH + TAG_LENGTH + RTCP_INDEX_LENGTH {
+            return Err(SrtpError::TooShort);
+        }
+
+        let length = packet.len() - TAG_LENGTH - RTCP_INDEX_LENGTH;
+        let (rtcp, trailer) = packet.split_at_mut(length);
+        let e_index: [u8; RTCP_INDEX_LENGTH] = trailer[TAG_LENGTH..].try_into().unwrap();
+        let index = u32::from_be_bytes(e_index);
+        if index & RTCP_ENCRYPTED == 0 {
+            return Err(SrtpError::InvalidHeader);
+        }
+        let index = index & !RTCP_ENCRYPTED;
+        let tag = Tag::try_from(&trailer[..TAG_LENGTH]).map_err(|_| SrtpError::TooShort)?;
+        let nonce = self.rtcp_packet_nonce(index, rtcp);
+
+        let (header, payload) = rtcp.split_at_mut(RTCP_HEADER_LENGTH);
+        let aad = rtcp_aad(header, e_index);
+        self.rtcp
+            .open_in_place_detached(nonce, &aad, payload, &tag)
+            .map_err(tag_error)?;
+        Ok((rtcp, index))
+    }
+
+    ///
+    /// Protects an RTCP packet, returning the SRTCP packet.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn protect_rtcp(&self, index: u32, packet: &[u8]) -> Result<Vec<u8>, SrtpError> {
+        let mut output = Vec::with_capacity(packet.len() + TAG_LENGTH + RTCP_INDEX_LENGTH);
+        output.extend_from_slice(packet);
+        let trailer = self.protect_rtcp_in_place(index, &mut output)?;
+        output.extend_from_slice(&trailer);
+        Ok(output)
+    }
+
+    ///
+    /// Verifies and decrypts an SRTCP packet produced by `protect_rtcp`,
+    /// returning the RTCP packet and its SRTCP index.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn unprotect_rtcp(&self, packet: &[u8]) -> Result<(Vec<u8>, u32), SrtpError> {
+        let mut buffer = packet.to_vec();
+        let (rtcp, index) = self.unprotect_rtcp_in_place(&mut buffer)?;
+        let length = rtcp.len();
+        buffer.truncate(length);
+        Ok((buffer, index))
+    }
+
+    fn rtp_packet_nonce(&self, roc: u32, packet: &[u8]) -> [u8; 12] {
+        let sequence = u16::from_be_bytes([packet[2], packet[3]]);
+        let ssrc = u32::from_be_bytes(packet[8..12].try_into().unwrap());
+        rtp_nonce(&self.rtp_salt, ssrc, roc, sequence)
+    }
+
+    fn rtcp_packet_nonce(&self, index: u32, packet: &[u8]) -> [u8; 12] {
+        let ssrc = u32::from_be_bytes(packet[4..8].try_into().unwrap());
+        rtcp_nonce(&self.rtcp_salt, ssrc, index)
+    }
+}
+
+///
+/// The associated data of an SRTCP packet: its first 8 bytes, then the E
+/// flag and SRTCP index.
+///
+fn rtcp_aad(header: &[u8], e_index: [u8; RTCP_INDEX_LENGTH]) -> [u8; 12] {
+    let mut aad = [0u8; RTCP_HEADER_LENGTH + RTCP_INDEX_LENGTH];
+    aad[..RTCP_HEADER_LENGTH].copy_from_slice(header);
+    aad[RTCP_HEADER_LENGTH..].copy_from_slice(&e_index);
+    aad
+}
+
+fn tag_error(error: AeadError) -> SrtpError {
+    match error {
+        AeadError::InvalidTag => SrtpError::InvalidTag,
+        _ => SrtpError::TooShort,
+    }
+}
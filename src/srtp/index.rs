@@ -0,0 +1,140 @@
+use super::SrtpError;
+
+/// Number of packets behind the highest index the replay window covers.
+pub const REPLAY_WINDOW: u64 = 64;
+
+///
+/// Tracks a receiver's view of one RTP stream: the highest packet index
+/// authenticated so far, and which of the packets just before it were
+/// seen.  The 48-bit index is the rollover counter (ROC) in the high 32
+/// bits and the sequence number in the low 16.
+///
+/// A packet is handled in three steps: `estimate` its index from the
+/// sequence number, `check` that index against replays, and only after
+/// the packet authenticated under the ROC `index >> 16`, `accept` it.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc3711#section-3.3.1)
+///
+#[derive(Debug, Clone)]
+pub struct RtpIndexTracker {
+    roc: u32,
+    // Highest sequence number accepted, None before the first packet
+    highest: Option<u16>,
+    // Bit i is set if the packet i places behind the highest was accepted
+    window: u64,
+}
+
+impl RtpIndexTracker {
+    ///
+    /// Starts tracking a stream whose rollover counter is `initial_roc`:
+    /// zero for a stream followed from its start, or the signalled value
+    /// when joining later.
+    ///
+    pub fn new(initial_roc: u32) -> Self {
+        RtpIndexTracker {
+            roc: initial_roc,
+            highest: None,
+            window: 0,
+        }
+    }
+
+    ///
+    /// Returns the index of the packet with sequence number `sequence`:
+    /// the one of the current, previous or next ROC closest to the highest
+    /// sequence number accepted.  Before the first packet, and for packets
+    /// that would belong before ROC zero, the current ROC is used.
+    ///
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc3711#appendix-A)
+    ///
+    pub fn estimate(&self, sequence: u16) -> u64 {
+        let highest = match self.highest {
+            Some(highest) => highest,
+            None => return index(self.roc, sequence),
+        };
+
+        let roc = if highest < 0x8000 {
+            if sequence > highest && sequence - highest > 0x8000 {
+                self.roc.saturating_sub(1)
+            } else {
+                self.roc
+            }
+        } else if sequence < highest - 0x8000 {
+            self.roc.wrapping_add(1)
+        } else {
+            self.roc
+        };
+        index(roc, sequence)
+    }
+
+    ///
+    /// Fails with `SrtpError::Replayed` if the packet at `index` was
+    /// accepted already, or is more than `REPLAY_WINDOW` packets behind the
+    /// highest one.
+    ///
+    pub fn check(&self, index: u64) -> Result<(), SrtpError> {
+        let highest = match self.highest_index() {
+            Some(highest) => highest,
+            None => return Ok(()),
+        };
+        if index > highest {
+            return Ok(());
+        }
+
+        let behind = highest - index;
+        if behind >= REPLAY_WINDOW || self.window & (1 << behind) != 0 {
+            return Err(SrtpError::Replayed);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Records that the packet at `index` authenticated, moving the highest
+    /// index and the ROC forward if it is newer.
+    ///
+    pub fn accept(&mut self, index: u64) {
+        let highest = match self.highest_index() {
+            Some(highest) => highest,
+            None => {
+                self.set_highest(index);
+                self.window = 1;
+                return;
+            }
+        };
+
+        if index > highest {
+            let ahead = index - highest;
+            self.window = if ahead >= REPLAY_WINDOW {
+                0
+            } else {
+                self.window << ahead
+            };
+            self.window |= 1;
+            self.set_highest(index);
+        } else if highest - index < REPLAY_WINDOW {
+            self.window |= 1 << (highest - index);
+        }
+    }
+
+    ///
+    /// The ROC of the highest packet accepted so far.
+    ///
+    pub fn roc(&self) -> u32 {
+        self.roc
+    }
+
+    fn highest_index(&self) -> Option<u64> {
+        self.highest.map(|highest| index(self.roc, highest))
+    }
+
+    fn set_highest(&mut self, index: u64) {
+        self.roc = (index >> 16) as u32;
+        self.highest = Some(index as u16);
+    }
+}
+
+///
+/// Combines a ROC and a sequence number into a packet index.
+///
+fn index(roc: u32, sequence: u16) -> u64 {
+    (roc as u64) << 16 | sequence as u64
+}
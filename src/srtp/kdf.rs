@@ -0,0 +1,55 @@
+use crate::aes::{Aes, BLOCK_LENGTH};
+use crate::modes::Ctr;
+
+/// The longest master salt the key derivation function takes.
+pub const MAX_MASTER_SALT_LENGTH: usize = 14;
+
+///
+/// What a session key derived from the master key is for.  The label is
+/// the byte the key derivation function mixes into the salt.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLabel {
+    RtpEncryption = 0x00,
+    RtpAuthentication = 0x01,
+    RtpSalt = 0x02,
+    RtcpEncryption = 0x03,
+    RtcpAuthentication = 0x04,
+    RtcpSalt = 0x05,
+}
+
+///
+/// The SRTP key derivation function: fills `output` with the AES-CM
+/// keystream whose IV is the master salt XOR the label, followed by two
+/// zero bytes.  The key length picks AES-128, AES-192 or AES-256.
+///
+/// Salts shorter than 14 bytes, like the 12-byte salts of the AEAD
+/// profiles, are padded with zeros on the right.  The key derivation rate
+/// is taken to be zero, as DTLS-SRTP requires, so the packet index never
+/// enters the derivation.
+///
+/// # Panics
+///
+/// Panics if `master_salt` is longer than `MAX_MASTER_SALT_LENGTH`.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc3711#section-4.3)
+///
+pub fn srtp_kdf<const KEY_LENGTH: usize>(
+    master_key: [u8; KEY_LENGTH],
+    master_salt: &[u8],
+    label: KeyLabel,
+    output: &mut [u8],
+) {
+    assert!(
+        master_salt.len() <= MAX_MASTER_SALT_LENGTH,
+        "SRTP master salts are at most 14 bytes long"
+    );
+
+    // The label sits 7 bytes from the end of the salt, followed by the
+    // 48-bit index divided by the key derivation rate, always zero here
+    let mut iv = [0u8; BLOCK_LENGTH];
+    iv[..master_salt.len()].copy_from_slice(master_salt);
+    iv[MAX_MASTER_SALT_LENGTH - 7] ^= label as u8;
+
+    Ctr::new(Aes::new(master_key), iv).fill(output);
+}
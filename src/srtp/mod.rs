@@ -0,0 +1,45 @@
+//!
+//! The pieces of SRTP (RFC 3711) around an AEAD: the AES-CM key derivation
+//! function, the estimate of a packet's index from its sequence number and
+//! the rollover counter, replay protection, and packet protection with
+//! ChaCha20-Poly1305 laid out like the AES-GCM profiles of RFC 7714.
+//!
+mod chacha20poly1305;
+mod index;
+mod kdf;
+
+pub use chacha20poly1305::*;
+pub use index::*;
+pub use kdf::*;
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SrtpError {
+    /// The packet is not RTP or RTCP version 2, or is shorter than its
+    /// header.
+    InvalidHeader,
+    /// The packet is shorter than its header plus the tag.
+    TooShort,
+    /// The Poly1305 tag did not match the packet.
+    InvalidTag,
+    /// The SRTCP index does not fit in 31 bits.
+    IndexOverflow,
+    /// The packet was received before, or is too old to tell.
+    Replayed,
+}
+
+impl fmt::Display for SrtpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SrtpError::InvalidHeader => write!(f, "invalid packet header"),
+            SrtpError::TooShort => write!(f, "packet is too short"),
+            SrtpError::InvalidTag => write!(f, "packet authentication failed"),
+            SrtpError::IndexOverflow => write!(f, "SRTCP index overflow"),
+            SrtpError::Replayed => write!(f, "packet was replayed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SrtpError {}
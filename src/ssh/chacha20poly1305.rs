@@ -0,0 +1,141 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::chacha::{self, ChaCha20, ChaCha20Block, Nonce};
+use crate::poly::{poly1305_key_gen, poly1305_mac, tags_equal};
+
+const KEY_LENGTH: usize = 64;
+const LENGTH_FIELD: usize = 4;
+pub const TAG_LENGTH: usize = 16;
+
+pub type Key = [u8; KEY_LENGTH];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenSshError {
+    /// The packet is shorter than its length field plus the tag.
+    PacketTooShort,
+    /// The Poly1305 tag did not match the packet.
+    InvalidTag,
+}
+
+impl fmt::Display for OpenSshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenSshError::PacketTooShort => write!(f, "packet is too short"),
+            OpenSshError::InvalidTag => write!(f, "packet authentication failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpenSshError {}
+
+///
+/// The chacha20-poly1305@openssh.com packet cipher.
+///
+/// The 64-byte key is split in two: the first half (K_2) encrypts the packet
+/// payload and keys Poly1305, the second half (K_1) only encrypts the 4-byte
+/// packet length.  Both instances use the original ChaCha20 layout with the
+/// 64-bit packet sequence number as nonce.
+///
+/// [Source](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.chacha20poly1305)
+///
+pub struct OpenSshChaCha20Poly1305 {
+    main_key: chacha::Key,
+    header_key: chacha::Key,
+}
+
+///
+/// Maps the 64-bit sequence number onto the IETF state layout.  The four
+/// zero bytes take the place of the high word of the original 64-bit
+/// counter, which never leaves zero for SSH-sized packets.
+///
+fn sequence_nonce(sequence_number: u64) -> Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&sequence_number.to_be_bytes());
+    Nonce::from(nonce)
+}
+
+impl OpenSshChaCha20Poly1305 {
+    pub fn new(key: Key) -> Self {
+        OpenSshChaCha20Poly1305 {
+            main_key: chacha::Key::try_from(&key[..32]).unwrap(),
+            header_key: chacha::Key::try_from(&key[32..]).unwrap(),
+        }
+    }
+
+    ///
+    /// Decrypts the 4-byte packet length so the caller knows how much more
+    /// to read from the wire.  The length is not authenticated until the
+    /// whole packet is passed to `decrypt`.
+    ///
+    pub fn decrypt_length(&self, sequence_number: u64, encrypted_length: [u8; 4]) -> u32 {
+        let nonce = sequence_nonce(sequence_number);
+        let mut block = ChaCha20Block::with_key(&self.header_key, &nonce, 0);
+        let keystream = block.get_keystream();
+
+        let mut length = encrypted_length;
+        length.iter_mut().zip(&keystream).for_each(|(x, y)| *x ^= y);
+        u32::from_be_bytes(length)
+    }
+
+    ///
+    /// Encrypts a packet consisting of its 4-byte big-endian length followed
+    /// by the rest of the binary packet, returning the encrypted packet with
+    /// the 16-byte tag appended.
+    ///
+    pub fn encrypt(&self, sequence_number: u64, packet: &[u8]) -> Result<Vec<u8>, OpenSshError> {
+        if packet.len() < LENGTH_FIELD {
+            return Err(OpenSshError::PacketTooShort);
+        }
+        let nonce = sequence_nonce(sequence_number);
+
+        let header_keystream = ChaCha20Block::with_key(&self.header_key, &nonce, 0).get_keystream();
+        let mut output = packet[..LENGTH_FIELD]
+            .iter()
+            .zip(&header_keystream)
+            .map(|(x, y)| x ^ y)
+            .collect::<Vec<u8>>();
+        let mut cipher = ChaCha20::new(self.main_key.clone(), nonce.clone());
+        output.extend(cipher.encrypt(&packet[LENGTH_FIELD..]));
+
+        let tag = poly1305_mac(self.poly1305_key(&nonce), &output);
+        output.extend_from_slice(tag.as_ref());
+        Ok(output)
+    }
+
+    ///
+    /// Verifies and decrypts a packet produced by `encrypt`, returning the
+    /// plaintext length field followed by the rest of the binary packet.
+    ///
+    pub fn decrypt(&self, sequence_number: u64, packet: &[u8]) -> Result<Vec<u8>, OpenSshError> {
+        if packet.len() < LENGTH_FIELD + TAG_LENGTH {
+            return Err(OpenSshError::PacketTooShort);
+        }
+        let nonce = sequence_nonce(sequence_number);
+        let (ciphertext, tag) = packet.split_at(packet.len() - TAG_LENGTH);
+
+        // Constant-time tag comparison
+        let expected = poly1305_mac(self.poly1305_key(&nonce), ciphertext);
+        if !tags_equal(&expected, tag) {
+            return Err(OpenSshError::InvalidTag);
+        }
+
+        let length = self.decrypt_length(
+            sequence_number,
+            ciphertext[..LENGTH_FIELD].try_into().unwrap(),
+        );
+        let mut output = length.to_be_bytes().to_vec();
+        let mut cipher = ChaCha20::new(self.main_key.clone(), nonce);
+        output.extend(cipher.encrypt(&ciphertext[LENGTH_FIELD..]));
+        Ok(output)
+    }
+
+    ///
+    /// The Poly1305 key is the first 32 bytes of the main key's keystream
+    /// block 0.
+    ///
+    fn poly1305_key(&self, nonce: &Nonce) -> [u8; 32] {
+        poly1305_key_gen(self.main_key.clone(), nonce.clone())
+    }
+}
@@ -0,0 +1,3 @@
+mod chacha20poly1305;
+
+pub use chacha20poly1305::*;
@@ -0,0 +1,215 @@
+use zeroize::Zeroize;
+
+use crate::hash::Digest;
+use crate::hkdf::{expand, extract, HkdfError};
+use crate::mac::Hmac;
+
+/// Prefix every label gets inside `HKDF-Expand-Label`.
+const LABEL_PREFIX: &[u8] = b"tls13 ";
+
+/// Longest label, so the prefixed label fits its one-byte length.
+pub const MAX_LABEL_LENGTH: usize = 255 - LABEL_PREFIX.len();
+
+/// Longest context, which has a one-byte length.
+pub const MAX_CONTEXT_LENGTH: usize = 255;
+
+/// Longest `HkdfLabel` structure: the output length, then the label and
+/// the context with their lengths.
+const MAX_HKDF_LABEL_LENGTH: usize = 2 + 1 + 255 + 1 + MAX_CONTEXT_LENGTH;
+
+/// Longest hash output of any `Digest`, the length of the zero inputs.
+const MAX_HASH_LENGTH: usize = 64;
+
+pub const EXTERNAL_BINDER: &[u8] = b"ext binder";
+pub const RESUMPTION_BINDER: &[u8] = b"res binder";
+pub const CLIENT_EARLY_TRAFFIC: &[u8] = b"c e traffic";
+pub const EARLY_EXPORTER_MASTER: &[u8] = b"e exp master";
+pub const CLIENT_HANDSHAKE_TRAFFIC: &[u8] = b"c hs traffic";
+pub const SERVER_HANDSHAKE_TRAFFIC: &[u8] = b"s hs traffic";
+pub const CLIENT_APPLICATION_TRAFFIC: &[u8] = b"c ap traffic";
+pub const SERVER_APPLICATION_TRAFFIC: &[u8] = b"s ap traffic";
+pub const EXPORTER_MASTER: &[u8] = b"exp master";
+pub const RESUMPTION_MASTER: &[u8] = b"res master";
+
+/// Label of the salt that leads from one stage of the schedule to the next.
+const DERIVED: &[u8] = b"derived";
+
+///
+/// Builds the `HkdfLabel` structure into `buffer` and returns its length.
+///
+fn hkdf_label(
+    buffer: &mut [u8; MAX_HKDF_LABEL_LENGTH],
+    length: u16,
+    label: &[u8],
+    context: &[u8],
+) -> usize {
+    assert!(
+        label.len() <= MAX_LABEL_LENGTH,
+        "TLS 1.3 labels are at most 249 bytes long"
+    );
+    assert!(
+        context.len() <= MAX_CONTEXT_LENGTH,
+        "TLS 1.3 contexts are at most 255 bytes long"
+    );
+
+    let mut position = 0;
+    let mut push = |bytes: &[u8]| {
+        buffer[position..position + bytes.len()].copy_from_slice(bytes);
+        position += bytes.len();
+    };
+    push(&length.to_be_bytes());
+    push(&[(LABEL_PREFIX.len() + label.len()) as u8]);
+    push(LABEL_PREFIX);
+    push(label);
+    push(&[context.len() as u8]);
+    push(context);
+    position
+}
+
+///
+/// `HKDF-Expand-Label`: expands `secret` into `output`, with the output
+/// length, `"tls13 "` followed by `label`, and `context` as the info.
+///
+/// Fails like `hkdf::expand`, with `HkdfError::InvalidPrkLength` if
+/// `secret` is shorter than a hash output, or
+/// `HkdfError::InvalidOutputLength` if `output` is longer than 255 of them.
+///
+/// # Panics
+///
+/// Panics if `label` is longer than `MAX_LABEL_LENGTH` or `context` longer
+/// than `MAX_CONTEXT_LENGTH`.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-7.1)
+///
+pub fn hkdf_expand_label<D: Digest>(
+    secret: &[u8],
+    label: &[u8],
+    context: &[u8],
+    output: &mut [u8],
+) -> Result<(), HkdfError> {
+    let length = u16::try_from(output.len()).map_err(|_| HkdfError::InvalidOutputLength)?;
+    let mut info = [0u8; MAX_HKDF_LABEL_LENGTH];
+    let info_length = hkdf_label(&mut info, length, label, context);
+    expand::<D>(secret, &info[..info_length], output)
+}
+
+///
+/// `Derive-Secret`: `HKDF-Expand-Label` of one hash output, with the hash
+/// of the handshake messages so far as the context.
+///
+/// # Panics
+///
+/// Panics if `label` is longer than `MAX_LABEL_LENGTH`.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-7.1)
+///
+pub fn derive_secret<D: Digest>(secret: &[u8], label: &[u8], transcript_hash: &[u8]) -> D::Output {
+    let mut info = [0u8; MAX_HKDF_LABEL_LENGTH];
+    let info_length = hkdf_label(&mut info, D::OUTPUT_LENGTH as u16, label, transcript_hash);
+
+    // One hash output of HKDF-Expand is its first block, T(1)
+    let mut mac = Hmac::<D>::new(secret);
+    mac.update(&info[..info_length]);
+    mac.update(&[1]);
+    mac.finalize()
+}
+
+///
+/// The running hash of the handshake messages, which every secret after
+/// the early secret is bound to.  Cloning it keeps a hash of the messages
+/// so far while more are added.
+///
+#[derive(Clone)]
+pub struct Transcript<D: Digest> {
+    hash: D,
+}
+
+impl<D: Digest> Transcript<D> {
+    pub fn new() -> Self {
+        Transcript { hash: D::new() }
+    }
+
+    ///
+    /// Adds a handshake message, with its 4-byte handshake header.
+    ///
+    pub fn update(&mut self, message: &[u8]) {
+        self.hash.update(message);
+    }
+
+    ///
+    /// Returns the hash of the messages added so far, and keeps going.
+    ///
+    pub fn hash(&self) -> D::Output {
+        self.hash.clone().finalize()
+    }
+}
+
+impl<D: Digest> Default for Transcript<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// The secret at the current stage of the key schedule: the early secret,
+/// then the handshake secret, then the master secret.  Each stage's
+/// traffic and exporter secrets come from `derive_secret`, and `advance`
+/// moves on to the next stage.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-7.1)
+///
+pub struct KeySchedule<D: Digest> {
+    secret: D::Output,
+}
+
+impl<D: Digest> KeySchedule<D> {
+    ///
+    /// Starts with the early secret, extracted from the pre-shared key, or
+    /// from a hash output of zeros without one.
+    ///
+    pub fn new(psk: Option<&[u8]>) -> Self {
+        let zeros = [0u8; MAX_HASH_LENGTH];
+        let psk = psk.unwrap_or(&zeros[..D::OUTPUT_LENGTH]);
+        KeySchedule {
+            secret: extract::<D>(&zeros[..D::OUTPUT_LENGTH], psk),
+        }
+    }
+
+    ///
+    /// Derives a secret of the current stage, such as
+    /// `CLIENT_HANDSHAKE_TRAFFIC` from the handshake secret, bound to the
+    /// messages in `transcript`.
+    ///
+    pub fn derive_secret(&self, label: &[u8], transcript: &Transcript<D>) -> D::Output {
+        derive_secret::<D>(self.secret.as_ref(), label, transcript.hash().as_ref())
+    }
+
+    ///
+    /// Moves to the next stage, extracting `input` with a salt derived from
+    /// the current secret: the (EC)DHE shared secret to reach the handshake
+    /// secret, and `None`, a hash output of zeros, for the master secret.
+    ///
+    pub fn advance(&mut self, input: Option<&[u8]>) {
+        let zeros = [0u8; MAX_HASH_LENGTH];
+        let input = input.unwrap_or(&zeros[..D::OUTPUT_LENGTH]);
+        let empty_hash = Transcript::<D>::new().hash();
+
+        let mut salt = derive_secret::<D>(self.secret.as_ref(), DERIVED, empty_hash.as_ref());
+        self.secret.as_mut().zeroize();
+        self.secret = extract::<D>(salt.as_ref(), input);
+        salt.as_mut().zeroize();
+    }
+
+    ///
+    /// The secret of the current stage.
+    ///
+    pub fn secret(&self) -> &D::Output {
+        &self.secret
+    }
+}
+
+impl<D: Digest> Drop for KeySchedule<D> {
+    fn drop(&mut self) {
+        self.secret.as_mut().zeroize();
+    }
+}
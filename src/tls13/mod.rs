@@ -0,0 +1,11 @@
+//!
+//! The TLS 1.3 key schedule: `HKDF-Expand-Label`, `Derive-Secret`, the
+//! running transcript hash, and the traffic keys and IVs of the record
+//! layer, with `TLS_CHACHA20_POLY1305_SHA256` in mind.  Handshake messages,
+//! key exchange and record framing are left to the protocol built on top.
+//!
+mod key_schedule;
+mod traffic;
+
+pub use key_schedule::*;
+pub use traffic::*;
@@ -0,0 +1,102 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::aead::ChaCha20Poly1305;
+use crate::hash::Digest;
+use crate::mac::Hmac;
+
+use super::{derive_secret, hkdf_expand_label};
+
+pub const IV_LENGTH: usize = 12;
+
+///
+/// The write key and IV of one direction of the record layer, expanded
+/// from a traffic secret.  The key length is the AEAD's: 32 bytes for
+/// ChaCha20-Poly1305, 16 for AES-128-GCM.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-7.3)
+///
+pub struct TrafficKeys<const KEY_LENGTH: usize> {
+    pub key: [u8; KEY_LENGTH],
+    pub iv: [u8; IV_LENGTH],
+}
+
+pub type ChaCha20Poly1305TrafficKeys = TrafficKeys<32>;
+
+impl<const KEY_LENGTH: usize> TrafficKeys<KEY_LENGTH> {
+    ///
+    /// Expands the `key` and `iv` labels of `traffic_secret`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `traffic_secret` is shorter than a hash output.
+    ///
+    pub fn new<D: Digest>(traffic_secret: &[u8]) -> Self {
+        let mut keys = TrafficKeys {
+            key: [0u8; KEY_LENGTH],
+            iv: [0u8; IV_LENGTH],
+        };
+        hkdf_expand_label::<D>(traffic_secret, b"key", &[], &mut keys.key)
+            .expect("traffic secret is shorter than a hash output");
+        hkdf_expand_label::<D>(traffic_secret, b"iv", &[], &mut keys.iv)
+            .expect("traffic secret is shorter than a hash output");
+        keys
+    }
+
+    ///
+    /// Returns the nonce of the record with sequence number `sequence`:
+    /// the IV XOR the sequence number, as a big-endian integer padded on
+    /// the left.  The sequence number starts at zero for every new key.
+    ///
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-5.3)
+    ///
+    pub fn nonce(&self, sequence: u64) -> [u8; IV_LENGTH] {
+        let mut nonce = self.iv;
+        nonce[IV_LENGTH - 8..]
+            .iter_mut()
+            .zip(sequence.to_be_bytes())
+            .for_each(|(x, y)| *x ^= y);
+        nonce
+    }
+}
+
+impl TrafficKeys<32> {
+    ///
+    /// The ChaCha20-Poly1305 instance that protects the records.
+    ///
+    pub fn aead(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(self.key)
+    }
+}
+
+impl<const KEY_LENGTH: usize> Drop for TrafficKeys<KEY_LENGTH> {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.iv.zeroize();
+    }
+}
+
+impl<const KEY_LENGTH: usize> ZeroizeOnDrop for TrafficKeys<KEY_LENGTH> {}
+
+///
+/// Derives the next traffic secret after a KeyUpdate.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-7.2)
+///
+pub fn next_traffic_secret<D: Digest>(traffic_secret: &[u8]) -> D::Output {
+    derive_secret::<D>(traffic_secret, b"traffic upd", &[])
+}
+
+///
+/// Computes the `verify_data` of a Finished message: the HMAC of the
+/// transcript hash under the `finished` key expanded from a handshake
+/// traffic secret.
+///
+/// [Source](https://datatracker.ietf.org/doc/html/rfc8446#section-4.4.4)
+///
+pub fn finished_verify_data<D: Digest>(traffic_secret: &[u8], transcript_hash: &[u8]) -> D::Output {
+    let mut finished_key = derive_secret::<D>(traffic_secret, b"finished", &[]);
+    let mut mac = Hmac::<D>::new(finished_key.as_ref());
+    finished_key.as_mut().zeroize();
+    mac.update(transcript_hash);
+    mac.finalize()
+}
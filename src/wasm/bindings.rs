@@ -0,0 +1,61 @@
+//!
+//! JavaScript bindings, built with `wasm-pack` or `wasm-bindgen`.  Byte
+//! arguments are taken as `Uint8Array`s and results returned as new ones;
+//! invalid key or nonce lengths and failed authentication are thrown as
+//! `Error`s.
+//!
+//! JavaScript sees `encrypt` and `decrypt` for ChaCha20, and `seal` and
+//! `open` for ChaCha20-Poly1305.
+//!
+use std::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::aead::ChaCha20Poly1305;
+use crate::chacha::{ChaCha20, Key, Nonce};
+
+fn key_and_nonce(key: &[u8], nonce: &[u8]) -> Result<(Key, Nonce), JsError> {
+    Ok((Key::try_from(key)?, Nonce::try_from(nonce)?))
+}
+
+///
+/// Encrypts `data` with ChaCha20, starting at block counter 1 like
+/// `ChaCha20::new`.  `key` must be 32 bytes and `nonce` 12 bytes.
+///
+/// ChaCha20 alone does not authenticate the data; use `seal` unless the
+/// ciphertext is authenticated some other way.
+///
+#[wasm_bindgen(js_name = encrypt)]
+pub fn chacha20_encrypt(key: &[u8], nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, JsError> {
+    let (key, nonce) = key_and_nonce(key, nonce)?;
+    Ok(ChaCha20::new(key, nonce).try_encrypt(data)?)
+}
+
+///
+/// Decrypts data encrypted by `encrypt`.  Encryption and decryption
+/// are the same operation, so this only exists for readability.
+///
+#[wasm_bindgen(js_name = decrypt)]
+pub fn chacha20_decrypt(key: &[u8], nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, JsError> {
+    chacha20_encrypt(key, nonce, data)
+}
+
+///
+/// Encrypts and authenticates `plaintext` and `aad` with
+/// ChaCha20-Poly1305, returning the ciphertext with its tag appended.
+///
+#[wasm_bindgen]
+pub fn seal(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, JsError> {
+    let (key, nonce) = key_and_nonce(key, nonce)?;
+    Ok(ChaCha20Poly1305::new(key).seal(nonce, aad, plaintext))
+}
+
+///
+/// Verifies and decrypts a ciphertext produced by `seal`, throwing if the
+/// tag does not match.
+///
+#[wasm_bindgen]
+pub fn open(key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, JsError> {
+    let (key, nonce) = key_and_nonce(key, nonce)?;
+    Ok(ChaCha20Poly1305::new(key).open(nonce, aad, ciphertext)?)
+}
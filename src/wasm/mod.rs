@@ -0,0 +1,3 @@
+mod bindings;
+
+pub use bindings::*;
@@ -0,0 +1,764 @@
+#[cfg(test)]
+mod aead_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::aead::aegis::{Aegis128L, Aegis256};
+    use armadillo::aead::{
+        AeadError, Aes128Ccm, Aes128Gcm, Aes256Ccm, Aes256Gcm, ChaCha20Poly1305,
+        ChaCha20Poly1305Siv, EncryptedLog, LogReader, StreamDecryptor, StreamEncryptor,
+    };
+    use armadillo::poly::{poly1305_mac, Tag};
+
+    const TEST_KEY: [u8; 32] =
+        hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+    const TEST_NONCE: [u8; 12] = hex!("070000004041424344454647");
+    const TEST_AAD: [u8; 12] = hex!("50515253c0c1c2c3c4c5c6c7");
+    const TEST_PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    ///
+    /// Simple test to verify that sealing and opening work correctly.
+    ///
+    /// Taken from the RFC8439 specification.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.8.2)
+    ///
+    #[test]
+    fn simple_seal_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+
+        let expected = hex!("d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b61161ae10b594f09e26a7e902ecbd0600691");
+        let sealed = aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        assert_eq!(sealed, expected);
+
+        let opened = aead.open(TEST_NONCE, &TEST_AAD, &sealed).unwrap();
+        assert_eq!(opened, TEST_PLAINTEXT);
+    }
+
+    ///
+    /// Empty plaintext and empty associated data must both produce a tag.
+    ///
+    #[test]
+    fn empty_inputs_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+
+        let sealed = aead.seal(TEST_NONCE, &[], &[]);
+        assert_eq!(sealed, hex!("a0784d7a4716f3feb4f64e7f4b39bf04"));
+        assert_eq!(aead.open(TEST_NONCE, &[], &sealed).unwrap(), []);
+
+        let sealed = aead.seal(TEST_NONCE, &TEST_AAD, &[]);
+        assert_eq!(sealed, hex!("e622e5647a38d967a7ecbcb46c7f675c"));
+
+        let sealed = aead.seal(TEST_NONCE, &[], TEST_PLAINTEXT);
+        assert_eq!(
+            sealed[TEST_PLAINTEXT.len()..],
+            hex!("6a23a4681fd59456aea1d29f82477216")
+        );
+    }
+
+    ///
+    /// Every associated data and plaintext length from 0 to 130 bytes must
+    /// seal and open.  The tags are folded into a single MAC to keep the
+    /// expected value short.
+    ///
+    #[test]
+    fn boundary_lengths_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+        let message: Vec<u8> = (0..130).collect();
+
+        let mut tags = Vec::new();
+        for len in 0..=130 {
+            let (aad, plaintext) = (&message[..len], &message[130 - len..]);
+            let sealed = aead.seal(TEST_NONCE, aad, plaintext);
+            assert_eq!(sealed.len(), len + 16);
+            assert_eq!(aead.open(TEST_NONCE, aad, &sealed).unwrap(), plaintext);
+            tags.extend_from_slice(&sealed[len..]);
+        }
+
+        let expected = hex!("a097365e8664f5a88e6a1d1bfd36a0f1");
+        assert_eq!(poly1305_mac(TEST_KEY, &tags), expected);
+    }
+
+    ///
+    /// Any modification of the ciphertext, tag, associated data, or nonce
+    /// must fail authentication.
+    ///
+    #[test]
+    fn tampered_ciphertext_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+        let sealed = aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+
+        for i in 0..sealed.len() {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 0x01;
+            assert_eq!(
+                aead.open(TEST_NONCE, &TEST_AAD, &tampered),
+                Err(AeadError::InvalidTag)
+            );
+        }
+        assert_eq!(
+            aead.open(TEST_NONCE, &TEST_AAD[1..], &sealed),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(
+            aead.open([0u8; 12], &TEST_AAD, &sealed),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(
+            aead.open(TEST_NONCE, &TEST_AAD, &sealed[..15]),
+            Err(AeadError::TooShort)
+        );
+    }
+
+    ///
+    /// The detached ciphertext and tag are exactly the two parts of the
+    /// combined output, and a wrong tag is rejected.
+    ///
+    #[test]
+    fn detached_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+        let sealed = aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+
+        let (ciphertext, tag) = aead.seal_detached(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        assert_eq!(ciphertext, &sealed[..TEST_PLAINTEXT.len()]);
+        assert_eq!(tag.as_ref(), &sealed[TEST_PLAINTEXT.len()..]);
+
+        let opened = aead.open_detached(TEST_NONCE, &TEST_AAD, &ciphertext, &tag);
+        assert_eq!(opened.unwrap(), TEST_PLAINTEXT);
+
+        let mut forged = tag.into_bytes();
+        forged[0] ^= 0x01;
+        assert_eq!(
+            aead.open_detached(TEST_NONCE, &TEST_AAD, &ciphertext, &Tag::from(forged)),
+            Err(AeadError::InvalidTag)
+        );
+    }
+
+    ///
+    /// Sealing and opening in place give the same bytes as the allocating
+    /// versions, and a failed open leaves the buffer as it was.
+    ///
+    #[test]
+    fn in_place_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+        let sealed = aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+
+        let mut buffer = TEST_PLAINTEXT.to_vec();
+        aead.seal_in_place_append_tag(TEST_NONCE, &TEST_AAD, &mut buffer);
+        assert_eq!(buffer, sealed);
+
+        let mut tampered = buffer.clone();
+        tampered[0] ^= 0x01;
+        let copy = tampered.clone();
+        assert_eq!(
+            aead.open_in_place(TEST_NONCE, &TEST_AAD, &mut tampered),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(tampered, copy);
+
+        let opened = aead
+            .open_in_place(TEST_NONCE, &TEST_AAD, &mut buffer)
+            .unwrap();
+        assert_eq!(opened, TEST_PLAINTEXT);
+
+        let mut buffer = TEST_PLAINTEXT.to_vec();
+        let tag = aead.seal_in_place_detached(TEST_NONCE, &TEST_AAD, &mut buffer);
+        assert_eq!(tag.as_ref(), &sealed[TEST_PLAINTEXT.len()..]);
+        aead.open_in_place_detached(TEST_NONCE, &TEST_AAD, &mut buffer, &tag)
+            .unwrap();
+        assert_eq!(buffer, TEST_PLAINTEXT);
+
+        assert_eq!(
+            aead.open_in_place(TEST_NONCE, &TEST_AAD, &mut [0u8; 15]),
+            Err(AeadError::TooShort)
+        );
+    }
+
+    ///
+    /// Through the RustCrypto traits the construction gives the RFC8439
+    /// output, both combined and detached in place.  AES-GCM and AES-CCM give
+    /// their own output as well.
+    ///
+    #[test]
+    #[cfg(feature = "rustcrypto-compat")]
+    fn rustcrypto_traits_test() {
+        use aead::{Aead, AeadInPlace, KeyInit, Payload};
+
+        fn seal<A: Aead + KeyInit>(key: &[u8], nonce: &[u8], aad: &[u8], msg: &[u8]) -> Vec<u8> {
+            let aead = A::new_from_slice(key).unwrap();
+            aead.encrypt(nonce.into(), Payload { msg, aad }).unwrap()
+        }
+
+        let sealed = seal::<ChaCha20Poly1305>(&TEST_KEY, &TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        let aead = <ChaCha20Poly1305 as KeyInit>::new(&TEST_KEY.into());
+        assert_eq!(sealed, aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT));
+
+        let payload = Payload {
+            msg: &sealed,
+            aad: &TEST_AAD,
+        };
+        let opened = Aead::decrypt(&aead, &TEST_NONCE.into(), payload).unwrap();
+        assert_eq!(opened, TEST_PLAINTEXT);
+
+        let mut buffer = sealed[..TEST_PLAINTEXT.len()].to_vec();
+        let mut tag: [u8; 16] = sealed[TEST_PLAINTEXT.len()..].try_into().unwrap();
+        tag[15] ^= 0x80;
+        assert!(aead
+            .decrypt_in_place_detached(&TEST_NONCE.into(), &TEST_AAD, &mut buffer, &tag.into())
+            .is_err());
+        assert_eq!(buffer, &sealed[..TEST_PLAINTEXT.len()]);
+
+        let sealed = seal::<Aes256Gcm>(&TEST_KEY, &TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        let aead = Aes256Gcm::new(TEST_KEY);
+        assert_eq!(sealed, aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT));
+
+        let sealed = seal::<Aes256Ccm<8, 12>>(&TEST_KEY, &TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        let aead = Aes256Ccm::<8, 12>::new(TEST_KEY);
+        assert_eq!(sealed, aead.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT));
+    }
+
+    ///
+    /// STREAM chunks open in order, and reordering chunks or passing off
+    /// an inner chunk as the last one is detected.
+    ///
+    #[test]
+    fn stream_chunks_test() {
+        let prefix = [7u8; 7];
+        let mut encryptor = StreamEncryptor::new(TEST_KEY, prefix);
+        let first = encryptor.next_chunk(&TEST_AAD, b"first").unwrap();
+        let second = encryptor.next_chunk(&TEST_AAD, b"second").unwrap();
+        let last = encryptor.last_chunk(&TEST_AAD, b"last");
+
+        let mut decryptor = StreamDecryptor::new(TEST_KEY, prefix);
+        assert_eq!(decryptor.next_chunk(&TEST_AAD, &first).unwrap(), b"first");
+        assert_eq!(
+            decryptor.next_chunk(&TEST_AAD, &last),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(decryptor.next_chunk(&TEST_AAD, &second).unwrap(), b"second");
+        assert_eq!(decryptor.last_chunk(&TEST_AAD, &last).unwrap(), b"last");
+
+        let mut decryptor = StreamDecryptor::new(TEST_KEY, prefix);
+        assert_eq!(
+            decryptor.next_chunk(&TEST_AAD, &second),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(
+            StreamDecryptor::new(TEST_KEY, prefix).last_chunk(&TEST_AAD, &first),
+            Err(AeadError::InvalidTag)
+        );
+    }
+
+    ///
+    /// The Read and Write adapters round-trip streams of any length,
+    /// including empty ones and ones ending on a chunk boundary, and reject
+    /// streams cut short.
+    ///
+    #[test]
+    #[cfg(feature = "std")]
+    fn stream_io_test() {
+        use std::io::{ErrorKind, Read, Write};
+
+        use armadillo::aead::{StreamReader, StreamWriter};
+
+        let prefix = [1u8; 7];
+        let seal = |data: &[u8]| {
+            let encryptor = StreamEncryptor::new(TEST_KEY, prefix);
+            let mut writer = StreamWriter::with_chunk_length(Vec::new(), encryptor, 1000);
+            for piece in data.chunks(777) {
+                writer.write_all(piece).unwrap();
+            }
+            writer.finish().unwrap()
+        };
+        let open = |sealed: &[u8]| {
+            let decryptor = StreamDecryptor::new(TEST_KEY, prefix);
+            let mut reader = StreamReader::with_chunk_length(sealed, decryptor, 1000);
+            let mut output = Vec::new();
+            reader.read_to_end(&mut output).map(|_| output)
+        };
+
+        for length in [0usize, 1, 999, 1000, 1001, 3000, 12345] {
+            let data: Vec<u8> = (0..length).map(|i| (i % 251) as u8).collect();
+            let sealed = seal(&data);
+            // A full chunk at the end is the last one, only empty data
+            // needs an empty last chunk
+            let chunks = length.div_ceil(1000).max(1);
+            assert_eq!(sealed.len(), length + 16 * chunks, "length {}", length);
+            assert_eq!(open(&sealed).unwrap(), data, "length {}", length);
+        }
+
+        let data = [0x42u8; 3000];
+        let sealed = seal(&data);
+        for cut in [0, 1016, 2032, sealed.len() - 1] {
+            let error = open(&sealed[..cut]).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData, "cut at {}", cut);
+        }
+    }
+
+    ///
+    /// Padded sealing hides the plaintext length up to its PADMÉ bucket,
+    /// and a plaintext sealed without padding is rejected by `open_padded`.
+    ///
+    #[test]
+    fn padded_seal_test() {
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+
+        // 114 bytes and the 0x80 marker pad to 120
+        let sealed = aead.seal_padded(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        assert_eq!(sealed.len(), 120 + 16);
+        assert_eq!(
+            sealed.len(),
+            aead.seal_padded(TEST_NONCE, &TEST_AAD, &TEST_PLAINTEXT[..113])
+                .len()
+        );
+        assert_eq!(
+            aead.open_padded(TEST_NONCE, &TEST_AAD, &sealed).unwrap(),
+            TEST_PLAINTEXT
+        );
+        assert_eq!(
+            aead.open_padded(TEST_NONCE, &[], &sealed),
+            Err(AeadError::InvalidTag)
+        );
+
+        let unpadded = aead.seal(TEST_NONCE, &TEST_AAD, &[0u8; 32]);
+        assert_eq!(
+            aead.open_padded(TEST_NONCE, &TEST_AAD, &unpadded),
+            Err(AeadError::InvalidPadding)
+        );
+
+        let empty = aead.seal_padded(TEST_NONCE, &[], &[]);
+        assert_eq!(empty.len(), 1 + 16);
+        assert!(aead
+            .open_padded(TEST_NONCE, &[], &empty)
+            .unwrap()
+            .is_empty());
+    }
+
+    ///
+    /// A padded STREAM pads every chunk, and only opens with a padded
+    /// decryptor.
+    ///
+    #[test]
+    fn padded_stream_chunks_test() {
+        let prefix = [9u8; 7];
+        let mut encryptor = StreamEncryptor::new_padded(TEST_KEY, prefix);
+        let first = encryptor.next_chunk(&TEST_AAD, &[1u8; 1000]).unwrap();
+        let last = encryptor.last_chunk(&TEST_AAD, b"last");
+        assert_eq!(first.len(), 1024 + 16);
+        assert_eq!(last.len(), 5 + 16);
+
+        let mut decryptor = StreamDecryptor::new_padded(TEST_KEY, prefix);
+        assert_eq!(
+            decryptor.next_chunk(&TEST_AAD, &first).unwrap(),
+            [1u8; 1000]
+        );
+        assert_eq!(decryptor.last_chunk(&TEST_AAD, &last).unwrap(), b"last");
+
+        let mut decryptor = StreamDecryptor::new(TEST_KEY, prefix);
+        assert_eq!(decryptor.next_chunk(&TEST_AAD, &first).unwrap().len(), 1024);
+    }
+
+    ///
+    /// The Read and Write adapters round-trip padded streams, whose full
+    /// chunks all pad to the same length.
+    ///
+    #[test]
+    #[cfg(feature = "std")]
+    fn padded_stream_io_test() {
+        use std::io::{Read, Write};
+
+        use armadillo::aead::{StreamReader, StreamWriter};
+        use armadillo::padding::padme_length;
+
+        let prefix = [2u8; 7];
+        for length in [0usize, 1, 999, 1000, 1001, 12345] {
+            let data: Vec<u8> = (0..length).map(|i| (i % 251) as u8).collect();
+
+            let encryptor = StreamEncryptor::new_padded(TEST_KEY, prefix);
+            let mut writer = StreamWriter::with_chunk_length(Vec::new(), encryptor, 1000);
+            writer.write_all(&data).unwrap();
+            let sealed = writer.finish().unwrap();
+
+            let chunks = length.div_ceil(1000).max(1);
+            let last = length - 1000 * (chunks - 1);
+            assert_eq!(
+                sealed.len(),
+                (chunks - 1) * (padme_length(1001) + 16) + padme_length(last + 1) + 16,
+                "length {}",
+                length
+            );
+
+            let decryptor = StreamDecryptor::new_padded(TEST_KEY, prefix);
+            let mut reader = StreamReader::with_chunk_length(&sealed[..], decryptor, 1000);
+            let mut output = Vec::new();
+            reader.read_to_end(&mut output).unwrap();
+            assert_eq!(output, data, "length {}", length);
+        }
+    }
+
+    ///
+    /// Records of an `EncryptedLog` replay in order, the first one is sealed
+    /// under the log id and index nonce with an all-zero tag as associated
+    /// data, and appending after a replay or from a checkpoint agree.
+    ///
+    #[test]
+    fn log_test() {
+        let records: [&[u8]; 4] = [b"login alice", b"", b"grant admin", TEST_PLAINTEXT];
+        let mut log = EncryptedLog::new(TEST_KEY, *b"audt");
+        let sealed: Vec<Vec<u8>> = records.iter().map(|r| log.append(r).unwrap()).collect();
+        let checkpoint = log.checkpoint();
+        assert_eq!(checkpoint.records, 4);
+        assert_eq!(checkpoint.tag, sealed[3][sealed[3].len() - 16..]);
+
+        // The first record is chained to an all-zero tag
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+        let nonce = hex!("61756474 0000000000000000");
+        assert_eq!(sealed[0], aead.seal(nonce, &[0u8; 16], records[0]));
+
+        let mut reader = LogReader::new(TEST_KEY, *b"audt");
+        for (record, sealed) in records.iter().zip(&sealed) {
+            assert_eq!(reader.next_record(sealed).unwrap(), *record);
+        }
+        assert_eq!(reader.verify_checkpoint(&checkpoint), Ok(()));
+
+        // Appending after a replay continues the same chain
+        let mut log = reader.into_log();
+        let fifth = log.append(b"logout alice").unwrap();
+        let mut resumed = EncryptedLog::resume(TEST_KEY, *b"audt", &checkpoint);
+        assert_eq!(resumed.append(b"logout alice").unwrap(), fifth);
+    }
+
+    ///
+    /// Changed, reordered, dropped and foreign records fail to open, and a
+    /// log cut short fails against the checkpoint.
+    ///
+    #[test]
+    fn log_tampering_test() {
+        let mut log = EncryptedLog::new(TEST_KEY, *b"audt");
+        let sealed: Vec<Vec<u8>> = (0..3u8).map(|i| log.append(&[i; 20]).unwrap()).collect();
+        let checkpoint = log.checkpoint();
+
+        let mut reader = LogReader::new(TEST_KEY, *b"audt");
+        let mut changed = sealed[0].clone();
+        changed[3] ^= 1;
+        assert_eq!(reader.next_record(&changed), Err(AeadError::InvalidTag));
+        assert_eq!(reader.next_record(&sealed[1]), Err(AeadError::InvalidTag));
+        assert_eq!(reader.next_record(&[0u8; 15]), Err(AeadError::TooShort));
+        reader.next_record(&sealed[0]).unwrap();
+        assert_eq!(reader.next_record(&sealed[2]), Err(AeadError::InvalidTag));
+        reader.next_record(&sealed[1]).unwrap();
+        assert_eq!(
+            reader.verify_checkpoint(&checkpoint),
+            Err(AeadError::InvalidTag)
+        );
+        reader.next_record(&sealed[2]).unwrap();
+        assert_eq!(reader.verify_checkpoint(&checkpoint), Ok(()));
+
+        let mut reader = LogReader::new(TEST_KEY, *b"othr");
+        assert_eq!(reader.next_record(&sealed[0]), Err(AeadError::InvalidTag));
+    }
+
+    ///
+    /// The SIV mode is deterministic, so a repeated nonce only shows that a
+    /// message repeated.  The expected output was computed with an
+    /// independent implementation of the construction.
+    ///
+    #[test]
+    fn siv_test() {
+        let siv = ChaCha20Poly1305Siv::new(TEST_KEY);
+
+        let expected = hex!("4fac4cd95bcf3e8c5fcadc5acace8ac2e8ca3bb91a2a75e784da6f9d1c32961e381f21101216bf4c3ab18d2758654009fc1f953c50e6646612bd97a32ab584d779f61f6412fc00868fb27b5f2f781ea19381f63cd91e362de8f5888691e78517c26b41c1bd87816443bdcb60ffc36290047b2333522a5ee7e6e894a223be0a646f2c");
+        let sealed = siv.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT);
+        assert_eq!(sealed, expected);
+        assert_eq!(siv.seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT), sealed);
+        assert_eq!(
+            siv.open(TEST_NONCE, &TEST_AAD, &sealed).unwrap(),
+            TEST_PLAINTEXT
+        );
+        assert_eq!(
+            siv.seal(TEST_NONCE, &[], &[]),
+            hex!("bfaa755af90c8a9229648669e970bd5a")
+        );
+
+        // Any change to the nonce or plaintext changes the whole output
+        let other = siv.seal([0u8; 12], &TEST_AAD, TEST_PLAINTEXT);
+        assert_ne!(other[..16], sealed[..16]);
+
+        for i in [0, 50, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 0x01;
+            assert_eq!(
+                siv.open(TEST_NONCE, &TEST_AAD, &tampered),
+                Err(AeadError::InvalidTag)
+            );
+        }
+
+        let (ciphertext, tag) = sealed.split_at(TEST_PLAINTEXT.len());
+        let mut buffer = ciphertext.to_vec();
+        let tag = tag.try_into().unwrap();
+        assert_eq!(
+            siv.open_in_place_detached(TEST_NONCE, &TEST_AAD[1..], &mut buffer, &tag),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(buffer, ciphertext);
+        assert_eq!(
+            siv.open(TEST_NONCE, &TEST_AAD, &sealed[..15]),
+            Err(AeadError::TooShort)
+        );
+    }
+
+    ///
+    /// AEGIS-128L with both tag lengths, on full and partial blocks, and a
+    /// rejected forgery.
+    ///
+    /// Taken from the AEGIS draft.
+    /// [Source](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-aegis-aead#appendix-A.2)
+    ///
+    #[test]
+    fn aegis_128l_test() {
+        let aegis = Aegis128L::new(hex!("10010000000000000000000000000000"));
+        let nonce = hex!("10000200000000000000000000000000");
+        let aad = hex!("0001020304050607");
+
+        let mut buffer = [0u8; 16];
+        let tag = aegis.seal_in_place_detached(nonce, &[], &mut buffer);
+        assert_eq!(buffer, hex!("c1c0e58bd913006feba00f4b3cc3594e"));
+        assert_eq!(tag, hex!("abe0ece80c24868a226a35d16bdae37a"));
+
+        let mut buffer = [0u8; 16];
+        let tag = aegis.seal_in_place_detached_256(nonce, &[], &mut buffer);
+        assert_eq!(
+            tag,
+            hex!("25835bfbb21632176cf03840687cb968cace4617af1bd0f7d064c639a5c79ee4")
+        );
+        aegis
+            .open_in_place_detached_256(nonce, &[], &mut buffer, &tag)
+            .unwrap();
+        assert_eq!(buffer, [0u8; 16]);
+
+        let tag = aegis.seal_in_place_detached(nonce, &[], &mut []);
+        assert_eq!(tag, hex!("c2b879a67def9d74e6c14f708bbcc9b4"));
+
+        let sealed = aegis.seal(
+            nonce,
+            &aad,
+            &hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+        );
+        assert_eq!(sealed, hex!("79d94593d8c2119d7e8fd9b8fc77845c5c077a05b2528b6ac54b563aed8efe84cc6f3372f6aa1bb82388d695c3962d9a"));
+
+        let sealed = aegis.seal(nonce, &aad, &hex!("000102030405060708090a0b0c0d"));
+        assert_eq!(
+            sealed,
+            hex!("79d94593d8c2119d7e8fd9b8fc775c04b3dba849b2701effbe32c7f0fab7")
+        );
+        assert_eq!(
+            aegis.open(nonce, &aad, &sealed).unwrap(),
+            hex!("000102030405060708090a0b0c0d")
+        );
+
+        let mut tampered = sealed.clone();
+        tampered[0] ^= 1;
+        assert_eq!(
+            aegis.open(nonce, &aad, &tampered),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(aegis.open(nonce, &[], &sealed), Err(AeadError::InvalidTag));
+    }
+
+    ///
+    /// AEGIS-256 with both tag lengths, on full and partial blocks, and a
+    /// rejected forgery, which leaves a zeroed buffer behind.
+    ///
+    /// Taken from the AEGIS draft.
+    /// [Source](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-aegis-aead#appendix-A.3)
+    ///
+    #[test]
+    fn aegis_256_test() {
+        let aegis = Aegis256::new(hex!(
+            "1001000000000000000000000000000000000000000000000000000000000000"
+        ));
+        let nonce = hex!("1000020000000000000000000000000000000000000000000000000000000000");
+        let aad = hex!("0001020304050607");
+
+        let mut buffer = [0u8; 16];
+        let tag = aegis.seal_in_place_detached(nonce, &[], &mut buffer);
+        assert_eq!(buffer, hex!("754fc3d8c973246dcc6d741412a4b236"));
+        assert_eq!(tag, hex!("3fe91994768b332ed7f570a19ec5896e"));
+
+        let mut buffer = [0u8; 16];
+        let tag = aegis.seal_in_place_detached_256(nonce, &[], &mut buffer);
+        assert_eq!(
+            tag,
+            hex!("1181a1d18091082bf0266f66297d167d2e68b845f61a3b0527d31fc7b7b89f13")
+        );
+
+        let sealed = aegis.seal(
+            nonce,
+            &aad,
+            &hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+        );
+        assert_eq!(sealed, hex!("f373079ed84b2709faee373584585d60accd191db310ef5d8b11833df9dec7118d86f91ee606e9ff26a01b64ccbdd91d"));
+
+        let sealed = aegis.seal(nonce, &aad, &hex!("000102030405060708090a0b0c0d"));
+        assert_eq!(
+            sealed,
+            hex!("f373079ed84b2709faee37358458c60b9c2d33ceb058f96e6dd03c215652")
+        );
+        assert_eq!(
+            aegis.open(nonce, &aad, &sealed).unwrap(),
+            hex!("000102030405060708090a0b0c0d")
+        );
+
+        let (ciphertext, tag) = sealed.split_at(14);
+        let mut buffer = ciphertext.to_vec();
+        buffer[13] ^= 1;
+        let tag = Tag::try_from(tag).unwrap();
+        assert_eq!(
+            aegis.open_in_place_detached(nonce, &aad, &mut buffer, &tag),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(buffer, [0u8; 14]);
+    }
+
+    ///
+    /// AES-GCM test cases 1, 2, 4 and 16: empty inputs, a single block, and
+    /// partial blocks with associated data under both key lengths, followed
+    /// by a rejected forgery, which leaves the buffer untouched.
+    ///
+    /// Taken from the GCM specification.
+    /// [Source](https://csrc.nist.rip/groups/ST/toolkit/BCM/documents/proposedmodes/gcm/gcm-spec.pdf) (Appendix B)
+    ///
+    #[test]
+    fn aes_gcm_test() {
+        let gcm = Aes128Gcm::new([0u8; 16]);
+        assert_eq!(
+            gcm.seal([0u8; 12], &[], &[]),
+            hex!("58e2fccefa7e3061367f1d57a4e7455a")
+        );
+        assert_eq!(
+            gcm.seal([0u8; 12], &[], &[0u8; 16]),
+            hex!("0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf")
+        );
+
+        let key = hex!("feffe9928665731c6d6a8f9467308308");
+        let nonce = hex!("cafebabefacedbaddecaf888");
+        let aad = hex!("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex!("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+
+        let gcm = Aes128Gcm::new(key);
+        let sealed = gcm.seal(nonce, &aad, &plaintext);
+        assert_eq!(sealed, hex!("42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e0915bc94fbc3221a5db94fae95ae7121a47"));
+        assert_eq!(gcm.open(nonce, &aad, &sealed).unwrap(), plaintext);
+
+        let mut double_key = [0u8; 32];
+        double_key[..16].copy_from_slice(&key);
+        double_key[16..].copy_from_slice(&key);
+        let gcm = Aes256Gcm::new(double_key);
+        let (ciphertext, tag) = gcm.seal_detached(nonce, &aad, &plaintext);
+        assert_eq!(ciphertext, hex!("522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662"));
+        assert_eq!(tag, hex!("76fc6ece0f4e1768cddf8853bb2d551b"));
+        assert_eq!(
+            gcm.open_detached(nonce, &aad, &ciphertext, &tag).unwrap(),
+            plaintext
+        );
+
+        let mut buffer = ciphertext.clone();
+        buffer[0] ^= 1;
+        assert_eq!(
+            gcm.open_in_place_detached(nonce, &aad, &mut buffer, &tag),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(buffer[1..], ciphertext[1..]);
+    }
+
+    ///
+    /// A message longer than the eight counter blocks encrypted together,
+    /// with a partial last block, sealed in place and through `seal` alike.
+    ///
+    #[test]
+    fn aes_gcm_batches_test() {
+        let gcm = Aes256Gcm::new([7u8; 32]);
+        let plaintext: Vec<u8> = (0..300).map(|i| (i % 251) as u8).collect();
+
+        let sealed = gcm.seal([1u8; 12], &[3u8; 20], &plaintext);
+        assert_eq!(sealed[..16], hex!("76e08bb494baea09d9ddd62a7c3a7e3e"));
+        assert_eq!(sealed[288..300], hex!("81916716fae698bf86cbbc53"));
+        assert_eq!(sealed[300..], hex!("bf771ec7044c3f1a610c6623adb3690f"));
+
+        let mut buffer = plaintext.clone();
+        gcm.seal_in_place_append_tag([1u8; 12], &[3u8; 20], &mut buffer);
+        assert_eq!(buffer, sealed);
+        let opened = gcm
+            .open_in_place([1u8; 12], &[3u8; 20], &mut buffer)
+            .unwrap();
+        assert_eq!(opened, &plaintext[..]);
+    }
+
+    ///
+    /// AES-CCM with 13-, 7- and 8-byte nonces and 8-, 4- and 6-byte tags,
+    /// followed by a rejected forgery, which leaves a zeroed buffer behind.
+    ///
+    /// Taken from RFC3610 (packet vector 1) and SP 800-38C (examples 1
+    /// and 2).
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc3610#section-8)
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/38/c/upd1/final) (Appendix C)
+    ///
+    #[test]
+    fn aes_ccm_test() {
+        let ccm = Aes128Ccm::<8, 13>::new(hex!("c0c1c2c3c4c5c6c7c8c9cacbcccdcecf"));
+        let nonce = hex!("00000003020100a0a1a2a3a4a5");
+        let aad = hex!("0001020304050607");
+        let plaintext = hex!("08090a0b0c0d0e0f101112131415161718191a1b1c1d1e");
+        let sealed = ccm.seal(nonce, &aad, &plaintext);
+        assert_eq!(
+            sealed,
+            hex!("588c979a61c663d2f066d0c2c0f989806d5f6b61dac38417e8d12cfdf926e0")
+        );
+        assert_eq!(ccm.open(nonce, &aad, &sealed).unwrap(), plaintext);
+
+        let key = hex!("404142434445464748494a4b4c4d4e4f");
+        let mut buffer = hex!("20212223");
+        let tag = Aes128Ccm::<4, 7>::new(key).seal_in_place_detached(
+            hex!("10111213141516"),
+            &aad,
+            &mut buffer,
+        );
+        assert_eq!(buffer, hex!("7162015b"));
+        assert_eq!(tag, hex!("4dac255d"));
+
+        let ccm = Aes128Ccm::<6, 8>::new(key);
+        let nonce = hex!("1011121314151617");
+        let aad = hex!("000102030405060708090a0b0c0d0e0f");
+        let sealed = ccm.seal(nonce, &aad, &hex!("202122232425262728292a2b2c2d2e2f"));
+        assert_eq!(sealed, hex!("d2a1f0e051ea5f62081a7792073d593d1fc64fbfaccd"));
+
+        let (ciphertext, tag) = sealed.split_at(16);
+        let mut buffer = ciphertext.to_vec();
+        buffer[0] ^= 1;
+        assert_eq!(
+            ccm.open_in_place_detached(nonce, &aad, &mut buffer, tag.try_into().unwrap()),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(buffer, [0u8; 16]);
+        assert_eq!(
+            ccm.open(nonce, &aad, &sealed[..5]),
+            Err(AeadError::TooShort)
+        );
+    }
+
+    ///
+    /// Associated data long enough for the six-byte length encoding, and a
+    /// message longer than the eight counter blocks encrypted together.
+    ///
+    #[test]
+    fn aes_ccm_long_test() {
+        let ccm = Aes256Ccm::<16, 12>::new([9u8; 32]);
+        let aad: Vec<u8> = (0..0xff10).map(|i| i as u8).collect();
+        let plaintext: Vec<u8> = (0..300).map(|i| (i % 251) as u8).collect();
+
+        let sealed = ccm.seal([2u8; 12], &aad, &plaintext);
+        assert_eq!(sealed[..16], hex!("2f3b7b1d511f16666d9b439e45b3ca14"));
+        assert_eq!(sealed[300..], hex!("bf170fa38182cdccd6ed67767f185389"));
+        assert_eq!(ccm.open([2u8; 12], &aad, &sealed).unwrap(), plaintext);
+    }
+}
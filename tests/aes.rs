@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod aes_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::aes::{Aes128, Aes192, Aes256, Block};
+
+    const TEST_PLAINTEXT: Block = hex!("00112233445566778899aabbccddeeff");
+
+    ///
+    /// The example vectors for all three key lengths, in both directions.
+    ///
+    /// Taken from the FIPS 197 specification.
+    /// [Source](https://csrc.nist.gov/pubs/fips/197/final) (Appendix C)
+    ///
+    #[test]
+    fn fips_197_test() {
+        let mut block = TEST_PLAINTEXT;
+        let aes = Aes128::new(hex!("000102030405060708090a0b0c0d0e0f"));
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("69c4e0d86a7b0430d8cdb78070b4c55a"));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, TEST_PLAINTEXT);
+
+        let aes = Aes192::new(hex!("000102030405060708090a0b0c0d0e0f1011121314151617"));
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("dda97ca4864cdfe06eaf70a0ec0d7191"));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, TEST_PLAINTEXT);
+
+        let aes = Aes256::new(hex!(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+        ));
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("8ea2b7ca516745bfeafc49904b496089"));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, TEST_PLAINTEXT);
+    }
+
+    ///
+    /// Several blocks at once give the same result as one at a time.
+    ///
+    /// Taken from the SP 800-38A ECB-AES128 example.
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/38/a/final) (Appendix F.1.1)
+    ///
+    #[test]
+    fn sp_800_38a_test() {
+        let aes = Aes128::new(hex!("2b7e151628aed2a6abf7158809cf4f3c"));
+        let plaintext = [
+            hex!("6bc1bee22e409f96e93d7e117393172a"),
+            hex!("ae2d8a571e03ac9c9eb76fac45af8e51"),
+            hex!("30c81c46a35ce411e5fbc1191a0a52ef"),
+            hex!("f69f2445df4f9b17ad2b417be66c3710"),
+        ];
+        let ciphertext = [
+            hex!("3ad77bb40d7a3660a89ecaf32466ef97"),
+            hex!("f5d3d58503b9699de785895a96fdbaaf"),
+            hex!("43b1cd7f598ece23881b00e3ed030688"),
+            hex!("7b0c785e27e8ad3f8223207104725dd4"),
+        ];
+
+        let mut blocks = plaintext;
+        aes.encrypt_blocks(&mut blocks);
+        assert_eq!(blocks, ciphertext);
+        aes.decrypt_blocks(&mut blocks);
+        assert_eq!(blocks, plaintext);
+
+        for (plaintext, ciphertext) in plaintext.iter().zip(&ciphertext) {
+            let mut block = *plaintext;
+            aes.encrypt_block(&mut block);
+            assert_eq!(&block, ciphertext);
+        }
+    }
+
+    ///
+    /// Batches longer than the eight blocks processed together, and with a
+    /// partial last group, match single-block encryption.
+    ///
+    #[test]
+    fn many_blocks_test() {
+        let aes = Aes256::new([7u8; 32]);
+        let mut blocks: Vec<Block> = (0..19u8).map(|i| [i; 16]).collect();
+        let original = blocks.clone();
+
+        aes.encrypt_blocks(&mut blocks);
+        for (block, original) in blocks.iter().zip(&original) {
+            let mut expected = *original;
+            aes.encrypt_block(&mut expected);
+            assert_eq!(block, &expected);
+        }
+        aes.decrypt_blocks(&mut blocks);
+        assert_eq!(blocks, original);
+    }
+
+    ///
+    /// The software fallback, which AES-NI would otherwise always replace
+    /// on x86, gives the same results on the FIPS 197 and SP 800-38A
+    /// vectors, and on batches with a partial group of eight.
+    ///
+    /// Taken from the FIPS 197 and SP 800-38A specifications.
+    /// [Source](https://csrc.nist.gov/pubs/fips/197/final) (Appendix C)
+    ///
+    #[test]
+    fn soft_test() {
+        let mut block = TEST_PLAINTEXT;
+        let aes = Aes128::new_soft(hex!("000102030405060708090a0b0c0d0e0f"));
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("69c4e0d86a7b0430d8cdb78070b4c55a"));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, TEST_PLAINTEXT);
+
+        let aes = Aes192::new_soft(hex!("000102030405060708090a0b0c0d0e0f1011121314151617"));
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("dda97ca4864cdfe06eaf70a0ec0d7191"));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, TEST_PLAINTEXT);
+
+        let aes = Aes256::new_soft(hex!(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+        ));
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, hex!("8ea2b7ca516745bfeafc49904b496089"));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, TEST_PLAINTEXT);
+
+        let aes = Aes128::new_soft(hex!("2b7e151628aed2a6abf7158809cf4f3c"));
+        let mut blocks = [
+            hex!("6bc1bee22e409f96e93d7e117393172a"),
+            hex!("ae2d8a571e03ac9c9eb76fac45af8e51"),
+            hex!("30c81c46a35ce411e5fbc1191a0a52ef"),
+            hex!("f69f2445df4f9b17ad2b417be66c3710"),
+        ];
+        aes.encrypt_blocks(&mut blocks);
+        assert_eq!(
+            blocks,
+            [
+                hex!("3ad77bb40d7a3660a89ecaf32466ef97"),
+                hex!("f5d3d58503b9699de785895a96fdbaaf"),
+                hex!("43b1cd7f598ece23881b00e3ed030688"),
+                hex!("7b0c785e27e8ad3f8223207104725dd4"),
+            ]
+        );
+
+        let mut soft: Vec<Block> = (0..19u8).map(|i| [i; 16]).collect();
+        let mut detected = soft.clone();
+        Aes256::new_soft([7u8; 32]).encrypt_blocks(&mut soft);
+        Aes256::new([7u8; 32]).encrypt_blocks(&mut detected);
+        assert_eq!(soft, detected);
+        Aes256::new_soft([7u8; 32]).decrypt_blocks(&mut soft);
+        assert_eq!(soft, (0..19u8).map(|i| [i; 16]).collect::<Vec<Block>>());
+    }
+}
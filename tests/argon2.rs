@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod argon2_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::argon2::{
+        argon2, argon2_keyed, hash_password, verify_password, verify_password_bounded, Argon2Error,
+        Params, Variant,
+    };
+
+    ///
+    /// All three variants with a secret and associated data, four lanes and
+    /// three passes.
+    ///
+    /// Taken from RFC 9106.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc9106#section-5)
+    ///
+    #[test]
+    fn rfc_9106_test() {
+        let cases = [
+            (
+                Variant::Argon2d,
+                hex!("512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb"),
+            ),
+            (
+                Variant::Argon2i,
+                hex!("c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8"),
+            ),
+            (
+                Variant::Argon2id,
+                hex!("0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"),
+            ),
+        ];
+
+        let params = Params::new(32, 3, 4).unwrap();
+        for (variant, expected) in cases {
+            let mut output = [0u8; 32];
+            argon2_keyed(
+                variant,
+                &params,
+                &[0x01; 32],
+                &[0x02; 16],
+                &[0x03; 8],
+                &[0x04; 12],
+                &mut output,
+            )
+            .unwrap();
+            assert_eq!(output, expected, "{:?}", variant);
+        }
+    }
+
+    ///
+    /// Outputs shorter and longer than one BLAKE2b hash, with memory that
+    /// is not a multiple of four blocks per lane.
+    ///
+    /// Generated with Python's cryptography package.
+    ///
+    #[test]
+    fn output_length_test() {
+        let mut output = [0u8; 24];
+        let params = Params::new(16, 1, 2).unwrap();
+        argon2(
+            Variant::Argon2i,
+            &params,
+            b"hunter2",
+            b"saltsalt",
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            hex!("5651c477423188b1a5cf093714d51585a47f41e1661e8dc9")
+        );
+
+        let mut output = [0u8; 100];
+        let params = Params::new(8, 1, 1).unwrap();
+        argon2(
+            Variant::Argon2id,
+            &params,
+            b"password",
+            b"somesaltsomesalt",
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "58ae2d3d361048b0b9d268a2fdeb3e48167f23c1e022e681b65f9022c76a1647"
+                "eb8e675e7b1fbb55213b53d31a597714aaf6168afdc376448f16a7e116ab8e92"
+                "20792995ff5a409bfc6ae751abc9ca2817cfa99a84c2cf3637246269e79ccc4f"
+                "d9001782"
+            )
+        );
+    }
+
+    ///
+    /// PHC strings round-trip, match another implementation's encoding, and
+    /// reject wrong passwords and malformed strings.
+    ///
+    /// Generated with Python's cryptography package.
+    ///
+    #[test]
+    fn phc_string_test() {
+        let encoded = "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqvgR3wcPX7SleI4c9NtXk6E";
+        let params = Params::new(64, 2, 1).unwrap();
+        assert_eq!(
+            hash_password(Variant::Argon2id, &params, b"password", b"somesaltsomesalt").unwrap(),
+            encoded
+        );
+        assert_eq!(verify_password(encoded, b"password"), Ok(true));
+        assert_eq!(verify_password(encoded, b"passwort"), Ok(false));
+
+        let params = Params::new(16, 1, 2).unwrap();
+        let encoded = hash_password(Variant::Argon2d, &params, b"hunter2", b"saltsalt").unwrap();
+        assert!(encoded.starts_with("$argon2d$v=19$m=16,t=1,p=2$c2FsdHNhbHQ$"));
+        assert_eq!(verify_password(&encoded, b"hunter2"), Ok(true));
+
+        for malformed in [
+            "",
+            "$argon2x$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg",
+            "$argon2id$v=16$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg",
+            "$argon2id$v=19$t=2,m=64,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg",
+            "$argon2id$v=19$m=064,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg",
+            "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA=$Gpj7qOY5RCXJvcMzqcdQqg",
+            "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdB$Gpj7qOY5RCXJvcMzqcdQqg",
+            "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg$",
+        ] {
+            assert_eq!(
+                verify_password(malformed, b"password"),
+                Err(Argon2Error::InvalidEncoding),
+                "{}",
+                malformed
+            );
+        }
+        assert_eq!(
+            verify_password(
+                "$argon2id$v=19$m=64,t=0,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg",
+                b"password"
+            ),
+            Err(Argon2Error::InvalidIterations)
+        );
+    }
+
+    ///
+    /// Bounded verification rejects strings that ask for more memory or
+    /// passes than the caller allows, before allocating anything.
+    ///
+    #[test]
+    fn bounded_verify_test() {
+        let encoded = "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqvgR3wcPX7SleI4c9NtXk6E";
+        assert_eq!(
+            verify_password_bounded(encoded, b"password", 64, 2),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_password_bounded(encoded, b"passwort", 64, 2),
+            Ok(false)
+        );
+        assert_eq!(
+            verify_password_bounded(encoded, b"password", 63, 2),
+            Err(Argon2Error::InvalidMemory)
+        );
+        assert_eq!(
+            verify_password_bounded(encoded, b"password", 64, 1),
+            Err(Argon2Error::InvalidIterations)
+        );
+
+        let huge =
+            "$argon2id$v=19$m=4294967295,t=1,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqg";
+        assert_eq!(
+            verify_password_bounded(huge, b"password", 1 << 20, 10),
+            Err(Argon2Error::InvalidMemory)
+        );
+    }
+
+    ///
+    /// Parameters and lengths outside what Argon2 allows are rejected.
+    ///
+    #[test]
+    fn parameters_test() {
+        assert_eq!(Params::new(64, 1, 0), Err(Argon2Error::InvalidLanes));
+        assert_eq!(Params::new(64, 1, 1 << 24), Err(Argon2Error::InvalidLanes));
+        assert_eq!(Params::new(64, 0, 1), Err(Argon2Error::InvalidIterations));
+        assert_eq!(Params::new(31, 1, 4), Err(Argon2Error::InvalidMemory));
+
+        let params = Params::new(8, 1, 1).unwrap();
+        assert_eq!(
+            argon2(
+                Variant::Argon2id,
+                &params,
+                b"password",
+                b"salt",
+                &mut [0u8; 32]
+            ),
+            Err(Argon2Error::InvalidSaltLength)
+        );
+        assert_eq!(
+            argon2(
+                Variant::Argon2id,
+                &params,
+                b"password",
+                b"saltsalt",
+                &mut [0u8; 3]
+            ),
+            Err(Argon2Error::InvalidOutputLength)
+        );
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod ascon_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::aead::{AeadError, Tag};
+    use armadillo::ascon::{ascon_hash, Ascon128, AsconHash};
+
+    const TEST_KEY: [u8; 16] = hex!("000102030405060708090a0b0c0d0e0f");
+    const TEST_NONCE: [u8; 16] = hex!("000102030405060708090a0b0c0d0e0f");
+
+    ///
+    /// Known-answer tests with and without associated data and plaintext.
+    ///
+    /// Taken from the LWC_AEAD_KAT_128_128 file of the Ascon v1.2 reference
+    /// implementation, counts 1, 2 and 34.
+    /// [Source](https://github.com/ascon/ascon-c)
+    ///
+    #[test]
+    fn aead_known_answer_test() {
+        let ascon = Ascon128::new(TEST_KEY);
+
+        let tag = ascon.seal_in_place_detached(TEST_NONCE, &[], &mut []);
+        assert_eq!(tag, hex!("e355159f292911f794cb1432a0103a8a"));
+
+        let tag = ascon.seal_in_place_detached(TEST_NONCE, &[0x00], &mut []);
+        assert_eq!(tag, hex!("944df887cd4901614c5dedbc42fc0da0"));
+
+        let sealed = ascon.seal(TEST_NONCE, &[], &[0x00]);
+        assert_eq!(sealed, hex!("bc18c3f4e39eca7222490d967c79bffc92"));
+        assert_eq!(ascon.open(TEST_NONCE, &[], &sealed).unwrap(), [0x00]);
+    }
+
+    ///
+    /// Every combination of partial and full blocks opens again, and
+    /// tampering with any byte is caught and leaves a zeroed buffer.
+    ///
+    #[test]
+    fn aead_round_trip_test() {
+        let ascon = Ascon128::new(TEST_KEY);
+        let data: Vec<u8> = (0..40).collect();
+
+        for plaintext_length in 0..data.len() {
+            for aad_length in 0..20 {
+                let plaintext = &data[..plaintext_length];
+                let aad = &data[..aad_length];
+                let sealed = ascon.seal(TEST_NONCE, aad, plaintext);
+                assert_eq!(ascon.open(TEST_NONCE, aad, &sealed).unwrap(), plaintext);
+            }
+        }
+
+        let sealed = ascon.seal(TEST_NONCE, b"header", &data);
+        for i in 0..sealed.len() {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 0x01;
+            assert_eq!(
+                ascon.open(TEST_NONCE, b"header", &tampered),
+                Err(AeadError::InvalidTag)
+            );
+        }
+
+        let mut buffer = sealed[..data.len()].to_vec();
+        let tag = Tag::try_from(&sealed[data.len()..]).unwrap();
+        assert_eq!(
+            ascon.open_in_place_detached(TEST_NONCE, b"footer", &mut buffer, &tag),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(buffer, [0u8; 40]);
+    }
+
+    ///
+    /// Known-answer tests for the empty message and a single zero byte.
+    ///
+    /// Taken from the LWC_HASH_KAT_256 file of the Ascon v1.2 reference
+    /// implementation, counts 1 and 2.
+    /// [Source](https://github.com/ascon/ascon-c)
+    ///
+    #[test]
+    fn hash_known_answer_test() {
+        assert_eq!(
+            ascon_hash(&[]),
+            hex!("7346bc14f036e87ae03d0997913088f5f68411434b3cf8b54fa796a80d251f91")
+        );
+        assert_eq!(
+            ascon_hash(&[0x00]),
+            hex!("8dd446ada58a7740ecf56eb638ef775f7d5c0fd5f0c2bbbdfdec29609d3c43a2")
+        );
+    }
+
+    ///
+    /// Splitting the input across `update` calls does not change the hash.
+    ///
+    #[test]
+    fn hash_streaming_test() {
+        let data: Vec<u8> = (0..100).collect();
+        let expected = ascon_hash(&data);
+
+        for chunk_length in [1, 3, 7, 8, 9, 64] {
+            let mut hash = AsconHash::new();
+            for chunk in data.chunks(chunk_length) {
+                hash.update(chunk);
+            }
+            assert_eq!(hash.finalize(), expected, "chunk length {}", chunk_length);
+        }
+    }
+
+    ///
+    /// Through the RustCrypto traits Ascon-128 gives the same output as its
+    /// own methods.
+    ///
+    #[test]
+    #[cfg(feature = "rustcrypto-compat")]
+    fn rustcrypto_traits_test() {
+        use aead::{Aead, KeyInit, Payload};
+
+        let ascon = <Ascon128 as KeyInit>::new(&TEST_KEY.into());
+        let payload = Payload {
+            msg: b"message",
+            aad: b"header",
+        };
+        let sealed = Aead::encrypt(&ascon, &TEST_NONCE.into(), payload).unwrap();
+        assert_eq!(sealed, ascon.seal(TEST_NONCE, b"header", b"message"));
+
+        let payload = Payload {
+            msg: &sealed,
+            aad: b"header",
+        };
+        let opened = Aead::decrypt(&ascon, &TEST_NONCE.into(), payload).unwrap();
+        assert_eq!(opened, b"message");
+    }
+}
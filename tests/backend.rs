@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod backend_tests {
+    extern crate armadillo;
+
+    use armadillo::backend::{backend, has_aes_ni, has_avx2, has_clmul, has_sha_ni, Backend};
+
+    ///
+    /// Detection runs once, so every call must report the same backend.  SSE2
+    /// and NEON are part of the x86_64 and aarch64 baselines.
+    ///
+    #[test]
+    fn detection_test() {
+        let detected = backend();
+        assert_eq!(backend(), detected);
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        assert_ne!(detected, Backend::Neon);
+        #[cfg(target_arch = "x86_64")]
+        assert!(detected >= Backend::Sse2);
+        #[cfg(target_arch = "aarch64")]
+        assert_eq!(detected, Backend::Neon);
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        assert_eq!(detected, Backend::Scalar);
+    }
+
+    ///
+    /// The AVX-512 backend runs the AVX2 kernels, so it must only be picked
+    /// when AVX2 is there too.
+    ///
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn avx512_requires_avx2_test() {
+        if backend() >= Backend::Avx2 {
+            assert!(std::is_x86_feature_detected!("avx2"));
+        }
+        if backend() == Backend::Avx512 {
+            assert!(std::is_x86_feature_detected!("avx512f"));
+        }
+    }
+
+    ///
+    /// The detected backend and the scalar one can always be used, and a
+    /// backend of another architecture never can.
+    ///
+    #[test]
+    fn supported_test() {
+        assert!(backend().is_supported());
+        assert!(Backend::Scalar.is_supported());
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        assert!(!Backend::Neon.is_supported());
+        #[cfg(target_arch = "aarch64")]
+        assert!(!Backend::Sse2.is_supported());
+    }
+
+    ///
+    /// AVX2 detection, which gates the batched Poly1305 code, is cached like
+    /// the backend and agrees with it.
+    ///
+    #[test]
+    fn avx2_detection_test() {
+        let detected = has_avx2();
+        assert_eq!(has_avx2(), detected);
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        assert_eq!(detected, std::is_x86_feature_detected!("avx2"));
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert!(!detected);
+    }
+
+    ///
+    /// AES-NI detection is cached like the backend, and only ever reported
+    /// on x86.
+    ///
+    #[test]
+    fn aes_ni_detection_test() {
+        let detected = has_aes_ni();
+        assert_eq!(has_aes_ni(), detected);
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert!(!detected);
+    }
+
+    ///
+    /// Carry-less multiplication is detected the same way.
+    ///
+    #[test]
+    fn clmul_detection_test() {
+        let detected = has_clmul();
+        assert_eq!(has_clmul(), detected);
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert!(!detected);
+    }
+
+    ///
+    /// The SHA extensions are detected the same way.
+    ///
+    #[test]
+    fn sha_ni_detection_test() {
+        let detected = has_sha_ni();
+        assert_eq!(has_sha_ni(), detected);
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert!(!detected);
+    }
+}
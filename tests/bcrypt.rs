@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod bcrypt_tests {
+    extern crate armadillo;
+
+    use armadillo::bcrypt::{bcrypt, hash_password, verify_password, BcryptError};
+
+    ///
+    /// Hash strings including the empty password, a password past the
+    /// 72-byte limit, and the `$2a$` and `$2y$` prefixes.
+    ///
+    /// The first two are taken from crypt_blowfish, the rest generated with
+    /// libxcrypt.
+    /// [Source](https://www.openwall.com/crypt/)
+    ///
+    #[test]
+    fn hash_string_test() {
+        let cases: [(&[u8], &str); 5] = [
+            (
+                b"U*U",
+                "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW",
+            ),
+            (
+                b"",
+                "$2b$05$CCCCCCCCCCCCCCCCCCCCC.7uG0VCzI2bS7j6ymqJi9CdcdxiRTWNy",
+            ),
+            (
+                b"password",
+                "$2b$04$abcdefghijklmnopqrstuughE8Ev8uGFaUgY2cNEySvxngrb/Jzdm",
+            ),
+            (
+                b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789chars after 72 are ignored",
+                "$2b$05$abcdefghijklmnopqrstuu5s2v8.iXieOjg/.AySBTTZIIVFJeBui",
+            ),
+            (
+                b"correct horse battery staple",
+                "$2y$10$/OK.fbVrR/bpIqNJ5ianF.Tl7EHamNnyU2AWXYxQNUOU.CibBZ2vW",
+            ),
+        ];
+
+        for (password, encoded) in cases {
+            assert_eq!(verify_password(encoded, password), Ok(true), "{}", encoded);
+            assert_eq!(verify_password(encoded, b"wrong"), Ok(false), "{}", encoded);
+        }
+    }
+
+    ///
+    /// Hashing writes a `$2b$` string that verifies, and matches the one
+    /// libxcrypt writes for the same salt.
+    ///
+    #[test]
+    fn hash_password_test() {
+        // "abcdefghijklmnopqrstuu" in bcrypt's base64
+        let salt = [
+            0x71, 0xd7, 0x9f, 0x82, 0x18, 0xa3, 0x92, 0x59, 0xa7, 0xa2, 0x9a, 0xab, 0xb2, 0xdb,
+            0xaf, 0xc3,
+        ];
+        let encoded = hash_password(b"password", &salt, 4).unwrap();
+        assert_eq!(
+            encoded,
+            "$2b$04$abcdefghijklmnopqrstuughE8Ev8uGFaUgY2cNEySvxngrb/Jzdm"
+        );
+        assert_eq!(verify_password(&encoded, b"password"), Ok(true));
+        assert_eq!(bcrypt(b"password", &salt, 3), Err(BcryptError::InvalidCost));
+        assert_eq!(
+            bcrypt(b"password", &salt, 32),
+            Err(BcryptError::InvalidCost)
+        );
+    }
+
+    ///
+    /// Malformed strings and unsupported versions are rejected.
+    ///
+    #[test]
+    fn malformed_test() {
+        for malformed in [
+            "",
+            "$2x$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW",
+            "$2a$5$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeWW",
+            "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOe",
+            "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOe+",
+        ] {
+            assert_eq!(
+                verify_password(malformed, b"U*U"),
+                Err(BcryptError::InvalidEncoding),
+                "{}",
+                malformed
+            );
+        }
+        assert_eq!(
+            verify_password(
+                "$2a$03$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW",
+                b"U*U"
+            ),
+            Err(BcryptError::InvalidCost)
+        );
+    }
+}
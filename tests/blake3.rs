@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod blake3_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::blake3::{blake3, blake3_derive_key, blake3_keyed, Blake3};
+
+    const TEST_KEY: [u8; 32] = *b"whats the Elvish word for friend";
+    const TEST_CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+    /// The input of the official test vectors: bytes counting up modulo 251.
+    fn test_input(length: usize) -> Vec<u8> {
+        (0..length).map(|i| (i % 251) as u8).collect()
+    }
+
+    ///
+    /// The hash, keyed hash and derived key of inputs around the block,
+    /// chunk and tree boundaries.
+    ///
+    /// Taken from the official BLAKE3 test vectors.
+    /// [Source](https://github.com/BLAKE3-team/BLAKE3/blob/master/test_vectors/test_vectors.json)
+    ///
+    #[test]
+    fn test_vectors_test() {
+        let vectors = [
+            (
+                0,
+                hex!("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"),
+                hex!("92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26"),
+                hex!("2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d"),
+            ),
+            (
+                1,
+                hex!("2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213"),
+                hex!("6d7878dfff2f485635d39013278ae14f1454b8c0a3a2d34bc1ab38228a80c95b"),
+                hex!("b3e2e340a117a499c6cf2398a19ee0d29cca2bb7404c73063382693bf66cb06c"),
+            ),
+            (
+                1023,
+                hex!("10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11"),
+                hex!("c951ecdf03288d0fcc96ee3413563d8a6d3589547f2c2fb36d9786470f1b9d6e"),
+                hex!("74a16c1c3d44368a86e1ca6df64be6a2f64cce8f09220787450722d85725dea5"),
+            ),
+            (
+                1024,
+                hex!("42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7"),
+                hex!("75c46f6f3d9eb4f55ecaaee480db732e6c2105546f1e675003687c31719c7ba4"),
+                hex!("7356cd7720d5b66b6d0697eb3177d9f8d73a4a5c5e968896eb6a689684302706"),
+            ),
+            (
+                1025,
+                hex!("d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444"),
+                hex!("357dc55de0c7e382c900fd6e320acc04146be01db6a8ce7210b7189bd664ea69"),
+                hex!("effaa245f065fbf82ac186839a249707c3bddf6d3fdda22d1b95a3c970379bcb"),
+            ),
+            (
+                3073,
+                hex!("7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3"),
+                hex!("68dede9bef00ba89e43f31a6825f4cf433389fedae75c04ee9f0cf16a427c95a"),
+                hex!("72613c9ec9ff7e40f8f5c173784c532ad852e827dba2bf85b2ab4b76f7079081"),
+            ),
+            (
+                8193,
+                hex!("bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b"),
+                hex!("954a2a75420c8d6547e3ba5b98d963e6fa6491addc8c023189cc519821b4a1f5"),
+                hex!("af1e0346e389b17c23200270a64aa4e1ead98c61695d917de7d5b00491c9b0f1"),
+            ),
+            (
+                31744,
+                hex!("62b6960e1a44bcc1eb1a611a8d6235b6b4b78f32e7abc4fb4c6cdcce94895c47"),
+                hex!("efa53b389ab67c593dba624d898d0f7353ab99e4ac9d42302ee64cbf9939a419"),
+                hex!("39772aef80e0ebe60596361e45b061e8f417429d529171b6764468c22928e28e"),
+            ),
+        ];
+
+        for (length, hash, keyed_hash, derived_key) in vectors {
+            let input = test_input(length);
+            assert_eq!(blake3(&input), hash);
+            assert_eq!(blake3_keyed(TEST_KEY, &input), keyed_hash);
+            assert_eq!(blake3_derive_key(TEST_CONTEXT, &input), derived_key);
+        }
+    }
+
+    ///
+    /// Extended output, read in uneven pieces across several root blocks.
+    ///
+    /// Taken from the official BLAKE3 test vectors, input length 102400.
+    /// [Source](https://github.com/BLAKE3-team/BLAKE3/blob/master/test_vectors/test_vectors.json)
+    ///
+    #[test]
+    fn extended_output_test() {
+        let mut hash = Blake3::new();
+        hash.update(&test_input(102400));
+        let mut reader = hash.finalize_xof();
+        let mut output = [0u8; 131];
+        for chunk in output.chunks_mut(30) {
+            reader.read(chunk);
+        }
+        assert_eq!(
+            output,
+            hex!(
+                "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085"
+                "e01c59dab908c04c3342b816941a26d69c2605ebee5ec5291cc55e15b76146e6"
+                "745f0601156c3596cb75065a9c57f35585a52e1ac70f69131c23d611ce11ee4a"
+                "b1ec2c009012d236648e77be9295dd0426f29b764d65de58eb7d01dd42248204"
+                "f45f8e"
+            )
+        );
+    }
+
+    ///
+    /// Splitting the input anywhere gives the same hash, whether the pieces
+    /// fall inside a chunk, on a chunk boundary or across whole subtrees.
+    ///
+    #[test]
+    fn streaming_test() {
+        let input = test_input(9 * 1024 + 100);
+
+        for length in [0, 64, 1024, 2048, 4096, 5000, input.len()] {
+            let message = &input[..length];
+            let expected = blake3(message);
+            for split in [0, 1.min(length), 1024.min(length), length / 3, length] {
+                let mut hash = Blake3::default();
+                hash.update(&message[..split]);
+                hash.update(&message[split..]);
+                assert_eq!(hash.finalize(), expected);
+            }
+        }
+
+        let mut hash = Blake3::new_keyed(TEST_KEY);
+        for chunk in input.chunks(333) {
+            hash.update(chunk);
+        }
+        assert_eq!(hash.finalize(), blake3_keyed(TEST_KEY, &input));
+    }
+
+    ///
+    /// Hashing on the thread pool gives exactly the sequential hash, also
+    /// after input that leaves a partial chunk or an odd number of chunks.
+    ///
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_update_test() {
+        let input = test_input((1 << 20) | 12345);
+        let expected = hex!("7a7e1c6a800e0cfbd45304d16a3544d5d55e2a723a11fc021bf9fb45ee8c1472");
+
+        let mut hash = Blake3::new();
+        hash.par_update(&input);
+        assert_eq!(hash.finalize(), expected);
+
+        for prefix in [1, 1024, 3 * 1024, 70_000] {
+            let mut hash = Blake3::new();
+            hash.update(&input[..prefix]);
+            hash.par_update(&input[prefix..]);
+            assert_eq!(hash.finalize(), expected);
+        }
+
+        let mut hash = Blake3::new_derive_key(TEST_CONTEXT);
+        hash.par_update(&input);
+        assert_eq!(hash.finalize(), blake3_derive_key(TEST_CONTEXT, &input));
+    }
+}
@@ -4,7 +4,14 @@ mod chacha_tests {
 
     use hex_literal::hex;
 
-    use armadillo::chacha::{ChaCha20, ChaCha20Block};
+    use armadillo::backend::Backend;
+    use armadillo::chacha::{
+        hchacha20, ChaCha, ChaCha12, ChaCha20, ChaCha20Block, ChaCha20Legacy, ChaCha20Reader,
+        ChaCha20Writer, ChaCha8, ChaCha8Block, ChaChaBlock, ChaChaError, Key, Nonce, XChaCha12,
+        XChaCha20,
+    };
+    use std::io::{Read, Write};
+    use zeroize::Zeroize;
 
     const TEST_KEY: [u8; 32] =
         hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
@@ -81,4 +88,942 @@ mod chacha_tests {
         let ciphertext = cipher.encrypt("Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_bytes());
         assert_eq!(ciphertext, expected);
     }
+
+    ///
+    /// Simple test to verify that a single block encrypts in place.
+    ///
+    /// Taken from the RFC7539 specification, the first block of the
+    /// sunscreen plaintext.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc7539#section-2.4.2)
+    ///
+    #[test]
+    fn simple_encrypt_block_test() {
+        let nonce = hex!("000000000000004a00000000");
+        let mut block = ChaCha20Block::new(TEST_KEY, nonce, 1);
+        let mut data = *b"Ladies and Gentlemen of the class of '99: If I could offer you o";
+
+        let expected = hex!("6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0bf91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8");
+        block.encrypt_block(&mut data);
+        assert_eq!(data, expected);
+    }
+
+    ///
+    /// Every length from 0 to 130 bytes must encrypt without panicking, keep
+    /// its length, and produce a prefix of the same keystream.  This covers
+    /// empty input, partial blocks, and inputs of exactly one or two blocks.
+    ///
+    #[test]
+    fn boundary_lengths_encrypt_test() {
+        let expected = hex!("10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e0a88837739d7bf4ef8ccacb0ea2bb9d69d56c394aa351dfda5bf459f0a2e9fe8e721f89255f9c486bf21679c683d4f9c5cf2fa27865526005b06ca374c86af3bdcbf");
+
+        for len in 0..=130 {
+            let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            let ciphertext = cipher.encrypt(&vec![0u8; len]);
+            assert_eq!(ciphertext, expected[..len], "length {}", len);
+        }
+    }
+
+    ///
+    /// Encrypting a message in chunks must give the same result as
+    /// encrypting it at once, whatever the chunk boundaries are.
+    ///
+    #[test]
+    fn chunked_encrypt_test() {
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut chunked = cipher.encrypt(b"ab");
+        chunked.extend(cipher.encrypt(b"cd"));
+        assert_eq!(
+            chunked,
+            ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(b"abcd")
+        );
+
+        let plaintext: Vec<u8> = (0..=255).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&plaintext);
+        for chunk_size in [1, 3, 16, 63, 64, 65, 100] {
+            let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            let chunked: Vec<u8> = plaintext
+                .chunks(chunk_size)
+                .flat_map(|chunk| cipher.encrypt(chunk))
+                .collect();
+            assert_eq!(chunked, expected, "chunk size {}", chunk_size);
+        }
+    }
+
+    ///
+    /// In-place encryption must match `encrypt` and share its keystream
+    /// position, so the two can be mixed on one cipher.
+    ///
+    #[test]
+    fn apply_keystream_test() {
+        let plaintext: Vec<u8> = (0..200).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&plaintext);
+
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut buffer = plaintext.clone();
+        cipher.apply_keystream(&mut buffer[..70]);
+        buffer[70..130].copy_from_slice(&cipher.encrypt(&plaintext[70..130]));
+        cipher.apply_keystream(&mut buffer[130..]);
+        assert_eq!(buffer, expected);
+
+        // Applying the keystream twice restores the plaintext
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.apply_keystream(&mut buffer);
+        assert_eq!(buffer, plaintext);
+    }
+
+    ///
+    /// Seeking to any byte offset must decrypt the same bytes as processing
+    /// the stream from the start.
+    ///
+    #[test]
+    fn seek_test() {
+        let plaintext: Vec<u8> = (0..=255).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&plaintext);
+
+        for offset in [0, 1, 63, 64, 65, 128, 200, 255] {
+            let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            cipher.seek(offset as u64);
+            assert_eq!(cipher.position(), offset as u64);
+            assert_eq!(cipher.encrypt(&plaintext[offset..]), expected[offset..]);
+            assert_eq!(cipher.position(), 256);
+        }
+
+        // Seeking backwards works as well
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.encrypt(&plaintext);
+        cipher.seek(10);
+        assert_eq!(cipher.encrypt(&plaintext[10..20]), expected[10..20]);
+    }
+
+    ///
+    /// Seeking to a block uses the absolute block counter, so block 2 starts
+    /// at byte 64 of a stream that starts at counter 1.
+    ///
+    #[test]
+    fn seek_to_block_test() {
+        let plaintext = [0u8; 128];
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&plaintext);
+
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.seek_to_block(2);
+        assert_eq!(cipher.position(), 64);
+        assert_eq!(cipher.encrypt(&plaintext[64..]), expected[64..]);
+    }
+
+    ///
+    /// Seeking or forking to a block before the initial counter would leave
+    /// `position` undefined, so it panics.
+    ///
+    #[test]
+    #[should_panic(expected = "block counter before the start of the stream")]
+    fn seek_before_start_test() {
+        let mut cipher = ChaCha20::new_with_counter(TEST_KEY, TEST_NONCE, 5);
+        cipher.seek_to_block(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "block counter before the start of the stream")]
+    fn fork_before_start_test() {
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let _ = cipher.fork_at(0);
+    }
+
+    ///
+    /// Encrypting a range at an offset matches the same range of the whole
+    /// stream, for offsets inside and across blocks, and leaves the
+    /// cipher's own position alone.
+    ///
+    #[test]
+    fn encrypt_at_test() {
+        let data: Vec<u8> = (0..2000).map(|i| (i * 7 + 3) as u8).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&data);
+
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.encrypt(&data[..10]);
+        for (start, end) in [(0, 0), (0, 2000), (5, 6), (63, 65), (100, 612), (777, 1999)] {
+            assert_eq!(
+                cipher.encrypt_at(start as u64, &data[start..end]),
+                expected[start..end],
+                "range {}..{}",
+                start,
+                end
+            );
+        }
+        assert_eq!(cipher.position(), 10);
+        assert_eq!(cipher.encrypt(&data[10..20]), expected[10..20]);
+
+        let end = ((1u64 << 32) - 1) * 64;
+        let mut block = [0u8; 64];
+        assert!(cipher.try_apply_keystream_at(end - 64, &mut block).is_ok());
+        assert_eq!(
+            cipher.try_apply_keystream_at(end - 63, &mut block),
+            Err(ChaChaError::CounterOverflow)
+        );
+        assert_eq!(
+            cipher.try_apply_keystream_at(u64::MAX, &mut block),
+            Err(ChaChaError::CounterOverflow)
+        );
+    }
+
+    ///
+    /// `fill` and the keystream iterator produce the same bytes as
+    /// encrypting zeros, share one position with `apply_keystream`, and the
+    /// iterator ends with the block counter.
+    ///
+    #[test]
+    fn keystream_fill_and_iterator_test() {
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&[0u8; 1500]);
+
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut filled = [0xaau8; 100];
+        cipher.fill(&mut filled);
+        let iterated: Vec<u8> = cipher.keystream().take(700).collect();
+        let mut applied = [0u8; 700];
+        cipher.apply_keystream(&mut applied);
+
+        assert_eq!(filled, expected[..100]);
+        assert_eq!(iterated, expected[100..800]);
+        assert_eq!(applied, expected[800..]);
+        assert_eq!(cipher.position(), 1500);
+
+        let mut last = ChaCha20::new_with_counter(TEST_KEY, TEST_NONCE, u32::MAX);
+        assert_eq!(last.keystream().size_hint(), (64, Some(64)));
+        assert_eq!(last.keystream().count(), 64);
+        assert_eq!(last.keystream().next(), None);
+        assert_eq!(
+            last.try_fill(&mut [0u8; 1]),
+            Err(ChaChaError::CounterOverflow)
+        );
+    }
+
+    ///
+    /// Keys and nonces only build from slices of the right length, never
+    /// show their bytes in `Debug`, and can be wiped.
+    ///
+    #[test]
+    fn key_and_nonce_test() {
+        assert_eq!(Key::try_from(&TEST_KEY[..]).unwrap(), Key::from(TEST_KEY));
+        assert_eq!(
+            Key::try_from(&TEST_KEY[..31]),
+            Err(ChaChaError::InvalidLength)
+        );
+        assert_eq!(
+            Nonce::try_from(&TEST_KEY[..12]).unwrap().as_bytes(),
+            &TEST_KEY[..12]
+        );
+        assert_eq!(
+            Nonce::try_from(&TEST_KEY[..13]),
+            Err(ChaChaError::InvalidLength)
+        );
+
+        let key = Key::from(TEST_KEY);
+        assert_eq!(format!("{:?}", key), "Key([REDACTED])");
+        assert_eq!(
+            format!("{:?}", Nonce::from(TEST_NONCE)),
+            "Nonce([REDACTED])"
+        );
+        assert_ne!(key, Key::from([0u8; 32]));
+
+        let mut expected = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut cipher = ChaCha20::new(key.clone(), Nonce::from(TEST_NONCE));
+        assert_eq!(cipher.encrypt(&[0u8; 100]), expected.encrypt(&[0u8; 100]));
+
+        let mut key = key;
+        key.zeroize();
+        assert_eq!(key.as_bytes(), &[0u8; 32]);
+    }
+
+    ///
+    /// Generated keys and nonces come from the operating system, so two of
+    /// them are (overwhelmingly likely) different and not all zero.
+    ///
+    #[test]
+    #[cfg(feature = "os_rng")]
+    fn generate_key_and_nonce_test() {
+        let (first, second) = (Key::generate(), Key::generate());
+        assert_ne!(first, second);
+        assert_ne!(first.as_bytes(), &[0u8; 32]);
+        assert_ne!(Nonce::generate(), Nonce::generate());
+    }
+
+    ///
+    /// Through the RustCrypto traits the stream starts at block 0, so seeking
+    /// one block ahead reproduces the RFC7539 example.  Positions and errors
+    /// follow the trait conventions.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc7539#section-2.4.2)
+    ///
+    #[test]
+    #[cfg(feature = "rustcrypto-compat")]
+    fn rustcrypto_traits_test() {
+        use cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+
+        let nonce = hex!("000000000000004a00000000");
+        let plaintext = "Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_bytes();
+        let expected = ChaCha20::new(TEST_KEY, nonce).encrypt(plaintext);
+
+        let mut cipher = <ChaCha20 as KeyIvInit>::new(&TEST_KEY.into(), &nonce.into());
+        StreamCipherSeek::seek(&mut cipher, 64u32);
+
+        let mut buffer = plaintext.to_vec();
+        StreamCipher::apply_keystream(&mut cipher, &mut buffer[..10]);
+        assert_eq!(cipher.current_pos::<u64>(), 74);
+        let mut output = vec![0u8; buffer.len() - 10];
+        cipher
+            .apply_keystream_b2b(&buffer[10..], &mut output)
+            .unwrap();
+        buffer[10..].copy_from_slice(&output);
+        assert_eq!(buffer, expected);
+
+        let end = (1u64 << 32) * 64;
+        assert!(cipher.try_seek(end).is_ok());
+        assert!(cipher.try_seek(end + 1).is_err());
+        cipher.seek_to_block(u32::MAX);
+        assert!(StreamCipher::try_apply_keystream(&mut cipher, &mut [0u8; 65]).is_err());
+        assert!(cipher.try_current_pos::<u32>().is_err());
+    }
+
+    ///
+    /// A cipher created at another initial counter starts its keystream, and
+    /// counts its position, from that block.
+    ///
+    #[test]
+    fn new_with_counter_test() {
+        let mut cipher = ChaCha20::new_with_counter(TEST_KEY, TEST_NONCE, 0);
+        let mut block = ChaCha20Block::new(TEST_KEY, TEST_NONCE, 0);
+        assert_eq!(cipher.encrypt(&[0u8; 64]), block.get_keystream());
+        assert_eq!(cipher.position(), 64);
+
+        let mut expected = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        expected.seek_to_block(1000);
+        let mut cipher = ChaCha20::new_with_counter(TEST_KEY, TEST_NONCE, 1000);
+        assert_eq!(cipher.encrypt(&[0u8; 100]), expected.encrypt(&[0u8; 100]));
+
+        cipher.seek(0);
+        assert_eq!(cipher.position(), 0);
+        assert_eq!(
+            cipher.encrypt(&[0u8; 10]),
+            expected.fork_at(1000).encrypt(&[0u8; 10])
+        );
+    }
+
+    ///
+    /// A clone continues from the same position, and forks at separate
+    /// counters encrypt their parts of the stream independently, also from
+    /// other threads.
+    ///
+    #[test]
+    fn clone_and_fork_test() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ChaCha20>();
+        assert_send_sync::<XChaCha20>();
+        assert_send_sync::<ChaCha20Legacy>();
+
+        let data: Vec<u8> = (0..4096).map(|i| (i * 7 + 3) as u8).collect();
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.encrypt(&data[..100]);
+        let mut clone = cipher.clone();
+        assert_eq!(clone.encrypt(&data), cipher.encrypt(&data));
+
+        let base = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let expected = base.clone().encrypt(&data);
+        let parts: Vec<Vec<u8>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = data
+                .chunks(1024)
+                .enumerate()
+                .map(|(i, chunk)| {
+                    let mut fork = base.fork_at(1 + 16 * i as u32);
+                    scope.spawn(move || fork.encrypt(chunk))
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+        assert_eq!(parts.concat(), expected);
+        assert_eq!(base.position(), 0);
+    }
+
+    ///
+    /// The last block of the 32-bit counter can be used, but nothing past it.
+    /// A failed call must leave the keystream position untouched.
+    ///
+    #[test]
+    fn counter_overflow_test() {
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.seek_to_block(u32::MAX);
+
+        assert_eq!(
+            cipher.try_encrypt(&[0u8; 65]),
+            Err(ChaChaError::CounterOverflow)
+        );
+        assert_eq!(cipher.try_encrypt(&[0u8; 60]).unwrap().len(), 60);
+        assert_eq!(cipher.try_encrypt(&[0u8; 4]).unwrap().len(), 4);
+        assert_eq!(cipher.try_encrypt(&[]).unwrap(), [0u8; 0]);
+        assert_eq!(
+            cipher.try_encrypt(&[0u8; 1]),
+            Err(ChaChaError::CounterOverflow)
+        );
+    }
+
+    ///
+    /// The unchecked variant panics instead of wrapping the counter around.
+    ///
+    #[test]
+    #[should_panic(expected = "block counter overflow")]
+    fn counter_overflow_panic_test() {
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.seek_to_block(u32::MAX);
+        cipher.encrypt(&[0u8; 65]);
+    }
+
+    ///
+    /// Parallel encryption must give the same output and leave the cipher at
+    /// the same position as sequential encryption, also when it starts in
+    /// the middle of a block and spans several thread chunks.
+    ///
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_encrypt_test() {
+        let data: Vec<u8> = (0..200_000).map(|i| (i * 7 + 3) as u8).collect();
+        for (start, len) in [
+            (0, 0),
+            (0, 100),
+            (0, 65536),
+            (10, 65536 + 100),
+            (70, 200_000 - 70),
+        ] {
+            let mut sequential = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            let mut parallel = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            let prefix = &data[..start];
+            sequential.encrypt(prefix);
+            parallel.encrypt(prefix);
+
+            let chunk = &data[start..start + len];
+            assert_eq!(parallel.par_encrypt(chunk), sequential.encrypt(chunk));
+            assert_eq!(parallel.position(), sequential.position());
+            assert_eq!(
+                parallel.encrypt(&data[..100]),
+                sequential.encrypt(&data[..100])
+            );
+        }
+
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.seek_to_block(u32::MAX);
+        assert_eq!(
+            cipher.try_par_apply_keystream(&mut [0u8; 65]),
+            Err(ChaChaError::CounterOverflow)
+        );
+    }
+
+    ///
+    /// A cipher recreated from an exported state must continue the keystream
+    /// exactly where the original was, also in the middle of a block.
+    ///
+    #[test]
+    fn export_import_state_test() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7 + 3) as u8).collect();
+        for split in [0, 1, 64, 100, 513, 1000] {
+            let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            let expected = cipher.encrypt(&data);
+
+            let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            let mut ciphertext = cipher.encrypt(&data[..split]);
+            let state = cipher.export_state();
+            assert_eq!(state.position, split as u64);
+
+            let mut resumed = ChaCha20::import_state(state).unwrap();
+            ciphertext.extend(resumed.encrypt(&data[split..]));
+            assert_eq!(ciphertext, expected, "split {}", split);
+        }
+    }
+
+    ///
+    /// States of another variant, or past the end of the counter, are
+    /// rejected.
+    ///
+    #[test]
+    fn import_invalid_state_test() {
+        let state = ChaCha20::new(TEST_KEY, TEST_NONCE).export_state();
+        assert_eq!(
+            ChaCha8::import_state(state.clone()).err(),
+            Some(ChaChaError::InvalidState)
+        );
+
+        let mut end = state.clone();
+        end.position = ((1u64 << 32) - 1) * 64;
+        assert!(ChaCha20::import_state(end.clone()).is_ok());
+        end.position += 1;
+        assert_eq!(
+            ChaCha20::import_state(end).err(),
+            Some(ChaChaError::InvalidState)
+        );
+    }
+
+    ///
+    /// With the `serde` feature, the exported state survives a round trip
+    /// through JSON.
+    ///
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_state_test() {
+        let mut cipher = ChaCha12::new(TEST_KEY, TEST_NONCE);
+        cipher.encrypt(&[0u8; 77]);
+
+        let json = serde_json::to_string(&cipher.export_state()).unwrap();
+        let state = serde_json::from_str(&json).unwrap();
+        let mut resumed = ChaCha12::import_state(state).unwrap();
+        assert_eq!(resumed.encrypt(&[0u8; 50]), cipher.encrypt(&[0u8; 50]));
+    }
+
+    ///
+    /// Data read through a `ChaCha20Reader` comes out encrypted exactly like
+    /// `encrypt` would, whatever the read sizes.
+    ///
+    #[test]
+    fn reader_test() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7 + 3) as u8).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&data);
+
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut reader = ChaCha20Reader::new(&data[..], cipher);
+        let mut output = vec![0u8; 10];
+        reader.read_exact(&mut output).unwrap();
+        reader.read_to_end(&mut output).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    ///
+    /// Checks the keystream of every backend this CPU supports against the
+    /// single block function, for a length that takes several multi-block
+    /// batches and ends on a partial one.
+    ///
+    fn check_backends<const ROUNDS: usize>() {
+        let mut expected = Vec::new();
+        for counter in 1..=40 {
+            let mut block = ChaChaBlock::<ROUNDS>::new(TEST_KEY, TEST_NONCE, counter);
+            expected.extend_from_slice(&block.get_keystream());
+        }
+        expected.truncate(40 * 64 - 13);
+
+        for backend in [
+            Backend::Scalar,
+            Backend::Sse2,
+            Backend::Avx2,
+            Backend::Avx512,
+            Backend::Neon,
+        ] {
+            if !backend.is_supported() {
+                continue;
+            }
+            let mut cipher = ChaCha::<ROUNDS>::new_with_backend(TEST_KEY, TEST_NONCE, backend);
+            let mut keystream = vec![0u8; expected.len()];
+            cipher.fill(&mut keystream);
+            assert_eq!(keystream, expected, "{} with {} rounds", backend, ROUNDS);
+
+            let mut keystream = vec![0u8; expected.len() - 100];
+            cipher.apply_keystream_at(100, &mut keystream);
+            assert_eq!(keystream, expected[100..], "{} at an offset", backend);
+        }
+    }
+
+    ///
+    /// Every multi-block kernel, not only the one the CPU running the tests
+    /// would pick, produces the same keystream as the block function.
+    ///
+    #[test]
+    fn backends_test() {
+        assert!(Backend::Scalar.is_supported());
+        check_backends::<20>();
+        check_backends::<12>();
+        check_backends::<8>();
+    }
+
+    ///
+    /// A writer that takes at most a few bytes per call, to exercise the
+    /// pending data of `ChaCha20Writer`.
+    ///
+    struct TrickleWriter(Vec<u8>);
+
+    impl Write for TrickleWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let length = buf.len().min(7);
+            self.0.extend_from_slice(&buf[..length]);
+            Ok(length)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    ///
+    /// Data written through a `ChaCha20Writer` reaches the inner writer
+    /// encrypted exactly like `encrypt` would, also when the inner writer
+    /// only accepts part of it at a time.
+    ///
+    #[test]
+    fn writer_test() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7 + 3) as u8).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&data);
+
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut writer = ChaCha20Writer::new(TrickleWriter(Vec::new()), cipher);
+        for chunk in data.chunks(100) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.into_inner().unwrap().0, expected);
+
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut output = Vec::new();
+        let mut writer = ChaCha20Writer::new(&mut output, cipher);
+        writer.write_all(&data).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        assert_eq!(output, expected);
+    }
+
+    ///
+    /// A writer that follows a script of results, one per call, and then
+    /// takes everything: a count takes that many bytes, an error kind fails.
+    ///
+    struct ScriptedWriter {
+        data: Vec<u8>,
+        script: std::collections::VecDeque<Result<usize, std::io::ErrorKind>>,
+    }
+
+    impl Write for ScriptedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let length = match self.script.pop_front() {
+                Some(Ok(length)) => length.min(buf.len()),
+                Some(Err(kind)) => return Err(kind.into()),
+                None => buf.len(),
+            };
+            self.data.extend_from_slice(&buf[..length]);
+            Ok(length)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    ///
+    /// Partial writes followed by `WouldBlock` leave the rest pending without
+    /// repeating what was written, and other errors of the inner writer
+    /// consume nothing, so retrying the same data stays in sync.
+    ///
+    #[test]
+    fn writer_errors_test() {
+        use std::io::ErrorKind;
+
+        let data: Vec<u8> = (0..40).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&data);
+
+        let inner = ScriptedWriter {
+            data: Vec::new(),
+            script: [
+                Ok(3),
+                Ok(2),
+                Err(ErrorKind::WouldBlock),
+                Err(ErrorKind::Other),
+            ]
+            .into(),
+        };
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut writer = ChaCha20Writer::new(inner, cipher);
+
+        assert_eq!(writer.write(&data[..27]).unwrap(), 27);
+        assert_eq!(writer.flush().unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(writer.get_ref().data, expected[..5]);
+        assert_eq!(writer.flush().unwrap_err().kind(), ErrorKind::Other);
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref().data, expected[..27]);
+
+        writer.get_mut().script = [Err(ErrorKind::Other), Err(ErrorKind::WouldBlock)].into();
+        assert_eq!(
+            writer.write(&data[27..]).unwrap_err().kind(),
+            ErrorKind::Other
+        );
+        assert_eq!(writer.write(&data[27..]).unwrap(), 13);
+        assert_eq!(writer.into_inner().unwrap().data, expected);
+    }
+
+    ///
+    /// Runs a future to completion by polling it in a loop, which is all the
+    /// async adapter tests need from an executor.
+    ///
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake, Waker};
+
+        struct NoopWake;
+        impl Wake for NoopWake {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    ///
+    /// An async writer that alternates between not being ready and taking at
+    /// most a few bytes, to exercise back-pressure in `ChaCha20AsyncWriter`.
+    ///
+    #[cfg(feature = "async")]
+    struct TrickleAsyncWriter {
+        data: Vec<u8>,
+        ready: bool,
+    }
+
+    #[cfg(feature = "async")]
+    impl tokio::io::AsyncWrite for TrickleAsyncWriter {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return std::task::Poll::Pending;
+            }
+            let length = buf.len().min(7);
+            self.data.extend_from_slice(&buf[..length]);
+            std::task::Poll::Ready(Ok(length))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    ///
+    /// The async adapters must encrypt exactly like `encrypt`, whatever the
+    /// read sizes, and also when the inner writer pushes back.
+    ///
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_reader_writer_test() {
+        use armadillo::chacha::{ChaCha20AsyncReader, ChaCha20AsyncWriter};
+        use std::future::poll_fn;
+        use std::pin::Pin;
+        use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7 + 3) as u8).collect();
+        let expected = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&data);
+
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let mut reader = ChaCha20AsyncReader::new(&data[..], cipher);
+        let mut output = Vec::new();
+        loop {
+            let mut chunk = [0u8; 77];
+            let mut buf = ReadBuf::new(&mut chunk);
+            block_on(poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))).unwrap();
+            if buf.filled().is_empty() {
+                break;
+            }
+            output.extend_from_slice(buf.filled());
+        }
+        assert_eq!(output, expected);
+
+        let cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        let inner = TrickleAsyncWriter {
+            data: Vec::new(),
+            ready: false,
+        };
+        let mut writer = ChaCha20AsyncWriter::new(inner, cipher);
+        for mut chunk in data.chunks(100) {
+            while !chunk.is_empty() {
+                let written =
+                    block_on(poll_fn(|cx| Pin::new(&mut writer).poll_write(cx, chunk))).unwrap();
+                chunk = &chunk[written..];
+            }
+        }
+        block_on(poll_fn(|cx| Pin::new(&mut writer).poll_shutdown(cx))).unwrap();
+        assert_eq!(writer.into_inner().data, expected);
+    }
+
+    ///
+    /// Reduced-round keystreams for an all-zero key and nonce.
+    ///
+    /// Taken from the ChaCha test vectors draft, TC1 with a 256-bit key.
+    /// [Source](https://datatracker.ietf.org/doc/html/draft-strombergson-chacha-test-vectors-01)
+    ///
+    #[test]
+    fn reduced_round_keystream_test() {
+        let mut block = ChaCha8Block::new([0u8; 32], [0u8; 12], 0);
+        let expected = hex!("3e00ef2f895f40d67f5bb8e81f09a5a12c840ec3ce9a7f3b181be188ef711a1e984ce172b9216f419f445367456d5619314a42a3da86b001387bfdb80e0cfe42");
+        assert_eq!(block.get_keystream(), expected);
+
+        let mut cipher = ChaCha12::new_with_counter([0u8; 32], [0u8; 12], 0);
+        let expected = hex!("9bf49a6a0755f953811fce125f2683d50429c3bb49e074147e0089a52eae155f0564f879d27ae3c02ce82834acfa8c793a629f2ca0de6919610be82f411326be");
+        assert_eq!(cipher.encrypt(&[0u8; 64]), expected);
+    }
+
+    ///
+    /// Reduced-round ciphers spanning several blocks, compared against an
+    /// independent reference implementation.
+    ///
+    #[test]
+    fn reduced_round_encrypt_test() {
+        let expected = hex!("eead9dfbbc60443e9d6811bab8e60a3ac6001e0dfb985f65efcb0ea42454411c64747ef73d4766e0c20e19208e5cb11777d487263152e65dc5ff947fcab23b2b44c429b0a1af2f89d77f155f2bd60db1b04f98160aa828a2aed9c4624db388bf1429b11c64489f3e3a8720bd577592511a1bed4f9b196e58cfcbc1899fe5c9292027");
+        assert_eq!(
+            ChaCha8::new(TEST_KEY, TEST_NONCE).encrypt(&[0u8; 130]),
+            expected
+        );
+
+        let expected = hex!("7f8b136677c73799e3e7777d16e6d8ccc787ce39694990c628e087029ce9190bda4be31ac3fe2102a9ad737cf82fa3b06e68b63371c65c827299040ade1ba8a0d690077cf8c919770d2b5f339e61deac67358d5688eb1f89e28d6aa0c3c6b07a3ed2d67dfc9bcf34bdc37161abd3c343caa9572fea65aa1bf8ed4c2ab38dae2fd9c4");
+        assert_eq!(
+            ChaCha12::new(TEST_KEY, TEST_NONCE).encrypt(&[0u8; 130]),
+            expected
+        );
+    }
+
+    ///
+    /// The original 64-bit nonce layout, compared against libsodium's
+    /// crypto_stream_chacha20_xor_ic.
+    ///
+    #[test]
+    fn simple_legacy_encrypt_test() {
+        let nonce = hex!("0000004a00000000");
+        let mut cipher = ChaCha20Legacy::new(TEST_KEY, nonce);
+
+        let expected = hex!("af051e40bba0354981329a806a140eafd258a22a6dcb4bb9f6569cb3efe2deaf837bd87ca20b5ba12081a306af0eb35c41a239d20dfc74c81771560d9c9c1e4b224f51f3401bd9e12fde276fb8631ded8c131f823d2c06e27e4fcaec9ef3cf788a3b0aa372600a92b57974cded2b9334794cba40c63e34cdea212c4cf07d41b769a6");
+        assert_eq!(cipher.encrypt(&[0u8; 130]), expected);
+    }
+
+    ///
+    /// The 64-bit counter must carry into its high word instead of wrapping.
+    ///
+    #[test]
+    fn legacy_counter_carry_test() {
+        let nonce = hex!("0000004a00000000");
+        let mut cipher = ChaCha20Legacy::new(TEST_KEY, nonce);
+        cipher.seek_to_block(0xffffffff);
+
+        let expected = hex!("6d29da5bd16a472910e8c0bdb47edfc8499c3222cc168d3721747fc2b21266d9f15c8339f10f354d16cc9b8e118eb182bf858ce5718fa4e76389ea4eb50a9475ebc17a3b93d30a5802739e841950e3bfddb3f6f44eda6d6082d558fc6cb863a0d58325d200a316e2c0620d2321c9ee4ff1b236c7de304fa135a1f1fe195136e1d2b6");
+        assert_eq!(cipher.encrypt(&[0u8; 130]), expected);
+    }
+
+    ///
+    /// Long messages go through the multi-block (and, where available,
+    /// vectorized) keystream path.  It must produce exactly the blocks the
+    /// scalar block function does, for every round count.
+    ///
+    #[test]
+    fn multi_block_keystream_test() {
+        fn scalar_keystream(blocks: u32) -> Vec<u8> {
+            (1..=blocks)
+                .flat_map(|counter| {
+                    ChaCha20Block::new(TEST_KEY, TEST_NONCE, counter).get_keystream()
+                })
+                .collect()
+        }
+
+        let expected = scalar_keystream(32);
+        for len in [256, 300, 512, 513, 1000, 2048] {
+            let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+            assert_eq!(
+                cipher.encrypt(&vec![0u8; len]),
+                expected[..len],
+                "length {}",
+                len
+            );
+        }
+
+        let expected: Vec<u8> = (0..16)
+            .flat_map(|counter| ChaCha8Block::new(TEST_KEY, TEST_NONCE, counter).get_keystream())
+            .collect();
+        let mut cipher = ChaCha8::new_with_counter(TEST_KEY, TEST_NONCE, 0);
+        assert_eq!(cipher.encrypt(&[0u8; 1024]), expected);
+    }
+
+    ///
+    /// A multi-block request close to the end of the counter must stop at the
+    /// last block rather than wrap around.
+    ///
+    #[test]
+    fn multi_block_counter_limit_test() {
+        let expected: Vec<u8> = (u32::MAX - 5..=u32::MAX)
+            .flat_map(|counter| ChaCha20Block::new(TEST_KEY, TEST_NONCE, counter).get_keystream())
+            .collect();
+
+        let mut cipher = ChaCha20::new(TEST_KEY, TEST_NONCE);
+        cipher.seek_to_block(u32::MAX - 5);
+        assert_eq!(cipher.try_encrypt(&[0u8; 6 * 64]).unwrap(), expected);
+        assert_eq!(
+            cipher.try_encrypt(&[0u8; 1]),
+            Err(ChaChaError::CounterOverflow)
+        );
+    }
+
+    ///
+    /// Simple test to verify that the HChaCha20 subkey derivation is working
+    /// correctly.
+    ///
+    /// Taken from the XChaCha draft specification.
+    /// [Source](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.2.1)
+    ///
+    #[test]
+    fn simple_hchacha20_test() {
+        let nonce = hex!("000000090000004a0000000031415927");
+
+        let expected = hex!("82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc");
+        assert_eq!(hchacha20(TEST_KEY, nonce), expected);
+    }
+
+    ///
+    /// Simple test to verify that XChaCha20 encryption is working correctly.
+    ///
+    /// Plaintext and key taken from the XChaCha draft, ciphertext compared
+    /// against libsodium's crypto_stream_xchacha20_xor_ic with counter 1.
+    /// [Source](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#appendix-A.3.2)
+    ///
+    #[test]
+    fn simple_xchacha20_encrypt_test() {
+        let key = hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let nonce = hex!("404142434445464748494a4b4c4d4e4f5051525354555657");
+        let mut cipher = XChaCha20::new(key, nonce);
+
+        let expected = hex!("a56416d43f989b369e325fa384c1f33a4729110b6164be88a5d6902e6b7564bb205d7c0e444df4135ea48f690fe07e2ea9748a55df52c504c787b720140af01d258f85a842a9d901e51676fec88ea8d02f77be63cc6f48966db9733572c9f6a462c2770990302aa7618b03a257d7c31fe173a349d32f1d20bafa9ff44e2b58f6286a5b45100fb9b6e95df060203ba2c232f5a2947545ad6f9dfcc3af1cb3f1b8c509a605205c998b2fcb1f8808d8f44060197e537581e429b70cbff7e79b10c671cac3b8dcb164d5669fc29269c38fd6c3e57cd2c29deba0abf9c3ed2dad9e28de01a2b596b88cd0033fd142eca425842ef0bc32580d4d14f193fe220e7e622ceb622b6a5cf8f5b5c90103e118436ed2940b543e0ea9ffd5bf4cca63aace9dac5d587628b7f97d16ac290d6ff580ca0b");
+        let ciphertext = cipher.encrypt(b"The dhole (pronounced \"dole\") is also known as the Asiatic wild dog, red dog, and whistling dog. It is about the size of a German shepherd but looks more like a long-legged fox. This highly elusive and skilled jumper is classified with wolves, coyotes, jackals, and foxes in the taxonomic family Canidae.");
+        assert_eq!(ciphertext, expected);
+    }
+
+    ///
+    /// A keystream started at block 0 is one block ahead of the one `new`
+    /// starts at block 1, with any number of rounds.
+    ///
+    #[test]
+    fn xchacha_counter_test() {
+        let key = hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let nonce = hex!("404142434445464748494a4b4c4d4e4f5051525354555657");
+
+        let mut from_zero = [0u8; 192];
+        XChaCha20::new_with_counter(key, nonce, 0).apply_keystream(&mut from_zero);
+        let mut from_one = [0u8; 128];
+        XChaCha20::new(key, nonce).apply_keystream(&mut from_one);
+        assert_eq!(from_zero[64..], from_one);
+
+        let mut from_zero = [0u8; 192];
+        XChaCha12::new_with_counter(key, nonce, 0).apply_keystream(&mut from_zero);
+        let mut from_two = [0u8; 128];
+        XChaCha12::new_with_counter(key, nonce, 2).apply_keystream(&mut from_two);
+        assert_eq!(from_zero[128..], from_two[..64]);
+    }
 }
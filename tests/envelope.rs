@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod envelope_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::envelope::{DataKey, Envelope, EnvelopeError, KeyWrapper, LocalKeyWrapper};
+
+    const TEST_KEK: [u8; 32] =
+        hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+    const TEST_DATA_KEY: [u8; 32] =
+        hex!("c0c1c2c3c4c5c6c7c8c9cacbcccdcecfd0d1d2d3d4d5d6d7d8d9dadbdcdddedf");
+
+    ///
+    /// A stand-in for a remote key management service, which keeps its
+    /// keys to itself and refuses some requests.
+    ///
+    struct MockKms {
+        key_id: &'static str,
+        online: bool,
+    }
+
+    impl KeyWrapper for MockKms {
+        fn key_id(&self) -> &str {
+            self.key_id
+        }
+
+        fn wrap(&self, data_key: &DataKey) -> Result<Vec<u8>, EnvelopeError> {
+            if !self.online {
+                return Err(EnvelopeError::WrapFailed);
+            }
+            let mut wrapped = b"kms:".to_vec();
+            wrapped.extend(data_key.as_bytes().iter().map(|byte| byte ^ 0x5a));
+            Ok(wrapped)
+        }
+
+        fn unwrap(&self, wrapped_key: &[u8]) -> Result<DataKey, EnvelopeError> {
+            let bytes = wrapped_key
+                .strip_prefix(b"kms:")
+                .filter(|_| self.online)
+                .ok_or(EnvelopeError::UnwrapFailed)?;
+            let mut key = [0u8; 32];
+            key.iter_mut().zip(bytes).for_each(|(x, y)| *x = y ^ 0x5a);
+            Ok(DataKey::from(key))
+        }
+    }
+
+    ///
+    /// An envelope survives serialization, and opens with the wrapper that
+    /// sealed it.
+    ///
+    #[test]
+    fn round_trip_test() {
+        let wrapper = LocalKeyWrapper::new("local-2024", TEST_KEK);
+        let envelope = Envelope::seal_with_data_key(
+            &wrapper,
+            DataKey::from(TEST_DATA_KEY),
+            b"invoice 42",
+            b"attack at dawn",
+        )
+        .unwrap();
+        assert_eq!(envelope.key_id(), "local-2024");
+        assert_eq!(envelope.wrapped_key().len(), 48);
+
+        let bytes = envelope.to_bytes();
+        assert_eq!(bytes[..12], *b"\x01\x0alocal-2024");
+        assert_eq!(bytes[12..14], [0, 48]);
+        assert_eq!(bytes.len(), 14 + 48 + 14 + 16);
+
+        let parsed = Envelope::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, envelope);
+        assert_eq!(
+            parsed.open(&wrapper, b"invoice 42").unwrap(),
+            b"attack at dawn"
+        );
+
+        assert_eq!(
+            wrapper.unwrap(envelope.wrapped_key()).unwrap(),
+            DataKey::from(TEST_DATA_KEY)
+        );
+    }
+
+    ///
+    /// Key wrappers are pluggable, and their failures are passed on.
+    ///
+    #[test]
+    fn custom_wrapper_test() {
+        let kms = MockKms {
+            key_id: "arn:kms:eu-west-1:key/7",
+            online: true,
+        };
+        let envelope =
+            Envelope::seal_with_data_key(&kms, DataKey::from(TEST_DATA_KEY), b"", b"payload")
+                .unwrap();
+        assert!(envelope.wrapped_key().starts_with(b"kms:"));
+        let bytes = envelope.to_bytes();
+        let wrapper: &dyn KeyWrapper = &kms;
+        assert_eq!(
+            Envelope::from_bytes(&bytes)
+                .unwrap()
+                .open(wrapper, b"")
+                .unwrap(),
+            b"payload"
+        );
+
+        let offline = MockKms {
+            key_id: "arn:kms:eu-west-1:key/7",
+            online: false,
+        };
+        assert_eq!(
+            Envelope::seal_with_data_key(&offline, DataKey::from(TEST_DATA_KEY), b"", b""),
+            Err(EnvelopeError::WrapFailed)
+        );
+        assert_eq!(
+            envelope.open(&offline, b""),
+            Err(EnvelopeError::UnwrapFailed)
+        );
+    }
+
+    ///
+    /// Changed envelopes, associated data and keys are all rejected.
+    ///
+    #[test]
+    fn rejection_test() {
+        let wrapper = LocalKeyWrapper::new("local-2024", TEST_KEK);
+        let envelope = Envelope::seal_with_data_key(
+            &wrapper,
+            DataKey::from(TEST_DATA_KEY),
+            b"invoice 42",
+            b"attack at dawn",
+        )
+        .unwrap();
+        let bytes = envelope.to_bytes();
+
+        assert_eq!(
+            envelope.open(&wrapper, b"invoice 43"),
+            Err(EnvelopeError::InvalidTag)
+        );
+        assert_eq!(
+            envelope.open(&LocalKeyWrapper::new("local-2025", TEST_KEK), b"invoice 42"),
+            Err(EnvelopeError::KeyIdMismatch)
+        );
+        assert_eq!(
+            envelope.open(&LocalKeyWrapper::new("local-2024", [0; 32]), b"invoice 42"),
+            Err(EnvelopeError::UnwrapFailed)
+        );
+
+        for i in 0..bytes.len() {
+            let mut tampered = bytes.clone();
+            tampered[i] ^= 0x01;
+            let result = Envelope::from_bytes(&tampered)
+                .and_then(|envelope| envelope.open(&wrapper, b"invoice 42"));
+            assert!(result.is_err(), "byte {}", i);
+        }
+
+        for length in [0, 1, 12, 13, 14 + 48 + 15] {
+            assert_eq!(
+                Envelope::from_bytes(&bytes[..length]),
+                Err(EnvelopeError::InvalidEncoding),
+                "{}",
+                length
+            );
+        }
+        let mut invalid_utf8 = bytes.clone();
+        invalid_utf8[2] = 0xff;
+        assert_eq!(
+            Envelope::from_bytes(&invalid_utf8),
+            Err(EnvelopeError::InvalidEncoding)
+        );
+
+        let long_id = LocalKeyWrapper::new("k".repeat(256), TEST_KEK);
+        assert_eq!(
+            Envelope::seal_with_data_key(&long_id, DataKey::from(TEST_DATA_KEY), b"", b""),
+            Err(EnvelopeError::InvalidEncoding)
+        );
+    }
+
+    ///
+    /// Every envelope gets a fresh data key.
+    ///
+    #[test]
+    #[cfg(feature = "os_rng")]
+    fn generated_data_key_test() {
+        let wrapper = LocalKeyWrapper::new("local-2024", TEST_KEK);
+        let first = Envelope::seal(&wrapper, b"", b"same payload").unwrap();
+        let second = Envelope::seal(&wrapper, b"", b"same payload").unwrap();
+        assert_ne!(first.wrapped_key(), second.wrapped_key());
+        assert_ne!(first.to_bytes(), second.to_bytes());
+        assert_eq!(second.open(&wrapper, b"").unwrap(), b"same payload");
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod ghash_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::ghash::GHash;
+
+    const TEST_H: [u8; 16] = hex!("66e94bd4ef8a2c3b884cfa59ca342b2e");
+    const TEST_CIPHERTEXT: [u8; 16] = hex!("0388dace60b6a392f328c2b971b2fe78");
+
+    ///
+    /// GHASH of one ciphertext block and the length block of AES-GCM test
+    /// case 2.
+    ///
+    /// Taken from the GCM specification.
+    /// [Source](https://csrc.nist.rip/groups/ST/toolkit/BCM/documents/proposedmodes/gcm/gcm-spec.pdf) (Appendix B)
+    ///
+    #[test]
+    fn simple_hash_test() {
+        let mut hash = GHash::new(TEST_H);
+        hash.update(&TEST_CIPHERTEXT);
+        hash.update(&hex!("00000000000000000000000000000080"));
+        assert_eq!(hash.finalize(), hex!("f38cbb1ad69223dcc3457ae5b6b0f885"));
+    }
+
+    ///
+    /// Splitting the message anywhere gives the same hash, and a partial
+    /// last block hashes like its zero-padded form.
+    ///
+    #[test]
+    fn streaming_test() {
+        let message: Vec<u8> = (0..100u8).collect();
+        let mut padded = message.clone();
+        padded.resize(112, 0);
+
+        let mut hash = GHash::new(TEST_H);
+        hash.update(&padded);
+        let expected = hash.finalize();
+
+        for split in [0, 1, 15, 16, 17, 50, 99, 100] {
+            let mut hash = GHash::new(TEST_H);
+            hash.update(&message[..split]);
+            hash.update(&message[split..]);
+            assert_eq!(hash.finalize(), expected);
+        }
+
+        let mut hash = GHash::new(TEST_H);
+        hash.update_padded(&message[..20]);
+        hash.update(&[0u8; 12]);
+        let mut separate = GHash::new(TEST_H);
+        separate.update(&message[..20]);
+        separate.update(&[0u8; 24]);
+        assert_eq!(hash.finalize(), separate.finalize());
+    }
+
+    ///
+    /// The software fallback, which PCLMULQDQ would otherwise always replace
+    /// on x86, gives the same hashes for AES-GCM test cases 2 and 3, and
+    /// agrees with the detected backend on a longer message.
+    ///
+    /// Taken from the GCM specification.
+    /// [Source](https://csrc.nist.rip/groups/ST/toolkit/BCM/documents/proposedmodes/gcm/gcm-spec.pdf) (Appendix B)
+    ///
+    #[test]
+    fn soft_test() {
+        let mut hash = GHash::new_soft(TEST_H);
+        hash.update(&TEST_CIPHERTEXT);
+        hash.update(&hex!("00000000000000000000000000000080"));
+        assert_eq!(hash.finalize(), hex!("f38cbb1ad69223dcc3457ae5b6b0f885"));
+
+        let mut hash = GHash::new_soft(hex!("b83b533708bf535d0aa6e52980d53b78"));
+        hash.update(&hex!("42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985"));
+        hash.update(&hex!("00000000000000000000000000000200"));
+        assert_eq!(hash.finalize(), hex!("7f1b32b81b820d02614f8895ac1d4eac"));
+
+        let message: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let mut soft = GHash::new_soft(TEST_H);
+        soft.update(&message);
+        let mut detected = GHash::new(TEST_H);
+        detected.update(&message);
+        assert_eq!(soft.finalize(), detected.finalize());
+    }
+}
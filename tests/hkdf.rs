@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod hkdf_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::hkdf::{derive, expand, expand_multi, extract, HkdfError};
+    use armadillo::sha2::{Sha256, Sha512};
+
+    ///
+    /// Basic, long and empty-salt cases with SHA-256.
+    ///
+    /// Taken from RFC 5869, test cases 1, 2 and 3.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc5869#appendix-A)
+    ///
+    #[test]
+    fn rfc_5869_test() {
+        let ikm = [0x0b; 22];
+        let prk = extract::<Sha256>(&hex!("000102030405060708090a0b0c"), &ikm);
+        assert_eq!(
+            prk,
+            hex!("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+        );
+        let mut okm = [0u8; 42];
+        expand::<Sha256>(&prk, &hex!("f0f1f2f3f4f5f6f7f8f9"), &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!(
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+                "34007208d5b887185865"
+            )
+        );
+
+        let ikm: Vec<u8> = (0x00..0x50).collect();
+        let salt: Vec<u8> = (0x60..0xb0).collect();
+        let info: Vec<u8> = (0xb0..=0xff).collect();
+        let mut okm = [0u8; 82];
+        derive::<Sha256>(&salt, &ikm, &info, &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!(
+                "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c"
+                "59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71"
+                "cc30c58179ec3e87c14c01d5c1f3434f1d87"
+            )
+        );
+
+        let mut okm = [0u8; 42];
+        derive::<Sha256>(&[], &[0x0b; 22], &[], &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!(
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d"
+                "9d201395faa4b61a96c8"
+            )
+        );
+    }
+
+    ///
+    /// HKDF over SHA-512, and several subkeys at once matching separate
+    /// expansions.
+    ///
+    #[test]
+    fn multi_output_test() {
+        let mut okm = [0u8; 100];
+        derive::<Sha512>(b"salt", b"secret", b"label", &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!(
+                "688c699eedc450ad2b6aae154ff8f298e6f93c23e76fa3240b41d5f2cf98519a"
+                "e6d18e78fceb0d28201ceeddaf81513429cd98af493539cd3100403368c41210"
+                "214e1627cc716e324e165128f070c96cc74d17a3f108a289a119994706431b65"
+                "f7518f8a"
+            )
+        );
+
+        let prk = extract::<Sha256>(b"salt", b"secret");
+        let (mut key, mut iv) = ([0u8; 32], [0u8; 12]);
+        expand_multi::<Sha256>(&prk, &mut [(b"key", &mut key), (b"iv", &mut iv)]).unwrap();
+        assert_eq!(
+            key,
+            hex!("13265376717c9955f26f9efd034872593e64f37826b9691b343b3f0134d40acc")
+        );
+        assert_eq!(iv, hex!("c53c997e7ed10d08e92e6569"));
+    }
+
+    ///
+    /// The output is limited to 255 hash outputs and the key must be at
+    /// least one, and nothing is written when either is violated.
+    ///
+    #[test]
+    fn invalid_length_test() {
+        let prk = extract::<Sha256>(b"salt", b"secret");
+        let mut okm = vec![0u8; 255 * 32];
+        assert_eq!(expand::<Sha256>(&prk, b"max", &mut okm), Ok(()));
+
+        let mut okm = vec![0u8; 255 * 32 + 1];
+        assert_eq!(
+            expand::<Sha256>(&prk, b"max", &mut okm),
+            Err(HkdfError::InvalidOutputLength)
+        );
+        assert_eq!(
+            expand::<Sha256>(&prk[..31], b"max", &mut [0u8; 32]),
+            Err(HkdfError::InvalidPrkLength)
+        );
+
+        let (mut key, mut too_long) = ([0u8; 32], vec![0u8; 255 * 32 + 1]);
+        assert_eq!(
+            expand_multi::<Sha256>(&prk, &mut [(b"key", &mut key), (b"long", &mut too_long)]),
+            Err(HkdfError::InvalidOutputLength)
+        );
+        assert_eq!(key, [0u8; 32]);
+    }
+}
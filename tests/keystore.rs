@@ -0,0 +1,233 @@
+#[cfg(all(test, feature = "std", feature = "os_rng"))]
+mod keystore_tests {
+    extern crate armadillo;
+
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use armadillo::argon2::Params;
+    use armadillo::keystore::{KeyStore, KeyStoreError, MAX_ITERATIONS};
+
+    const TEST_KEY: [u8; 16] = *b"0123456789abcdef";
+
+    ///
+    /// Parameters cheap enough for tests; real keystores want far more.
+    ///
+    fn test_params() -> Params {
+        Params::new(8, 1, 1).unwrap()
+    }
+
+    ///
+    /// A fresh directory for one test's files.
+    ///
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "armadillo-keystore-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    ///
+    /// Keys are added, generated, read back, rotated and removed by name.
+    ///
+    #[test]
+    fn entries_test() {
+        let mut store = KeyStore::create(b"hunter2", test_params()).unwrap();
+        store.add("signing", &TEST_KEY, "ed25519 seed").unwrap();
+        store.generate("backup", 32, "").unwrap();
+
+        let names: Vec<_> = store.entries().iter().map(|entry| entry.name()).collect();
+        assert_eq!(names, ["signing", "backup"]);
+        assert_eq!(store.entries()[0].metadata(), "ed25519 seed");
+        assert_eq!(store.get("signing").unwrap().as_bytes(), TEST_KEY);
+        assert_eq!(store.get("backup").unwrap().as_bytes().len(), 32);
+
+        let age = SystemTime::now()
+            .duration_since(store.entries()[0].created())
+            .unwrap();
+        assert!(age < Duration::from_secs(60));
+
+        let rotated = store.rotate("signing").unwrap();
+        assert_eq!(rotated.as_bytes().len(), TEST_KEY.len());
+        assert_ne!(rotated.as_bytes(), TEST_KEY);
+        assert_eq!(store.get("signing").unwrap().as_bytes(), rotated.as_bytes());
+        assert_eq!(store.entries()[0].metadata(), "ed25519 seed");
+
+        store.remove("backup").unwrap();
+        assert_eq!(store.entries().len(), 1);
+        assert_eq!(store.get("backup").err(), Some(KeyStoreError::KeyNotFound));
+    }
+
+    ///
+    /// Names must be unique and lengths fit the file format.
+    ///
+    #[test]
+    fn entry_errors_test() {
+        let mut store = KeyStore::create(b"hunter2", test_params()).unwrap();
+        store.add("signing", &TEST_KEY, "").unwrap();
+
+        assert_eq!(
+            store.add("signing", &TEST_KEY, ""),
+            Err(KeyStoreError::KeyExists)
+        );
+        assert_eq!(store.remove("missing"), Err(KeyStoreError::KeyNotFound));
+        assert_eq!(
+            store.rotate("missing").err(),
+            Some(KeyStoreError::KeyNotFound)
+        );
+
+        assert_eq!(
+            store.add("", &TEST_KEY, ""),
+            Err(KeyStoreError::InvalidLength)
+        );
+        assert_eq!(
+            store.add(&"k".repeat(256), &TEST_KEY, ""),
+            Err(KeyStoreError::InvalidLength)
+        );
+        assert_eq!(
+            store.add("long", &TEST_KEY, &"m".repeat(65536)),
+            Err(KeyStoreError::InvalidLength)
+        );
+        assert_eq!(
+            store.generate("long", 65536, ""),
+            Err(KeyStoreError::InvalidLength)
+        );
+        store.add(&"k".repeat(255), &TEST_KEY, "").unwrap();
+        assert_eq!(store.entries().len(), 2);
+    }
+
+    ///
+    /// A saved keystore loads with its password, and the temporary file is
+    /// gone once it is saved.
+    ///
+    #[test]
+    fn save_load_test() {
+        let directory = test_directory("save-load");
+        let path = directory.join("keys.aks");
+
+        let mut store = KeyStore::create(b"hunter2", test_params()).unwrap();
+        store.add("signing", &TEST_KEY, "ed25519 seed").unwrap();
+        store.save(&path).unwrap();
+        store.generate("backup", 32, "").unwrap();
+        store.save(&path).unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|file| file.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["keys.aks"]);
+
+        let loaded = KeyStore::load(&path, b"hunter2").unwrap();
+        assert_eq!(loaded.entries(), store.entries());
+        assert_eq!(loaded.get("signing").unwrap().as_bytes(), TEST_KEY);
+        assert_eq!(
+            loaded.get("backup").unwrap().as_bytes(),
+            store.get("backup").unwrap().as_bytes()
+        );
+
+        assert_eq!(
+            KeyStore::load(directory.join("missing.aks"), b"hunter2").err(),
+            Some(KeyStoreError::Io(std::io::ErrorKind::NotFound))
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    ///
+    /// A wrong password and any changed byte are rejected, and so are cut
+    /// short or unknown files.
+    ///
+    #[test]
+    fn rejection_test() {
+        let mut store = KeyStore::create(b"hunter2", test_params()).unwrap();
+        store.add("signing", &TEST_KEY, "ed25519 seed").unwrap();
+        let bytes = store.to_bytes();
+
+        assert_eq!(
+            KeyStore::from_bytes(&bytes, b"hunter3").err(),
+            Some(KeyStoreError::InvalidPassword)
+        );
+
+        // The header's parameters are covered too: changing them either
+        // makes them invalid or derives other keys.
+        for i in 0..bytes.len() {
+            let mut tampered = bytes.clone();
+            tampered[i] ^= 0x01;
+            assert!(
+                KeyStore::from_bytes(&tampered, b"hunter2").is_err(),
+                "byte {}",
+                i
+            );
+        }
+
+        for length in [0, 4, 31, 32, bytes.len() - 1] {
+            assert!(KeyStore::from_bytes(&bytes[..length], b"hunter2").is_err());
+        }
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert_eq!(
+            KeyStore::from_bytes(&magic, b"hunter2").err(),
+            Some(KeyStoreError::InvalidEncoding)
+        );
+        assert_eq!(
+            KeyStore::from_bytes(&bytes[..31], b"hunter2").err(),
+            Some(KeyStoreError::InvalidEncoding)
+        );
+    }
+
+    ///
+    /// Changing the password keeps every key and its creation time, and
+    /// the old password stops working.
+    ///
+    #[test]
+    fn change_password_test() {
+        let mut store = KeyStore::create(b"hunter2", test_params()).unwrap();
+        store.add("signing", &TEST_KEY, "ed25519 seed").unwrap();
+        store.generate("backup", 32, "").unwrap();
+        let entries = store.entries().to_vec();
+        let backup = store.get("backup").unwrap().as_bytes().to_vec();
+
+        store
+            .change_password(b"correct horse", Params::new(16, 2, 1).unwrap())
+            .unwrap();
+        let bytes = store.to_bytes();
+        assert_eq!(
+            KeyStore::from_bytes(&bytes, b"hunter2").err(),
+            Some(KeyStoreError::InvalidPassword)
+        );
+
+        let loaded = KeyStore::from_bytes(&bytes, b"correct horse").unwrap();
+        for (old, new) in entries.iter().zip(loaded.entries()) {
+            assert_eq!(old.name(), new.name());
+            assert_eq!(old.created(), new.created());
+            assert_eq!(old.metadata(), new.metadata());
+        }
+        assert_eq!(loaded.get("signing").unwrap().as_bytes(), TEST_KEY);
+        assert_eq!(loaded.get("backup").unwrap().as_bytes(), backup);
+    }
+
+    ///
+    /// Parameters above the limits are refused, so a forged file cannot
+    /// make loading it arbitrarily slow.
+    ///
+    #[test]
+    fn params_limit_test() {
+        let params = Params::new(8, MAX_ITERATIONS + 1, 1).unwrap();
+        assert_eq!(
+            KeyStore::create(b"hunter2", params).err(),
+            Some(KeyStoreError::InvalidParams)
+        );
+
+        let mut bytes = KeyStore::create(b"hunter2", test_params())
+            .unwrap()
+            .to_bytes();
+        bytes[8..12].copy_from_slice(&(MAX_ITERATIONS + 1).to_be_bytes());
+        assert_eq!(
+            KeyStore::from_bytes(&bytes, b"hunter2").err(),
+            Some(KeyStoreError::InvalidParams)
+        );
+    }
+}
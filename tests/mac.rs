@@ -0,0 +1,203 @@
+#[cfg(test)]
+mod mac_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::mac::{hmac, siphash, Hmac, HmacSha256, HmacSha512, SipHash, SipHashBuilder};
+    use armadillo::sha2::{Sha256, Sha512};
+    use armadillo::sha3::{Sha3_256, Sha3_512};
+
+    const LONG_KEY: [u8; 131] = [0xaa; 131];
+
+    ///
+    /// Short, long and hashed keys, and data shorter and longer than a
+    /// block.
+    ///
+    /// Taken from RFC 4231, test cases 1, 2, 3, 4, 6 and 7.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc4231#section-4)
+    ///
+    #[test]
+    fn hmac_sha2_test() {
+        // Key, data, and the HMAC-SHA256 and HMAC-SHA512 tags
+        type Case = (&'static [u8], &'static [u8], [u8; 32], [u8; 64]);
+        let cases: [Case; 6] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                hex!("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"),
+                hex!(
+                    "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde"
+                    "daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+                ),
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                hex!("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"),
+                hex!(
+                    "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554"
+                    "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+                ),
+            ),
+            (
+                &[0xaa; 20],
+                &[0xdd; 50],
+                hex!("773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe"),
+                hex!(
+                    "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39"
+                    "bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb"
+                ),
+            ),
+            (
+                &hex!("0102030405060708090a0b0c0d0e0f10111213141516171819"),
+                &[0xcd; 50],
+                hex!("82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b"),
+                hex!(
+                    "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db"
+                    "a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd"
+                ),
+            ),
+            (
+                &LONG_KEY,
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                hex!("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"),
+                hex!(
+                    "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352"
+                    "6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"
+                ),
+            ),
+            (
+                &LONG_KEY,
+                b"This is a test using a larger than block-size key and a larger than \
+                  block-size data. The key needs to be hashed before being used by the \
+                  HMAC algorithm.",
+                hex!("9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2"),
+                hex!(
+                    "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944"
+                    "b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58"
+                ),
+            ),
+        ];
+
+        for (key, data, sha256_tag, sha512_tag) in cases {
+            assert_eq!(hmac::<Sha256>(key, data), sha256_tag);
+            assert_eq!(hmac::<Sha512>(key, data), sha512_tag);
+
+            let mut mac = HmacSha256::new(key);
+            mac.update(data);
+            assert!(mac.verify(&sha256_tag));
+            let mut mac = HmacSha512::new(key);
+            mac.update(data);
+            assert!(mac.verify(&sha512_tag));
+        }
+    }
+
+    ///
+    /// A tag truncated to 128 bits verifies, and nothing else does.
+    ///
+    /// Taken from RFC 4231, test case 5.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc4231#section-4.6)
+    ///
+    #[test]
+    fn truncated_tag_test() {
+        let mut mac = HmacSha256::new(&[0x0c; 20]);
+        mac.update(b"Test With Truncation");
+        let truncated = hex!("a3b6167473100ee06e0c796c2955552b");
+
+        assert!(mac.clone().verify(&truncated));
+        assert!(!mac.clone().verify(&[]));
+        assert!(!mac.clone().verify(&[0; 33]));
+        let mut wrong = truncated;
+        wrong[15] ^= 1;
+        assert!(!mac.verify(&wrong));
+    }
+
+    ///
+    /// HMAC over SHA-3, whose block length is its rate, and streaming in
+    /// any split, also from a cloned keyed MAC.
+    ///
+    #[test]
+    fn hmac_generic_test() {
+        assert_eq!(
+            hmac::<Sha3_256>(&[0xaa; 200], b"abc"),
+            hex!("961fcf59ed455732e405e74f5dc78beb7aa41ad315af5e2b2a0dcf8cef9887e0")
+        );
+        assert_eq!(
+            hmac::<Sha3_512>(b"key", b"abc"),
+            hex!(
+                "085e4e83503f40b82fef38438bc4905a55dbaa8c8878097a899db0b57ce7da57"
+                "a368251c34474f60b3ebacb39b2edaca4b290456411c76ec7ab61944cfe2288e"
+            )
+        );
+
+        let data: Vec<u8> = (0..=255).collect();
+        let keyed = Hmac::<Sha512>::new(b"key");
+        for split in [0, 1, 127, 128, 129, 256] {
+            let mut mac = keyed.clone();
+            mac.update(&data[..split]);
+            mac.update(&data[split..]);
+            assert_eq!(mac.finalize(), hmac::<Sha512>(b"key", &data));
+        }
+    }
+
+    ///
+    /// SipHash-2-4 under the key 00..0f of the messages 00, 00 01, ...,
+    /// across the lengths where the padding changes.
+    ///
+    /// Taken from the SipHash reference implementation.
+    /// [Source](https://github.com/veorq/SipHash/blob/master/vectors.h)
+    ///
+    #[test]
+    fn siphash_test() {
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let cases = [
+            (0usize, 0x726fdb47dd0e0e31),
+            (1, 0x74f839c593dc67fd),
+            (7, 0xab0200f58b01d137),
+            (8, 0x93f5f5799a932462),
+            (15, 0xa129ca6149be45e5),
+            (16, 0x3f2acc7f57c29bdb),
+            (63, 0x958a324ceb064572),
+        ];
+
+        for (length, expected) in cases {
+            let data: Vec<u8> = (0..length as u8).collect();
+            assert_eq!(siphash(&key, &data), expected, "{}", length);
+
+            for split in [0, length / 3, length / 2, length] {
+                let mut hash = SipHash::new(&key);
+                hash.update(&data[..split]);
+                hash.update(&data[split..]);
+                assert!(hash.clone().verify(expected));
+                assert!(!hash.clone().verify(expected ^ 1));
+                assert_eq!(hash.finalize(), expected);
+            }
+        }
+    }
+
+    ///
+    /// SipHash as a `Hasher` and `BuildHasher`, hashing writes as one
+    /// message and keying a `HashMap`.
+    ///
+    #[test]
+    fn siphash_hasher_test() {
+        use std::collections::HashMap;
+        use std::hash::{BuildHasher, Hasher};
+
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let builder = SipHashBuilder::new(key);
+        let mut hasher = builder.build_hasher();
+        hasher.write(&[0, 1, 2, 3, 4]);
+        hasher.write_u64(u64::from_le_bytes([5, 6, 7, 8, 9, 10, 11, 12]));
+        hasher.write_u16(u16::from_le_bytes([13, 14]));
+        assert_eq!(hasher.finish(), 0xa129ca6149be45e5);
+        assert_eq!(hasher.finish(), 0xa129ca6149be45e5);
+
+        let mut map = HashMap::with_hasher(builder);
+        map.insert("armadillo", 1);
+        map.insert("pangolin", 2);
+        assert_eq!(map.get("armadillo"), Some(&1));
+        assert_eq!(map.get("pangolin"), Some(&2));
+    }
+}
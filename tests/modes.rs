@@ -0,0 +1,535 @@
+#[cfg(test)]
+mod modes_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::aes::{Aes128, Aes256};
+    use armadillo::modes::{
+        Adiantum, AdiantumError, Aes128Xts, Aes256Xts, Cbc, CbcError, Cmac, Ctr, Ff1, Ff1Error,
+        XtsError,
+    };
+    use armadillo::sha2::sha256;
+
+    const TEST_KEY: [u8; 16] = hex!("2b7e151628aed2a6abf7158809cf4f3c");
+    const TEST_PLAINTEXT: [u8; 64] = hex!("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710");
+
+    ///
+    /// The CTR-AES128 example, encrypted at once and in uneven pieces.
+    ///
+    /// Taken from SP 800-38A.
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/38/a/final) (Appendix F.5.1)
+    ///
+    #[test]
+    fn ctr_sp_800_38a_test() {
+        let expected = hex!("874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee");
+        let counter = hex!("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+
+        let mut ctr = Ctr::new(Aes128::new(TEST_KEY), counter);
+        assert_eq!(ctr.encrypt(&TEST_PLAINTEXT), expected);
+
+        let mut ctr = Ctr::new(Aes128::new(TEST_KEY), counter);
+        let mut buffer = TEST_PLAINTEXT;
+        for chunk in buffer.chunks_mut(7) {
+            ctr.apply_keystream(chunk);
+        }
+        assert_eq!(buffer, expected);
+        assert_eq!(ctr.position(), 64);
+    }
+
+    ///
+    /// Seeking and `apply_keystream_at` give the same keystream as reading
+    /// up to the offset, across the eight-block buffer.
+    ///
+    #[test]
+    fn ctr_seek_test() {
+        let aes = Aes256::new([5u8; 32]);
+        let mut keystream = vec![0u8; 300];
+        Ctr::new(&aes, [0u8; 16]).fill(&mut keystream);
+
+        for offset in [0, 1, 16, 17, 127, 128, 129, 250] {
+            let mut ctr = Ctr::new(&aes, [0u8; 16]);
+            ctr.seek(offset as u64);
+            assert_eq!(ctr.position(), offset as u64);
+            let mut buffer = vec![0u8; 300 - offset];
+            ctr.fill(&mut buffer);
+            assert_eq!(buffer, keystream[offset..]);
+
+            let mut buffer = vec![0u8; 300 - offset];
+            ctr.apply_keystream_at(offset as u64, &mut buffer);
+            assert_eq!(buffer, keystream[offset..]);
+        }
+    }
+
+    ///
+    /// The counter block wraps around as a 128-bit integer.
+    ///
+    #[test]
+    fn ctr_wrap_test() {
+        let mut counter = [0xffu8; 16];
+        counter[15] = 0xfe;
+        let mut ctr = Ctr::new(Aes256::new([5u8; 32]), counter);
+
+        let mut keystream = [0u8; 48];
+        ctr.fill(&mut keystream);
+        assert_eq!(keystream, hex!("8935c0abcfc829ffce8285cb9a5ebb11b3a5e1587b281c44491795269804dff482301dbb8cc3658a83eb102789312194"));
+    }
+
+    ///
+    /// Through the RustCrypto traits the mode gives the SP 800-38A output,
+    /// and seeks the same way.
+    ///
+    #[test]
+    #[cfg(feature = "rustcrypto-compat")]
+    fn rustcrypto_traits_test() {
+        use cipher::{StreamCipher, StreamCipherSeek};
+
+        let counter = hex!("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let mut ctr = Ctr::new(Aes128::new(TEST_KEY), counter);
+        let mut buffer = TEST_PLAINTEXT;
+        StreamCipher::apply_keystream(&mut ctr, &mut buffer[..20]);
+        assert_eq!(ctr.current_pos::<u64>(), 20);
+
+        StreamCipherSeek::seek(&mut ctr, 0u64);
+        StreamCipher::apply_keystream(&mut ctr, &mut buffer[..20]);
+        assert_eq!(buffer[..20], TEST_PLAINTEXT[..20]);
+    }
+
+    ///
+    /// The CBC-AES128 example in both directions, without padding.
+    ///
+    /// Taken from SP 800-38A.
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/38/a/final) (Appendix F.2.1)
+    ///
+    #[test]
+    fn cbc_sp_800_38a_test() {
+        let expected = hex!("7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b273bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7");
+        let iv = hex!("000102030405060708090a0b0c0d0e0f");
+        let cbc = Cbc::new(Aes128::new(TEST_KEY));
+
+        let mut buffer = TEST_PLAINTEXT;
+        cbc.encrypt_in_place(iv, &mut buffer).unwrap();
+        assert_eq!(buffer, expected);
+        cbc.decrypt_in_place(iv, &mut buffer).unwrap();
+        assert_eq!(buffer, TEST_PLAINTEXT);
+
+        assert_eq!(
+            cbc.encrypt_in_place(iv, &mut buffer[..17]),
+            Err(CbcError::InvalidLength)
+        );
+    }
+
+    ///
+    /// PKCS#7 padded messages round trip, including empty, block-aligned
+    /// and multi-batch ones.
+    ///
+    #[test]
+    fn cbc_padded_test() {
+        let iv = hex!("000102030405060708090a0b0c0d0e0f");
+        let cbc = Cbc::new(Aes128::new(TEST_KEY));
+
+        let ciphertext = cbc.encrypt_padded(iv, b"attack at dawn");
+        assert_eq!(ciphertext, hex!("89fc2ea14333a74c6319ab8d763d2829"));
+        assert_eq!(
+            cbc.decrypt_padded(iv, &ciphertext).unwrap(),
+            b"attack at dawn"
+        );
+
+        let message: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        for length in [0, 1, 15, 16, 17, 127, 128, 200] {
+            let ciphertext = cbc.encrypt_padded(iv, &message[..length]);
+            assert_eq!(ciphertext.len(), (length / 16 + 1) * 16);
+            assert_eq!(
+                cbc.decrypt_padded(iv, &ciphertext).unwrap(),
+                &message[..length]
+            );
+        }
+    }
+
+    ///
+    /// Every kind of malformed padding gives the same error, and wipes the
+    /// decrypted buffer.
+    ///
+    #[test]
+    fn cbc_invalid_padding_test() {
+        let iv = [0u8; 16];
+        let cbc = Cbc::new(Aes256::new([5u8; 32]));
+
+        let mut zero = [1u8; 32];
+        zero[31] = 0;
+        let mut too_long = [1u8; 32];
+        too_long[31] = 17;
+        let mut mismatched = [4u8; 32];
+        mismatched[29] = 3;
+        for plaintext in [zero, too_long, mismatched] {
+            let mut buffer = plaintext;
+            cbc.encrypt_in_place(iv, &mut buffer).unwrap();
+            assert_eq!(
+                cbc.decrypt_padded_in_place(iv, &mut buffer),
+                Err(CbcError::InvalidPadding)
+            );
+            assert_eq!(buffer, [0u8; 32]);
+        }
+
+        assert_eq!(cbc.decrypt_padded(iv, &[]), Err(CbcError::InvalidLength));
+        assert_eq!(
+            cbc.decrypt_padded(iv, &[0u8; 20]),
+            Err(CbcError::InvalidLength)
+        );
+    }
+
+    ///
+    /// XTS-AES-256 on a 512-byte data unit, in both directions.
+    ///
+    /// Taken from IEEE 1619 (vector 10).
+    /// [Source](https://doi.org/10.1109/IEEESTD.2019.8637988) (Annex B)
+    ///
+    #[test]
+    fn xts_ieee_1619_test() {
+        let xts = Aes256Xts::new(
+            Aes256::new(hex!(
+                "2718281828459045235360287471352662497757247093699959574966967627"
+            )),
+            Aes256::new(hex!(
+                "3141592653589793238462643383279502884197169399375105820974944592"
+            )),
+        );
+        let plaintext: Vec<u8> = (0..512).map(|i| i as u8).collect();
+
+        let mut buffer = plaintext.clone();
+        xts.encrypt_sector(0xff, &mut buffer).unwrap();
+        assert_eq!(buffer, hex!("1c3b3a102f770386e4836c99e370cf9bea00803f5e482357a4ae12d414a3e63b5d31e276f8fe4a8d66b317f9ac683f44680a86ac35adfc3345befecb4bb188fd5776926c49a3095eb108fd1098baec70aaa66999a72a82f27d848b21d4a741b0c5cd4d5fff9dac89aeba122961d03a757123e9870f8acf1000020887891429ca2a3e7a7d7df7b10355165c8b9a6d0a7de8b062c4500dc4cd120c0f7418dae3d0b5781c34803fa75421c790dfe1de1834f280d7667b327f6c8cd7557e12ac3a0f93ec05c52e0493ef31a12d3d9260f79a289d6a379bc70c50841473d1a8cc81ec583e9645e07b8d9670655ba5bbcfecc6dc3966380ad8fecb17b6ba02469a020a84e18e8f84252070c13e9f1f289be54fbc481457778f616015e1327a02b140f1505eb309326d68378f8374595c849d84f4c333ec4423885143cb47bd71c5edae9be69a2ffeceb1bec9de244fbe15992b11b77c040f12bd8f6a975a44a0f90c29a9abc3d4d893927284c58754cce294529f8614dcd2aba991925fedc4ae74ffac6e333b93eb4aff0479da9a410e4450e0dd7ae4c6e2910900575da401fc07059f645e8b7e9bfdef33943054ff84011493c27b3429eaedb4ed5376441a77ed43851ad77f16f541dfd269d50d6a5f14fb0aab1cbb4c1550be97f7ab4066193c4caa773dad38014bd2092fa755c824bb5e54c4f36ffda9fcea70b9c6e693e148c151"));
+        xts.decrypt_sector(0xff, &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+    }
+
+    ///
+    /// XTS-AES-128 on data units of 17 to 20 bytes, which steal ciphertext
+    /// from the first block.
+    ///
+    /// Taken from IEEE 1619 (vectors 15 to 18).
+    /// [Source](https://doi.org/10.1109/IEEESTD.2019.8637988) (Annex B)
+    ///
+    #[test]
+    fn xts_ciphertext_stealing_test() {
+        let xts = Aes128Xts::new(
+            Aes128::new(hex!("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0")),
+            Aes128::new(hex!("bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0")),
+        );
+        let expected: [&[u8]; 4] = [
+            &hex!("6c1625db4671522d3d7599601de7ca09ed"),
+            &hex!("d069444b7a7e0cab09e24447d24deb1fedbf"),
+            &hex!("e5df1351c0544ba1350b3363cd8ef4beedbf9d"),
+            &hex!("9d84c813f719aa2c7be3f66171c7c5c2edbf9dac"),
+        ];
+        let plaintext: Vec<u8> = (0..20).collect();
+
+        for expected in expected {
+            let mut buffer = plaintext[..expected.len()].to_vec();
+            xts.encrypt_sector(0x123456789a, &mut buffer).unwrap();
+            assert_eq!(buffer, expected);
+            xts.decrypt_sector(0x123456789a, &mut buffer).unwrap();
+            assert_eq!(buffer, &plaintext[..expected.len()]);
+        }
+    }
+
+    ///
+    /// Several data units at once number them consecutively, and lengths
+    /// that do not fit are rejected without touching the data.
+    ///
+    #[test]
+    fn xts_sectors_test() {
+        let xts = Aes256Xts::new(Aes256::new([1u8; 32]), Aes256::new([2u8; 32]));
+        let plaintext: Vec<u8> = (0..4 * 520).map(|i| i as u8).collect();
+
+        let mut buffer = plaintext.clone();
+        xts.encrypt_sectors(7, 520, &mut buffer).unwrap();
+        for (sector, chunk) in (7..).zip(plaintext.chunks(520)) {
+            let mut expected = chunk.to_vec();
+            xts.encrypt_sector(sector, &mut expected).unwrap();
+            assert_eq!(buffer[(sector as usize - 7) * 520..][..520], expected);
+        }
+        xts.decrypt_sectors(7, 520, &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+
+        assert_eq!(
+            xts.encrypt_sectors(0, 512, &mut buffer),
+            Err(XtsError::InvalidLength)
+        );
+        assert_eq!(
+            xts.encrypt_sectors(0, 8, &mut buffer[..16]),
+            Err(XtsError::InvalidLength)
+        );
+        assert_eq!(
+            xts.encrypt_sector(0, &mut buffer[..15]),
+            Err(XtsError::InvalidLength)
+        );
+        assert_eq!(buffer, plaintext);
+    }
+
+    ///
+    /// Adiantum on a single block, where only AES and the hashes apply, on
+    /// one byte more, and on a 64-byte sector through the sector tweak.
+    ///
+    /// Generated with an independent Python implementation of the paper,
+    /// using Python's cryptography package for AES.
+    /// [Source](https://doi.org/10.13154/tosc.v2018.i4.39-61)
+    ///
+    #[test]
+    fn adiantum_test() {
+        let tweak: [u8; 32] = core::array::from_fn(|i| i as u8);
+
+        let adiantum = Adiantum::new(core::array::from_fn::<u8, 32, _>(|i| i as u8));
+        let plaintext: Vec<u8> = (0..16).collect();
+        let mut buffer = plaintext.clone();
+        adiantum.encrypt(&[0; 32], &mut buffer).unwrap();
+        assert_eq!(buffer, hex!("8869f386191267ab40019a299a37f998"));
+        adiantum.decrypt(&[0; 32], &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+
+        let adiantum = Adiantum::new(core::array::from_fn::<u8, 32, _>(|i| 0x10 + i as u8));
+        let plaintext: Vec<u8> = (0..17).collect();
+        let mut buffer = plaintext.clone();
+        adiantum.encrypt(&tweak, &mut buffer).unwrap();
+        assert_eq!(buffer, hex!("d978cccbc24a7b34264e872d36c9af0082"));
+        adiantum.decrypt(&tweak, &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+
+        let adiantum = Adiantum::new(core::array::from_fn::<u8, 32, _>(|i| 0x80 + i as u8));
+        let plaintext: Vec<u8> = (0..64).map(|i| (i * 7) as u8).collect();
+        let mut buffer = plaintext.clone();
+        adiantum.encrypt_sector(7, &mut buffer).unwrap();
+        assert_eq!(buffer, hex!("a00065147a57cddf7e95a2d930fc8502546de134726be75beff9c143bed2ebf9ef468c9a7347b684dbe47f18378b835b34aaeb52401a775219b02371d6620ca8"));
+        adiantum.decrypt_sector(7, &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+    }
+
+    ///
+    /// Adiantum on a 4096-byte sector, four whole NH pieces, and on 1041
+    /// bytes, where the last NH piece is padded.  The ciphertexts are
+    /// compared by their SHA-256.
+    ///
+    /// Generated with an independent Python implementation of the paper,
+    /// using Python's cryptography package for AES.
+    /// [Source](https://doi.org/10.13154/tosc.v2018.i4.39-61)
+    ///
+    #[test]
+    fn adiantum_long_test() {
+        let adiantum = Adiantum::new(core::array::from_fn::<u8, 32, _>(|i| 0x20 + i as u8));
+        let tweak: [u8; 32] = core::array::from_fn(|i| 32 + i as u8);
+        let plaintext: Vec<u8> = (0..4096).map(|i| (i * 13 + 5) as u8).collect();
+        let mut buffer = plaintext.clone();
+        adiantum.encrypt(&tweak, &mut buffer).unwrap();
+        assert_eq!(
+            buffer[..32],
+            hex!("43a57124bfa919464235a2b5c5dffa4ab2ba741b205e53feeb788f90b36f5ecc")
+        );
+        assert_eq!(
+            sha256(&buffer),
+            hex!("e2a3d7df578d82857930546a7284b72519eea64588d259314404a77e380bc467")
+        );
+        adiantum.decrypt(&tweak, &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+
+        let adiantum = Adiantum::new(core::array::from_fn::<u8, 32, _>(|i| 0x40 + i as u8));
+        let plaintext: Vec<u8> = (0..1041).map(|i| i as u8).collect();
+        let mut buffer = plaintext.clone();
+        adiantum.encrypt(&[0; 32], &mut buffer).unwrap();
+        assert_eq!(
+            sha256(&buffer),
+            hex!("08a25766bc67965d5bca67ee32fbbd743cf6f50a0dfaa1e20b750e80cf6b8f55")
+        );
+        adiantum.decrypt(&[0; 32], &mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+    }
+
+    ///
+    /// Changing one plaintext bit or the sector changes every block of the
+    /// ciphertext, and messages shorter than a block are rejected.
+    ///
+    #[test]
+    fn adiantum_wide_block_test() {
+        let adiantum = Adiantum::new([7u8; 32]);
+        let plaintext = vec![0u8; 512];
+
+        let mut first = plaintext.clone();
+        adiantum.encrypt_sector(1, &mut first).unwrap();
+        let mut changed = plaintext.clone();
+        changed[0] ^= 1;
+        adiantum.encrypt_sector(1, &mut changed).unwrap();
+        let mut other_sector = plaintext.clone();
+        adiantum.encrypt_sector(2, &mut other_sector).unwrap();
+        for other in [changed, other_sector] {
+            for (a, b) in first.chunks(16).zip(other.chunks(16)) {
+                assert_ne!(a, b);
+            }
+        }
+
+        let mut short = [0u8; 15];
+        assert_eq!(
+            adiantum.encrypt(&[0; 32], &mut short),
+            Err(AdiantumError::InvalidLength)
+        );
+        assert_eq!(
+            adiantum.decrypt_sector(0, &mut short),
+            Err(AdiantumError::InvalidLength)
+        );
+    }
+
+    ///
+    /// AES-128-CMAC of the empty, one-block, partial-block and four-block
+    /// messages, and AES-256-CMAC of the last.
+    ///
+    /// Taken from RFC4493 and SP 800-38B.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc4493#section-4)
+    /// [Source](https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/AES_CMAC.pdf)
+    ///
+    #[test]
+    fn cmac_test() {
+        let expected = [
+            (0, hex!("bb1d6929e95937287fa37d129b756746")),
+            (16, hex!("070a16b46b4d4144f79bdd9dd04a287c")),
+            (40, hex!("dfa66747de9ae63030ca32611497c827")),
+            (64, hex!("51f0bebf7e3b9d92fc49741779363cfe")),
+        ];
+        let aes = Aes128::new(TEST_KEY);
+        for (length, tag) in expected {
+            let mut mac = Cmac::new(&aes);
+            mac.update(&TEST_PLAINTEXT[..length]);
+            assert_eq!(mac.finalize(), tag);
+        }
+
+        let mut mac = Cmac::new(Aes256::new(hex!(
+            "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4"
+        )));
+        mac.update(&TEST_PLAINTEXT);
+        assert_eq!(mac.finalize(), hex!("e1992190549f6ed5696a2c056c315410"));
+    }
+
+    ///
+    /// Splitting the message anywhere gives the same tag, and verification
+    /// accepts the full or a truncated tag but nothing else.
+    ///
+    #[test]
+    fn cmac_streaming_test() {
+        let aes = Aes128::new(TEST_KEY);
+        let expected = hex!("51f0bebf7e3b9d92fc49741779363cfe");
+
+        for split in [0, 1, 15, 16, 17, 32, 48, 63, 64] {
+            let mut mac = Cmac::new(&aes);
+            mac.update(&TEST_PLAINTEXT[..split]);
+            mac.update(&[]);
+            mac.update(&TEST_PLAINTEXT[split..]);
+            assert_eq!(mac.finalize(), expected);
+        }
+
+        let mut mac = Cmac::new(&aes);
+        mac.update(&TEST_PLAINTEXT);
+        assert!(mac.clone().verify(&expected));
+        assert!(mac.clone().verify(&expected[..8]));
+        assert!(!mac.clone().verify(&[]));
+        let mut wrong = expected;
+        wrong[15] ^= 1;
+        assert!(!mac.verify(&wrong));
+    }
+
+    ///
+    /// Taken from the NIST FF1 samples 1 to 3 and 7 to 9.
+    ///
+    /// [Source](https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/FF1samples.pdf)
+    ///
+    #[test]
+    fn ff1_test() {
+        let aes128 = Aes128::new(TEST_KEY);
+        let aes256 = Aes256::new(hex!(
+            "2b7e151628aed2a6abf7158809cf4f3cef4359d8d580aa4f7f036d6f04fc6a94"
+        ));
+        let decimal = [
+            (&[][..], "2433477484", "6657667009"),
+            (
+                &hex!("39383736353433323130")[..],
+                "6124200773",
+                "1001623463",
+            ),
+        ];
+
+        for (tweak, expected128, expected256) in decimal {
+            let ff1 = Ff1::new(&aes128, 10).unwrap();
+            assert_eq!(ff1.encrypt_str(tweak, "0123456789").unwrap(), expected128);
+            assert_eq!(ff1.decrypt_str(tweak, expected128).unwrap(), "0123456789");
+
+            let ff1 = Ff1::new(&aes256, 10).unwrap();
+            assert_eq!(ff1.encrypt_str(tweak, "0123456789").unwrap(), expected256);
+            assert_eq!(ff1.decrypt_str(tweak, expected256).unwrap(), "0123456789");
+        }
+
+        let tweak = hex!("3737373770717273373737");
+        let plaintext = "0123456789abcdefghi";
+        let ff1 = Ff1::new(&aes128, 36).unwrap();
+        assert_eq!(
+            ff1.encrypt_str(&tweak, plaintext).unwrap(),
+            "a9tv40mll9kdu509eum"
+        );
+        let ff1 = Ff1::new(&aes256, 36).unwrap();
+        assert_eq!(
+            ff1.encrypt_str(&tweak, plaintext).unwrap(),
+            "xs8a0azh2avyalyzuwd"
+        );
+        assert_eq!(
+            ff1.decrypt_str(&tweak, "xs8a0azh2avyalyzuwd").unwrap(),
+            plaintext
+        );
+    }
+
+    ///
+    /// Numerals round-trip at odd lengths and radixes beyond 36, and bad
+    /// parameters are rejected.
+    ///
+    #[test]
+    fn ff1_parameters_test() {
+        let aes = Aes128::new(TEST_KEY);
+
+        let ff1 = Ff1::new(&aes, 1000).unwrap();
+        let plaintext = [999, 0, 123, 456, 789];
+        let mut numerals = plaintext;
+        ff1.encrypt(b"tweak", &mut numerals).unwrap();
+        assert_ne!(numerals, plaintext);
+        assert!(numerals.iter().all(|&numeral| numeral < 1000));
+        ff1.decrypt(b"tweak", &mut numerals).unwrap();
+        assert_eq!(numerals, plaintext);
+
+        let ff1 = Ff1::new(&aes, 10).unwrap();
+        let card = "4111111111111111111111111111111111111111111111111111111111111111111111";
+        let encrypted = ff1.encrypt_str(&[], card).unwrap();
+        assert_eq!(encrypted.len(), card.len());
+        assert_eq!(ff1.decrypt_str(&[], &encrypted).unwrap(), card);
+
+        assert_eq!(Ff1::new(&aes, 1).err(), Some(Ff1Error::InvalidRadix));
+        assert_eq!(Ff1::new(&aes, 65537).err(), Some(Ff1Error::InvalidRadix));
+        assert_eq!(ff1.encrypt_str(&[], "12345"), Err(Ff1Error::InvalidLength));
+        assert_eq!(ff1.encrypt(&[], &mut [1; 72]), Ok(()));
+        assert_eq!(ff1.encrypt(&[], &mut [1; 73]), Err(Ff1Error::InvalidLength));
+
+        let binary = Ff1::new(&aes, 2).unwrap();
+        let mut bits = [1; 238];
+        binary.encrypt(&[], &mut bits).unwrap();
+        binary.decrypt(&[], &mut bits).unwrap();
+        assert_eq!(bits, [1; 238]);
+        assert_eq!(
+            binary.encrypt(&[], &mut [1; 239]),
+            Err(Ff1Error::InvalidLength)
+        );
+        assert_eq!(
+            binary.encrypt(&[], &mut [1; 240]),
+            Err(Ff1Error::InvalidLength)
+        );
+        assert_eq!(
+            ff1.encrypt_str(&[], "12345a"),
+            Err(Ff1Error::InvalidNumeral)
+        );
+        assert_eq!(
+            ff1.encrypt(&[], &mut [10; 6]),
+            Err(Ff1Error::InvalidNumeral)
+        );
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod openpgp_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::openpgp::{
+        decrypt_seipd, encrypt_seipd_with_salt, OpenPgpError, SymmetricAlgorithm,
+    };
+
+    const TEST_SALT: [u8; 32] =
+        hex!("202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f");
+
+    ///
+    /// A single chunk with AES-128 and the default 4 KiB chunks.
+    ///
+    /// Generated with Python's cryptography package, following RFC 9580.
+    /// [Source](https://www.rfc-editor.org/rfc/rfc9580#section-5.13.2)
+    ///
+    #[test]
+    fn single_chunk_test() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f");
+        let expected = hex!("d25102070306202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3fed9aa3ce084dee22eb1f71bd374efca2ffda61eec5f2849649f3529fbd70e8435db2a632c150e91b53ed71422f");
+
+        let packet = encrypt_seipd_with_salt(
+            &key,
+            SymmetricAlgorithm::Aes128,
+            6,
+            TEST_SALT,
+            b"Hello, world!",
+        )
+        .unwrap();
+        assert_eq!(packet, expected);
+        assert_eq!(decrypt_seipd(&key, &packet).unwrap(), b"Hello, world!");
+    }
+
+    ///
+    /// 150 bytes in 64-byte chunks with AES-256, so the last chunk is short
+    /// and the packet needs a two-octet length.
+    ///
+    /// Generated with Python's cryptography package, following RFC 9580.
+    /// [Source](https://www.rfc-editor.org/rfc/rfc9580#section-5.13.2)
+    ///
+    #[test]
+    fn multiple_chunks_test() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let salt = hex!("404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f");
+        let plaintext: Vec<u8> = (0..150).map(|i| (i % 251) as u8).collect();
+        let expected = hex!("d2c03a02090300404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5fcac823ae7e38419a4cd4ba68e5a64a51db8e2a7d1d7197b0d8e2899fd21118cd3b1ec9dde486223f24ae0fda50a23bfd6e9fe6fe0deb2047c3cd714fb04166a3f93c41477f810cc36b2a46018295113506bc996c4a80c804b654f211784cf9cacb5cb6e339d02c23f628989e9b277ce592dd1c07615ff79590c3a59ff826ff9f4877f8ac44f01fb4dbbcf23aa0ca53cc89b2d54e4f8cb76a259e85d01d6789c71434ca281ea835e4a944a60fe8566afcf2cb7e6d32e25670ccd9137e2ea63bd9aa13908280568a07a194048c9fe135ca211df98e2994");
+
+        let packet =
+            encrypt_seipd_with_salt(&key, SymmetricAlgorithm::Aes256, 0, salt, &plaintext).unwrap();
+        assert_eq!(packet, expected);
+        assert_eq!(decrypt_seipd(&key, &packet).unwrap(), plaintext);
+    }
+
+    ///
+    /// An empty plaintext has no chunks, only the final tag.
+    ///
+    /// Generated with Python's cryptography package, following RFC 9580.
+    /// [Source](https://www.rfc-editor.org/rfc/rfc9580#section-5.13.2)
+    ///
+    #[test]
+    fn empty_test() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f1011121314151617");
+        let expected = hex!("d2340208031000000000000000000000000000000000000000000000000000000000000000007b54e9c654871826bff31ba2625e3e36");
+
+        let packet =
+            encrypt_seipd_with_salt(&key, SymmetricAlgorithm::Aes192, 16, [0; 32], b"").unwrap();
+        assert_eq!(packet, expected);
+        assert_eq!(decrypt_seipd(&key, &packet).unwrap(), b"");
+    }
+
+    ///
+    /// Bodies split into partial lengths read like definite ones, and large
+    /// bodies get a five-octet length.
+    ///
+    #[test]
+    fn packet_length_test() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f");
+        let plaintext = vec![0x61; 9000];
+        let packet =
+            encrypt_seipd_with_salt(&key, SymmetricAlgorithm::Aes128, 6, TEST_SALT, &plaintext)
+                .unwrap();
+        let body_length = 4 + 32 + 9000 + 4 * 16;
+        assert_eq!(packet[..6], [0xd2, 0xff, 0, 0, 0x23, 0x8c]);
+        assert_eq!(packet.len(), 6 + body_length);
+        assert_eq!(decrypt_seipd(&key, &packet).unwrap(), plaintext);
+
+        // 4096 and 512 bytes in partial lengths, then the rest.
+        let body = &packet[6..];
+        let mut partial = vec![0xd2, 0xec];
+        partial.extend_from_slice(&body[..4096]);
+        partial.push(0xe9);
+        partial.extend_from_slice(&body[4096..4608]);
+        let rest = body_length - 4608;
+        partial.extend_from_slice(&[((rest - 192) >> 8) as u8 + 192, (rest - 192) as u8]);
+        partial.extend_from_slice(&body[4608..]);
+        assert_eq!(decrypt_seipd(&key, &partial).unwrap(), plaintext);
+    }
+
+    ///
+    /// Changed, cut off and rearranged packets, and wrong keys, are all
+    /// rejected.
+    ///
+    #[test]
+    fn rejection_test() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f");
+        let plaintext = [0x62; 200];
+        let packet =
+            encrypt_seipd_with_salt(&key, SymmetricAlgorithm::Aes128, 0, TEST_SALT, &plaintext)
+                .unwrap();
+        // Header, then version, cipher, mode and chunk size, the salt, four
+        // chunks and the final tag.
+        let header = &packet[..3];
+        let body = &packet[3..];
+        assert_eq!(header, [0xd2, 0xc0, 0x7c]);
+        assert_eq!(body.len(), 36 + 200 + 5 * 16);
+
+        for i in 0..packet.len() {
+            let mut tampered = packet.clone();
+            tampered[i] ^= 0x01;
+            assert!(decrypt_seipd(&key, &tampered).is_err(), "byte {}", i);
+        }
+        assert_eq!(
+            decrypt_seipd(&hex!("ff0102030405060708090a0b0c0d0e0f"), &packet),
+            Err(OpenPgpError::InvalidTag)
+        );
+        assert_eq!(
+            decrypt_seipd(&[0; 32], &packet),
+            Err(OpenPgpError::InvalidKeyLength)
+        );
+
+        let reframe = |body: &[u8]| {
+            let mut packet = vec![0xd2, ((body.len() - 192) >> 8) as u8 + 192];
+            packet.push((body.len() - 192) as u8);
+            packet.extend_from_slice(body);
+            packet
+        };
+        assert_eq!(reframe(body), packet);
+
+        // Dropping the last chunk or swapping two chunks breaks the tags.
+        let mut truncated = body[..36 + 3 * 80].to_vec();
+        truncated.extend_from_slice(&body[body.len() - 16..]);
+        assert_eq!(
+            decrypt_seipd(&key, &reframe(&truncated)),
+            Err(OpenPgpError::InvalidTag)
+        );
+        let mut swapped = body.to_vec();
+        swapped[36..116].copy_from_slice(&body[116..196]);
+        swapped[116..196].copy_from_slice(&body[36..116]);
+        assert_eq!(
+            decrypt_seipd(&key, &reframe(&swapped)),
+            Err(OpenPgpError::InvalidTag)
+        );
+
+        for (offset, value, error) in [
+            (0, 1, OpenPgpError::InvalidEncoding),
+            (1, 2, OpenPgpError::UnsupportedAlgorithm),
+            (2, 1, OpenPgpError::UnsupportedAlgorithm),
+            (2, 2, OpenPgpError::UnsupportedAlgorithm),
+            (3, 17, OpenPgpError::InvalidChunkSize),
+        ] {
+            let mut changed = body.to_vec();
+            changed[offset] = value;
+            assert_eq!(decrypt_seipd(&key, &reframe(&changed)), Err(error));
+        }
+
+        assert_eq!(
+            decrypt_seipd(&key, &packet[..packet.len() - 1]),
+            Err(OpenPgpError::InvalidEncoding)
+        );
+        let mut trailing = packet.clone();
+        trailing.push(0);
+        assert_eq!(
+            decrypt_seipd(&key, &trailing),
+            Err(OpenPgpError::InvalidEncoding)
+        );
+        let mut other_packet = packet.clone();
+        other_packet[0] = 0xc9;
+        assert_eq!(
+            decrypt_seipd(&key, &other_packet),
+            Err(OpenPgpError::InvalidEncoding)
+        );
+        assert_eq!(
+            encrypt_seipd_with_salt(&key, SymmetricAlgorithm::Aes128, 17, TEST_SALT, b""),
+            Err(OpenPgpError::InvalidChunkSize)
+        );
+        assert_eq!(
+            encrypt_seipd_with_salt(&key, SymmetricAlgorithm::Aes256, 6, TEST_SALT, b""),
+            Err(OpenPgpError::InvalidKeyLength)
+        );
+    }
+}
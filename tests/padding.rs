@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod padding_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::padding::{
+        iso7816_pad, iso7816_unpad, padme_length, padme_pad, padme_unpad, pkcs7_pad, pkcs7_unpad,
+        PaddingError,
+    };
+
+    ///
+    /// PKCS#7 appends n bytes of value n, with a full block for aligned input.
+    ///
+    #[test]
+    fn simple_pkcs7_test() {
+        assert_eq!(pkcs7_pad(b"abcdefgh", 16).unwrap()[8..], [8u8; 8]);
+        assert_eq!(pkcs7_pad(&[0u8; 16], 16).unwrap()[16..], [16u8; 16]);
+        assert_eq!(pkcs7_pad(&[], 4).unwrap(), [4u8; 4]);
+
+        for len in 0..=48 {
+            let data: Vec<u8> = (0..len as u8).collect();
+            let padded = pkcs7_pad(&data, 16).unwrap();
+            assert_eq!(padded.len() % 16, 0);
+            assert_eq!(pkcs7_unpad(&padded, 16).unwrap(), data);
+        }
+    }
+
+    ///
+    /// Malformed PKCS#7 paddings all produce the same error.
+    ///
+    #[test]
+    fn invalid_pkcs7_test() {
+        let invalid = [
+            hex!("0102030405060708090a0b0c0d0e0f00"),
+            hex!("0102030405060708090a0b0c0d0e0f11"),
+            hex!("0102030405060708090a0b0c0d030203"),
+            hex!("0102030405060708090a0b0c0d0e0f0f"),
+        ];
+        for data in invalid {
+            assert_eq!(pkcs7_unpad(&data, 16), Err(PaddingError::InvalidPadding));
+        }
+
+        assert_eq!(pkcs7_unpad(&[], 16), Err(PaddingError::InvalidLength));
+        assert_eq!(
+            pkcs7_unpad(&[1u8; 15], 16),
+            Err(PaddingError::InvalidLength)
+        );
+        assert_eq!(pkcs7_pad(&[], 0), Err(PaddingError::InvalidBlockSize));
+        assert_eq!(pkcs7_pad(&[], 256), Err(PaddingError::InvalidBlockSize));
+    }
+
+    ///
+    /// ISO/IEC 7816-4 appends 0x80 followed by zeros.
+    ///
+    #[test]
+    fn simple_iso7816_test() {
+        assert_eq!(iso7816_pad(b"abc", 8).unwrap(), hex!("6162638000000000"));
+        assert_eq!(iso7816_pad(&[], 4).unwrap(), hex!("80000000"));
+
+        for len in 0..=48 {
+            let data: Vec<u8> = (0..len as u8).map(|x| x | 0x80).collect();
+            let padded = iso7816_pad(&data, 16).unwrap();
+            assert_eq!(padded.len() % 16, 0);
+            assert_eq!(iso7816_unpad(&padded, 16).unwrap(), data);
+        }
+    }
+
+    ///
+    /// Missing markers and non-zero filler bytes are rejected.
+    ///
+    #[test]
+    fn invalid_iso7816_test() {
+        assert_eq!(
+            iso7816_unpad(&hex!("0102030405060700"), 8),
+            Err(PaddingError::InvalidPadding)
+        );
+        assert_eq!(
+            iso7816_unpad(&hex!("0102038000000100"), 8),
+            Err(PaddingError::InvalidPadding)
+        );
+        assert_eq!(
+            iso7816_unpad(&[0u8; 8], 8),
+            Err(PaddingError::InvalidPadding)
+        );
+        assert_eq!(
+            iso7816_unpad(&[0x80; 7], 8),
+            Err(PaddingError::InvalidLength)
+        );
+    }
+
+    ///
+    /// PADMÉ lengths, checked against the reference algorithm from the paper.
+    /// [Source](https://petsymposium.org/popets/2019/popets-2019-0056.pdf)
+    ///
+    #[test]
+    fn simple_padme_length_test() {
+        assert_eq!(padme_length(0), 0);
+        assert_eq!(padme_length(1), 1);
+        assert_eq!(padme_length(9), 10);
+        assert_eq!(padme_length(100), 104);
+        assert_eq!(padme_length(1000), 1024);
+        assert_eq!(padme_length(1025), 1088);
+        assert_eq!(padme_length(1_000_000), 1_015_808);
+
+        // The overhead is bounded by 12%, and the result never shrinks
+        for len in 1..5000 {
+            let padded = padme_length(len);
+            assert!(padded >= len);
+            assert!((padded - len) * 100 <= len * 12, "length {}", len);
+        }
+    }
+
+    ///
+    /// PADMÉ padding can be removed without knowing the original length.
+    ///
+    #[test]
+    fn padme_round_trip_test() {
+        for len in 0..=300 {
+            let data: Vec<u8> = (0..len).map(|x| (x % 3) as u8 * 0x40).collect();
+            let padded = padme_pad(&data);
+            assert_eq!(padded.len(), padme_length(len + 1));
+            assert_eq!(padme_unpad(&padded).unwrap(), data);
+        }
+        assert_eq!(padme_unpad(&[]), Err(PaddingError::InvalidLength));
+        assert_eq!(padme_unpad(&[1, 0, 0]), Err(PaddingError::InvalidPadding));
+    }
+}
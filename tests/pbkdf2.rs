@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod pbkdf2_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::pbkdf2::{
+        calibrate, pbkdf2, pbkdf2_hmac_sha256, pbkdf2_hmac_sha512, Pbkdf2Error,
+    };
+    use armadillo::sha2::Sha256;
+    use armadillo::sha3::Sha3_256;
+
+    ///
+    /// PBKDF2-HMAC-SHA256 with one and 80,000 iterations, for two output
+    /// blocks.
+    ///
+    /// Taken from RFC 7914.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc7914#section-11)
+    ///
+    #[test]
+    fn pbkdf2_sha256_test() {
+        let mut output = [0u8; 64];
+        pbkdf2_hmac_sha256(b"passwd", b"salt", 1, &mut output).unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+                "49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+            )
+        );
+
+        pbkdf2_hmac_sha256(b"Password", b"NaCl", 80000, &mut output).unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56"
+                "a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"
+            )
+        );
+
+        let mut output = [0u8; 40];
+        pbkdf2::<Sha256>(b"password", b"salt", 4096, &mut output).unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134af7ad98c1b458ce3f"
+            )
+        );
+    }
+
+    ///
+    /// PBKDF2-HMAC-SHA512 with one and 4096 iterations, the second with a
+    /// long password and salt.
+    ///
+    #[test]
+    fn pbkdf2_sha512_test() {
+        let mut output = [0u8; 64];
+        pbkdf2_hmac_sha512(b"password", b"salt", 1, &mut output).unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252"
+                "c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce"
+            )
+        );
+
+        pbkdf2_hmac_sha512(
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "8c0511f4c6e597c6ac6315d8f0362e225f3c501495ba23b868c005174dc4ee71"
+                "115b59f9e60cd9532fa33e0f75aefe30225c583a186cd82bd4daea9724a3d3b8"
+            )
+        );
+    }
+
+    ///
+    /// Zero iterations are rejected, and calibration returns a usable
+    /// count that grows with the target.
+    ///
+    #[test]
+    fn parameters_test() {
+        assert_eq!(
+            pbkdf2_hmac_sha256(b"password", b"salt", 0, &mut [0u8; 32]),
+            Err(Pbkdf2Error::InvalidIterations)
+        );
+
+        let short = calibrate::<Sha3_256>(1);
+        let long = calibrate::<Sha3_256>(200);
+        assert!(short >= 1);
+        assert!(long > short);
+    }
+}
@@ -4,7 +4,10 @@ mod chacha_tests {
 
     use hex_literal::hex;
 
-    use armadillo::poly::{poly1305_mac, poly1305_r_clamp, R};
+    use armadillo::poly::{
+        poly1305_key_gen, poly1305_mac, poly1305_r_clamp, poly1305_verify, Poly1305, Poly1305Error,
+        Tag, R,
+    };
 
     const TEST_KEY: [u8; 32] =
         hex!("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
@@ -29,9 +32,9 @@ mod chacha_tests {
         assert!(clamped[15] < 16);
         // r[4], r[8], and r[12] are required to have their bottom two bits
         // clear (be divisible by 4)
-        assert!(clamped[4] % 4 == 0);
-        assert!(clamped[8] % 4 == 0);
-        assert!(clamped[12] % 4 == 0);
+        assert_eq!(clamped[4] % 4, 0);
+        assert_eq!(clamped[8] % 4, 0);
+        assert_eq!(clamped[12] % 4, 0);
     }
 
     #[test]
@@ -41,4 +44,340 @@ mod chacha_tests {
 
         assert_eq!(code, expected);
     }
+
+    ///
+    /// Every message length from 0 to 130 bytes must produce a tag, covering
+    /// empty input and trailing blocks shorter than 16 bytes.  The tags are
+    /// folded into a single MAC to keep the expected value short.
+    ///
+    #[test]
+    fn boundary_lengths_mac_test() {
+        let message: Vec<u8> = (0..130).collect();
+        let tags: Vec<u8> = (0..=130)
+            .flat_map(|len| poly1305_mac(TEST_KEY, &message[..len]).into_bytes())
+            .collect();
+
+        let expected = hex!("fb0a65d345359cf26c7d03e21c1753df");
+        assert_eq!(poly1305_mac(TEST_KEY, &tags), expected);
+    }
+
+    ///
+    /// Streaming the message in pieces of any size, including empty ones and
+    /// pieces that cross block boundaries, gives the one-shot tag.
+    ///
+    #[test]
+    fn streaming_mac_test() {
+        let message: Vec<u8> = (0..200).map(|i| (i * 31 + 7) as u8).collect();
+        let expected = poly1305_mac(TEST_KEY, &message);
+
+        for piece in [1, 3, 15, 16, 17, 33, 200] {
+            let mut mac = Poly1305::new(TEST_KEY);
+            for chunk in message.chunks(piece) {
+                mac.update(chunk);
+                mac.update(&[]);
+            }
+            assert_eq!(mac.finalize(), expected, "pieces of {}", piece);
+        }
+
+        let mut mac = Poly1305::new(TEST_KEY);
+        mac.update(&TEST_DATA[..5]);
+        mac.update(&TEST_DATA[5..]);
+        assert_eq!(mac.finalize(), hex!("a8061dc1305136c6c22b8baf0c0127a9"));
+    }
+
+    ///
+    /// Verification accepts the right tag and rejects every single bit
+    /// flip, for the one-shot and the streaming MAC.
+    ///
+    #[test]
+    fn verify_test() {
+        let tag = Tag::from(hex!("a8061dc1305136c6c22b8baf0c0127a9"));
+        assert!(poly1305_verify(TEST_KEY, &TEST_DATA, &tag));
+
+        let mut mac = Poly1305::new(TEST_KEY);
+        mac.update(&TEST_DATA);
+        assert!(mac.verify(&tag));
+
+        for bit in 0..128 {
+            let mut forged = tag.into_bytes();
+            forged[bit / 8] ^= 1 << (bit % 8);
+            assert!(!poly1305_verify(TEST_KEY, &TEST_DATA, &forged.into()));
+        }
+        assert!(!poly1305_verify(TEST_KEY, &TEST_DATA[1..], &tag));
+    }
+
+    ///
+    /// A tag whose high bytes are zero must still be returned in full.
+    ///
+    #[test]
+    fn zero_tag_test() {
+        assert_eq!(poly1305_mac([0u8; 32], &[]), [0u8; 16]);
+    }
+
+    ///
+    /// Key generation is the first half of ChaCha20 block 0.
+    ///
+    /// Taken from the RFC8439 specification.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8439#section-2.6.2)
+    ///
+    #[test]
+    fn key_gen_test() {
+        let key = hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let nonce = hex!("000000000001020304050607");
+
+        assert_eq!(
+            poly1305_key_gen(key, nonce),
+            hex!("8ad5a08b905f81cc815040274ab29471a833b637e3fd0da508dbb8e2fdd1a646")
+        );
+    }
+
+    ///
+    /// Accumulators that land on or just above p = 2^130 - 5 must be fully
+    /// reduced before s is added.  Taken from the RFC8439 test vectors.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8439#appendix-A.3)
+    ///
+    #[test]
+    fn reduction_edge_cases_test() {
+        let mut r2 = [0u8; 32];
+        r2[0] = 2;
+        let mut r1 = [0u8; 32];
+        r1[0] = 1;
+
+        assert_eq!(
+            poly1305_mac(r2, &[0xff; 16]),
+            hex!("03000000000000000000000000000000")
+        );
+
+        let mut key = r2;
+        key[16..].fill(0xff);
+        let mut data = [0u8; 16];
+        data[0] = 2;
+        assert_eq!(
+            poly1305_mac(key, &data),
+            hex!("03000000000000000000000000000000")
+        );
+
+        let data = hex!(
+            "ffffffffffffffffffffffffffffffff"
+            "f0ffffffffffffffffffffffffffffff"
+            "11000000000000000000000000000000"
+        );
+        assert_eq!(
+            poly1305_mac(r1, &data),
+            hex!("05000000000000000000000000000000")
+        );
+
+        let data = hex!(
+            "ffffffffffffffffffffffffffffffff"
+            "fbfefefefefefefefefefefefefefefe"
+            "01010101010101010101010101010101"
+        );
+        assert_eq!(poly1305_mac(r1, &data), [0u8; 16]);
+
+        assert_eq!(
+            poly1305_mac(r2, &hex!("fdffffffffffffffffffffffffffffff")),
+            hex!("faffffffffffffffffffffffffffffff")
+        );
+    }
+
+    ///
+    /// MACing a reader, or writing to the MAC, gives the same tag as
+    /// `poly1305_mac` over the whole message.
+    ///
+    #[test]
+    #[cfg(feature = "std")]
+    fn reader_and_writer_test() {
+        use std::io::{Cursor, Write};
+
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7) as u8).collect();
+        let expected = poly1305_mac(TEST_KEY, &data);
+
+        let tag = Poly1305::mac_reader(TEST_KEY, Cursor::new(&data)).unwrap();
+        assert_eq!(tag, expected);
+
+        let mut mac = Poly1305::new(TEST_KEY);
+        for chunk in data.chunks(1001) {
+            mac.write_all(chunk).unwrap();
+        }
+        assert_eq!(mac.finalize(), expected);
+    }
+
+    ///
+    /// Long messages take the vectorized path where there is one.  The tag
+    /// must match the reference, also with the message split at awkward
+    /// points and the key and message saturated.
+    ///
+    #[test]
+    fn long_message_test() {
+        let data: Vec<u8> = (0..4099u32).map(|i| (i * 7) as u8).collect();
+        let expected = hex!("1cf45ae528c77c7caf7680342c55efe8");
+        assert_eq!(poly1305_mac(TEST_KEY, &data), expected);
+
+        for split in [1, 15, 63, 64, 255, 256, 257, 1000] {
+            let mut mac = Poly1305::new(TEST_KEY);
+            mac.update(&data[..split]);
+            mac.update(&data[split..]);
+            assert_eq!(mac.finalize(), expected);
+        }
+
+        assert_eq!(
+            poly1305_mac([0xff; 32], &[0xff; 1024]),
+            hex!("25d4926a53bb480da228ec61e0a31a38")
+        );
+    }
+
+    ///
+    /// Tags round-trip through hex in either case, and malformed hex or
+    /// slices of the wrong length are rejected.
+    ///
+    #[test]
+    fn tag_test() {
+        let tag = poly1305_mac(TEST_KEY, &TEST_DATA);
+        assert_eq!(tag.to_string(), "a8061dc1305136c6c22b8baf0c0127a9");
+        assert_eq!("A8061DC1305136C6C22B8BAF0C0127A9".parse::<Tag>(), Ok(tag));
+        assert_eq!(Tag::try_from(tag.as_ref()), Ok(tag));
+        assert!(tag.verify(&tag.into_bytes().into()));
+
+        assert_eq!(
+            "a8061dc1305136c6c22b8baf0c0127".parse::<Tag>(),
+            Err(Poly1305Error::InvalidLength)
+        );
+        assert_eq!(
+            "a8061dc1305136c6c22b8baf0c0127ag".parse::<Tag>(),
+            Err(Poly1305Error::InvalidHex)
+        );
+        assert_eq!(
+            Tag::try_from(&[0u8; 15][..]),
+            Err(Poly1305Error::InvalidLength)
+        );
+    }
+
+    ///
+    /// Through the RustCrypto traits, blocks are zero-padded like the AEAD
+    /// constructions expect, which matches the MAC of the padded message.
+    ///
+    #[test]
+    #[cfg(feature = "rustcrypto-compat")]
+    fn universal_hash_traits_test() {
+        use universal_hash::{KeyInit, UniversalHash};
+
+        fn padded_mac<U: KeyInit + UniversalHash>(key: &[u8], data: &[u8]) -> U {
+            let mut mac = U::new_from_slice(key).unwrap();
+            mac.update_padded(data);
+            mac
+        }
+
+        let data: Vec<u8> = (0..300u32).map(|i| i as u8).collect();
+        let mut padded = data.clone();
+        padded.resize(304, 0);
+        let expected = poly1305_mac(TEST_KEY, &padded);
+
+        let mac = padded_mac::<Poly1305>(&TEST_KEY, &data);
+        assert_eq!(
+            UniversalHash::finalize(mac.clone()).as_slice(),
+            expected.as_ref()
+        );
+        assert!(UniversalHash::verify(mac, &expected.into_bytes().into()).is_ok());
+        assert!(<Poly1305 as KeyInit>::new_from_slice(&TEST_KEY[1..]).is_err());
+    }
+
+    ///
+    /// Keys taken from slices must be exactly 32 bytes long.
+    ///
+    #[test]
+    fn new_from_slice_test() {
+        let mut mac = Poly1305::new_from_slice(&TEST_KEY).unwrap();
+        mac.update(&TEST_DATA);
+        assert_eq!(mac.finalize(), poly1305_mac(TEST_KEY, &TEST_DATA));
+
+        let long = [0u8; 33];
+        assert!(matches!(
+            Poly1305::new_from_slice(&long),
+            Err(Poly1305Error::InvalidLength)
+        ));
+        assert!(matches!(
+            Poly1305::new_from_slice(&long[..31]),
+            Err(Poly1305Error::InvalidLength)
+        ));
+    }
+
+    ///
+    /// Batch verification reports every message separately, in order, and
+    /// the parallel version agrees with it.
+    ///
+    #[test]
+    fn verify_batch_test() {
+        use armadillo::poly::poly1305_verify_batch;
+
+        let messages: Vec<Vec<u8>> = (0..300u32)
+            .map(|i| (0..i).map(|j| (i ^ j) as u8).collect())
+            .collect();
+        let items: Vec<([u8; 32], &[u8], Tag)> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| {
+                let key = [i as u8; 32];
+                let mut tag = poly1305_mac(key, message).into_bytes();
+                // Forge every seventh tag
+                tag[i % 16] ^= (i % 7 == 0) as u8;
+                (key, message.as_slice(), tag.into())
+            })
+            .collect();
+
+        let expected: Vec<bool> = (0..300).map(|i| i % 7 != 0).collect();
+        assert_eq!(poly1305_verify_batch(&items), expected);
+        #[cfg(feature = "parallel")]
+        assert_eq!(armadillo::poly::par_poly1305_verify_batch(&items), expected);
+        assert!(poly1305_verify_batch(&[]).is_empty());
+    }
+
+    ///
+    /// Poly1305-AES tags of an empty, a 2-byte, a 32-byte and a 63-byte
+    /// message, with the key laid out as k followed by r.
+    ///
+    /// Taken from appendix B of the Poly1305-AES paper.
+    /// [Source](https://cr.yp.to/mac/poly1305-20050329.pdf)
+    ///
+    #[test]
+    fn poly1305_aes_test() {
+        use armadillo::poly::{poly1305_aes_mac, poly1305_aes_verify};
+
+        type Case = ([u8; 32], [u8; 16], &'static [u8], [u8; 16]);
+        let cases: [Case; 4] = [
+            (
+                hex!("ec074c835580741701425b623235add6851fc40c3467ac0be05cc20404f3f700"),
+                hex!("fb447350c4e868c52ac3275cf9d4327e"),
+                &hex!("f3f6"),
+                hex!("f4c633c3044fc145f84f335cb81953de"),
+            ),
+            (
+                hex!("75deaa25c09f208e1dc4ce6b5cad3fbfa0f3080000f46400d0c7e9076c834403"),
+                hex!("61ee09218d29b0aaed7e154a2c5509cc"),
+                &[],
+                hex!("dd3fab2251f11ac759f0887129cc2ee7"),
+            ),
+            (
+                hex!("6acb5f61a7176dd320c5c1eb2edcdc7448443d0bb0d21109c89a100b5ce2c208"),
+                hex!("ae212a55399729595dea458bc621ff0e"),
+                &hex!("663cea190ffb83d89593f3f476b6bc24d7e679107ea26adb8caf6652d0656136"),
+                hex!("0ee1c16bb73f0f4fd19881753c01cdbe"),
+            ),
+            (
+                hex!("e1a5668a4d5b66a5f68cc5424ed5982d12976a08c4426d0ce8a82407c4f48207"),
+                hex!("9ae831e743978d3a23527c7128149e3a"),
+                &hex!("ab0812724a7f1e342742cbed374d94d136c6b8795d45b3819830f2c04491faf0990c62e48b8018b2c3e4a0fa3134cb67fa83e158c994d961c4cb21095c1bf9"),
+                hex!("5154ad0d2cb26e01274fc51148491f1b"),
+            ),
+        ];
+
+        for (key, nonce, data, expected) in cases {
+            let tag = poly1305_aes_mac(key, nonce, data);
+            assert_eq!(tag.into_bytes(), expected);
+            assert!(poly1305_aes_verify(key, nonce, data, &Tag::from(expected)));
+
+            let mut forged = expected;
+            forged[15] ^= 1;
+            assert!(!poly1305_aes_verify(key, nonce, data, &Tag::from(forged)));
+        }
+    }
 }
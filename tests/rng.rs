@@ -0,0 +1,400 @@
+#[cfg(test)]
+mod rng_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::rng::{
+        CtrDrbgAes128, CtrDrbgAes192, CtrDrbgAes256, EntropySource, HmacDrbgSha256, HmacDrbgSha512,
+        RngError, MAX_REQUEST_LENGTH,
+    };
+
+    ///
+    /// An entropy source that counts up from a starting byte, so tests
+    /// can replay what it returned.
+    ///
+    struct CountingEntropy(u8);
+
+    impl EntropySource for CountingEntropy {
+        fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+            for byte in dest {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+            Ok(())
+        }
+    }
+
+    struct FailingEntropy;
+
+    impl EntropySource for FailingEntropy {
+        fn fill_entropy(&mut self, _: &mut [u8]) -> Result<(), RngError> {
+            Err(RngError::EntropySourceFailed)
+        }
+    }
+
+    fn counting(start: u8, length: usize) -> Vec<u8> {
+        (0..length).map(|i| start.wrapping_add(i as u8)).collect()
+    }
+
+    ///
+    /// Two 128-byte requests without additional input, of which the second
+    /// is compared.
+    ///
+    /// Taken from the NIST CAVP HMAC_DRBG test vectors, SHA-256 without
+    /// prediction resistance, COUNT = 0.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/random-number-generators)
+    ///
+    #[test]
+    fn hmac_drbg_sha256_test() {
+        let entropy = hex!("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488");
+        let nonce = hex!("659ba96c601dc69fc902940805ec0ca8");
+        let expected = hex!("e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc107694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8");
+
+        let mut drbg = HmacDrbgSha256::new(&entropy, &nonce, b"").unwrap();
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, b"").unwrap();
+        drbg.generate(&mut output, b"").unwrap();
+        assert_eq!(output, expected);
+    }
+
+    ///
+    /// Two 128-byte requests with additional input, of which the second is
+    /// compared.
+    ///
+    /// Taken from the NIST CAVP HMAC_DRBG test vectors, SHA-256 without
+    /// prediction resistance, with additional input, COUNT = 0.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/random-number-generators)
+    ///
+    #[test]
+    fn hmac_drbg_additional_input_test() {
+        let entropy = hex!("d3cc4d1acf3dde0c4bd2290d262337042dc632948223d3a2eaab87da44295fbd");
+        let nonce = hex!("0109b0e729f457328aa18569a9224921");
+        let additional = [
+            hex!("3c311848183c9a212a26f27f8c6647e40375e466a0857cc39c4e47575d53f1f6"),
+            hex!("fcb9abd19ccfbccef88c9c39bfb3dd7b1c12266c9808992e305bc3cff566e4e4"),
+        ];
+        let expected = hex!("9c7b758b212cd0fcecd5daa489821712e3cdea4467b560ef5ddc24ab47749a1f1ffdbbb118f4e62fcfca3371b8fbfc5b0646b83e06bfbbab5fac30ea09ea2bc76f1ea568c9be0444b2cc90517b20ca825f2d0eccd88e7175538b85d90ab390183ca6395535d34473af6b5a5b88f5a59ee7561573337ea819da0dcc3573a22974");
+
+        let mut drbg = HmacDrbgSha256::new(&entropy, &nonce, b"").unwrap();
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &additional[0]).unwrap();
+        drbg.generate(&mut output, &additional[1]).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    ///
+    /// HMAC_DRBG with SHA-512, a personalization string, a reseed and
+    /// additional input.
+    ///
+    /// Generated with an independent Python implementation of SP 800-90A,
+    /// and checked against OpenSSL's HMAC-DRBG.
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/90/a/r1/final)
+    ///
+    #[test]
+    fn hmac_drbg_sha512_reseed_test() {
+        let mut drbg = HmacDrbgSha512::new(
+            &counting(0x00, 32),
+            &counting(0x20, 16),
+            b"armadillo personalization",
+        )
+        .unwrap();
+
+        let mut output = [0u8; 100];
+        drbg.generate(&mut output, b"").unwrap();
+        assert_eq!(output, hex!("738fc6d4d19cbf882106ab46056d3ad9b77820469109f5f75619adc86fbd48bbcb8aff0043bb2846cb510d10d9891249c4349de53d06148644e3798fe06c5857682ad80ec3270a38fff6c2bbe541500df1fc7b6ce06549bbf5bf38111a9f2e974fc9eb47"));
+
+        drbg.reseed(&counting(0x80, 32), b"reseed input").unwrap();
+        let mut output = [0u8; 64];
+        drbg.generate(&mut output, b"additional input").unwrap();
+        assert_eq!(output, hex!("4251c2383cc3f4f567258d06d3a09ddc9d1d444382fd00330814227cf218eaee055b4f07d2eaf1cd0b582d1b6bc4636ee9a948939c2badd8cfc89a7826db49ad"));
+    }
+
+    ///
+    /// CTR_DRBG with AES-128, a personalization string, a reseed and
+    /// additional input.
+    ///
+    /// Generated with Python's cryptography package, following SP 800-90A,
+    /// and checked against OpenSSL's CTR-DRBG.
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/90/a/r1/final)
+    ///
+    #[test]
+    fn ctr_drbg_aes128_test() {
+        let mut drbg = CtrDrbgAes128::new(
+            &counting(0x00, 16),
+            &counting(0x20, 8),
+            b"armadillo personalization",
+        )
+        .unwrap();
+
+        let mut output = [0u8; 64];
+        drbg.generate(&mut output, b"first additional input")
+            .unwrap();
+        assert_eq!(output, hex!("526b270ca6ae4a781139dc521a5eda7b6c7c092f476374ffdbe83f0c08aeab66d93f36d30b6b75aa545d74809165b5203a6264ec7dcd26ada583f272eb9eb90b"));
+
+        drbg.reseed(&counting(0x80, 16), b"reseed input").unwrap();
+        drbg.generate(&mut output, b"").unwrap();
+        assert_eq!(output, hex!("1f2329ffc4be81e7a7409cd2a5f7cd9d7b1b0b84ccbd7eb75235ebfe54ec4cf19750bd064a95e122d6102691507e8037bbbcc40fa3984df1506ec7b703486a63"));
+    }
+
+    ///
+    /// CTR_DRBG with AES-192 and AES-256, where the seed is not a whole
+    /// number of blocks and requests end mid-block.
+    ///
+    /// Generated with Python's cryptography package, following SP 800-90A,
+    /// and checked against OpenSSL's CTR-DRBG.
+    /// [Source](https://csrc.nist.gov/pubs/sp/800/90/a/r1/final)
+    ///
+    #[test]
+    fn ctr_drbg_aes192_aes256_test() {
+        let mut drbg = CtrDrbgAes192::new(&counting(0x40, 24), &counting(0x60, 12), b"").unwrap();
+        let mut output = [0u8; 37];
+        drbg.generate(&mut output, b"").unwrap();
+        assert_eq!(
+            output,
+            hex!("169a40d622ed27855b9d8385b0649c6e3e7a3f292d1de477c34452e94da18aba4bc3cd9d45")
+        );
+        let mut output = [0u8; 5];
+        drbg.generate(&mut output, &hex!("a0a1a2")).unwrap();
+        assert_eq!(output, hex!("caccab9b5c"));
+
+        let mut drbg = CtrDrbgAes256::new(&counting(0x00, 32), &counting(0x20, 16), b"").unwrap();
+        let mut output = [0u8; 64];
+        drbg.generate(&mut output, b"").unwrap();
+        assert_eq!(output, hex!("7ad7f0612b3eef3e51f8b3517deca58df1dbb97783e8b2930334c5c76cd7161268f055e64dc811da093af4d36c943982e73534533239ddcde72c40662e151179"));
+        drbg.generate(&mut output, b"").unwrap();
+        assert_eq!(output, hex!("c5b1ae8dbc23056b19cf88b1997e8498b4b394c0db9760a3704b0c1d6a4c926e5bfe234afb31b498a30810bdb8d3542b5530849f8b9b8bea8cad70e633f32a24"));
+    }
+
+    ///
+    /// Seeding from an entropy source takes the entropy and then the nonce
+    /// from it, and prediction resistance reseeds before every request.
+    ///
+    #[test]
+    fn entropy_source_test() {
+        let mut drbg = CtrDrbgAes256::from_entropy_source(&mut CountingEntropy(0), b"").unwrap();
+        let mut expected =
+            CtrDrbgAes256::new(&counting(0x00, 32), &counting(0x20, 16), b"").unwrap();
+        let (mut output, mut expected_output) = ([0u8; 64], [0u8; 64]);
+        drbg.generate(&mut output, b"").unwrap();
+        expected.generate(&mut expected_output, b"").unwrap();
+        assert_eq!(output, expected_output);
+
+        drbg.generate_with_prediction_resistance(&mut CountingEntropy(0x80), &mut output, b"input")
+            .unwrap();
+        expected.reseed(&counting(0x80, 32), b"input").unwrap();
+        expected.generate(&mut expected_output, b"").unwrap();
+        assert_eq!(output, expected_output);
+
+        let mut drbg = HmacDrbgSha256::from_entropy_source(&mut CountingEntropy(0), b"").unwrap();
+        let mut expected =
+            HmacDrbgSha256::new(&counting(0x00, 32), &counting(0x20, 16), b"").unwrap();
+        drbg.generate_with_prediction_resistance(&mut CountingEntropy(0x80), &mut output, b"")
+            .unwrap();
+        expected
+            .reseed_from(&mut CountingEntropy(0x80), b"")
+            .unwrap();
+        expected.generate(&mut expected_output, b"").unwrap();
+        assert_eq!(output, expected_output);
+
+        assert!(matches!(
+            HmacDrbgSha256::from_entropy_source(&mut FailingEntropy, b""),
+            Err(RngError::EntropySourceFailed)
+        ));
+        assert_eq!(
+            drbg.generate_with_prediction_resistance(&mut FailingEntropy, &mut output, b""),
+            Err(RngError::EntropySourceFailed)
+        );
+    }
+
+    ///
+    /// Short entropy, oversized requests and an exhausted reseed interval
+    /// are rejected.
+    ///
+    #[test]
+    fn limits_test() {
+        assert!(matches!(
+            HmacDrbgSha256::new(&[0; 31], &[0; 16], b""),
+            Err(RngError::InsufficientEntropy)
+        ));
+        assert!(matches!(
+            CtrDrbgAes128::new(&[0; 15], &[0; 8], b""),
+            Err(RngError::InsufficientEntropy)
+        ));
+
+        let mut drbg = CtrDrbgAes128::new(&[0; 16], &[0; 8], b"").unwrap();
+        let mut output = vec![0u8; MAX_REQUEST_LENGTH + 1];
+        assert_eq!(
+            drbg.generate(&mut output, b""),
+            Err(RngError::RequestTooLarge)
+        );
+        drbg.generate(&mut output[..MAX_REQUEST_LENGTH], b"")
+            .unwrap();
+        assert_eq!(
+            drbg.reseed(&[0; 15], b""),
+            Err(RngError::InsufficientEntropy)
+        );
+
+        let mut drbg = HmacDrbgSha256::new(&[0; 32], &[0; 16], b"").unwrap();
+        drbg.set_reseed_interval(2);
+        drbg.generate(&mut output[..32], b"").unwrap();
+        drbg.generate(&mut output[..32], b"").unwrap();
+        assert_eq!(
+            drbg.generate(&mut output[..32], b""),
+            Err(RngError::ReseedRequired)
+        );
+        drbg.reseed(&[1; 32], b"").unwrap();
+        drbg.generate(&mut output[..32], b"").unwrap();
+    }
+
+    ///
+    /// Generators seeded by the operating system differ from each other.
+    ///
+    #[cfg(feature = "os_rng")]
+    #[test]
+    fn os_entropy_test() {
+        use armadillo::rng::OsEntropy;
+
+        let mut first = HmacDrbgSha256::from_entropy_source(&mut OsEntropy, b"").unwrap();
+        let mut second = HmacDrbgSha256::from_entropy_source(&mut OsEntropy, b"").unwrap();
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        first.generate(&mut a, b"").unwrap();
+        second.generate(&mut b, b"").unwrap();
+        assert_ne!(a, b);
+    }
+
+    ///
+    /// Two 32-byte events fill pool 0, which seeds the generator on the
+    /// first request; the second request runs on the key the first left.
+    ///
+    /// Generated with Python's hashlib and cryptography packages, following
+    /// the Fortuna design with a ChaCha20 generator.
+    /// [Source](https://www.schneier.com/wp-content/uploads/2015/12/fortuna.pdf)
+    ///
+    #[cfg(feature = "std")]
+    #[test]
+    fn fortuna_test() {
+        use armadillo::rng::Fortuna;
+
+        let mut fortuna = Fortuna::new();
+        fortuna.add_random_event(7, 0, &counting(0x00, 32)).unwrap();
+        fortuna.add_random_event(7, 0, &counting(0x20, 32)).unwrap();
+
+        let mut output = [0u8; 80];
+        fortuna.random_data(&mut output).unwrap();
+        assert_eq!(output, hex!("c96985b4884b73659694c79007c2909be7cf626661611ab67004e5e2b0d5d0d78f85f3a93ae5eeec8f2dbab1dde35620b92702cae6ea11ce87b2e221054585c804277e44657766baa627149a25880c77"));
+        let mut output = [0u8; 40];
+        fortuna.random_data(&mut output).unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "5a1dab0c6eeae96669fdf2f1738764b72f7a5e87680b4f92da90dd1146a5a9d0b90c178bb1d64cc0"
+            )
+        );
+        assert_eq!(fortuna.reseed_count(), 1);
+    }
+
+    ///
+    /// Reseeds wait for 64 bytes in pool 0 and 100 ms since the last one,
+    /// and pool 1 only joins every second reseed.
+    ///
+    #[cfg(feature = "std")]
+    #[test]
+    fn fortuna_reseed_test() {
+        use armadillo::rng::{EventSource, Fortuna};
+
+        let mut first = Fortuna::new();
+        let mut second = Fortuna::new();
+        let mut output = [0u8; 32];
+        assert_eq!(first.random_data(&mut output), Err(RngError::NotSeeded));
+
+        // Both sources give the same events to pool 0 and different ones to
+        // pool 1.
+        let (mut first_source, mut second_source) = (EventSource::new(1), EventSource::new(1));
+        let mut feed = |first: &mut Fortuna, second: &mut Fortuna| {
+            for _ in 0..2 * 32 {
+                first_source.add_event(first, &[0xaa; 32]).unwrap();
+                second_source.add_event(second, &[0xaa; 32]).unwrap();
+            }
+            first.add_random_event(2, 1, b"first").unwrap();
+            second.add_random_event(2, 1, b"second").unwrap();
+        };
+
+        feed(&mut first, &mut second);
+        let mut second_output = [0u8; 32];
+        first.random_data(&mut output).unwrap();
+        second.random_data(&mut second_output).unwrap();
+        assert_eq!(output, second_output);
+        assert_eq!(first.reseed_count(), 1);
+
+        // Too soon after the first reseed
+        feed(&mut first, &mut second);
+        first.random_data(&mut output).unwrap();
+        assert_eq!(first.reseed_count(), 1);
+
+        std::thread::sleep(std::time::Duration::from_millis(110));
+        first.random_data(&mut output).unwrap();
+        second.random_data(&mut second_output).unwrap();
+        assert_eq!(first.reseed_count(), 2);
+        assert_eq!(second.reseed_count(), 2);
+        assert_ne!(output, second_output);
+
+        // No new events in pool 0, so no reseed however long it has been
+        std::thread::sleep(std::time::Duration::from_millis(110));
+        first.random_data(&mut output).unwrap();
+        assert_eq!(first.reseed_count(), 2);
+
+        assert_eq!(
+            first.add_random_event(0, 32, b"data"),
+            Err(RngError::InvalidEvent)
+        );
+        assert_eq!(
+            first.add_random_event(0, 0, b""),
+            Err(RngError::InvalidEvent)
+        );
+        assert_eq!(
+            first.add_random_event(0, 0, &[0; 33]),
+            Err(RngError::InvalidEvent)
+        );
+    }
+
+    ///
+    /// A seed file seeds a fresh accumulator and is replaced as it is
+    /// read, and the accumulator seeds a DRBG as an entropy source.
+    ///
+    #[cfg(feature = "std")]
+    #[test]
+    fn fortuna_seed_file_test() {
+        use armadillo::rng::{Fortuna, SEED_FILE_LENGTH};
+
+        let path =
+            std::env::temp_dir().join(format!("armadillo-fortuna-{}.seed", std::process::id()));
+        let mut fortuna = Fortuna::new();
+        assert!(fortuna.write_seed_file(&path).is_err());
+        assert!(!path.exists());
+        fortuna.add_random_event(0, 0, &[0x55; 32]).unwrap();
+        fortuna.add_random_event(0, 0, &[0x66; 32]).unwrap();
+        fortuna.write_seed_file(&path).unwrap();
+        let seed = std::fs::read(&path).unwrap();
+        assert_eq!(seed.len(), SEED_FILE_LENGTH);
+
+        let mut restarted = Fortuna::new();
+        restarted.load_seed_file(&path).unwrap();
+        assert_eq!(restarted.reseed_count(), 0);
+        assert_ne!(std::fs::read(&path).unwrap(), seed);
+        let mut drbg = HmacDrbgSha256::from_entropy_source(&mut restarted, b"").unwrap();
+        let mut output = [0u8; 32];
+        drbg.generate(&mut output, b"").unwrap();
+
+        std::fs::write(&path, &seed[..SEED_FILE_LENGTH - 1]).unwrap();
+        assert_eq!(
+            Fortuna::new().load_seed_file(&path).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert!(matches!(
+            HmacDrbgSha256::from_entropy_source(&mut Fortuna::new(), b""),
+            Err(RngError::EntropySourceFailed)
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
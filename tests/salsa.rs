@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod salsa_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::salsa::{hsalsa20, Salsa20, Salsa20Block, XSalsa20};
+
+    const TEST_KEY: [u8; 32] =
+        hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+
+    ///
+    /// Simple test to verify that the quarter round operation is working
+    /// correctly.
+    ///
+    /// Taken from the Salsa20 specification, quarterround(1, 0, 0, 0).
+    /// [Source](https://cr.yp.to/snuffle/spec.pdf)
+    ///
+    #[test]
+    fn simple_quarter_round_test() {
+        // Words 1-4 hold the first key bytes, so (1, 0, 0, 0) can be set
+        // through the key.
+        let mut key = [0u8; 32];
+        key[0] = 1;
+        let mut block = Salsa20Block::new(key, [0u8; 8], 0);
+        block.quarter_round(1, 2, 3, 4);
+
+        let result_state = block.get_state();
+        assert_eq!(result_state[1], 0x08008145);
+        assert_eq!(result_state[2], 0x00000080);
+        assert_eq!(result_state[3], 0x00010200);
+        assert_eq!(result_state[4], 0x20500000);
+    }
+
+    ///
+    /// Salsa20 keystream compared against libsodium's crypto_stream_salsa20.
+    ///
+    #[test]
+    fn simple_keystream_test() {
+        let mut cipher = Salsa20::new(TEST_KEY, hex!("0102030405060708"));
+
+        let expected = hex!("2d8626a68e241c92749dc7efa74b6ee4b86f375ea5fef57c0d7c5d431c17dc3cdc87684cf21de0336c440a48569906510c3524e9a11077ce75c23321ce4afcdc09aab4c3d031467ab246a5732bce5b2c486243475819666bf9a698f90d3cb64b4b482e5eed4d0323e7d6548d3445ed9a4db2326f14ddcbacee90caeb3a8a4bd660f1");
+        assert_eq!(cipher.encrypt(&[0u8; 130]), expected);
+    }
+
+    ///
+    /// HSalsa20 subkey compared against libsodium's crypto_core_hsalsa20.
+    ///
+    #[test]
+    fn simple_hsalsa20_test() {
+        let nonce = hex!("6465666768696a6b6c6d6e6f70717273");
+
+        let expected = hex!("d0e0df786a40b0aec89ca886cd211b1571660c9902c957cef234a547650a993a");
+        assert_eq!(hsalsa20(TEST_KEY, nonce), expected);
+    }
+
+    ///
+    /// XSalsa20 keystream compared against libsodium's crypto_stream_xsalsa20.
+    ///
+    #[test]
+    fn simple_xsalsa20_test() {
+        let nonce = hex!("c8c9cacbcccdcecfd0d1d2d3d4d5d6d7d8d9dadbdcdddedf");
+        let mut cipher = XSalsa20::new(TEST_KEY, nonce);
+
+        let expected = hex!("e94d28ab44d7b0185ee2b121046e9e3a1a3b2b80d9a659de04284c51060f9e81fa526f75403251c28de883c3ae3dbf0fe5139e9503bd258166279d9e89b322f69f96b54bead85b8d3825ecd6c2d35bb1c998eae6df24bbdd11652c8848bae6c5b0dcff559a2f4616389b154160bb7eaa6bad0c8bddffe3c84c35d314de3baa93df4c");
+        assert_eq!(cipher.encrypt(&[0u8; 130]), expected);
+    }
+
+    ///
+    /// Encrypting in chunks must give the same result as encrypting at once.
+    ///
+    #[test]
+    fn chunked_encrypt_test() {
+        let nonce = hex!("0102030405060708");
+        let plaintext: Vec<u8> = (0..=255).collect();
+        let expected = Salsa20::new(TEST_KEY, nonce).encrypt(&plaintext);
+
+        let mut cipher = Salsa20::new(TEST_KEY, nonce);
+        let chunked: Vec<u8> = plaintext
+            .chunks(7)
+            .flat_map(|chunk| cipher.encrypt(chunk))
+            .collect();
+        assert_eq!(chunked, expected);
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod scrypt_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    #[cfg(feature = "parallel")]
+    use armadillo::scrypt::par_scrypt;
+    use armadillo::scrypt::{scrypt, Params, ScryptError};
+
+    ///
+    /// The first three scrypt test vectors.  The fourth, with N = 2^20,
+    /// takes a gigabyte and is left out.
+    ///
+    /// Taken from RFC 7914.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc7914#section-12)
+    ///
+    #[test]
+    fn rfc_7914_test() {
+        type Case = (&'static [u8], &'static [u8], u64, u32, u32, [u8; 64]);
+        let cases: [Case; 3] = [
+            (
+                b"",
+                b"",
+                16,
+                1,
+                1,
+                hex!(
+                    "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442"
+                    "fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+                ),
+            ),
+            (
+                b"password",
+                b"NaCl",
+                1024,
+                8,
+                16,
+                hex!(
+                    "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162"
+                    "2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+                ),
+            ),
+            (
+                b"pleaseletmein",
+                b"SodiumChloride",
+                16384,
+                8,
+                1,
+                hex!(
+                    "7023bdcb3afd7348461c06cd81fd38ebfda8fbba904f8e3ea9b543f6545da1f2"
+                    "d5432955613f0fcf62d49705242a9af9e61e85dc0d651e40dfcf017b45575887"
+                ),
+            ),
+        ];
+
+        for (password, salt, n, r, p, expected) in cases {
+            let params = Params::new(n, r, p).unwrap();
+            let mut output = [0u8; 64];
+            scrypt(password, salt, &params, &mut output).unwrap();
+            assert_eq!(output, expected);
+        }
+    }
+
+    ///
+    /// The parallel version derives the same key.
+    ///
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_test() {
+        let params = Params::new(1024, 8, 16).unwrap();
+        let mut output = [0u8; 64];
+        par_scrypt(b"password", b"NaCl", &params, &mut output).unwrap();
+        assert_eq!(
+            output,
+            hex!(
+                "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162"
+                "2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+            )
+        );
+    }
+
+    ///
+    /// Parameters outside what scrypt allows are rejected.
+    ///
+    #[test]
+    fn parameters_test() {
+        assert_eq!(Params::new(0, 8, 1), Err(ScryptError::InvalidCost));
+        assert_eq!(Params::new(1, 8, 1), Err(ScryptError::InvalidCost));
+        assert_eq!(Params::new(1000, 8, 1), Err(ScryptError::InvalidCost));
+        assert_eq!(Params::new(1 << 16, 1, 1), Err(ScryptError::InvalidCost));
+        assert_eq!(Params::new(1024, 0, 1), Err(ScryptError::InvalidBlockSize));
+        assert_eq!(
+            Params::new(1024, 8, 0),
+            Err(ScryptError::InvalidParallelism)
+        );
+        assert_eq!(
+            Params::new(1024, 1 << 15, 1 << 15),
+            Err(ScryptError::InvalidParallelism)
+        );
+
+        let params = Params::new(16, 1, 1).unwrap();
+        assert_eq!(
+            scrypt(b"password", b"salt", &params, &mut []),
+            Err(ScryptError::InvalidOutputLength)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod secretbox_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::secretbox::{open, open_detached, seal, seal_detached, SecretBoxError};
+
+    const TEST_KEY: [u8; 32] =
+        hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+    const TEST_NONCE: [u8; 24] = hex!("c8c9cacbcccdcecfd0d1d2d3d4d5d6d7d8d9dadbdcdddedf");
+    const TEST_MESSAGE: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    ///
+    /// Box compared against libsodium's crypto_secretbox_easy.
+    ///
+    #[test]
+    fn simple_seal_test() {
+        let expected = hex!("d40bccb26f44f631ced4fd92553ef7c2b6330b1c254171a3e38ca384cb53cb63807efbfb23d243a1124ff8beeadf4385ecb6da2dcaff62b40205a5b0e29a7bd2a6ed8682ff4bddbb74170cf127cfc6aadeb08675f54123364cf2656106d40c8a1fc569abbb8a97bd3e50ff34ad4ec4e0bc3e1056bfd5e746ab4abf0f03487a97fd35");
+        assert_eq!(seal(TEST_KEY, TEST_NONCE, TEST_MESSAGE), expected);
+        assert_eq!(open(TEST_KEY, TEST_NONCE, &expected).unwrap(), TEST_MESSAGE);
+    }
+
+    ///
+    /// An empty message still produces and verifies a tag.
+    ///
+    #[test]
+    fn empty_message_test() {
+        let expected = hex!("1a3b2b80d9a659de04284c51060f9e81");
+        assert_eq!(seal(TEST_KEY, TEST_NONCE, &[]), expected);
+        assert_eq!(open(TEST_KEY, TEST_NONCE, &expected).unwrap(), []);
+    }
+
+    ///
+    /// Any modification of the box, or the wrong nonce, must be rejected.
+    ///
+    #[test]
+    fn tampered_box_test() {
+        let sealed = seal(TEST_KEY, TEST_NONCE, TEST_MESSAGE);
+
+        for i in 0..sealed.len() {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 0x80;
+            assert_eq!(
+                open(TEST_KEY, TEST_NONCE, &tampered),
+                Err(SecretBoxError::InvalidTag)
+            );
+        }
+        assert_eq!(
+            open(TEST_KEY, [0u8; 24], &sealed),
+            Err(SecretBoxError::InvalidTag)
+        );
+        assert_eq!(
+            open(TEST_KEY, TEST_NONCE, &sealed[..15]),
+            Err(SecretBoxError::TooShort)
+        );
+    }
+
+    ///
+    /// The detached ciphertext and tag are the two halves of the combined box.
+    ///
+    #[test]
+    fn detached_test() {
+        let sealed = seal(TEST_KEY, TEST_NONCE, TEST_MESSAGE);
+        let (ciphertext, tag) = seal_detached(TEST_KEY, TEST_NONCE, TEST_MESSAGE);
+        assert_eq!(tag.as_ref(), &sealed[..16]);
+        assert_eq!(ciphertext, &sealed[16..]);
+        assert_eq!(
+            open_detached(TEST_KEY, TEST_NONCE, &ciphertext, &tag).unwrap(),
+            TEST_MESSAGE
+        );
+
+        let mut tampered = ciphertext.clone();
+        tampered[0] ^= 1;
+        assert_eq!(
+            open_detached(TEST_KEY, TEST_NONCE, &tampered, &tag),
+            Err(SecretBoxError::InvalidTag)
+        );
+    }
+}
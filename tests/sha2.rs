@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod sha2_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::sha2::{sha256, sha512, Sha256, Sha512};
+
+    const TWO_BLOCK_MESSAGE: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    const SHA512_TWO_BLOCK_MESSAGE: &[u8] =
+        b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+        hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    ///
+    /// One-block, two-block and million-byte messages, and the empty one.
+    ///
+    /// Taken from the NIST examples for FIPS 180-4.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values)
+    ///
+    #[test]
+    fn sha256_test() {
+        assert_eq!(
+            sha256(b""),
+            hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        assert_eq!(
+            sha256(b"abc"),
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            sha256(TWO_BLOCK_MESSAGE),
+            hex!("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+        );
+        assert_eq!(
+            sha256(&[b'a'; 1_000_000]),
+            hex!("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+        );
+    }
+
+    ///
+    /// One-block, two-block and million-byte messages, and the empty one.
+    ///
+    /// Taken from the NIST examples for FIPS 180-4.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values)
+    ///
+    #[test]
+    fn sha512_test() {
+        assert_eq!(
+            sha512(b""),
+            hex!(
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce"
+                "47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+            )
+        );
+        assert_eq!(
+            sha512(b"abc"),
+            hex!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            )
+        );
+        assert_eq!(
+            sha512(SHA512_TWO_BLOCK_MESSAGE),
+            hex!(
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018"
+                "501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+            )
+        );
+        assert_eq!(
+            sha512(&[b'a'; 1_000_000]),
+            hex!(
+                "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb"
+                "de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+            )
+        );
+    }
+
+    ///
+    /// Splitting the message anywhere gives the same hash, including around
+    /// the lengths where the padding spills into another block.
+    ///
+    #[test]
+    fn streaming_test() {
+        let data: Vec<u8> = (0..=255).collect();
+
+        for length in [0, 55, 56, 63, 64, 111, 112, 127, 128, 200, 256] {
+            let message = &data[..length];
+            for split in [0, length / 3, length / 2, length] {
+                let mut hash = Sha256::new();
+                hash.update(&message[..split]);
+                hash.update(&message[split..]);
+                assert_eq!(hash.finalize(), sha256(message));
+
+                let mut hash = Sha512::default();
+                hash.update(&message[..split]);
+                hash.clone().update(b"ignored");
+                hash.update(&message[split..]);
+                assert_eq!(hash.finalize(), sha512(message));
+            }
+        }
+    }
+
+    ///
+    /// The portable compression function, which the SHA extensions would
+    /// otherwise always replace on x86, gives the same digests, whether the
+    /// message arrives at once or in pieces.
+    ///
+    /// Taken from the NIST examples for FIPS 180-4.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values)
+    ///
+    #[test]
+    fn sha256_soft_test() {
+        let soft_sha256 = |data: &[u8]| {
+            let mut hash = Sha256::new_soft();
+            hash.update(data);
+            hash.finalize()
+        };
+        assert_eq!(
+            soft_sha256(b""),
+            hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        assert_eq!(
+            soft_sha256(b"abc"),
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            soft_sha256(TWO_BLOCK_MESSAGE),
+            hex!("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+        );
+
+        let mut hash = Sha256::new_soft();
+        for _ in 0..1000 {
+            hash.update(&[b'a'; 1000]);
+        }
+        assert_eq!(
+            hash.finalize(),
+            hex!("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+        );
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod sha3_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::sha3::{sha3_256, sha3_512, Sha3_256, Sha3_512, Shake128, Shake256};
+
+    /// The 1600-bit message of the NIST examples, longer than every rate.
+    const LONG_MESSAGE: [u8; 200] = [0xa3; 200];
+
+    ///
+    /// The empty message, "abc" and the 1600-bit message.
+    ///
+    /// Taken from the NIST examples for FIPS 202.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values)
+    ///
+    #[test]
+    fn sha3_test() {
+        assert_eq!(
+            sha3_256(b""),
+            hex!("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+        );
+        assert_eq!(
+            sha3_256(b"abc"),
+            hex!("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+        );
+        assert_eq!(
+            sha3_256(&LONG_MESSAGE),
+            hex!("79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787")
+        );
+
+        assert_eq!(
+            sha3_512(b"abc"),
+            hex!(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e"
+                "10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+            )
+        );
+        assert_eq!(
+            sha3_512(&LONG_MESSAGE),
+            hex!(
+                "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8"
+                "1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00"
+            )
+        );
+    }
+
+    ///
+    /// SHAKE128 and SHAKE256 of the empty and the 1600-bit message, and
+    /// output read across several permutations in uneven pieces.
+    ///
+    /// Taken from the NIST examples for FIPS 202.
+    /// [Source](https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values)
+    ///
+    #[test]
+    fn shake_test() {
+        let mut output = [0u8; 32];
+        Shake128::new().finalize_xof().read(&mut output);
+        assert_eq!(
+            output,
+            hex!("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")
+        );
+
+        let mut shake = Shake128::new();
+        shake.update(&LONG_MESSAGE);
+        shake.finalize_xof().read(&mut output);
+        assert_eq!(
+            output,
+            hex!("131ab8d2b594946b9c81333f9bb6e0ce75c3b93104fa3469d3917457385da037")
+        );
+
+        let mut output = [0u8; 64];
+        let mut shake = Shake256::new();
+        shake.update(&LONG_MESSAGE);
+        shake.finalize_xof().read(&mut output);
+        assert_eq!(
+            output,
+            hex!(
+                "cd8a920ed141aa0407a22d59288652e9d9f1a7ee0c1e7c1ca699424da84a904d"
+                "2d700caae7396ece96604440577da4f3aa22aeb8857f961c4cd8e06f0ae6610b"
+            )
+        );
+
+        let mut long = [0u8; 500];
+        let mut reader = Shake128::new().finalize_xof();
+        for chunk in long.chunks_mut(67) {
+            reader.read(chunk);
+        }
+        assert_eq!(
+            long[468..],
+            hex!("4e1300b9b2153d2041d205b443e41b45a653f2a5c4492c1add544512dda25298")
+        );
+    }
+
+    ///
+    /// Splitting the message anywhere gives the same output, including
+    /// around the rate boundaries.
+    ///
+    #[test]
+    fn streaming_test() {
+        let data: Vec<u8> = (0..=255).collect();
+
+        for length in [0, 7, 8, 71, 72, 135, 136, 167, 168, 256] {
+            let message = &data[..length];
+            for split in [0, 1.min(length), length / 3, length] {
+                let mut hash = Sha3_256::new();
+                hash.update(&message[..split]);
+                hash.update(&message[split..]);
+                assert_eq!(hash.finalize(), sha3_256(message));
+
+                let mut hash = Sha3_512::default();
+                hash.update(&message[..split]);
+                hash.update(&message[split..]);
+                assert_eq!(hash.finalize(), sha3_512(message));
+
+                let (mut whole, mut split_output) = ([0u8; 40], [0u8; 40]);
+                let mut shake = Shake256::new();
+                shake.update(message);
+                shake.finalize_xof().read(&mut whole);
+                let mut shake = Shake256::new();
+                shake.update(&message[..split]);
+                shake.update(&message[split..]);
+                shake.finalize_xof().read(&mut split_output);
+                assert_eq!(whole, split_output);
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+#![cfg(feature = "libsodium-interop")]
+
+#[cfg(test)]
+mod sodium_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+    use libsodium_sys as sodium;
+
+    use armadillo::aead::ChaCha20Poly1305;
+    use armadillo::chacha::ChaCha20;
+    use armadillo::poly::poly1305_mac;
+    use armadillo::secretbox;
+
+    const TEST_KEY: [u8; 32] =
+        hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+    const TEST_NONCE: [u8; 12] = hex!("000000090000004a00000000");
+
+    ///
+    /// Message lengths exercised against libsodium, chosen around the block
+    /// boundaries of both ChaCha20 and Poly1305.
+    ///
+    const LENGTHS: [usize; 10] = [0, 1, 15, 16, 17, 63, 64, 65, 130, 1000];
+
+    fn init() {
+        assert!(unsafe { sodium::sodium_init() } >= 0);
+    }
+
+    fn message(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + 3) as u8).collect()
+    }
+
+    fn sodium_chacha20(data: &[u8], nonce: &[u8; 12], counter: u32, key: &[u8; 32]) -> Vec<u8> {
+        let mut out = vec![0u8; data.len()];
+        let result = unsafe {
+            sodium::crypto_stream_chacha20_ietf_xor_ic(
+                out.as_mut_ptr(),
+                data.as_ptr(),
+                data.len() as u64,
+                nonce.as_ptr(),
+                counter,
+                key.as_ptr(),
+            )
+        };
+        assert_eq!(result, 0);
+        out
+    }
+
+    ///
+    /// Ciphertext produced by armadillo must decrypt with libsodium's IETF
+    /// ChaCha20, and the other way around.
+    ///
+    #[test]
+    fn chacha20_both_directions_test() {
+        init();
+        for len in LENGTHS {
+            let plaintext = message(len);
+
+            let ciphertext = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&plaintext);
+            let decrypted = sodium_chacha20(&ciphertext, &TEST_NONCE, 1, &TEST_KEY);
+            assert_eq!(decrypted, plaintext, "length {}", len);
+
+            let ciphertext = sodium_chacha20(&plaintext, &TEST_NONCE, 1, &TEST_KEY);
+            let decrypted = ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(&ciphertext);
+            assert_eq!(decrypted, plaintext, "length {}", len);
+        }
+    }
+
+    ///
+    /// libsodium's IETF API takes the initial block counter as a parameter,
+    /// which must match `new_with_counter`.
+    ///
+    #[test]
+    fn chacha20_initial_counter_test() {
+        init();
+        let plaintext = message(300);
+        for counter in [0, 1, 7, u32::MAX - 4] {
+            let ciphertext =
+                ChaCha20::new_with_counter(TEST_KEY, TEST_NONCE, counter).encrypt(&plaintext);
+            let expected = sodium_chacha20(&plaintext, &TEST_NONCE, counter, &TEST_KEY);
+            assert_eq!(ciphertext, expected, "counter {}", counter);
+        }
+    }
+
+    ///
+    /// Tags produced by armadillo must verify with libsodium, and match the
+    /// tags libsodium produces itself.
+    ///
+    #[test]
+    fn poly1305_both_directions_test() {
+        init();
+        for len in LENGTHS {
+            let data = message(len);
+
+            let tag = poly1305_mac(TEST_KEY, &data);
+            let verified = unsafe {
+                sodium::crypto_onetimeauth_poly1305_verify(
+                    tag.as_bytes().as_ptr(),
+                    data.as_ptr(),
+                    data.len() as u64,
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(verified, 0, "length {}", len);
+
+            let mut expected = [0u8; 16];
+            let result = unsafe {
+                sodium::crypto_onetimeauth_poly1305(
+                    expected.as_mut_ptr(),
+                    data.as_ptr(),
+                    data.len() as u64,
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0);
+            assert_eq!(tag, expected, "length {}", len);
+        }
+    }
+
+    ///
+    /// Boxes sealed by armadillo must open with libsodium's
+    /// crypto_secretbox_open_easy, and the other way around.
+    ///
+    #[test]
+    fn secretbox_both_directions_test() {
+        init();
+        let nonce = [7u8; 24];
+        for len in LENGTHS {
+            let message = message(len);
+
+            let sealed = secretbox::seal(TEST_KEY, nonce, &message);
+            let mut opened = vec![0u8; len];
+            let result = unsafe {
+                sodium::crypto_secretbox_open_easy(
+                    opened.as_mut_ptr(),
+                    sealed.as_ptr(),
+                    sealed.len() as u64,
+                    nonce.as_ptr(),
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0, "length {}", len);
+            assert_eq!(opened, message, "length {}", len);
+
+            let mut sealed = vec![0u8; len + 16];
+            let result = unsafe {
+                sodium::crypto_secretbox_easy(
+                    sealed.as_mut_ptr(),
+                    message.as_ptr(),
+                    message.len() as u64,
+                    nonce.as_ptr(),
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0);
+            let opened = secretbox::open(TEST_KEY, nonce, &sealed).unwrap();
+            assert_eq!(opened, message, "length {}", len);
+        }
+    }
+
+    ///
+    /// Detached boxes sealed by armadillo must open with libsodium's
+    /// crypto_secretbox_open_detached, and the other way around.
+    ///
+    #[test]
+    fn secretbox_detached_both_directions_test() {
+        init();
+        let nonce = [9u8; 24];
+        for len in LENGTHS {
+            let message = message(len);
+
+            let (ciphertext, tag) = secretbox::seal_detached(TEST_KEY, nonce, &message);
+            let mut opened = vec![0u8; len];
+            let result = unsafe {
+                sodium::crypto_secretbox_open_detached(
+                    opened.as_mut_ptr(),
+                    ciphertext.as_ptr(),
+                    tag.as_bytes().as_ptr(),
+                    ciphertext.len() as u64,
+                    nonce.as_ptr(),
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0, "length {}", len);
+            assert_eq!(opened, message, "length {}", len);
+
+            let mut ciphertext = vec![0u8; len];
+            let mut tag = [0u8; 16];
+            let result = unsafe {
+                sodium::crypto_secretbox_detached(
+                    ciphertext.as_mut_ptr(),
+                    tag.as_mut_ptr(),
+                    message.as_ptr(),
+                    message.len() as u64,
+                    nonce.as_ptr(),
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0);
+            let opened =
+                secretbox::open_detached(TEST_KEY, nonce, &ciphertext, &tag.into()).unwrap();
+            assert_eq!(opened, message, "length {}", len);
+        }
+    }
+
+    ///
+    /// ChaCha20-Poly1305 ciphertexts must open with libsodium's IETF AEAD,
+    /// and the other way around.
+    ///
+    #[test]
+    fn chacha20_poly1305_both_directions_test() {
+        init();
+        let aead = ChaCha20Poly1305::new(TEST_KEY);
+        for len in LENGTHS {
+            let message = message(len);
+            let aad = &message[..len / 2];
+
+            let sealed = aead.seal(TEST_NONCE, aad, &message);
+            let mut opened = vec![0u8; len];
+            let mut opened_len = 0u64;
+            let result = unsafe {
+                sodium::crypto_aead_chacha20poly1305_ietf_decrypt(
+                    opened.as_mut_ptr(),
+                    &mut opened_len,
+                    std::ptr::null_mut(),
+                    sealed.as_ptr(),
+                    sealed.len() as u64,
+                    aad.as_ptr(),
+                    aad.len() as u64,
+                    TEST_NONCE.as_ptr(),
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0, "length {}", len);
+            assert_eq!(opened, message, "length {}", len);
+
+            let mut sealed = vec![0u8; len + 16];
+            let mut sealed_len = 0u64;
+            let result = unsafe {
+                sodium::crypto_aead_chacha20poly1305_ietf_encrypt(
+                    sealed.as_mut_ptr(),
+                    &mut sealed_len,
+                    message.as_ptr(),
+                    message.len() as u64,
+                    aad.as_ptr(),
+                    aad.len() as u64,
+                    std::ptr::null(),
+                    TEST_NONCE.as_ptr(),
+                    TEST_KEY.as_ptr(),
+                )
+            };
+            assert_eq!(result, 0);
+            let opened = aead.open(TEST_NONCE, aad, &sealed).unwrap();
+            assert_eq!(opened, message, "length {}", len);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+#[cfg(test)]
+mod srtp_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::srtp::{
+        rtp_header_length, srtp_kdf, KeyLabel, RtpIndexTracker, SrtpChaCha20Poly1305, SrtpError,
+        REPLAY_WINDOW,
+    };
+
+    const TEST_MASTER_KEY: [u8; 32] =
+        hex!("202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f");
+    const TEST_MASTER_SALT: [u8; 12] = hex!("a0a1a2a3a4a5a6a7a8a9aaab");
+
+    /// An RTP packet with one CSRC and a one-word header extension.
+    const TEST_RTP: [u8; 48] = hex!("916012340000a8fdcafebabe11223344beef00010102030448656c6c6f2c205352545020776974682043686143686121");
+    const TEST_SRTP: [u8; 64] = hex!("916012340000a8fdcafebabe11223344beef0001010203043167662525cf73ed6b959aa447c2162193f4ac7fe0b9c7fef8fca7fbfe5ce9bf0c597516cb2f89f9");
+
+    /// An RTCP receiver report from SSRC deadbeef.
+    const TEST_RTCP: [u8; 32] =
+        hex!("81c90007deadbeef000102030405060708090a0b0c0d0e0f1011121314151617");
+    const TEST_SRTCP: [u8; 52] = hex!("81c90007deadbeef8c4ec1f906b119b112e1fe0980c74344c0f582bb080a743c104310da21f7191312166e763f65eea381234567");
+
+    ///
+    /// Session key and salt derivation with AES-256.
+    ///
+    /// Taken from RFC 6188, Section 7.2.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc6188#section-7.2)
+    ///
+    #[test]
+    fn kdf_test() {
+        let master_key = hex!("f0f04914b513f2763a1b1fa130f10e2998f6f6e43e4309d1e622a0e332b9f1b6");
+        let master_salt = hex!("3b04803de51ee7c96423ab5b78d2");
+
+        let mut cipher_key = [0u8; 32];
+        srtp_kdf(
+            master_key,
+            &master_salt,
+            KeyLabel::RtpEncryption,
+            &mut cipher_key,
+        );
+        assert_eq!(
+            cipher_key,
+            hex!("5ba1064e30ec51613cad926c5a28ef731ec7fb397f70a960653caf06554cd8c4")
+        );
+
+        let mut cipher_salt = [0u8; 14];
+        srtp_kdf(
+            master_key,
+            &master_salt,
+            KeyLabel::RtpSalt,
+            &mut cipher_salt,
+        );
+        assert_eq!(cipher_salt, hex!("fa31791685ca444a9e07c6c64e93"));
+    }
+
+    ///
+    /// Protecting an RTP packet authenticates its header, CSRC and
+    /// extension, and encrypts only the payload.
+    ///
+    /// Generated with Python's cryptography package.
+    ///
+    #[test]
+    fn rtp_test() {
+        let context = SrtpChaCha20Poly1305::new(TEST_MASTER_KEY, TEST_MASTER_SALT);
+        assert_eq!(rtp_header_length(&TEST_RTP), Ok(24));
+
+        assert_eq!(context.protect_rtp(7, &TEST_RTP).unwrap(), TEST_SRTP);
+        assert_eq!(context.unprotect_rtp(7, &TEST_SRTP).unwrap(), TEST_RTP);
+
+        let mut buffer = TEST_RTP;
+        let tag = context.protect_rtp_in_place(7, &mut buffer).unwrap();
+        assert_eq!(buffer[..], TEST_SRTP[..48]);
+        assert_eq!(tag.as_ref(), &TEST_SRTP[48..]);
+
+        let mut buffer = TEST_SRTP;
+        let rtp = context.unprotect_rtp_in_place(7, &mut buffer).unwrap();
+        assert_eq!(rtp, &TEST_RTP[..]);
+    }
+
+    ///
+    /// Protecting an RTCP packet encrypts everything after the sender
+    /// SSRC, and appends the tag and the SRTCP index with the E flag.
+    ///
+    /// Generated with Python's cryptography package.
+    ///
+    #[test]
+    fn rtcp_test() {
+        let context = SrtpChaCha20Poly1305::new(TEST_MASTER_KEY, TEST_MASTER_SALT);
+
+        assert_eq!(
+            context.protect_rtcp(0x1234567, &TEST_RTCP).unwrap(),
+            TEST_SRTCP
+        );
+        assert_eq!(
+            context.unprotect_rtcp(&TEST_SRTCP).unwrap(),
+            (TEST_RTCP.to_vec(), 0x1234567)
+        );
+        assert_eq!(
+            context.protect_rtcp(1 << 31, &TEST_RTCP),
+            Err(SrtpError::IndexOverflow)
+        );
+    }
+
+    ///
+    /// Changing any byte, or using the wrong ROC, fails authentication,
+    /// and malformed packets are rejected.
+    ///
+    #[test]
+    fn rejection_test() {
+        let context = SrtpChaCha20Poly1305::new(TEST_MASTER_KEY, TEST_MASTER_SALT);
+
+        for i in 0..TEST_SRTP.len() {
+            let mut tampered = TEST_SRTP;
+            tampered[i] ^= 0x01;
+            let result = context.unprotect_rtp(7, &tampered);
+            assert!(
+                result == Err(SrtpError::InvalidTag) || result == Err(SrtpError::InvalidHeader),
+                "byte {}",
+                i
+            );
+        }
+        assert_eq!(
+            context.unprotect_rtp(8, &TEST_SRTP),
+            Err(SrtpError::InvalidTag)
+        );
+        for i in 0..TEST_SRTCP.len() - 4 {
+            let mut tampered = TEST_SRTCP;
+            tampered[i] ^= 0x01;
+            let result = context.unprotect_rtcp(&tampered);
+            assert!(
+                result == Err(SrtpError::InvalidTag) || result == Err(SrtpError::InvalidHeader),
+                "byte {}",
+                i
+            );
+        }
+
+        let mut unencrypted = TEST_SRTCP;
+        unencrypted[48] &= 0x7f;
+        assert_eq!(
+            context.unprotect_rtcp(&unencrypted),
+            Err(SrtpError::InvalidHeader)
+        );
+
+        assert_eq!(
+            context.unprotect_rtp(7, &TEST_SRTP[..24 + 15]),
+            Err(SrtpError::TooShort)
+        );
+        assert_eq!(
+            context.unprotect_rtcp(&TEST_SRTCP[..27]),
+            Err(SrtpError::TooShort)
+        );
+        assert_eq!(
+            rtp_header_length(&TEST_RTP[..23]),
+            Err(SrtpError::InvalidHeader)
+        );
+        let mut version_one = TEST_RTP;
+        version_one[0] = 0x51;
+        assert_eq!(
+            context.protect_rtp(0, &version_one),
+            Err(SrtpError::InvalidHeader)
+        );
+    }
+
+    ///
+    /// The index estimate follows the sequence number across wraps in both
+    /// directions.
+    ///
+    /// The cases follow the algorithm of RFC 3711, Appendix A.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc3711#appendix-A)
+    ///
+    #[test]
+    fn index_estimate_test() {
+        let mut tracker = RtpIndexTracker::new(0);
+        assert_eq!(tracker.estimate(0xfff0), 0xfff0);
+        tracker.accept(0xfff0);
+
+        // Wrapping forward moves to the next ROC
+        assert_eq!(tracker.estimate(0x0005), 0x1_0005);
+        tracker.accept(0x1_0005);
+        assert_eq!(tracker.roc(), 1);
+
+        // A late packet from before the wrap keeps the previous ROC
+        assert_eq!(tracker.estimate(0xfff8), 0xfff8);
+        tracker.accept(0xfff8);
+        assert_eq!(tracker.roc(), 1);
+        assert_eq!(tracker.estimate(0x8004), 0x1_8004);
+
+        // Past half the sequence space, the small numbers are the next ROC
+        tracker.accept(0x1_9000);
+        assert_eq!(tracker.estimate(0x0fff), 0x2_0fff);
+        assert_eq!(tracker.estimate(0x1001), 0x1_1001);
+
+        // There is no ROC before zero
+        let mut tracker = RtpIndexTracker::new(0);
+        tracker.accept(0x0010);
+        assert_eq!(tracker.estimate(0xfff0), 0xfff0);
+
+        assert_eq!(RtpIndexTracker::new(5).estimate(0x0001), 0x5_0001);
+    }
+
+    ///
+    /// Packets accepted before, and packets older than the window, are
+    /// reported as replays.
+    ///
+    #[test]
+    fn replay_test() {
+        let mut tracker = RtpIndexTracker::new(0);
+        assert_eq!(tracker.check(100), Ok(()));
+        tracker.accept(100);
+        assert_eq!(tracker.check(100), Err(SrtpError::Replayed));
+
+        // Out of order, within the window
+        assert_eq!(tracker.check(98), Ok(()));
+        tracker.accept(98);
+        assert_eq!(tracker.check(98), Err(SrtpError::Replayed));
+        assert_eq!(tracker.check(99), Ok(()));
+
+        tracker.accept(110);
+        assert_eq!(tracker.check(100), Err(SrtpError::Replayed));
+        assert_eq!(tracker.check(99), Ok(()));
+        assert_eq!(tracker.check(110 - REPLAY_WINDOW), Err(SrtpError::Replayed));
+        assert_eq!(tracker.check(111 - REPLAY_WINDOW), Ok(()));
+
+        // A jump beyond the window forgets everything before it
+        tracker.accept(1000);
+        assert_eq!(tracker.check(999), Ok(()));
+        assert_eq!(tracker.check(1000), Err(SrtpError::Replayed));
+    }
+}
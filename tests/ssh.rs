@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod ssh_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::ssh::{OpenSshChaCha20Poly1305, OpenSshError};
+
+    const TEST_KEY: [u8; 64] = hex!("01060b10151a1f24292e33383d42474c51565b60656a6f74797e83888d92979ca1a6abb0b5babfc4c9ced3d8dde2e7ecf1f6fb00050a0f14191e23282d32373c");
+
+    ///
+    /// A service request packet, encrypted as the first packet after key
+    /// exchange (sequence number 0).
+    ///
+    /// Expected output computed independently from the OpenSSH protocol
+    /// description, with the original 64-bit nonce ChaCha20 layout.
+    /// [Source](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.chacha20poly1305)
+    ///
+    #[test]
+    fn service_request_encrypt_test() {
+        let cipher = OpenSshChaCha20Poly1305::new(TEST_KEY);
+        let packet = hex!("0000001c0a050000000c7373682d757365726175746800000000000000000000");
+
+        let expected = hex!("52bc0f67cabd0e227f3f21c800b5803198dad944ea5f9d8c2f825d6c21bde16394245f06dc9a4314f8c5903e9ce4482e");
+        assert_eq!(cipher.encrypt(0, &packet).unwrap(), expected);
+        assert_eq!(cipher.decrypt(0, &expected).unwrap(), packet);
+    }
+
+    ///
+    /// A sequence number using all 64 bits must be placed big-endian in the
+    /// nonce.
+    ///
+    #[test]
+    fn large_sequence_number_test() {
+        let cipher = OpenSshChaCha20Poly1305::new(TEST_KEY);
+        let mut packet = 100u32.to_be_bytes().to_vec();
+        packet.extend(0..100u8);
+
+        let expected = hex!("04bc91a8d0d2d97f9a49ea6918284a5f1f2a0810f69c1b4ed049f7f2e783a345d4a025920b8a9488092fa9dd70f8de3fda61384b9f5854b5d20f25144a660a1025ddc8529368191f13fa8365ec62b17d90c12c6c6f709656073b130a58d810afde8061f7ad1daaac140a67fc69d1da1feb77bd8b09b62d27");
+        let sequence_number = 0x0102030405060708;
+        assert_eq!(cipher.encrypt(sequence_number, &packet).unwrap(), expected);
+        assert_eq!(
+            cipher.decrypt_length(sequence_number, expected[..4].try_into().unwrap()),
+            100
+        );
+        assert_eq!(cipher.decrypt(sequence_number, &expected).unwrap(), packet);
+    }
+
+    ///
+    /// Any modification of the packet, or a wrong sequence number, must fail
+    /// authentication.
+    ///
+    #[test]
+    fn tampered_packet_test() {
+        let cipher = OpenSshChaCha20Poly1305::new(TEST_KEY);
+        let packet = hex!("0000001c0a050000000c7373682d757365726175746800000000000000000000");
+        let encrypted = cipher.encrypt(3, &packet).unwrap();
+
+        for i in 0..encrypted.len() {
+            let mut tampered = encrypted.clone();
+            tampered[i] ^= 0x01;
+            assert_eq!(cipher.decrypt(3, &tampered), Err(OpenSshError::InvalidTag));
+        }
+        assert_eq!(cipher.decrypt(4, &encrypted), Err(OpenSshError::InvalidTag));
+        assert_eq!(
+            cipher.decrypt(3, &encrypted[..19]),
+            Err(OpenSshError::PacketTooShort)
+        );
+    }
+}
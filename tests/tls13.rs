@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod tls13_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::hkdf::HkdfError;
+    use armadillo::sha2::{sha256, Sha256};
+    use armadillo::tls13::{
+        derive_secret, finished_verify_data, hkdf_expand_label, next_traffic_secret,
+        ChaCha20Poly1305TrafficKeys, KeySchedule, TrafficKeys, Transcript,
+        CLIENT_HANDSHAKE_TRAFFIC, SERVER_HANDSHAKE_TRAFFIC,
+    };
+
+    /// The X25519 shared secret of the simple 1-RTT handshake.
+    const ECDHE: [u8; 32] =
+        hex!("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d");
+    /// The hash of ClientHello and ServerHello.
+    const HELLO_HASH: [u8; 32] =
+        hex!("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8");
+    const CLIENT_HANDSHAKE_SECRET: [u8; 32] =
+        hex!("b3eddb126e067f35a780b3abf45e2d8f3b1a950738f52e9600746a0e27a55a21");
+    const SERVER_HANDSHAKE_SECRET: [u8; 32] =
+        hex!("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38");
+
+    ///
+    /// The early, handshake and master secrets of a full handshake without
+    /// a pre-shared key.
+    ///
+    /// Taken from RFC 8448, Section 3.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8448#section-3)
+    ///
+    #[test]
+    fn key_schedule_test() {
+        let mut schedule = KeySchedule::<Sha256>::new(None);
+        assert_eq!(
+            *schedule.secret(),
+            hex!("33ad0a1c607ec03b09e6cd9893680ce210adf300aa1f2660e1b22e10f170f92a")
+        );
+        assert_eq!(
+            derive_secret::<Sha256>(schedule.secret(), b"derived", &sha256(b"")),
+            hex!("6f2615a108c702c5678f54fc9dbab69716c076189c48250cebeac3576c3611ba")
+        );
+
+        schedule.advance(Some(&ECDHE));
+        assert_eq!(
+            *schedule.secret(),
+            hex!("1dc826e93606aa6fdc0aadc12f741b01046aa6b99f691ed221a9f0ca043fbeac")
+        );
+        assert_eq!(
+            derive_secret::<Sha256>(schedule.secret(), CLIENT_HANDSHAKE_TRAFFIC, &HELLO_HASH),
+            CLIENT_HANDSHAKE_SECRET
+        );
+        assert_eq!(
+            derive_secret::<Sha256>(schedule.secret(), SERVER_HANDSHAKE_TRAFFIC, &HELLO_HASH),
+            SERVER_HANDSHAKE_SECRET
+        );
+
+        schedule.advance(None);
+        assert_eq!(
+            *schedule.secret(),
+            hex!("18df06843d13a08bf2a449844c5f8a478001bc4d4c627984d5a41da8d0402919")
+        );
+    }
+
+    ///
+    /// Handshake traffic keys and IVs, and the server's Finished, for
+    /// `TLS_AES_128_GCM_SHA256`.
+    ///
+    /// Taken from RFC 8448, Section 3.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8448#section-3)
+    ///
+    #[test]
+    fn traffic_keys_test() {
+        let server = TrafficKeys::<16>::new::<Sha256>(&SERVER_HANDSHAKE_SECRET);
+        assert_eq!(server.key, hex!("3fce516009c21727d0f2e4e86ee403bc"));
+        assert_eq!(server.iv, hex!("5d313eb2671276ee13000b30"));
+
+        let client = TrafficKeys::<16>::new::<Sha256>(&CLIENT_HANDSHAKE_SECRET);
+        assert_eq!(client.key, hex!("dbfaa693d1762c5b666af5d950258d01"));
+        assert_eq!(client.iv, hex!("5bd3c71b836e0b76bb73265f"));
+
+        let transcript_hash =
+            hex!("edb7725fa7a3473b031ec8ef65a2485493900138a2b91291407d7951a06110ed");
+        assert_eq!(
+            finished_verify_data::<Sha256>(&SERVER_HANDSHAKE_SECRET, &transcript_hash),
+            hex!("9b9b141d906337fbd2cbdce71df4deda4ab42c309572cb7fffee5454b78f0718")
+        );
+    }
+
+    ///
+    /// Traffic keys for `TLS_CHACHA20_POLY1305_SHA256`, per-record nonces,
+    /// and the next secret after a KeyUpdate.
+    ///
+    /// Generated with Python's cryptography package.
+    ///
+    #[test]
+    fn chacha20_poly1305_test() {
+        let keys = ChaCha20Poly1305TrafficKeys::new::<Sha256>(&SERVER_HANDSHAKE_SECRET);
+        assert_eq!(
+            keys.key,
+            hex!("ac70443f7fe3bdaf568b1dcdb0a7f3fea098bca189c3455ba41fcd9d488348a4")
+        );
+        assert_eq!(keys.iv, hex!("5d313eb2671276ee13000b30"));
+        assert_eq!(keys.nonce(0), keys.iv);
+        assert_eq!(keys.nonce(1), hex!("5d313eb2671276ee13000b31"));
+        assert_eq!(
+            keys.nonce(0x0102030405060708),
+            hex!("5d313eb2661075ea16060c38")
+        );
+
+        let record = keys.aead().seal(keys.nonce(0), b"header", b"record");
+        assert_eq!(
+            keys.aead().open(keys.nonce(0), b"header", &record).unwrap(),
+            b"record"
+        );
+
+        assert_eq!(
+            next_traffic_secret::<Sha256>(&SERVER_HANDSHAKE_SECRET),
+            hex!("c5847ffa1bfea2d5c409eee45d2813181327a78a52ee6d02d8a5e10fbf0fface")
+        );
+    }
+
+    ///
+    /// The transcript hash covers every message added so far, and keeps
+    /// going after it is read.
+    ///
+    #[test]
+    fn transcript_test() {
+        let mut transcript = Transcript::<Sha256>::new();
+        assert_eq!(transcript.hash(), sha256(b""));
+        transcript.update(b"client hello");
+        let hello = transcript.hash();
+        transcript.update(b"server hello");
+
+        assert_eq!(hello, sha256(b"client hello"));
+        assert_eq!(transcript.hash(), sha256(b"client helloserver hello"));
+
+        let schedule = KeySchedule::<Sha256>::new(Some(&[7; 32]));
+        assert_eq!(
+            schedule.derive_secret(CLIENT_HANDSHAKE_TRAFFIC, &transcript),
+            derive_secret::<Sha256>(
+                schedule.secret(),
+                CLIENT_HANDSHAKE_TRAFFIC,
+                &sha256(b"client helloserver hello")
+            )
+        );
+    }
+
+    ///
+    /// `Derive-Secret` is `HKDF-Expand-Label` of one hash output, and
+    /// outputs HKDF cannot produce are rejected.
+    ///
+    /// The server's finished key is taken from RFC 8448, Section 3.
+    /// [Source](https://datatracker.ietf.org/doc/html/rfc8448#section-3)
+    ///
+    #[test]
+    fn expand_label_test() {
+        let mut output = [0u8; 32];
+        hkdf_expand_label::<Sha256>(&SERVER_HANDSHAKE_SECRET, b"finished", &[], &mut output)
+            .unwrap();
+        assert_eq!(
+            output,
+            hex!("008d3b66f816ea559f96b537e885c31fc068bf492c652f01f288a1d8cdc19fc8")
+        );
+        assert_eq!(
+            output,
+            derive_secret::<Sha256>(&SERVER_HANDSHAKE_SECRET, b"finished", &[])
+        );
+
+        let mut output = [0u8; 255 * 32 + 1];
+        assert_eq!(
+            hkdf_expand_label::<Sha256>(&SERVER_HANDSHAKE_SECRET, b"key", &[], &mut output),
+            Err(HkdfError::InvalidOutputLength)
+        );
+        assert_eq!(
+            hkdf_expand_label::<Sha256>(&[0; 16], b"key", &[], &mut [0; 16]),
+            Err(HkdfError::InvalidPrkLength)
+        );
+    }
+}
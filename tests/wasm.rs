@@ -0,0 +1,44 @@
+#[cfg(all(test, feature = "wasm"))]
+mod wasm_tests {
+    extern crate armadillo;
+
+    use hex_literal::hex;
+
+    use armadillo::aead::ChaCha20Poly1305;
+    use armadillo::chacha::ChaCha20;
+    use armadillo::wasm::{chacha20_decrypt, chacha20_encrypt, open, seal};
+
+    const TEST_KEY: [u8; 32] =
+        hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+    const TEST_NONCE: [u8; 12] = hex!("070000004041424344454647");
+    const TEST_AAD: [u8; 12] = hex!("50515253c0c1c2c3c4c5c6c7");
+    const TEST_PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99";
+
+    ///
+    /// The bindings must produce exactly what the Rust API does.  Only the
+    /// successful paths can run outside WebAssembly, as building the thrown
+    /// JavaScript errors needs a JavaScript host.
+    ///
+    #[test]
+    fn bindings_match_rust_api_test() {
+        let encrypted = chacha20_encrypt(&TEST_KEY, &TEST_NONCE, TEST_PLAINTEXT).unwrap();
+        assert_eq!(
+            encrypted,
+            ChaCha20::new(TEST_KEY, TEST_NONCE).encrypt(TEST_PLAINTEXT)
+        );
+        assert_eq!(
+            chacha20_decrypt(&TEST_KEY, &TEST_NONCE, &encrypted).unwrap(),
+            TEST_PLAINTEXT
+        );
+
+        let sealed = seal(&TEST_KEY, &TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT).unwrap();
+        assert_eq!(
+            sealed,
+            ChaCha20Poly1305::new(TEST_KEY).seal(TEST_NONCE, &TEST_AAD, TEST_PLAINTEXT)
+        );
+        assert_eq!(
+            open(&TEST_KEY, &TEST_NONCE, &TEST_AAD, &sealed).unwrap(),
+            TEST_PLAINTEXT
+        );
+    }
+}